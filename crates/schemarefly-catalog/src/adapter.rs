@@ -1,10 +1,12 @@
 //! Warehouse adapter trait for fetching table schemas
 
 use schemarefly_core::Schema;
+use std::collections::HashMap;
 use std::fmt;
+use std::time::Duration;
 
 /// Identifies a table in a warehouse
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TableIdentifier {
     /// Database/project name
     pub database: String,
@@ -61,6 +63,83 @@ pub enum FetchError {
 
     #[error("Configuration error: {0}")]
     ConfigError(String),
+
+    /// The warehouse rejected the request because of rate limiting or quotas
+    #[error("Rate limited by warehouse{}", retry_after_suffix(.retry_after))]
+    RateLimited {
+        /// Server-provided backoff, if the response included one
+        retry_after: Option<Duration>,
+    },
+}
+
+impl FetchError {
+    /// Rate-limit error with an optional server-provided backoff
+    pub fn rate_limited(retry_after: Option<Duration>) -> Self {
+        Self::RateLimited { retry_after }
+    }
+
+    /// Whether retrying the same request may succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::RateLimited { .. } | Self::NetworkError(_))
+    }
+
+    /// Server-provided backoff for rate-limit errors
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+fn retry_after_suffix(retry_after: &Option<Duration>) -> String {
+    match retry_after {
+        Some(d) => format!(" (retry after {:.1}s)", d.as_secs_f64()),
+        None => String::new(),
+    }
+}
+
+/// Extract a retry-after hint (in seconds) from a warehouse error message
+///
+/// Matches `Retry-After: 30`, `retry after 30 seconds` and similar phrasings;
+/// returns `None` when the message carries no hint.
+pub fn retry_after_hint(message: &str) -> Option<Duration> {
+    let lower = message.to_lowercase();
+
+    for marker in ["retry-after", "retry after", "retry_after"] {
+        if let Some(pos) = lower.find(marker) {
+            let rest = lower[pos + marker.len()..]
+                .trim_start_matches(|c: char| c == ':' || c == '=' || c.is_whitespace());
+            let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            if let Ok(secs) = digits.parse::<u64>() {
+                return Some(Duration::from_secs(secs));
+            }
+        }
+    }
+
+    None
+}
+
+/// Optional features a warehouse adapter supports
+///
+/// Callers check these before running checks that depend on them, so an
+/// unsupported feature is skipped with a clear message instead of failing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AdapterCapabilities {
+    /// Nested types (`ARRAY`, `STRUCT`/`OBJECT`) are mapped to their element types
+    pub supports_nested_types: bool,
+
+    /// Column nullability and defaults are reported
+    pub supports_constraints: bool,
+
+    /// Tables of a schema can be listed
+    pub supports_list_tables: bool,
+
+    /// `fetch_view_definition` returns stored view SQL
+    pub supports_view_defs: bool,
+
+    /// `fetch_schemas` fetches many tables per query instead of one at a time
+    pub supports_batch_fetch: bool,
 }
 
 /// Trait for warehouse adapters that can fetch table schemas
@@ -69,12 +148,44 @@ pub trait WarehouseAdapter: Send + Sync {
     /// Get the adapter name (e.g., "BigQuery", "Snowflake")
     fn name(&self) -> &'static str;
 
+    /// Optional features this adapter supports (none by default)
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::default()
+    }
+
     /// Fetch the schema for a specific table
     ///
     /// This should query the warehouse's INFORMATION_SCHEMA to get
     /// column names and types for the specified table.
     async fn fetch_schema(&self, table: &TableIdentifier) -> Result<Schema, FetchError>;
 
+    /// Fetch the schemas of many tables at once
+    ///
+    /// Tables that don't exist are absent from the result rather than an
+    /// error. The default fetches each table with `fetch_schema`; adapters
+    /// that can batch override it and set `supports_batch_fetch`.
+    async fn fetch_schemas(&self, tables: &[TableIdentifier]) -> Result<HashMap<TableIdentifier, Schema>, FetchError> {
+        let mut schemas = HashMap::new();
+        for table in tables {
+            match self.fetch_schema(table).await {
+                Ok(schema) => {
+                    schemas.insert(table.clone(), schema);
+                }
+                Err(FetchError::TableNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(schemas)
+    }
+
+    /// Fetch the stored SQL of a view
+    ///
+    /// Returns `None` when the table is not a view or the adapter cannot
+    /// read view definitions (the default).
+    async fn fetch_view_definition(&self, _table: &TableIdentifier) -> Result<Option<String>, FetchError> {
+        Ok(None)
+    }
+
     /// Test the connection to the warehouse
     ///
     /// This is useful for validating credentials before attempting
@@ -82,6 +193,33 @@ pub trait WarehouseAdapter: Send + Sync {
     async fn test_connection(&self) -> Result<(), FetchError>;
 }
 
+#[async_trait::async_trait]
+impl<T: WarehouseAdapter + ?Sized> WarehouseAdapter for Box<T> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        (**self).capabilities()
+    }
+
+    async fn fetch_schema(&self, table: &TableIdentifier) -> Result<Schema, FetchError> {
+        (**self).fetch_schema(table).await
+    }
+
+    async fn fetch_schemas(&self, tables: &[TableIdentifier]) -> Result<HashMap<TableIdentifier, Schema>, FetchError> {
+        (**self).fetch_schemas(tables).await
+    }
+
+    async fn fetch_view_definition(&self, table: &TableIdentifier) -> Result<Option<String>, FetchError> {
+        (**self).fetch_view_definition(table).await
+    }
+
+    async fn test_connection(&self) -> Result<(), FetchError> {
+        (**self).test_connection().await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,4 +233,16 @@ mod tests {
         assert_eq!(table.fqn(), "my_project.my_dataset.my_table");
         assert_eq!(table.to_string(), "my_project.my_dataset.my_table");
     }
+
+    #[test]
+    fn test_retry_after_hint() {
+        assert_eq!(retry_after_hint("429 Too Many Requests; Retry-After: 30"), Some(Duration::from_secs(30)));
+        assert_eq!(retry_after_hint("Rate limit hit, retry after 5 seconds"), Some(Duration::from_secs(5)));
+        assert_eq!(retry_after_hint("rateLimitExceeded"), None);
+
+        let err = FetchError::rate_limited(Some(Duration::from_secs(2)));
+        assert!(err.is_transient());
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(2)));
+        assert_eq!(err.to_string(), "Rate limited by warehouse (retry after 2.0s)");
+    }
 }
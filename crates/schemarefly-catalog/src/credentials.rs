@@ -0,0 +1,164 @@
+//! Credential providers for secret warehouse settings
+//!
+//! Secret settings (`password`, `private_key_passphrase`, `credentials`) can
+//! come from a secret store instead of `schemarefly.toml`. A setting that is
+//! missing from `[warehouse.settings]` is requested from a
+//! `CredentialProvider` by key before the config is validated. The CLI uses
+//! `EnvCredentialProvider`; embedders can plug in Vault, AWS Secrets Manager,
+//! OIDC token exchange and the like by implementing the trait.
+
+use schemarefly_core::config::WarehouseConfig;
+
+/// Warehouse settings that hold secrets
+pub const SECRET_SETTINGS: &[&str] = &["password", "private_key_passphrase", "credentials"];
+
+/// Errors that can occur when resolving a credential
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CredentialError {
+    /// The provider has no value for the key
+    #[error("Credential '{0}' not found")]
+    NotFound(String),
+
+    /// The provider could not be reached or refused the request
+    #[error("Credential provider failed for '{key}': {message}")]
+    Provider { key: String, message: String },
+}
+
+/// Resolves secret settings by key
+pub trait CredentialProvider: Send + Sync {
+    /// Secret value for `key` (e.g. `password`)
+    fn resolve(&self, key: &str) -> Result<String, CredentialError>;
+}
+
+/// Reads `<PREFIX><KEY>` from the process environment (`SCHEMAREFLY_PASSWORD`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvCredentialProvider {
+    prefix: String,
+}
+
+impl EnvCredentialProvider {
+    /// Provider reading `SCHEMAREFLY_<KEY>` variables
+    pub fn new() -> Self {
+        Self::with_prefix("SCHEMAREFLY_")
+    }
+
+    /// Provider reading `<prefix><KEY>` variables
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self { prefix: prefix.into() }
+    }
+
+    /// Environment variable consulted for `key`
+    pub fn var_name(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key.to_uppercase())
+    }
+}
+
+impl Default for EnvCredentialProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialProvider for EnvCredentialProvider {
+    fn resolve(&self, key: &str) -> Result<String, CredentialError> {
+        std::env::var(self.var_name(key))
+            .ok()
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| CredentialError::NotFound(key.to_string()))
+    }
+}
+
+/// Copy of `config` with missing secret settings filled in from `provider`
+///
+/// Values already in the config (including `${VAR}` references and
+/// `use_env_vars` lookups) win, and only `SECRET_SETTINGS` are requested.
+/// Run this before `WarehouseKind::from_config` so a secret held only by the
+/// provider counts as present.
+pub fn resolve_secrets(
+    config: &WarehouseConfig,
+    provider: &dyn CredentialProvider,
+) -> Result<WarehouseConfig, CredentialError> {
+    let mut resolved = config.clone();
+
+    for key in SECRET_SETTINGS {
+        if config.has_setting(key) {
+            continue;
+        }
+
+        match provider.resolve(key) {
+            Ok(value) => {
+                resolved.settings.insert(key.to_string(), value);
+            }
+            Err(CredentialError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Provider backed by a fixed map, standing in for a secret store
+    struct MockProvider(HashMap<&'static str, &'static str>);
+
+    impl CredentialProvider for MockProvider {
+        fn resolve(&self, key: &str) -> Result<String, CredentialError> {
+            self.0
+                .get(key)
+                .map(|value| value.to_string())
+                .ok_or_else(|| CredentialError::NotFound(key.to_string()))
+        }
+    }
+
+    fn warehouse_config(settings: &[(&str, &str)]) -> WarehouseConfig {
+        WarehouseConfig {
+            warehouse_type: "postgres".to_string(),
+            settings: settings.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            use_env_vars: false,
+        }
+    }
+
+    #[test]
+    fn env_provider_reads_prefixed_variable() {
+        let provider = EnvCredentialProvider::with_prefix("SCHEMAREFLY_TEST_CRED_");
+        assert_eq!(provider.var_name("password"), "SCHEMAREFLY_TEST_CRED_PASSWORD");
+
+        std::env::set_var("SCHEMAREFLY_TEST_CRED_PASSWORD", "from-env");
+        assert_eq!(provider.resolve("password").unwrap(), "from-env");
+        assert_eq!(
+            provider.resolve("private_key_passphrase"),
+            Err(CredentialError::NotFound("private_key_passphrase".to_string()))
+        );
+    }
+
+    #[test]
+    fn mock_provider_supplies_missing_secret() {
+        let provider = MockProvider(HashMap::from([("password", "vault-secret"), ("host", "ignored")]));
+
+        let resolved = resolve_secrets(&warehouse_config(&[("host", "db.internal")]), &provider).unwrap();
+        assert_eq!(resolved.get_setting("password").as_deref(), Some("vault-secret"));
+        assert_eq!(resolved.get_setting("host").as_deref(), Some("db.internal"));
+        assert_eq!(resolved.get_setting("private_key_passphrase"), None);
+
+        // A password in the config is used as is
+        let resolved = resolve_secrets(&warehouse_config(&[("password", "inline")]), &provider).unwrap();
+        assert_eq!(resolved.get_setting("password").as_deref(), Some("inline"));
+    }
+
+    #[test]
+    fn provider_failure_is_reported() {
+        struct Unreachable;
+        impl CredentialProvider for Unreachable {
+            fn resolve(&self, key: &str) -> Result<String, CredentialError> {
+                Err(CredentialError::Provider { key: key.to_string(), message: "connection refused".to_string() })
+            }
+        }
+
+        let err = resolve_secrets(&warehouse_config(&[]), &Unreachable).unwrap_err();
+        assert_eq!(err.to_string(), "Credential provider failed for 'password': connection refused");
+    }
+}
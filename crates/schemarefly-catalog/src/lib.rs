@@ -8,9 +8,25 @@
 //! Enable warehouse support via Cargo features:
 //! - `bigquery` - Google BigQuery support
 //! - `snowflake` - Snowflake support
-//! - `postgres` - PostgreSQL/Redshift support
+//! - `postgres` - PostgreSQL support
+//! - `redshift` - Amazon Redshift support (builds on `postgres`)
 //! - `all-warehouses` - All warehouse adapters
 //!
+//! Adapters pass warehouse type names through a `TypeNormalizer` before
+//! mapping them; register `TypeAliases` (or your own normalizer) with
+//! `with_type_normalizer` to map house type names to known ones.
+//!
+//! Secret settings missing from the config are requested from a
+//! `CredentialProvider` (`resolve_secrets`); `EnvCredentialProvider` reads
+//! them from the environment.
+//!
+//! `WarehouseAdapter::capabilities` reports optional features (nested types,
+//! constraints, table listing, view definitions) so callers can skip checks
+//! an adapter cannot back.
+//!
+//! Wrap any adapter in `RetryingAdapter` to retry rate limits and network
+//! errors, honoring server-provided `retry_after` backoff.
+//!
 //! ## Example
 //!
 //! ```rust,ignore
@@ -38,10 +54,18 @@ pub mod adapter;
 pub mod bigquery;
 pub mod snowflake;
 pub mod postgres;
+pub mod redshift;
 pub mod mock;
+pub mod normalize;
+pub mod retry;
+pub mod credentials;
 
-pub use adapter::{WarehouseAdapter, TableIdentifier, FetchError};
+pub use adapter::{WarehouseAdapter, AdapterCapabilities, TableIdentifier, FetchError};
 pub use bigquery::BigQueryAdapter;
 pub use snowflake::{SnowflakeAdapter, SnowflakeAdapterBuilder};
 pub use postgres::PostgresAdapter;
+pub use redshift::RedshiftAdapter;
 pub use mock::{MockAdapter, MockAdapterBuilder};
+pub use normalize::{TypeNormalizer, NoopTypeNormalizer, TypeAliases};
+pub use retry::RetryingAdapter;
+pub use credentials::{CredentialProvider, CredentialError, EnvCredentialProvider, resolve_secrets};
@@ -37,14 +37,21 @@
 //!
 //! // Simulate network latency
 //! let adapter = MockAdapter::new().with_latency(100); // 100ms delay
+//!
+//! // Fail the first 2 fetches of a table, then succeed
+//! let adapter = MockAdapter::new();
+//! adapter.add_transient_failures(table, 2).await;
 //! ```
 
-use crate::adapter::{WarehouseAdapter, TableIdentifier, FetchError};
+use crate::adapter::{WarehouseAdapter, AdapterCapabilities, TableIdentifier, FetchError};
 use schemarefly_core::Schema;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Remaining failures for a table and the error to return (`None` returns a `NetworkError`)
+type TransientFailure = (usize, Option<FetchError>);
+
 /// Mock warehouse adapter for testing
 ///
 /// This adapter stores schemas in memory and returns them when requested.
@@ -56,6 +63,7 @@ use tokio::sync::RwLock;
 /// - Simulate connection failures
 /// - Simulate network latency
 /// - Simulate specific error conditions per table
+/// - Simulate transient failures and rate limits that clear after N attempts
 /// - Thread-safe with async support
 ///
 /// # Example
@@ -72,6 +80,9 @@ pub struct MockAdapter {
     /// Errors to return for specific tables
     errors: Arc<RwLock<HashMap<String, FetchError>>>,
 
+    /// Remaining transient failures per table FQN
+    transient_failures: Arc<RwLock<HashMap<String, TransientFailure>>>,
+
     /// Simulate connection failure
     fail_connection: bool,
 
@@ -80,6 +91,9 @@ pub struct MockAdapter {
 
     /// Name to return from name() method
     adapter_name: &'static str,
+
+    /// Capabilities to return from capabilities()
+    capabilities: AdapterCapabilities,
 }
 
 impl MockAdapter {
@@ -88,9 +102,23 @@ impl MockAdapter {
         Self {
             schemas: Arc::new(RwLock::new(HashMap::new())),
             errors: Arc::new(RwLock::new(HashMap::new())),
+            transient_failures: Arc::new(RwLock::new(HashMap::new())),
             fail_connection: false,
             latency_ms: 0,
             adapter_name: "Mock",
+            capabilities: Self::default_capabilities(),
+        }
+    }
+
+    /// What the mock supports unless overridden: everything it can serve
+    /// from predefined schemas (no table listing, view definitions or batching)
+    fn default_capabilities() -> AdapterCapabilities {
+        AdapterCapabilities {
+            supports_nested_types: true,
+            supports_constraints: true,
+            supports_list_tables: false,
+            supports_view_defs: false,
+            supports_batch_fetch: false,
         }
     }
 
@@ -142,6 +170,46 @@ impl MockAdapter {
         self.errors.write().await.insert(table.fqn(), error);
     }
 
+    /// Configure transient failures for a specific table
+    ///
+    /// The first `failures` calls to `fetch_schema` for this table return a
+    /// `NetworkError`; later calls behave normally. Useful for testing retry logic.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let adapter = MockAdapter::new();
+    /// adapter.add_transient_failures(TableIdentifier::new("db", "schema", "flaky"), 2).await;
+    /// ```
+    pub async fn add_transient_failures(&self, table: TableIdentifier, failures: usize) {
+        self.transient_failures.write().await.insert(table.fqn(), (failures, None));
+    }
+
+    /// Configure rate limiting for a specific table
+    ///
+    /// The first `failures` calls to `fetch_schema` for this table return
+    /// `FetchError::RateLimited` with the given `retry_after`; later calls
+    /// behave normally.
+    pub async fn add_rate_limit(
+        &self,
+        table: TableIdentifier,
+        failures: usize,
+        retry_after: Option<std::time::Duration>,
+    ) {
+        let error = FetchError::rate_limited(retry_after);
+        self.transient_failures.write().await.insert(table.fqn(), (failures, Some(error)));
+    }
+
+    /// Number of transient failures still pending for a table
+    pub async fn remaining_transient_failures(&self, table: &TableIdentifier) -> usize {
+        self.transient_failures
+            .read()
+            .await
+            .get(&table.fqn())
+            .map(|(remaining, _)| *remaining)
+            .unwrap_or(0)
+    }
+
     /// Configure to fail all connection tests
     ///
     /// When enabled, `test_connection()` will always return an error.
@@ -171,6 +239,12 @@ impl MockAdapter {
         self
     }
 
+    /// Report custom capabilities, e.g. to mimic an adapter without constraint support
+    pub fn with_capabilities(mut self, capabilities: AdapterCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
     /// Create a mock adapter from a pre-built map of schemas
     ///
     /// # Example
@@ -187,9 +261,11 @@ impl MockAdapter {
         Self {
             schemas: Arc::new(RwLock::new(schemas)),
             errors: Arc::new(RwLock::new(HashMap::new())),
+            transient_failures: Arc::new(RwLock::new(HashMap::new())),
             fail_connection: false,
             latency_ms: 0,
             adapter_name: "Mock",
+            capabilities: Self::default_capabilities(),
         }
     }
 
@@ -203,9 +279,10 @@ impl MockAdapter {
         self.schemas.write().await.clear();
     }
 
-    /// Clear all stored errors
+    /// Clear all stored errors (including pending transient failures)
     pub async fn clear_errors(&self) {
         self.errors.write().await.clear();
+        self.transient_failures.write().await.clear();
     }
 
     /// Check if a schema exists for a table
@@ -237,9 +314,11 @@ impl Clone for MockAdapter {
         Self {
             schemas: Arc::clone(&self.schemas),
             errors: Arc::clone(&self.errors),
+            transient_failures: Arc::clone(&self.transient_failures),
             fail_connection: self.fail_connection,
             latency_ms: self.latency_ms,
             adapter_name: self.adapter_name,
+            capabilities: self.capabilities,
         }
     }
 }
@@ -250,6 +329,10 @@ impl WarehouseAdapter for MockAdapter {
         self.adapter_name
     }
 
+    fn capabilities(&self) -> AdapterCapabilities {
+        self.capabilities
+    }
+
     async fn fetch_schema(&self, table: &TableIdentifier) -> Result<Schema, FetchError> {
         self.simulate_latency().await;
 
@@ -258,6 +341,18 @@ impl WarehouseAdapter for MockAdapter {
             return Err(error.clone());
         }
 
+        // Consume a pending transient failure, if any
+        if let Some((remaining, error)) = self.transient_failures.write().await.get_mut(&table.fqn()) {
+            if *remaining > 0 {
+                *remaining -= 1;
+                return Err(error.clone().unwrap_or_else(|| FetchError::NetworkError(format!(
+                    "Simulated transient failure for {} ({} remaining)",
+                    table.fqn(),
+                    remaining
+                ))));
+            }
+        }
+
         // Return the schema if found
         let schemas = self.schemas.read().await;
         schemas
@@ -304,9 +399,11 @@ impl WarehouseAdapter for MockAdapter {
 pub struct MockAdapterBuilder {
     schemas: HashMap<String, Schema>,
     errors: HashMap<String, FetchError>,
+    transient_failures: HashMap<String, TransientFailure>,
     fail_connection: bool,
     latency_ms: u64,
     adapter_name: &'static str,
+    capabilities: AdapterCapabilities,
 }
 
 impl MockAdapterBuilder {
@@ -315,9 +412,11 @@ impl MockAdapterBuilder {
         Self {
             schemas: HashMap::new(),
             errors: HashMap::new(),
+            transient_failures: HashMap::new(),
             fail_connection: false,
             latency_ms: 0,
             adapter_name: "Mock",
+            capabilities: MockAdapter::default_capabilities(),
         }
     }
 
@@ -353,6 +452,34 @@ impl MockAdapterBuilder {
         self
     }
 
+    /// Add transient failures for a specific table
+    pub fn with_transient_failures(
+        mut self,
+        database: &str,
+        schema_name: &str,
+        table: &str,
+        failures: usize,
+    ) -> Self {
+        let fqn = format!("{}.{}.{}", database, schema_name, table);
+        self.transient_failures.insert(fqn, (failures, None));
+        self
+    }
+
+    /// Add rate-limit failures for a specific table
+    pub fn with_rate_limit(
+        mut self,
+        database: &str,
+        schema_name: &str,
+        table: &str,
+        failures: usize,
+        retry_after: Option<std::time::Duration>,
+    ) -> Self {
+        let fqn = format!("{}.{}.{}", database, schema_name, table);
+        self.transient_failures
+            .insert(fqn, (failures, Some(FetchError::rate_limited(retry_after))));
+        self
+    }
+
     /// Configure connection failure
     pub fn with_connection_failure(mut self) -> Self {
         self.fail_connection = true;
@@ -371,14 +498,22 @@ impl MockAdapterBuilder {
         self
     }
 
+    /// Set adapter capabilities
+    pub fn with_capabilities(mut self, capabilities: AdapterCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
     /// Build the MockAdapter
     pub fn build(self) -> MockAdapter {
         MockAdapter {
             schemas: Arc::new(RwLock::new(self.schemas)),
             errors: Arc::new(RwLock::new(self.errors)),
+            transient_failures: Arc::new(RwLock::new(self.transient_failures)),
             fail_connection: self.fail_connection,
             latency_ms: self.latency_ms,
             adapter_name: self.adapter_name,
+            capabilities: self.capabilities,
         }
     }
 }
@@ -455,6 +590,22 @@ mod tests {
         assert!(matches!(result, Err(FetchError::PermissionDenied(_))));
     }
 
+    #[test]
+    fn test_mock_adapter_capabilities() {
+        let adapter = MockAdapter::new();
+        let capabilities = adapter.capabilities();
+        assert!(capabilities.supports_nested_types);
+        assert!(capabilities.supports_constraints);
+        assert!(!capabilities.supports_list_tables);
+        assert!(!capabilities.supports_view_defs);
+
+        // Overrides survive cloning, boxing and the builder
+        let limited = AdapterCapabilities { supports_nested_types: true, ..AdapterCapabilities::default() };
+        let boxed: Box<dyn WarehouseAdapter> = Box::new(adapter.with_capabilities(limited).clone());
+        assert_eq!(boxed.capabilities(), limited);
+        assert_eq!(MockAdapterBuilder::new().with_capabilities(limited).build().capabilities(), limited);
+    }
+
     #[tokio::test]
     async fn test_mock_adapter_from_schemas() {
         let mut schemas = HashMap::new();
@@ -634,4 +785,40 @@ mod tests {
         assert!(names.contains(&"db.schema.table1".to_string()));
         assert!(names.contains(&"db.schema.table2".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_mock_adapter_transient_failures() {
+        let table = TableIdentifier::new("db", "schema", "flaky");
+        let adapter = MockAdapter::new();
+        adapter.add_transient_failures(table.clone(), 2).await;
+        adapter
+            .add_schema(table.clone(), Schema::from_columns(vec![Column::new("id", LogicalType::Int)]))
+            .await;
+
+        // First two calls fail with a network error
+        for _ in 0..2 {
+            let result = adapter.fetch_schema(&table).await;
+            assert!(matches!(result, Err(FetchError::NetworkError(_))));
+        }
+        assert_eq!(adapter.remaining_transient_failures(&table).await, 0);
+
+        // Third call returns the stored schema
+        let fetched = adapter.fetch_schema(&table).await.unwrap();
+        assert_eq!(fetched.columns.len(), 1);
+        assert_eq!(fetched.columns[0].name, "id");
+    }
+
+    #[tokio::test]
+    async fn test_builder_transient_failures_shared_across_clones() {
+        let adapter = MockAdapterBuilder::new()
+            .with_schema("db", "schema", "flaky", Schema::from_columns(vec![Column::new("id", LogicalType::Int)]))
+            .with_transient_failures("db", "schema", "flaky", 1)
+            .build();
+        let clone = adapter.clone();
+        let table = TableIdentifier::new("db", "schema", "flaky");
+
+        // The counter is shared, so a failure on one clone is consumed for both
+        assert!(clone.fetch_schema(&table).await.is_err());
+        assert!(adapter.fetch_schema(&table).await.is_ok());
+    }
 }
@@ -0,0 +1,103 @@
+//! Type-name normalization hook for warehouse adapters
+//!
+//! Every adapter passes the `data_type` reported by the warehouse through a
+//! `TypeNormalizer` before its `map_*_type` function sees it. The default
+//! leaves types untouched; register a normalizer with `with_type_normalizer`
+//! to handle house conventions such as domain types, collation suffixes or
+//! vendor aliases without patching the adapters.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Rewrites a warehouse type name before it is mapped to a `LogicalType`
+pub trait TypeNormalizer: Send + Sync {
+    /// Normalized type name; return `Cow::Borrowed(data_type)` to keep it as is
+    fn normalize<'a>(&self, data_type: &'a str) -> Cow<'a, str>;
+}
+
+/// Normalizer that leaves every type unchanged (the adapters' default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopTypeNormalizer;
+
+impl TypeNormalizer for NoopTypeNormalizer {
+    fn normalize<'a>(&self, data_type: &'a str) -> Cow<'a, str> {
+        Cow::Borrowed(data_type)
+    }
+}
+
+/// Normalizer used by adapters until one is registered
+pub(crate) fn default_normalizer() -> Arc<dyn TypeNormalizer> {
+    Arc::new(NoopTypeNormalizer)
+}
+
+/// Replaces whole type names with known ones (`MONEY_T` → `NUMERIC(19,4)`)
+///
+/// Aliases match case-insensitively, ignoring surrounding whitespace.
+/// Types without an alias pass through unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeAliases {
+    /// Uppercased alias → replacement type name
+    aliases: HashMap<String, String>,
+}
+
+impl TypeAliases {
+    /// Create an empty alias table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an alias
+    pub fn with_alias(mut self, alias: impl AsRef<str>, data_type: impl Into<String>) -> Self {
+        self.aliases.insert(alias.as_ref().trim().to_uppercase(), data_type.into());
+        self
+    }
+
+    /// Whether no aliases are registered
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+}
+
+impl From<HashMap<String, String>> for TypeAliases {
+    fn from(aliases: HashMap<String, String>) -> Self {
+        aliases
+            .into_iter()
+            .fold(Self::new(), |table, (alias, data_type)| table.with_alias(alias, data_type))
+    }
+}
+
+impl TypeNormalizer for TypeAliases {
+    fn normalize<'a>(&self, data_type: &'a str) -> Cow<'a, str> {
+        match self.aliases.get(&data_type.trim().to_uppercase()) {
+            Some(replacement) => Cow::Owned(replacement.clone()),
+            None => Cow::Borrowed(data_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PostgresAdapter;
+    use schemarefly_core::LogicalType;
+
+    #[test]
+    fn noop_keeps_type() {
+        assert!(matches!(NoopTypeNormalizer.normalize("VARCHAR(10)"), Cow::Borrowed("VARCHAR(10)")));
+    }
+
+    #[test]
+    fn alias_maps_custom_type_to_known_type() {
+        let aliases = TypeAliases::new().with_alias("money_t", "numeric(19,4)");
+
+        // Unnormalized, the house alias is unknown
+        assert_eq!(PostgresAdapter::map_postgres_type("MONEY_T"), LogicalType::Unknown);
+
+        assert_eq!(
+            PostgresAdapter::map_postgres_type(&aliases.normalize(" MONEY_T ")),
+            LogicalType::Decimal { precision: Some(19), scale: Some(4) }
+        );
+        assert_eq!(aliases.normalize("integer"), "integer");
+    }
+}
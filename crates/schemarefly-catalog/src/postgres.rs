@@ -3,7 +3,7 @@
 //! This adapter queries PostgreSQL's information_schema.columns view to fetch
 //! table schemas. It works with:
 //! - PostgreSQL 9.4+
-//! - Amazon Redshift
+//! - Amazon Redshift (see `RedshiftAdapter` for late-binding views)
 //! - CockroachDB
 //! - Other PostgreSQL-compatible databases
 //!
@@ -39,8 +39,13 @@
 //!
 //! Reference: https://www.postgresql.org/docs/current/information-schema-columns.html
 
-use crate::adapter::{WarehouseAdapter, TableIdentifier, FetchError};
-use schemarefly_core::{Schema, Column, LogicalType, Nullability};
+use crate::adapter::{WarehouseAdapter, AdapterCapabilities, TableIdentifier, FetchError};
+use crate::normalize::{TypeNormalizer, default_normalizer};
+use schemarefly_core::{Schema, LogicalType};
+use std::sync::Arc;
+
+#[cfg(feature = "postgres")]
+use schemarefly_core::{Column, Nullability};
 
 #[cfg(feature = "postgres")]
 use tokio_postgres::{Client, NoTls, Config as PgConfig};
@@ -51,6 +56,50 @@ use postgres_native_tls::MakeTlsConnector;
 #[cfg(feature = "postgres")]
 use native_tls::TlsConnector;
 
+/// Query for a view's stored SQL; returns no rows when the table is not a view
+///
+/// `information_schema.views` only lists views the user may access, and
+/// `pg_get_viewdef` returns the full definition where that view truncates it.
+pub const VIEW_DEFINITION_QUERY: &str = r#"
+    SELECT pg_get_viewdef(format('%I.%I', table_schema, table_name)::regclass, true)
+    FROM information_schema.views
+    WHERE table_catalog = $1
+      AND table_schema = $2
+      AND table_name = $3
+"#;
+
+/// Query for a table's columns, bound to database, schema and table
+///
+/// `is_identity` and `is_generated` are read so that identity and
+/// `GENERATED ALWAYS AS` columns count as populated by the warehouse.
+/// `enum_values` lists the labels of enum-typed columns (NULL otherwise).
+pub const COLUMNS_QUERY: &str = r#"
+    SELECT
+        column_name,
+        data_type,
+        is_nullable,
+        ordinal_position,
+        numeric_precision,
+        numeric_scale,
+        udt_name,
+        character_maximum_length,
+        column_default,
+        is_identity,
+        is_generated,
+        (
+            SELECT array_agg(e.enumlabel::text ORDER BY e.enumsortorder)
+            FROM pg_catalog.pg_enum e
+            JOIN pg_catalog.pg_type t ON t.oid = e.enumtypid
+            JOIN pg_catalog.pg_namespace n ON n.oid = t.typnamespace
+            WHERE t.typname = udt_name AND n.nspname = udt_schema
+        ) AS enum_values
+    FROM information_schema.columns
+    WHERE table_catalog = $1
+      AND table_schema = $2
+      AND table_name = $3
+    ORDER BY ordinal_position
+"#;
+
 /// PostgreSQL warehouse adapter
 ///
 /// This adapter connects to PostgreSQL databases and fetches table schemas
@@ -69,6 +118,9 @@ pub struct PostgresAdapter {
     /// Database name
     database: String,
 
+    /// Rewrites type names before they are mapped
+    type_normalizer: Arc<dyn TypeNormalizer>,
+
     /// Placeholder for when feature is disabled
     #[cfg(not(feature = "postgres"))]
     _phantom: std::marker::PhantomData<()>,
@@ -134,6 +186,7 @@ impl PostgresAdapter {
             host,
             port,
             database,
+            type_normalizer: default_normalizer(),
         })
     }
 
@@ -219,6 +272,7 @@ impl PostgresAdapter {
             host,
             port,
             database,
+            type_normalizer: default_normalizer(),
         })
     }
 
@@ -292,6 +346,7 @@ impl PostgresAdapter {
             host,
             port,
             database,
+            type_normalizer: default_normalizer(),
         })
     }
 
@@ -367,6 +422,7 @@ impl PostgresAdapter {
             host,
             port,
             database,
+            type_normalizer: default_normalizer(),
         })
     }
 
@@ -385,10 +441,27 @@ impl PostgresAdapter {
             host: String::new(),
             port: 0,
             database: String::new(),
+            type_normalizer: default_normalizer(),
             _phantom: std::marker::PhantomData,
         }
     }
 
+    /// Register a normalizer applied to warehouse type names before mapping
+    pub fn with_type_normalizer(mut self, normalizer: impl TypeNormalizer + 'static) -> Self {
+        self.type_normalizer = Arc::new(normalizer);
+        self
+    }
+
+    /// Map a warehouse type name to a LogicalType, normalizing it first
+    pub fn map_type(&self, data_type: &str) -> LogicalType {
+        Self::map_postgres_type(&self.type_normalizer.normalize(data_type))
+    }
+
+    /// Normalizer registered with `with_type_normalizer`
+    pub(crate) fn type_normalizer(&self) -> &dyn TypeNormalizer {
+        self.type_normalizer.as_ref()
+    }
+
     /// Convert PostgreSQL type to LogicalType
     ///
     /// This method handles all standard PostgreSQL data types and maps them
@@ -542,6 +615,64 @@ impl PostgresAdapter {
         }
     }
 
+    /// Whether an `information_schema.columns.is_generated` value marks a generated column
+    ///
+    /// PostgreSQL reports `ALWAYS` for `GENERATED ALWAYS AS (...) STORED`
+    /// columns and `NEVER` otherwise.
+    pub fn is_generated(value: Option<&str>) -> bool {
+        value.is_some_and(|value| value.eq_ignore_ascii_case("ALWAYS"))
+    }
+
+    /// Normalize an `information_schema.columns.column_default` expression
+    ///
+    /// Trailing top-level casts are stripped so `'active'::character varying`
+    /// becomes `'active'`; casts inside calls such as
+    /// `nextval('users_id_seq'::regclass)` are kept. A `NULL` default is
+    /// reported as no default.
+    pub fn normalize_default(raw: &str) -> Option<String> {
+        let mut default = raw.trim();
+        while let Some(cast) = Self::top_level_cast(default) {
+            default = default[..cast].trim_end();
+        }
+
+        if default.is_empty() || default.eq_ignore_ascii_case("NULL") {
+            None
+        } else {
+            Some(default.to_string())
+        }
+    }
+
+    /// Byte offset of the last `::` outside quotes and parentheses
+    fn top_level_cast(expr: &str) -> Option<usize> {
+        let bytes = expr.as_bytes();
+        let mut depth = 0usize;
+        let mut in_quote = false;
+        let mut last = None;
+
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\'' => in_quote = !in_quote,
+                b'(' if !in_quote => depth += 1,
+                b')' if !in_quote => depth = depth.saturating_sub(1),
+                b':' if !in_quote && depth == 0 && bytes.get(i + 1) == Some(&b':') => {
+                    last = Some(i);
+                    i += 1;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        last
+    }
+
+    /// Underlying client, shared with adapters for PostgreSQL-compatible warehouses
+    #[cfg(feature = "redshift")]
+    pub(crate) fn client(&self) -> &Client {
+        &self.client
+    }
+
     /// Get the connection host
     pub fn host(&self) -> &str {
         &self.host
@@ -564,29 +695,22 @@ impl WarehouseAdapter for PostgresAdapter {
         "PostgreSQL"
     }
 
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities {
+            supports_nested_types: true,
+            supports_constraints: true,
+            supports_list_tables: false,
+            supports_view_defs: true,
+            supports_batch_fetch: false,
+        }
+    }
+
     #[cfg(feature = "postgres")]
     async fn fetch_schema(&self, table: &TableIdentifier) -> Result<Schema, FetchError> {
         // Query information_schema.columns for the table schema
         // Using parameterized query to prevent SQL injection
-        let query = r#"
-            SELECT
-                column_name,
-                data_type,
-                is_nullable,
-                ordinal_position,
-                numeric_precision,
-                numeric_scale,
-                udt_name,
-                character_maximum_length
-            FROM information_schema.columns
-            WHERE table_catalog = $1
-              AND table_schema = $2
-              AND table_name = $3
-            ORDER BY ordinal_position
-        "#;
-
         let rows = self.client
-            .query(query, &[&table.database, &table.schema, &table.table])
+            .query(COLUMNS_QUERY, &[&table.database, &table.schema, &table.table])
             .await
             .map_err(|e| {
                 let err_str = e.to_string();
@@ -612,6 +736,16 @@ impl WarehouseAdapter for PostgresAdapter {
             let numeric_precision: Option<i32> = row.get(4);
             let numeric_scale: Option<i32> = row.get(5);
             let udt_name: String = row.get(6);
+            let column_default = row.get::<_, Option<String>>(8)
+                .and_then(|d| Self::normalize_default(&d));
+            let is_identity: Option<String> = row.get(9);
+            let is_generated = Self::is_generated(row.get::<_, Option<String>>(10).as_deref());
+            let enum_values: Option<Vec<String>> = row.get(11);
+
+            // Identity and generated columns are populated without an explicit default
+            let has_default = column_default.is_some()
+                || is_identity.as_deref() == Some("YES")
+                || is_generated;
 
             // Build full type string for numeric types with precision/scale
             let full_type = if data_type == "numeric" || data_type == "decimal" {
@@ -627,17 +761,26 @@ impl WarehouseAdapter for PostgresAdapter {
                 data_type.clone()
             };
 
-            let logical_type = Self::map_postgres_type(&full_type);
+            let logical_type = self.map_type(&full_type);
             let nullable = match is_nullable.to_uppercase().as_str() {
                 "YES" => Nullability::Yes,
                 "NO" => Nullability::No,
                 _ => Nullability::Unknown,
             };
 
-            columns.push(
-                Column::new(col_name, logical_type)
-                    .with_nullability(nullable)
-            );
+            let mut column = Column::new(col_name, logical_type)
+                .with_nullability(nullable)
+                .with_has_default(has_default)
+                .with_generated(is_generated)
+                .with_time_zone_of(&full_type)
+                .with_warehouse_type(full_type);
+            if let Some(default) = column_default {
+                column = column.with_default(default);
+            }
+            if let Some(values) = enum_values {
+                column = column.with_accepted_values(values);
+            }
+            columns.push(column);
         }
 
         if columns.is_empty() {
@@ -657,6 +800,26 @@ impl WarehouseAdapter for PostgresAdapter {
         ))
     }
 
+    #[cfg(feature = "postgres")]
+    async fn fetch_view_definition(&self, table: &TableIdentifier) -> Result<Option<String>, FetchError> {
+        let row = self.client
+            .query_opt(VIEW_DEFINITION_QUERY, &[&table.database, &table.schema, &table.table])
+            .await
+            .map_err(|e| {
+                let err_str = e.to_string();
+                if err_str.contains("permission denied") {
+                    FetchError::PermissionDenied(format!(
+                        "Cannot read view definition of {}: {}",
+                        table.fqn(), err_str
+                    ))
+                } else {
+                    FetchError::QueryError(err_str)
+                }
+            })?;
+
+        Ok(row.and_then(|row| row.get::<_, Option<String>>(0)))
+    }
+
     #[cfg(feature = "postgres")]
     async fn test_connection(&self) -> Result<(), FetchError> {
         // Simple query to test connection
@@ -821,4 +984,59 @@ mod tests {
         assert!(matches!(PostgresAdapter::map_postgres_type("custom_type"), LogicalType::Unknown));
         assert!(matches!(PostgresAdapter::map_postgres_type("some_extension_type"), LogicalType::Unknown));
     }
+
+    #[test]
+    fn test_view_definition_query() {
+        let query = VIEW_DEFINITION_QUERY;
+        assert!(query.contains("pg_get_viewdef("));
+        assert!(query.contains("FROM information_schema.views"));
+
+        // Bound in the order fetch_view_definition passes database, schema, table
+        let catalog = query.find("table_catalog = $1").unwrap();
+        let schema = query.find("table_schema = $2").unwrap();
+        let table = query.find("table_name = $3").unwrap();
+        assert!(catalog < schema && schema < table);
+    }
+
+    #[test]
+    fn test_columns_query_reads_is_generated_and_enum_values() {
+        let query = COLUMNS_QUERY;
+        assert!(query.contains("FROM information_schema.columns"));
+
+        // fetch_schema reads is_generated as the 11th column and enum_values as the 12th
+        let selected: Vec<&str> = query
+            .split("FROM information_schema.columns")
+            .next()
+            .and_then(|select| select.trim().strip_prefix("SELECT"))
+            .unwrap()
+            .split(',')
+            .map(str::trim)
+            .collect();
+        assert_eq!(selected.iter().position(|c| *c == "is_generated"), Some(10));
+        assert_eq!(selected.len(), 12);
+        assert!(selected[11].ends_with("AS enum_values"));
+
+        assert!(PostgresAdapter::is_generated(Some("ALWAYS")));
+        assert!(!PostgresAdapter::is_generated(Some("NEVER")));
+        assert!(!PostgresAdapter::is_generated(None));
+    }
+
+    #[test]
+    fn test_normalize_default() {
+        assert_eq!(
+            PostgresAdapter::normalize_default("'active'::character varying"),
+            Some("'active'".to_string())
+        );
+        assert_eq!(
+            PostgresAdapter::normalize_default("'{}'::jsonb::json"),
+            Some("'{}'".to_string())
+        );
+        assert_eq!(
+            PostgresAdapter::normalize_default("nextval('users_id_seq'::regclass)"),
+            Some("nextval('users_id_seq'::regclass)".to_string())
+        );
+        assert_eq!(PostgresAdapter::normalize_default("'a::b'::text"), Some("'a::b'".to_string()));
+        assert_eq!(PostgresAdapter::normalize_default(" now() "), Some("now()".to_string()));
+        assert_eq!(PostgresAdapter::normalize_default("NULL::character varying"), None);
+    }
 }
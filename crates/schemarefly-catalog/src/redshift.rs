@@ -0,0 +1,322 @@
+//! Amazon Redshift warehouse adapter using SVV_COLUMNS
+//!
+//! Redshift speaks the PostgreSQL wire protocol, so this adapter reuses the
+//! `PostgresAdapter` connection. It differs in where it reads columns from:
+//! Redshift's `information_schema.columns` omits late-binding views (created
+//! `WITH NO SCHEMA BINDING`), while `SVV_COLUMNS` covers regular tables,
+//! views, late-binding views and Spectrum external tables alike.
+//!
+//! Physical layout attributes such as DISTKEY, SORTKEY and column encodings
+//! don't change a table's logical schema and are not reported.
+//!
+//! ## Usage
+//!
+//! ```rust,ignore
+//! let adapter = RedshiftAdapter::connect(
+//!     "my-cluster.abc123.us-east-1.redshift.amazonaws.com",
+//!     5439,
+//!     "analytics",
+//!     "username",
+//!     "password"
+//! ).await?;
+//! ```
+//!
+//! Reference: https://docs.aws.amazon.com/redshift/latest/dg/r_SVV_COLUMNS.html
+
+use crate::adapter::{WarehouseAdapter, AdapterCapabilities, TableIdentifier, FetchError};
+use crate::normalize::TypeNormalizer;
+use crate::postgres::PostgresAdapter;
+use schemarefly_core::{Schema, LogicalType};
+
+#[cfg(feature = "redshift")]
+use schemarefly_core::{Column, Nullability};
+
+/// Amazon Redshift warehouse adapter
+///
+/// Wraps a `PostgresAdapter` connection and fetches schemas from `SVV_COLUMNS`.
+pub struct RedshiftAdapter {
+    /// Underlying PostgreSQL wire-protocol connection
+    inner: PostgresAdapter,
+}
+
+impl RedshiftAdapter {
+    /// Connect to a Redshift cluster with direct credentials
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - Cluster endpoint hostname
+    /// * `port` - Cluster port (usually 5439)
+    /// * `database` - Database name to connect to
+    /// * `user` - Username for authentication
+    /// * `password` - Password for authentication
+    #[cfg(feature = "redshift")]
+    pub async fn connect(
+        host: impl Into<String>,
+        port: u16,
+        database: impl Into<String>,
+        user: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Result<Self, FetchError> {
+        let inner = PostgresAdapter::connect(host, port, database, user, password).await?;
+        Ok(Self { inner })
+    }
+
+    /// Create adapter without redshift feature (returns error)
+    #[cfg(not(feature = "redshift"))]
+    pub async fn connect(
+        _host: impl Into<String>,
+        _port: u16,
+        _database: impl Into<String>,
+        _user: impl Into<String>,
+        _password: impl Into<String>,
+    ) -> Result<Self, FetchError> {
+        Err(FetchError::ConfigError(
+            "Redshift support not compiled. Rebuild with: cargo build --features redshift".to_string()
+        ))
+    }
+
+    /// Connect to a Redshift cluster over TLS
+    ///
+    /// Redshift clusters that set `require_ssl` reject plain connections.
+    #[cfg(feature = "redshift")]
+    pub async fn connect_with_tls(
+        host: impl Into<String>,
+        port: u16,
+        database: impl Into<String>,
+        user: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Result<Self, FetchError> {
+        let inner = PostgresAdapter::connect_with_tls(host, port, database, user, password).await?;
+        Ok(Self { inner })
+    }
+
+    /// Create adapter without redshift feature (returns error)
+    #[cfg(not(feature = "redshift"))]
+    pub async fn connect_with_tls(
+        _host: impl Into<String>,
+        _port: u16,
+        _database: impl Into<String>,
+        _user: impl Into<String>,
+        _password: impl Into<String>,
+    ) -> Result<Self, FetchError> {
+        Err(FetchError::ConfigError(
+            "Redshift support not compiled. Rebuild with: cargo build --features redshift".to_string()
+        ))
+    }
+
+    /// Register a normalizer applied to warehouse type names before mapping
+    pub fn with_type_normalizer(self, normalizer: impl TypeNormalizer + 'static) -> Self {
+        Self { inner: self.inner.with_type_normalizer(normalizer) }
+    }
+
+    /// Map a warehouse type name to a LogicalType, normalizing it first
+    pub fn map_type(&self, data_type: &str) -> LogicalType {
+        Self::map_redshift_type(&self.inner.type_normalizer().normalize(data_type))
+    }
+
+    /// Convert Redshift type to LogicalType
+    ///
+    /// Handles Redshift-specific types and falls back to the PostgreSQL
+    /// mapping for the types the two share.
+    ///
+    /// # Redshift-specific Types
+    ///
+    /// - **Semi-structured**: `super` (mapped to Json)
+    /// - **String**: `varchar(max)`, `character varying(65535)`, `nvarchar`, `nchar`, `bpchar`
+    /// - **Binary**: `varbyte`, `varbinary`, `binary varying`
+    /// - **Spatial**: `geometry`, `geography`
+    /// - **Sketches**: `hllsketch`
+    pub fn map_redshift_type(rs_type: &str) -> LogicalType {
+        let base_type = rs_type.split('(').next()
+            .unwrap_or(rs_type)
+            .trim()
+            .to_lowercase();
+
+        match base_type.as_str() {
+            // Semi-structured data (PartiQL)
+            "super" => LogicalType::Json,
+
+            // Strings, including VARCHAR(MAX) and the N* aliases
+            "varchar" | "character varying" | "nvarchar" | "text" => LogicalType::String,
+            "char" | "character" | "nchar" | "bpchar" => LogicalType::String,
+
+            // Binary data
+            "varbyte" | "varbinary" | "binary varying" => LogicalType::String,
+
+            // Spatial and sketch types
+            "geometry" | "geography" | "hllsketch" => LogicalType::String,
+
+            // Everything else (integers, numerics, dates, booleans) matches PostgreSQL
+            _ => PostgresAdapter::map_postgres_type(rs_type),
+        }
+    }
+
+    /// Get the connection host
+    pub fn host(&self) -> &str {
+        self.inner.host()
+    }
+
+    /// Get the connection port
+    pub fn port(&self) -> u16 {
+        self.inner.port()
+    }
+
+    /// Get the database name
+    pub fn database(&self) -> &str {
+        self.inner.database()
+    }
+}
+
+#[async_trait::async_trait]
+impl WarehouseAdapter for RedshiftAdapter {
+    fn name(&self) -> &'static str {
+        "Redshift"
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities {
+            supports_nested_types: false,
+            supports_constraints: true,
+            supports_list_tables: false,
+            supports_view_defs: false,
+            supports_batch_fetch: false,
+        }
+    }
+
+    #[cfg(feature = "redshift")]
+    async fn fetch_schema(&self, table: &TableIdentifier) -> Result<Schema, FetchError> {
+        // SVV_COLUMNS also lists late-binding view columns, which
+        // information_schema.columns omits. Casts keep the row types stable
+        // across regular and external tables.
+        let query = r#"
+            SELECT
+                column_name::varchar,
+                data_type::varchar,
+                is_nullable::varchar,
+                ordinal_position::int,
+                numeric_precision::int,
+                numeric_scale::int,
+                character_maximum_length::int,
+                column_default::varchar
+            FROM svv_columns
+            WHERE table_catalog = $1
+              AND table_schema = $2
+              AND table_name = $3
+            ORDER BY ordinal_position
+        "#;
+
+        let rows = self.inner.client()
+            .query(query, &[&table.database, &table.schema, &table.table])
+            .await
+            .map_err(|e| {
+                let err_str = e.to_string();
+                if err_str.contains("does not exist") {
+                    FetchError::TableNotFound(table.fqn())
+                } else if err_str.contains("permission denied") {
+                    FetchError::PermissionDenied(format!(
+                        "Cannot access {}: {}",
+                        table.fqn(), err_str
+                    ))
+                } else {
+                    FetchError::QueryError(err_str)
+                }
+            })?;
+
+        let mut columns = Vec::new();
+
+        for row in rows {
+            let col_name: String = row.get(0);
+            let data_type: String = row.get(1);
+            let is_nullable: Option<String> = row.get(2);
+            let numeric_precision: Option<i32> = row.get(4);
+            let numeric_scale: Option<i32> = row.get(5);
+            let column_default = row.get::<_, Option<String>>(7)
+                .and_then(|d| PostgresAdapter::normalize_default(&d));
+
+            // Build full type string for numeric types with precision/scale
+            let full_type = if data_type == "numeric" || data_type == "decimal" {
+                match (numeric_precision, numeric_scale) {
+                    (Some(p), Some(s)) => format!("numeric({},{})", p, s),
+                    (Some(p), None) => format!("numeric({})", p),
+                    _ => data_type.clone(),
+                }
+            } else {
+                data_type.clone()
+            };
+
+            let logical_type = self.map_type(&full_type);
+            let nullable = match is_nullable.as_deref().map(str::to_uppercase).as_deref() {
+                Some("YES") => Nullability::Yes,
+                Some("NO") => Nullability::No,
+                _ => Nullability::Unknown,
+            };
+
+            let column = Column::new(col_name, logical_type)
+                .with_nullability(nullable)
+                .with_time_zone_of(&full_type)
+                .with_warehouse_type(full_type);
+            columns.push(match column_default {
+                Some(default) => column.with_default(default),
+                None => column.with_has_default(false),
+            });
+        }
+
+        if columns.is_empty() {
+            return Err(FetchError::TableNotFound(format!(
+                "Table {} not found or has no columns",
+                table.fqn()
+            )));
+        }
+
+        Ok(Schema::from_columns(columns))
+    }
+
+    #[cfg(not(feature = "redshift"))]
+    async fn fetch_schema(&self, _table: &TableIdentifier) -> Result<Schema, FetchError> {
+        Err(FetchError::ConfigError(
+            "Redshift support not compiled. Rebuild with: cargo build --features redshift".to_string()
+        ))
+    }
+
+    async fn test_connection(&self) -> Result<(), FetchError> {
+        self.inner.test_connection().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_super_type_mapping() {
+        assert!(matches!(RedshiftAdapter::map_redshift_type("super"), LogicalType::Json));
+        assert!(matches!(RedshiftAdapter::map_redshift_type("SUPER"), LogicalType::Json));
+    }
+
+    #[test]
+    fn test_string_type_mapping() {
+        assert!(matches!(RedshiftAdapter::map_redshift_type("varchar(max)"), LogicalType::String));
+        assert!(matches!(RedshiftAdapter::map_redshift_type("VARCHAR(MAX)"), LogicalType::String));
+        assert!(matches!(RedshiftAdapter::map_redshift_type("character varying(65535)"), LogicalType::String));
+        assert!(matches!(RedshiftAdapter::map_redshift_type("nvarchar(256)"), LogicalType::String));
+        assert!(matches!(RedshiftAdapter::map_redshift_type("bpchar"), LogicalType::String));
+        assert!(matches!(RedshiftAdapter::map_redshift_type("varbyte(64)"), LogicalType::String));
+        assert!(matches!(RedshiftAdapter::map_redshift_type("geometry"), LogicalType::String));
+    }
+
+    #[test]
+    fn test_shared_postgres_type_mapping() {
+        assert!(matches!(RedshiftAdapter::map_redshift_type("integer"), LogicalType::Int));
+        assert!(matches!(RedshiftAdapter::map_redshift_type("bigint"), LogicalType::Int));
+        assert!(matches!(RedshiftAdapter::map_redshift_type("boolean"), LogicalType::Bool));
+        assert!(matches!(RedshiftAdapter::map_redshift_type("timestamp without time zone"), LogicalType::Timestamp));
+
+        match RedshiftAdapter::map_redshift_type("numeric(18,4)") {
+            LogicalType::Decimal { precision, scale } => {
+                assert_eq!(precision, Some(18));
+                assert_eq!(scale, Some(4));
+            }
+            _ => panic!("Expected Decimal type"),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Retry wrapper for warehouse adapters
+//!
+//! `RetryingAdapter` wraps any `WarehouseAdapter` and retries transient
+//! failures (`RateLimited`, `NetworkError`). A rate-limit error carrying a
+//! server-provided `retry_after` is honored exactly; otherwise the delay grows
+//! exponentially from `base_delay`.
+//!
+//! ```rust,ignore
+//! use schemarefly_catalog::{RetryingAdapter, MockAdapter};
+//!
+//! let adapter = RetryingAdapter::new(MockAdapter::new()).with_max_attempts(5);
+//! ```
+
+use crate::adapter::{WarehouseAdapter, AdapterCapabilities, TableIdentifier, FetchError};
+use schemarefly_core::Schema;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Default number of attempts per request (including the first)
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Default delay before the first retry when the server gives no hint
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Adapter wrapper that retries transient fetch failures
+pub struct RetryingAdapter<A> {
+    /// Wrapped adapter
+    inner: A,
+
+    /// Attempts per request, including the first
+    max_attempts: u32,
+
+    /// Backoff before the first retry; doubled on each further retry
+    base_delay: Duration,
+}
+
+impl<A: WarehouseAdapter> RetryingAdapter<A> {
+    /// Wrap an adapter with the default retry policy
+    pub fn new(inner: A) -> Self {
+        Self {
+            inner,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+        }
+    }
+
+    /// Set the number of attempts per request (at least 1)
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the backoff used when the server provides no `retry_after`
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// The wrapped adapter
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Delay before retry number `retry` (1-based) after `error`
+    pub fn delay_for(&self, error: &FetchError, retry: u32) -> Duration {
+        error
+            .retry_after()
+            .unwrap_or_else(|| self.base_delay.saturating_mul(2u32.saturating_pow(retry - 1)))
+    }
+}
+
+#[async_trait::async_trait]
+impl<A: WarehouseAdapter> WarehouseAdapter for RetryingAdapter<A> {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn fetch_schema(&self, table: &TableIdentifier) -> Result<Schema, FetchError> {
+        let mut attempt = 1;
+        loop {
+            match self.inner.fetch_schema(table).await {
+                Err(e) if e.is_transient() && attempt < self.max_attempts => {
+                    tokio::time::sleep(self.delay_for(&e, attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn fetch_schemas(&self, tables: &[TableIdentifier]) -> Result<HashMap<TableIdentifier, Schema>, FetchError> {
+        let mut attempt = 1;
+        loop {
+            match self.inner.fetch_schemas(tables).await {
+                Err(e) if e.is_transient() && attempt < self.max_attempts => {
+                    tokio::time::sleep(self.delay_for(&e, attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn fetch_view_definition(&self, table: &TableIdentifier) -> Result<Option<String>, FetchError> {
+        let mut attempt = 1;
+        loop {
+            match self.inner.fetch_view_definition(table).await {
+                Err(e) if e.is_transient() && attempt < self.max_attempts => {
+                    tokio::time::sleep(self.delay_for(&e, attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn test_connection(&self) -> Result<(), FetchError> {
+        self.inner.test_connection().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockAdapter;
+    use schemarefly_core::{Column, LogicalType};
+
+    fn table() -> TableIdentifier {
+        TableIdentifier::new("db", "schema", "flaky")
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures() {
+        let mock = MockAdapter::new();
+        mock.add_transient_failures(table(), 2).await;
+        mock.add_schema(table(), Schema::from_columns(vec![Column::new("id", LogicalType::Int)])).await;
+
+        let adapter = RetryingAdapter::new(mock).with_base_delay(Duration::from_millis(1));
+        let schema = adapter.fetch_schema(&table()).await.unwrap();
+
+        assert_eq!(schema.columns.len(), 1);
+        assert_eq!(adapter.inner().remaining_transient_failures(&table()).await, 0);
+    }
+
+    #[tokio::test]
+    async fn retries_batched_fetches() {
+        let mock = MockAdapter::new();
+        mock.add_transient_failures(table(), 1).await;
+        mock.add_schema(table(), Schema::from_columns(vec![Column::new("id", LogicalType::Int)])).await;
+
+        let adapter = RetryingAdapter::new(mock).with_base_delay(Duration::from_millis(1));
+        let missing = TableIdentifier::new("db", "schema", "missing");
+        let schemas = adapter.fetch_schemas(&[table(), missing.clone()]).await.unwrap();
+
+        assert_eq!(schemas[&table()].columns.len(), 1);
+        assert!(!schemas.contains_key(&missing));
+    }
+
+    #[tokio::test]
+    async fn honors_retry_after() {
+        let mock = MockAdapter::new();
+        mock.add_rate_limit(table(), 1, Some(Duration::from_millis(20))).await;
+        mock.add_schema(table(), Schema::from_columns(vec![Column::new("id", LogicalType::Int)])).await;
+
+        // A base delay this long would stall the test if retry_after were ignored
+        let adapter = RetryingAdapter::new(mock).with_base_delay(Duration::from_secs(60));
+
+        let started = std::time::Instant::now();
+        assert!(adapter.fetch_schema(&table()).await.is_ok());
+        let elapsed = started.elapsed();
+
+        assert!(elapsed >= Duration::from_millis(20));
+        assert!(elapsed < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let mock = MockAdapter::new();
+        mock.add_rate_limit(table(), 5, None).await;
+        let adapter = RetryingAdapter::new(mock)
+            .with_max_attempts(2)
+            .with_base_delay(Duration::from_millis(1));
+
+        let result = adapter.fetch_schema(&table()).await;
+
+        assert!(matches!(result, Err(FetchError::RateLimited { retry_after: None })));
+        assert_eq!(adapter.inner().remaining_transient_failures(&table()).await, 3);
+    }
+
+    #[test]
+    fn backoff_doubles_without_hint() {
+        let adapter = RetryingAdapter::new(MockAdapter::new()).with_base_delay(Duration::from_millis(100));
+        let error = FetchError::NetworkError("connection reset".to_string());
+
+        assert_eq!(adapter.delay_for(&error, 1), Duration::from_millis(100));
+        assert_eq!(adapter.delay_for(&error, 3), Duration::from_millis(400));
+        assert_eq!(
+            adapter.delay_for(&FetchError::rate_limited(Some(Duration::from_secs(7))), 3),
+            Duration::from_secs(7)
+        );
+    }
+}
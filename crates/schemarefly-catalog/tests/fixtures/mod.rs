@@ -4,6 +4,8 @@
 //! adapters and drift detection. These fixtures represent common table
 //! structures found in real-world data warehouses.
 
+#![allow(dead_code)]
+
 use schemarefly_core::{Column, LogicalType, Nullability, Schema};
 
 /// Create a typical users table schema
@@ -341,7 +341,7 @@ async fn test_drift_detection_no_drift() {
     adapter.add_schema(table.clone(), actual).await;
 
     let fetched = adapter.fetch_schema(&table).await.unwrap();
-    let drift = DriftDetection::detect(&table.fqn(), &expected, &fetched, None);
+    let drift = DriftDetection::detect(table.fqn(), &expected, &fetched, None);
 
     assert!(!drift.has_errors());
     assert!(!drift.has_warnings());
@@ -369,7 +369,7 @@ async fn test_drift_detection_dropped_column() {
     adapter.add_schema(table.clone(), actual).await;
 
     let fetched = adapter.fetch_schema(&table).await.unwrap();
-    let drift = DriftDetection::detect(&table.fqn(), &expected, &fetched, None);
+    let drift = DriftDetection::detect(table.fqn(), &expected, &fetched, None);
 
     assert!(drift.has_errors());
     assert_eq!(drift.error_count(), 1);
@@ -402,7 +402,7 @@ async fn test_drift_detection_type_change() {
     adapter.add_schema(table.clone(), actual).await;
 
     let fetched = adapter.fetch_schema(&table).await.unwrap();
-    let drift = DriftDetection::detect(&table.fqn(), &expected, &fetched, None);
+    let drift = DriftDetection::detect(table.fqn(), &expected, &fetched, None);
 
     assert!(drift.has_errors());
     assert_eq!(drift.error_count(), 1);
@@ -436,7 +436,7 @@ async fn test_drift_detection_new_column() {
     adapter.add_schema(table.clone(), actual).await;
 
     let fetched = adapter.fetch_schema(&table).await.unwrap();
-    let drift = DriftDetection::detect(&table.fqn(), &expected, &fetched, None);
+    let drift = DriftDetection::detect(table.fqn(), &expected, &fetched, None);
 
     assert!(!drift.has_errors()); // New columns are info, not errors
     assert!(drift.has_info());
@@ -470,7 +470,7 @@ async fn test_drift_detection_multiple_issues() {
     adapter.add_schema(table.clone(), actual).await;
 
     let fetched = adapter.fetch_schema(&table).await.unwrap();
-    let drift = DriftDetection::detect(&table.fqn(), &expected, &fetched, None);
+    let drift = DriftDetection::detect(table.fqn(), &expected, &fetched, None);
 
     assert!(drift.has_errors());
     assert!(drift.has_info());
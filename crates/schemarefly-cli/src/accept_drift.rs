@@ -0,0 +1,456 @@
+//! Reconcile contract YAML with accepted warehouse drift
+//!
+//! `schemarefly accept-drift` reads a drift report, maps each drift diagnostic
+//! back to its model (through the diagnostic's SQL file location) and rewrites
+//! the model's columns in its properties YAML to match the warehouse: data
+//! types are updated, new columns appended and dropped columns removed.
+//! Nullability drift is reported but left for manual review.
+//!
+//! Columns and types come from the diagnostics' `column` and `warehouse_type`
+//! fields, never from their messages. A report written with
+//! `[drift] summary = "instead"` has no per-column findings and is refused.
+
+use schemarefly_core::{Diagnostic, DiagnosticCode, Report, Severity};
+use schemarefly_dbt::Manifest;
+use std::path::{Path, PathBuf};
+
+use crate::contract_column_yaml;
+
+/// A single edit to a model's contract columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContractChange {
+    /// Change a column's `data_type`
+    SetType { column: String, data_type: String },
+
+    /// Append a new column
+    AddColumn { column: String, data_type: String },
+
+    /// Remove a column
+    RemoveColumn { column: String },
+}
+
+impl ContractChange {
+    /// Derive the contract edit that accepts a drift diagnostic
+    ///
+    /// Errors with the reason when the drift cannot be accepted automatically.
+    pub fn from_diagnostic(diag: &Diagnostic) -> Result<Self, &'static str> {
+        let column = || diag.column.clone().ok_or("no column in the report; re-run drift");
+        let data_type = || diag.warehouse_type.clone().ok_or("warehouse type unknown; must be reconciled by hand");
+
+        match diag.code {
+            DiagnosticCode::DriftTypeChange => Ok(Self::SetType { column: column()?, data_type: data_type()? }),
+            DiagnosticCode::DriftColumnAdded => Ok(Self::AddColumn { column: column()?, data_type: data_type()? }),
+            DiagnosticCode::DriftColumnDropped => Ok(Self::RemoveColumn { column: column()? }),
+            _ => Err("must be reconciled by hand"),
+        }
+    }
+
+    /// Column the change applies to
+    pub fn column(&self) -> &str {
+        match self {
+            Self::SetType { column, .. } | Self::AddColumn { column, .. } | Self::RemoveColumn { column } => column,
+        }
+    }
+
+    /// Short description for the dry-run summary
+    pub fn describe(&self) -> String {
+        match self {
+            Self::SetType { column, data_type } => format!("set '{}' data_type to {}", column, data_type),
+            Self::AddColumn { column, data_type } => format!("add column '{}' ({})", column, data_type),
+            Self::RemoveColumn { column } => format!("remove column '{}'", column),
+        }
+    }
+}
+
+/// Contract edits for one model
+#[derive(Debug, Clone)]
+pub struct ModelReconciliation {
+    /// Model name (as written in the YAML `- name:` entry)
+    pub model_name: String,
+
+    /// Project-relative path of the model's properties YAML
+    pub yaml_path: PathBuf,
+
+    /// Edits to apply, in report order
+    pub changes: Vec<ContractChange>,
+}
+
+/// Edits to apply, plus drift that could not be accepted automatically
+#[derive(Debug, Clone, Default)]
+pub struct ReconciliationPlan {
+    /// Models whose contract YAML will be rewritten
+    pub models: Vec<ModelReconciliation>,
+
+    /// Human-readable reasons for drift left untouched
+    pub skipped: Vec<String>,
+}
+
+impl ReconciliationPlan {
+    /// Plan contract edits for the drift diagnostics in a report
+    ///
+    /// `Error`-severity drift (dropped columns, type changes, stricter
+    /// nullability) is only accepted with `include_breaking`. Fails for a
+    /// report whose drift was replaced by per-table summaries.
+    pub fn from_report(report: &Report, manifest: &Manifest, include_breaking: bool) -> Result<Self, String> {
+        let is_drift = |diag: &&Diagnostic| {
+            matches!(
+                diag.code,
+                DiagnosticCode::DriftTypeChange
+                    | DiagnosticCode::DriftColumnAdded
+                    | DiagnosticCode::DriftColumnDropped
+                    | DiagnosticCode::DriftNullabilityChange
+            )
+        };
+
+        // Summaries without the findings behind them come from `summary = "instead"`
+        let has_summaries = report.diagnostics.iter().any(|d| d.code == DiagnosticCode::DriftSummary);
+        if has_summaries && !report.diagnostics.iter().any(|d| is_drift(&d)) {
+            return Err(
+                "the report only has per-table drift summaries ([drift] summary = \"instead\"); \
+                 re-run drift with summary = \"off\" or \"alongside\" to accept its findings"
+                    .to_string(),
+            );
+        }
+
+        let mut plan = Self::default();
+        let models = manifest.models();
+
+        for diag in report.diagnostics.iter().filter(is_drift) {
+
+            if diag.severity == Severity::Error && !include_breaking {
+                plan.skipped.push(format!("{} (breaking; pass --include-breaking to accept)", diag.message));
+                continue;
+            }
+
+            let change = match ContractChange::from_diagnostic(diag) {
+                Ok(change) => change,
+                Err(reason) => {
+                    plan.skipped.push(format!("{} ({})", diag.message, reason));
+                    continue;
+                }
+            };
+
+            let file = diag.location.as_ref().map(|l| l.file.as_str());
+            let node = models.values().find(|node| Some(node.original_file_path.as_str()) == file);
+            let Some(node) = node else {
+                plan.skipped.push(format!("{} (no model found for {})", diag.message, file.unwrap_or("unknown file")));
+                continue;
+            };
+            let Some(yaml_path) = node.patch_file() else {
+                plan.skipped.push(format!("{} (model '{}' has no properties YAML)", diag.message, node.name));
+                continue;
+            };
+
+            match plan.models.iter_mut().find(|m| m.model_name == node.name) {
+                Some(model) => model.changes.push(change),
+                None => plan.models.push(ModelReconciliation {
+                    model_name: node.name.clone(),
+                    yaml_path: PathBuf::from(yaml_path),
+                    changes: vec![change],
+                }),
+            }
+        }
+
+        Ok(plan)
+    }
+
+    /// Whether there is nothing to apply
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+}
+
+/// Apply a model's changes to its YAML file
+///
+/// Returns a line diff of the edit (empty if nothing changed). The file is
+/// only rewritten when `write` is set.
+pub fn reconcile_file(path: &Path, model: &ModelReconciliation, write: bool) -> Result<String, String> {
+    let original = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let updated = apply_changes(&original, &model.model_name, &model.changes)?;
+
+    if write && updated != original {
+        std::fs::write(path, &updated)
+            .map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+    }
+
+    Ok(line_diff(&original, &updated))
+}
+
+/// Apply contract changes to a model's entry in properties YAML
+pub fn apply_changes(yaml: &str, model: &str, changes: &[ContractChange]) -> Result<String, String> {
+    let mut lines: Vec<String> = yaml.lines().map(str::to_string).collect();
+
+    for change in changes {
+        apply_change(&mut lines, model, change)?;
+    }
+
+    let mut out = lines.join("\n");
+    if yaml.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn apply_change(lines: &mut Vec<String>, model: &str, change: &ContractChange) -> Result<(), String> {
+    let model_idx = lines
+        .iter()
+        .position(|l| entry_name(l).as_deref() == Some(model))
+        .ok_or_else(|| format!("Model '{}' not found in YAML", model))?;
+    let model_end = block_end(lines, model_idx, indent_of(&lines[model_idx]));
+
+    let columns_idx = (model_idx + 1..model_end)
+        .find(|&i| lines[i].trim() == "columns:")
+        .ok_or_else(|| format!("Model '{}' has no columns list", model))?;
+    let columns_end = block_end(lines, columns_idx, indent_of(&lines[columns_idx]));
+
+    // Column entries are the `- name:` items directly under `columns:`
+    let entry_indent = (columns_idx + 1..columns_end)
+        .find(|&i| lines[i].trim_start().starts_with("- "))
+        .map(|i| indent_of(&lines[i]))
+        .unwrap_or(indent_of(&lines[columns_idx]) + 2);
+    let entry = (columns_idx + 1..columns_end).find(|&i| {
+        indent_of(&lines[i]) == entry_indent
+            && entry_name(&lines[i]).is_some_and(|n| n.eq_ignore_ascii_case(change.column()))
+    });
+
+    match change {
+        ContractChange::SetType { column, data_type } => {
+            let idx = entry.ok_or_else(|| format!("Column '{}' not found in model '{}'", column, model))?;
+            let end = block_end(lines, idx, entry_indent);
+            let field_indent = " ".repeat(entry_indent + 2);
+
+            match (idx + 1..end).find(|&i| lines[i].trim_start().starts_with("data_type:")) {
+                Some(i) => {
+                    let indent = " ".repeat(indent_of(&lines[i]));
+                    lines[i] = format!("{}data_type: {}", indent, data_type);
+                }
+                None => lines.insert(idx + 1, format!("{}data_type: {}", field_indent, data_type)),
+            }
+        }
+        ContractChange::AddColumn { column, data_type } => {
+            if entry.is_some() {
+                return Ok(());
+            }
+
+            // Insert after the last non-blank line of the columns list
+            let mut insert_at = columns_end;
+            while insert_at > columns_idx + 1 && lines[insert_at - 1].trim().is_empty() {
+                insert_at -= 1;
+            }
+
+            let yaml = contract_column_yaml(&" ".repeat(entry_indent), column, data_type, None);
+            for (offset, line) in yaml.lines().enumerate() {
+                lines.insert(insert_at + offset, line.to_string());
+            }
+        }
+        ContractChange::RemoveColumn { .. } => {
+            if let Some(idx) = entry {
+                // Keep blank lines separating this list from what follows
+                let mut end = block_end(lines, idx, entry_indent);
+                while end > idx + 1 && lines[end - 1].trim().is_empty() {
+                    end -= 1;
+                }
+                lines.drain(idx..end);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Name of a `- name: x` list entry
+fn entry_name(line: &str) -> Option<String> {
+    let rest = line.trim_start().strip_prefix("- ")?.trim_start().strip_prefix("name:")?;
+    Some(rest.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Index after the last line belonging to the block that starts at `start`
+fn block_end(lines: &[String], start: usize, indent: usize) -> usize {
+    (start + 1..lines.len())
+        .find(|&i| !lines[i].trim().is_empty() && indent_of(&lines[i]) <= indent)
+        .unwrap_or(lines.len())
+}
+
+/// Minimal line diff (`-` removed, `+` added) with one line of context
+pub fn line_diff(old: &str, new: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence table
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<(char, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((' ', a[i]));
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            ops.push(('+', b[j]));
+            j += 1;
+        } else {
+            ops.push(('-', a[i]));
+            i += 1;
+        }
+    }
+
+    let changed: Vec<bool> = ops.iter().map(|(tag, _)| *tag != ' ').collect();
+    let near_change = |k: usize| {
+        changed[k]
+            || (k > 0 && changed[k - 1])
+            || changed.get(k + 1).copied().unwrap_or(false)
+    };
+
+    let mut out = String::new();
+    let mut gap = false;
+    for (k, (tag, line)) in ops.iter().enumerate() {
+        if near_change(k) {
+            if gap && !out.is_empty() {
+                out.push_str("...\n");
+            }
+            gap = false;
+            out.push_str(&format!("{} {}\n", tag, line));
+        } else {
+            gap = true;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{test_manifest, test_model};
+    use schemarefly_core::{Column, LogicalType, Schema};
+    use schemarefly_engine::DriftDetection;
+
+    const SCHEMA_YML: &str = "version: 2\n\nmodels:\n  - name: orders\n    config:\n      contract:\n        enforced: true\n    columns:\n      - name: id\n        data_type: int\n        description: \"Primary key\"\n      - name: amount\n        data_type: int\n      - name: legacy_flag\n        data_type: bool\n\n  - name: users\n    columns:\n      - name: id\n        data_type: int\n";
+
+    #[test]
+    fn accepts_type_change_in_contract_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schema.yml");
+        std::fs::write(&path, SCHEMA_YML).unwrap();
+
+        let model = ModelReconciliation {
+            model_name: "orders".to_string(),
+            yaml_path: PathBuf::from("schema.yml"),
+            changes: vec![ContractChange::SetType {
+                column: "amount".to_string(),
+                data_type: "decimal(10, 2)".to_string(),
+            }],
+        };
+
+        // Dry run leaves the file alone
+        let diff = reconcile_file(&path, &model, false).unwrap();
+        assert!(diff.contains("-         data_type: int"));
+        assert!(diff.contains("+         data_type: decimal(10, 2)"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), SCHEMA_YML);
+
+        reconcile_file(&path, &model, true).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("      - name: amount\n        data_type: decimal(10, 2)\n"));
+        // Other models are untouched
+        assert!(written.ends_with("  - name: users\n    columns:\n      - name: id\n        data_type: int\n"));
+    }
+
+    #[test]
+    fn adds_and_removes_columns() {
+        let changes = vec![
+            ContractChange::RemoveColumn { column: "legacy_flag".to_string() },
+            ContractChange::AddColumn { column: "created_at".to_string(), data_type: "timestamp".to_string() },
+        ];
+
+        let updated = apply_changes(SCHEMA_YML, "orders", &changes).unwrap();
+
+        assert!(!updated.contains("legacy_flag"));
+        assert!(updated.contains(
+            "      - name: amount\n        data_type: int\n      - name: created_at\n        data_type: timestamp\n\n  - name: users"
+        ));
+    }
+
+    /// Detector findings for a contract of `id INT, amount INT, legacy_flag BOOLEAN` against a
+    /// warehouse that retyped `amount`, dropped `legacy_flag` and added `total`
+    fn detected_drift() -> DriftDetection {
+        let expected = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("amount", LogicalType::Int),
+            Column::new("legacy_flag", LogicalType::Bool),
+        ]);
+        let decimal = |precision, scale| LogicalType::Decimal { precision: Some(precision), scale: Some(scale) };
+        let actual = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int).with_warehouse_type("NUMBER(38,0)"),
+            Column::new("amount", decimal(10, 2)).with_warehouse_type("NUMBER(10,2)"),
+            Column::new("total", decimal(12, 2)).with_warehouse_type("NUMBER(12,2)"),
+        ]);
+        DriftDetection::detect("db.analytics.orders", &expected, &actual, Some("models/orders.sql".to_string()))
+    }
+
+    #[test]
+    fn changes_from_drift_diagnostics() {
+        let changes: Vec<ContractChange> = detected_drift()
+            .diagnostics
+            .iter()
+            .filter_map(|d| ContractChange::from_diagnostic(d).ok())
+            .collect();
+
+        // Warehouse types are written as the warehouse declares them
+        assert_eq!(
+            changes,
+            vec![
+                ContractChange::SetType { column: "amount".to_string(), data_type: "NUMBER(10,2)".to_string() },
+                ContractChange::RemoveColumn { column: "legacy_flag".to_string() },
+                ContractChange::AddColumn { column: "total".to_string(), data_type: "NUMBER(12,2)".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn changes_do_not_depend_on_message_wording() {
+        let drift = detected_drift();
+        let reworded: Vec<Diagnostic> = drift
+            .diagnostics
+            .iter()
+            .map(|d| Diagnostic { message: "reworded".to_string(), ..d.clone() })
+            .collect();
+
+        let changes = |diagnostics: &[Diagnostic]| -> Vec<_> {
+            diagnostics.iter().map(ContractChange::from_diagnostic).collect()
+        };
+        assert_eq!(changes(&reworded), changes(&drift.diagnostics));
+
+        // Without a warehouse type there is nothing to write
+        let untyped = Diagnostic { warehouse_type: None, ..drift.diagnostics[0].clone() };
+        assert!(ContractChange::from_diagnostic(&untyped).is_err());
+    }
+
+    #[test]
+    fn summary_only_report_is_refused() {
+        let drift = detected_drift();
+        let manifest = test_manifest(&[test_model("orders")]);
+
+        let instead = Report::from_diagnostics(vec![drift.replacement_summary()]);
+        let err = ReconciliationPlan::from_report(&instead, &manifest, true).unwrap_err();
+        assert!(err.contains("summary = \"instead\""), "{err}");
+
+        let mut alongside = drift.diagnostics.clone();
+        alongside.push(drift.summary());
+        assert!(ReconciliationPlan::from_report(&Report::from_diagnostics(alongside), &manifest, true).is_ok());
+    }
+}
@@ -1,23 +1,54 @@
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 use anyhow::Result;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
 
-use schemarefly_core::{Report, Config, Diagnostic, DialectConfig};
+use schemarefly_core::{Report, Config, Diagnostic, DiagnosticCode, DialectConfig, Suppression, Schema, SchemaSnapshot, SCHEMA_SNAPSHOT_VERSION};
+use schemarefly_core::config::{DriftSummaryMode, WarehouseKind};
 use schemarefly_dbt::{Manifest, DependencyGraph, ContractExtractor};
-use schemarefly_engine::{DriftDetection, StateComparison, StateComparisonResult};
+use schemarefly_engine::{ChangedModels, CompareOptions, ContractPolicy, DriftDetection, FingerprintCache, MetaPolicy, StateComparison, StateComparisonResult, UnitTestCheck, VersionDiff, detect_view_definition_drift};
 use schemarefly_sql::DbtFunctionExtractor;
-use schemarefly_catalog::{WarehouseAdapter, TableIdentifier, BigQueryAdapter, SnowflakeAdapterBuilder, PostgresAdapter};
+use schemarefly_incremental::{CacheStats, WarehouseCache};
+use schemarefly_catalog::{WarehouseAdapter, TableIdentifier, BigQueryAdapter, SnowflakeAdapterBuilder, PostgresAdapter, RedshiftAdapter, RetryingAdapter, TypeAliases, EnvCredentialProvider, resolve_secrets};
+
+mod accept_drift;
+mod manifest_locator;
+mod output;
+mod panic_boundary;
+mod preflight;
+mod profile;
+mod refs;
+
+use manifest_locator::{ManifestLocateError, load_manifest_json, read_manifest};
+use output::{MarkdownWriter, OutputFormat, write_report, writer_for};
 
 /// SchemaRefly - Schema contract verification for dbt
 #[derive(Parser)]
 #[command(name = "schemarefly")]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// Path to config file (default: schemarefly.toml)
+    /// Path to config file (default: nearest schemarefly.toml in this or a parent directory)
     #[arg(short, long, global = true)]
     config: Option<PathBuf>,
 
+    /// dbt target directory holding manifest.json (overrides config and DBT_TARGET_PATH)
+    #[arg(long, global = true, value_name = "DIR")]
+    target_path: Option<PathBuf>,
+
+    /// Only fail on errors with this diagnostic code (repeatable; overrides config `fail_on_codes`)
+    #[arg(long = "fail-on", global = true, value_name = "CODE", value_parser = parse_diagnostic_code)]
+    fail_on: Vec<DiagnosticCode>,
+
+    /// Apply the config's `[env.NAME]` overlay (severity overrides, fail_on_codes, strict)
+    #[arg(long = "env", global = true, value_name = "NAME")]
+    env: Option<String>,
+
+    /// dbt variables for `var()`, as JSON or key=value pairs (repeatable; overrides config `[vars]`)
+    #[arg(long, global = true, value_name = "VARS", value_parser = parse_vars_arg)]
+    vars: Vec<HashMap<String, serde_json::Value>>,
+
     /// Enable verbose output
     #[arg(short, long, global = true)]
     verbose: bool,
@@ -38,6 +69,10 @@ enum Commands {
         #[arg(short, long)]
         markdown: Option<PathBuf>,
 
+        /// Also output diagnostics as CSV (one row per diagnostic)
+        #[arg(long, value_name = "PATH")]
+        csv: Option<PathBuf>,
+
         /// Path to production state manifest (for Slim CI mode)
         /// Compares current manifest against this state to find modified models
         #[arg(long, value_name = "PATH")]
@@ -52,6 +87,30 @@ enum Commands {
         /// Includes collapsible details and summary badge
         #[arg(long)]
         pr_comment: bool,
+
+        /// Only check models changed since this git ref (plus their downstream)
+        #[arg(long, value_name = "GIT_REF")]
+        since: Option<String>,
+
+        /// Output format for the summary printed to stdout
+        #[arg(long, value_enum, default_value = "human")]
+        format: OutputFormat,
+
+        /// Print wall time per phase and the slowest models to stderr
+        #[arg(long)]
+        profile: bool,
+
+        /// Parse every selected model before inference and report all parse errors up front
+        #[arg(long)]
+        preflight: bool,
+
+        /// With --preflight, stop before inference when any model fails to parse
+        #[arg(long, requires = "preflight")]
+        fail_fast: bool,
+
+        /// Report the inferred schema of models without a contract (info diagnostics)
+        #[arg(long)]
+        emit_inferred: bool,
     },
 
     /// Initialize SchemaRefly in a dbt project
@@ -78,9 +137,157 @@ enum Commands {
         /// Model name to analyze (can be short name or unique_id)
         model: String,
 
-        /// Path to dbt manifest.json
-        #[arg(short = 'f', long, default_value = "target/manifest.json")]
-        manifest: PathBuf,
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
+    },
+
+    /// Explain how a model's dbt references resolve for schema inference
+    Explain {
+        /// Model name to explain (can be short name or unique_id)
+        model: String,
+
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
+    },
+
+    /// Write a self-contained bundle (SQL plus upstream schemas) that replays a model's inference
+    Repro {
+        /// Model name (can be short name or unique_id)
+        model: String,
+
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
+
+        /// Write the bundle here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Rename identifiers and string literals (always on with redact_sensitive_data)
+        #[arg(long)]
+        redact: bool,
+    },
+
+    /// Write the inferred schema of every model to a versioned snapshot file
+    Snapshot {
+        /// Snapshot file to write
+        #[arg(short, long, default_value = "schemas.json")]
+        output: PathBuf,
+
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
+    },
+
+    /// Report schema changes between two snapshots written by `schemarefly snapshot`
+    SnapshotDiff {
+        /// Older snapshot
+        old: PathBuf,
+
+        /// Newer snapshot
+        new: PathBuf,
+    },
+
+    /// Compare the contracts of two versions of a model
+    CompareVersions {
+        /// Versioned model name (e.g. users)
+        model: String,
+
+        /// Old version (e.g. v1 or 1)
+        #[arg(long)]
+        from: String,
+
+        /// New version (e.g. v2 or 2)
+        #[arg(long)]
+        to: String,
+
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
+    },
+
+    /// Export the dependency graph as Graphviz DOT or Mermaid
+    Graph {
+        /// Graph format
+        #[arg(long, value_enum, default_value = "dot")]
+        format: GraphFormat,
+
+        /// Limit to a model and its upstream (`+model`), downstream (`model+`) or both (`+model+`)
+        #[arg(long, value_name = "SELECTOR")]
+        select: Option<String>,
+
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
+
+        /// Write the graph here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Filter diagnostics in an existing report.json
+    Query {
+        /// Path to report.json
+        report: PathBuf,
+
+        /// Keep only these diagnostic codes (repeatable)
+        #[arg(long = "code")]
+        codes: Vec<String>,
+
+        /// Keep only these severities: error, warn, info (repeatable)
+        #[arg(long = "severity")]
+        severities: Vec<String>,
+
+        /// Keep only diagnostics whose file path matches this glob
+        #[arg(long)]
+        path: Option<String>,
+
+        /// Write the filtered report here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Check manifest.json for dangling dependencies, duplicate names and missing relations
+    ValidateManifest {
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
+
+        /// Also save the report to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Output format for the summary printed to stdout
+        #[arg(long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+
+    /// List every ref()/source() in model SQL and whether it resolves in the manifest
+    Refs {
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
+
+        /// Print the inventory as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check contract YAML for columns without a data_type, unknown types and duplicate columns
+    LintContracts {
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
+
+        /// Also save the report to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Output format for the summary printed to stdout
+        #[arg(long, value_enum, default_value = "human")]
+        format: OutputFormat,
     },
 
     /// Detect schema drift from warehouse
@@ -88,6 +295,33 @@ enum Commands {
         /// Output file for drift report
         #[arg(short, long, default_value = "drift-report.json")]
         output: PathBuf,
+
+        /// Compare every table, even those unchanged since the last clean run
+        #[arg(long)]
+        full: bool,
+
+        /// Print fingerprint and warehouse schema cache hit/miss counts to stderr
+        #[arg(long)]
+        cache_stats: bool,
+    },
+
+    /// Update contract YAML to accept drift from a drift report
+    AcceptDrift {
+        /// Drift report produced by `schemarefly drift`
+        #[arg(default_value = "drift-report.json")]
+        report: PathBuf,
+
+        /// Write the changes (default: dry run that prints the diff)
+        #[arg(long)]
+        write: bool,
+
+        /// Also accept breaking drift (dropped columns, type changes)
+        #[arg(long)]
+        include_breaking: bool,
+
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
     },
 
     /// Initialize contracts for existing models (generates YAML stubs)
@@ -99,9 +333,9 @@ enum Commands {
         #[arg(short, long, default_value = "contracts")]
         output_dir: PathBuf,
 
-        /// Path to dbt manifest.json
-        #[arg(short = 'f', long, default_value = "target/manifest.json")]
-        manifest: PathBuf,
+        /// Path to dbt manifest.json, or `-` for stdin (default: manifest.json in the dbt target directory)
+        #[arg(short = 'f', long)]
+        manifest: Option<PathBuf>,
 
         /// Path to catalog.json (optional, improves type inference)
         #[arg(long)]
@@ -117,15 +351,55 @@ enum Commands {
     },
 }
 
+/// Output format for `schemarefly graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum GraphFormat {
+    /// Graphviz `digraph`
+    Dot,
+
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+/// Parse a `--vars` value into dbt variables
+fn parse_vars_arg(value: &str) -> Result<HashMap<String, serde_json::Value>, String> {
+    schemarefly_core::config::parse_vars(value).map_err(|e| e.to_string())
+}
+
+/// Parse a `--fail-on` value into a diagnostic code
+fn parse_diagnostic_code(value: &str) -> Result<DiagnosticCode, String> {
+    DiagnosticCode::parse(value).ok_or_else(|| format!("Unknown diagnostic code '{}'", value))
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    // Manifest lookup failures get their own exit codes
+    if let Err(err) = run(cli).await {
+        if let Some(locate_err) = err.downcast_ref::<ManifestLocateError>() {
+            eprintln!("Error: {}", locate_err);
+            std::process::exit(locate_err.exit_code());
+        }
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+async fn run(cli: Cli) -> Result<()> {
+
     // Load config if specified
-    let config = if let Some(config_path) = &cli.config {
+    let mut config = if let Some(config_path) = &cli.config {
         Config::from_file(config_path)?
-    } else if std::path::Path::new("schemarefly.toml").exists() {
-        Config::from_file(std::path::Path::new("schemarefly.toml"))?
+    } else if let Some(config_path) = std::env::current_dir()
+        .ok()
+        .and_then(|dir| Config::find_config_file(&dir))
+    {
+        if cli.verbose {
+            eprintln!("{} {}", "Using config".cyan(), config_path.display());
+        }
+        Config::from_file(&config_path)?
     } else {
         if cli.verbose {
             eprintln!("{}", "No config file found, using defaults".yellow());
@@ -133,41 +407,110 @@ async fn main() -> Result<()> {
         Config::default()
     };
 
+    // Environment overlay first, so command-line flags still override it
+    if let Some(env) = &cli.env {
+        config.apply_env(env)?;
+        if cli.verbose {
+            eprintln!("{} [env.{}] overlay", "Using".cyan(), env);
+        }
+    }
+
+    if let Some(target_path) = cli.target_path {
+        config.target_path = Some(target_path);
+    }
+
+    if !cli.fail_on.is_empty() {
+        config.fail_on_codes = cli.fail_on;
+    }
+
+    config.vars.extend(cli.vars.into_iter().flatten());
+
     if cli.verbose {
         eprintln!("{} dialect: {:?}", "Using".cyan(), config.dialect);
     }
 
     match cli.command {
-        Commands::Check { output, markdown, state, modified_only, pr_comment } => {
-            check_command(&config, &output, markdown.as_deref(), state.as_ref(), modified_only, pr_comment, cli.verbose)
+        Commands::Check { output, markdown, csv, state, modified_only, pr_comment, since, format, profile, preflight, fail_fast, emit_inferred } => {
+            check_command(&config, &output, markdown.as_deref(), csv.as_deref(), state.as_ref(), modified_only, pr_comment, since.as_deref(), format, profile, preflight, fail_fast, emit_inferred, cli.verbose).await
         }
         Commands::Init { path, dialect, skip_workflow, force } => {
             init_command(path.as_ref(), &dialect, skip_workflow, force, cli.verbose)
         }
         Commands::Impact { model, manifest } => {
-            impact_command(&config, &model, &manifest, cli.verbose)
+            impact_command(&config, &model, &manifest.unwrap_or_else(|| config.manifest_path()), cli.verbose)
+        }
+        Commands::Explain { model, manifest } => {
+            explain_command(&config, &model, &manifest.unwrap_or_else(|| config.manifest_path()), cli.verbose)
+        }
+        Commands::Repro { model, manifest, output, redact } => {
+            repro_command(&config, &model, &manifest.unwrap_or_else(|| config.manifest_path()), output.as_deref(), redact, cli.verbose)
+        }
+        Commands::Snapshot { output, manifest } => {
+            snapshot_command(&config, &output, &manifest.unwrap_or_else(|| config.manifest_path()), cli.verbose)
+        }
+        Commands::SnapshotDiff { old, new } => {
+            snapshot_diff_command(&old, &new)
         }
-        Commands::Drift { output } => {
-            drift_command(&config, &output, cli.verbose).await
+        Commands::CompareVersions { model, from, to, manifest } => {
+            compare_versions_command(&config, &model, &from, &to, &manifest.unwrap_or_else(|| config.manifest_path()), cli.verbose)
+        }
+        Commands::Graph { format, select, manifest, output } => {
+            graph_command(&config, format, select.as_deref(), &manifest.unwrap_or_else(|| config.manifest_path()), output.as_deref(), cli.verbose)
+        }
+        Commands::Query { report, codes, severities, path, output } => {
+            query_command(&report, &codes, &severities, path.as_deref(), output.as_deref(), cli.verbose)
+        }
+        Commands::ValidateManifest { manifest, output, format } => {
+            validate_manifest_command(&config, &manifest.unwrap_or_else(|| config.manifest_path()), output.as_deref(), format, cli.verbose)
+        }
+        Commands::Refs { manifest, json } => {
+            refs_command(&config, &manifest.unwrap_or_else(|| config.manifest_path()), json, cli.verbose)
+        }
+        Commands::LintContracts { manifest, output, format } => {
+            lint_contracts_command(&config, &manifest.unwrap_or_else(|| config.manifest_path()), output.as_deref(), format, cli.verbose)
+        }
+        Commands::Drift { output, full, cache_stats } => {
+            // Ctrl-C stops the run after the model in flight; completed models are still reported
+            let cancel = CancellationToken::new();
+            let on_interrupt = cancel.clone();
+            tokio::spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    on_interrupt.cancel();
+                }
+            });
+            drift_command(&config, &output, full, cache_stats, cli.verbose, cancel).await
+        }
+        Commands::AcceptDrift { report, write, include_breaking, manifest } => {
+            accept_drift_command(&config, &report, &manifest.unwrap_or_else(|| config.manifest_path()), write, include_breaking, cli.verbose)
         }
         Commands::InitContracts { models, output_dir, manifest, catalog, force, enforced_only } => {
-            init_contracts_command(&config, &models, &output_dir, &manifest, catalog.as_ref(), force, enforced_only, cli.verbose)
+            init_contracts_command(&config, &models, &output_dir, &manifest.unwrap_or_else(|| config.manifest_path()), catalog.as_ref(), force, enforced_only, cli.verbose)
         }
     }
 }
 
 /// Check command - validate schema contracts (with Salsa incremental computation)
-fn check_command(
+#[allow(clippy::too_many_arguments)]
+async fn check_command(
     config: &Config,
     output: &Path,
     markdown: Option<&Path>,
+    csv: Option<&Path>,
     state_path: Option<&PathBuf>,
     modified_only: bool,
     pr_comment: bool,
+    since: Option<&str>,
+    format: OutputFormat,
+    profile: bool,
+    preflight: bool,
+    fail_fast: bool,
+    emit_inferred: bool,
     verbose: bool,
 ) -> Result<()> {
     use schemarefly_incremental::{SchemaReflyDatabase, queries};
 
+    let mut timer = profile::PhaseTimer::new();
+
     // Validate flags
     if modified_only && state_path.is_none() {
         return Err(anyhow::anyhow!(
@@ -185,27 +528,19 @@ fn check_command(
         }
     }
 
-    // Find manifest path
-    let manifest_path = Path::new("target/manifest.json");
-    if !manifest_path.exists() {
-        return Err(anyhow::anyhow!(
-            "Manifest not found at {}. Run 'dbt compile' or 'dbt build' first.",
-            manifest_path.display()
-        ));
-    }
+    // Read manifest JSON (local target directory or dbt Cloud)
+    let started = std::time::Instant::now();
+    let loaded = load_manifest_json(config).await?;
+    timer.record("manifest parse", started.elapsed());
 
     if verbose {
-        eprintln!("{} {}", "Loading manifest from:".cyan(), manifest_path.display());
+        eprintln!("{} {}", "Loaded manifest from:".cyan(), loaded.source);
     }
-
     // Initialize Salsa database for incremental computation
     let db = SchemaReflyDatabase::default();
 
-    // Read manifest JSON
-    let manifest_json = std::fs::read_to_string(manifest_path)?;
-
     // Create Salsa inputs
-    let manifest_input = queries::ManifestInput::new(&db, manifest_json.clone());
+    let manifest_input = queries::ManifestInput::new(&db, loaded.json.clone());
     let config_input = queries::ConfigInput::new(&db, config.clone());
 
     if verbose {
@@ -213,27 +548,26 @@ fn check_command(
     }
 
     // Get manifest from Salsa (cached)
-    let manifest_opt = queries::manifest(&db, manifest_input);
-    let manifest = manifest_opt.ok_or_else(|| anyhow::anyhow!("Failed to parse manifest"))?;
+    let manifest_opt = timer.time("manifest parse", || queries::manifest(&db, manifest_input));
+    // Salsa only logs the parse error; parse again to report why and exit with its code
+    let manifest = match manifest_opt {
+        Some(manifest) => manifest,
+        None => return Err(loaded.parse().err().map_or_else(
+            || anyhow::anyhow!("Failed to parse manifest"),
+            anyhow::Error::from,
+        )),
+    };
 
     // Build dependency graph for impact analysis
-    let dag = DependencyGraph::from_manifest(&manifest);
+    let dag = timer.time("dag build", || DependencyGraph::from_manifest(&manifest));
 
     // Slim CI: Compare against state manifest if provided
     let state_comparison = if let Some(state_manifest_path) = state_path {
-        if !state_manifest_path.exists() {
-            return Err(anyhow::anyhow!(
-                "State manifest not found at {}",
-                state_manifest_path.display()
-            ));
-        }
-
         if verbose {
             eprintln!("{} {}", "Loading state manifest from:".cyan(), state_manifest_path.display());
         }
 
-        let state_manifest = Manifest::from_file(state_manifest_path)
-            .map_err(|e| anyhow::anyhow!("Failed to load state manifest: {}", e))?;
+        let state_manifest = read_manifest(state_manifest_path)?;
 
         let comparison = StateComparison::compare(&manifest, &state_manifest);
 
@@ -276,41 +610,114 @@ fn check_command(
         }
     } else {
         // Check all models
-        manifest.models().keys().cloned().collect()
+        manifest.models_iter().map(|(node_id, _)| node_id.clone()).collect()
+    };
+
+    // Restrict to models changed since a git ref (if --since)
+    let since_selection = match since {
+        Some(git_ref) => {
+            let selection = ChangedModels::from_git(&manifest, &dag, &config.project_root, git_ref)
+                .map_err(|e| anyhow::anyhow!("Failed to list files changed since '{}': {}", git_ref, e))?;
+
+            if verbose {
+                eprintln!(
+                    "{} {} changed models since {} ({} including downstream)",
+                    "Found".cyan(),
+                    selection.changed.len(),
+                    git_ref,
+                    selection.all_affected.len()
+                );
+                for file in &selection.unmatched_files {
+                    eprintln!("  {} {} (no matching model)", "Ignoring".yellow(), file.display());
+                }
+            }
+
+            Some(selection.all_affected)
+        }
+        None => None,
     };
 
     if verbose {
-        if modified_only {
+        if let Some(ref selected) = since_selection {
+            eprintln!("{} {} models (changed since {} + downstream)...", "Checking".cyan(), selected.len(), since.unwrap_or_default());
+        } else if modified_only {
             eprintln!("{} {} models (modified + downstream)...", "Checking".cyan(), models_to_check.len());
         } else {
             eprintln!("{}", "Checking contracts for all models...".cyan());
         }
     }
 
+    // Whether a model is selected by --modified-only / --since
+    let is_selected = |node_id: &String| {
+        (!modified_only || models_to_check.contains(node_id))
+            && since_selection.as_ref().is_none_or(|selected| selected.contains(node_id))
+    };
+
+    // Parse every model that will be checked before any inference runs
+    let preflight_errors = if preflight {
+        let models: Vec<preflight::PreflightModel> = manifest.models_iter()
+            .filter(|(node_id, node)| is_selected(node_id) && ContractExtractor::extract_from_node(node).is_some())
+            .map(|(_, node)| (node.original_file_path.clone(), resolve_model_sql_path(&config.project_root, &node.original_file_path)))
+            .collect();
+
+        let errors = timer.time("preflight", || preflight::parse_all(&models, config, Some(&manifest)));
+
+        if verbose || !errors.is_empty() {
+            eprintln!("{} {} models parsed, {} with errors", "Preflight:".cyan(), models.len(), errors.len());
+        }
+        for diag in &errors {
+            let file = diag.location.as_ref().map(|l| l.file.as_str()).unwrap_or_default();
+            eprintln!("  {} {}: {}", "✗".red(), file, diag.message);
+        }
+        errors
+    } else {
+        Vec::new()
+    };
+    let skip_inference = fail_fast && !preflight_errors.is_empty();
+
     // Collect diagnostics from all contract checks
     let mut all_diagnostics = Vec::new();
+    let mut model_diffs = Vec::new();
     let mut checked_models = 0;
     let mut models_with_contracts = 0;
     let mut skipped_models = 0;
+    let mut suppressed_diagnostics = 0;
 
-    // Check each model with a contract
-    for (node_id, node) in manifest.models() {
-        // Skip if not in models_to_check (for Slim CI)
-        if modified_only && !models_to_check.contains(&node_id) {
+    // --fail-fast: report only the preflight parse errors
+    if skip_inference {
+        if verbose {
+            eprintln!("{}", "Skipping inference (--fail-fast)".yellow());
+        }
+        all_diagnostics.extend(preflight_errors);
+    }
+
+    // Check each model with a contract; a panic is reported for its model and the run continues
+    let models = manifest.models_iter().filter(|_| !skip_inference);
+    panic_boundary::check_models(models, &mut all_diagnostics, |node_id, node, all_diagnostics| {
+        // Skip if not in models_to_check (for Slim CI) or not changed since the git ref (for --since)
+        if !is_selected(node_id) {
             skipped_models += 1;
-            continue;
+            return;
+        }
+
+        // Models under require_contract_paths must enforce a contract
+        if let Some(diag) = ContractPolicy::check_node(node, &config.contract) {
+            all_diagnostics.push(diag);
         }
 
         // Extract contract if present
-        if let Some(_contract) = ContractExtractor::extract_from_node(node) {
+        if let Some(contract) = ContractExtractor::extract_from_node(node) {
             models_with_contracts += 1;
 
+            // dbt unit tests must only expect columns the contract declares
+            all_diagnostics.extend(UnitTestCheck::check_model(&manifest, node, &contract.schema));
+
             if verbose {
                 // Show if model is modified in Slim CI mode
                 let modified_indicator = if let Some(ref comparison) = state_comparison {
-                    if comparison.modified_model_ids().contains(&node_id) {
+                    if comparison.modified_model_ids().contains(node_id) {
                         " [MODIFIED]".yellow().to_string()
-                    } else if comparison.all_affected_models.contains(&node_id) {
+                    } else if comparison.all_affected_models.contains(node_id) {
                         " [DOWNSTREAM]".cyan().to_string()
                     } else {
                         String::new()
@@ -321,35 +728,18 @@ fn check_command(
                 eprintln!("  {} {}{}...", "Checking".cyan(), node.name, modified_indicator);
             }
 
-            // Find SQL file path
-            let sql_path = Path::new(&node.original_file_path);
-
-            // If the path is relative, try to resolve it from project root
-            let full_sql_path = if sql_path.is_relative() {
-                // Try common dbt project structures
-                let candidates = vec![
-                    sql_path.to_path_buf(),
-                    Path::new("models").join(sql_path),
-                    PathBuf::from(&node.original_file_path),
-                ];
-
-                candidates.into_iter().find(|p| p.exists())
-            } else {
-                Some(sql_path.to_path_buf())
-            };
-
-            let Some(sql_file_path) = full_sql_path else {
+            let Some(sql_file_path) = resolve_model_sql_path(&config.project_root, &node.original_file_path) else {
                 let diag = Diagnostic::new(
                     schemarefly_core::DiagnosticCode::SqlParseError,
                     schemarefly_core::Severity::Error,
                     format!("SQL file not found: {}", node.original_file_path),
                 );
                 all_diagnostics.push(diag);
-                continue;
+                return;
             };
 
             // Read SQL file
-            let sql_content = match std::fs::read_to_string(&sql_file_path) {
+            let sql_content = match timer.time_model(&node.name, "parse", || std::fs::read_to_string(&sql_file_path)) {
                 Ok(content) => content,
                 Err(e) => {
                     let diag = Diagnostic::new(
@@ -358,22 +748,62 @@ fn check_command(
                         format!("Failed to read SQL file {}: {}", sql_file_path.display(), e),
                     );
                     all_diagnostics.push(diag);
-                    continue;
+                    return;
                 }
             };
 
-            // Preprocess dbt template functions
-            let (preprocessed_sql, _) = DbtFunctionExtractor::preprocess(&sql_content, Some(&manifest));
+            // A ref() or source() naming nothing would only fail later as an unknown table
+            let unresolved = DbtFunctionExtractor::unresolved_references(
+                &sql_content,
+                &manifest,
+                Some(&node.original_file_path),
+            );
+            if !unresolved.is_empty() {
+                all_diagnostics.extend(unresolved);
+                return;
+            }
+
+            // Preprocess dbt template functions and parse (memoized by Salsa)
+            let sql_file = timer.time_model(&node.name, "parse", || {
+                let (preprocessed_sql, _) = DbtFunctionExtractor::preprocess_with_vars(&sql_content, Some(&manifest), &config.vars);
+
+                // Create Salsa input for this SQL file (enables caching per file)
+                let sql_file = queries::SqlFile::new(&db, PathBuf::from(&node.original_file_path), preprocessed_sql);
+                let _ = queries::parse_sql(&db, sql_file, config_input);
+                sql_file
+            });
 
-            // Create Salsa input for this SQL file (enables caching per file)
-            let sql_file = queries::SqlFile::new(&db, sql_file_path.clone(), preprocessed_sql);
+            // Infer the schema up front so the comparison below reuses the memoized result
+            timer.time_model(&node.name, "infer", || {
+                let _ = queries::infer_schema_partial(&db, sql_file, config_input, manifest_input);
+            });
 
             // Use Salsa to check contract (cached if file unchanged)
-            // This will automatically call parse_sql -> infer_schema -> compare
-            let diagnostics = queries::check_contract(&db, sql_file, config_input, manifest_input);
+            let diagnostics = timer.time_model(&node.name, "compare", || {
+                model_diffs.extend(queries::model_contract_diff(&db, sql_file, config_input, manifest_input));
+                queries::check_contract(&db, sql_file, config_input, manifest_input)
+            });
+
+            // Drop findings silenced by `-- schemarefly: ignore CODE` directives in the model SQL
+            let suppressions = Suppression::parse_sql(&sql_content);
+            for suppression in suppressions.iter().filter(|s| !s.unknown_codes.is_empty()) {
+                eprintln!(
+                    "{} {}:{}: unknown code(s) in ignore directive: {}",
+                    "⚠".yellow(),
+                    sql_file_path.display(),
+                    suppression.line,
+                    suppression.unknown_codes.join(", ")
+                );
+            }
+            let (mut diagnostics, suppressed) = Suppression::apply(&suppressions, diagnostics);
+            suppressed_diagnostics += suppressed;
+
+            if let Some(max) = config.max_diagnostics_per_model {
+                diagnostics = Diagnostic::cap(diagnostics, max, &node.name);
+            }
 
             // Add downstream impact to each diagnostic
-            let downstream = dag.downstream(&node_id);
+            let downstream = dag.downstream(node_id);
             let has_errors = diagnostics.iter().any(|d| d.severity == schemarefly_core::Severity::Error);
             let has_warnings = diagnostics.iter().any(|d| d.severity == schemarefly_core::Severity::Warn);
             let error_count = diagnostics.iter().filter(|d| d.severity == schemarefly_core::Severity::Error).count();
@@ -393,8 +823,20 @@ fn check_command(
             } else if verbose {
                 eprintln!("    {}", "✓ OK".green());
             }
+        } else if emit_inferred {
+            // Show what inference produced for models that could get a contract
+            let Some(sql_file_path) = resolve_model_sql_path(&config.project_root, &node.original_file_path) else {
+                return;
+            };
+            let Ok(sql_content) = std::fs::read_to_string(&sql_file_path) else {
+                return;
+            };
+
+            let (preprocessed_sql, _) = DbtFunctionExtractor::preprocess_with_vars(&sql_content, Some(&manifest), &config.vars);
+            let sql_file = queries::SqlFile::new(&db, PathBuf::from(&node.original_file_path), preprocessed_sql);
+            all_diagnostics.extend(queries::inferred_schema_info(&db, sql_file, config_input, manifest_input));
         }
-    }
+    });
 
     if verbose {
         eprintln!();
@@ -402,13 +844,15 @@ fn check_command(
             "Checked {} models ({} with contracts)",
             checked_models, models_with_contracts
         );
-        if modified_only {
+        if modified_only || since_selection.is_some() {
             eprintln!("Skipped {} unchanged models", skipped_models);
         }
     }
 
     // Build report with diagnostics
-    let mut report = Report::from_diagnostics(all_diagnostics);
+    let mut report = Report::from_diagnostics(all_diagnostics)
+        .with_model_diffs(model_diffs)
+        .with_suppressed(suppressed_diagnostics);
 
     // Add Slim CI metadata if state comparison was performed
     if let Some(ref comparison) = state_comparison {
@@ -444,24 +888,39 @@ fn check_command(
 
     // Save markdown report if requested
     if let Some(md_path) = markdown {
-        let markdown_content = generate_markdown_report(&report, state_comparison.as_ref());
-        std::fs::write(md_path, markdown_content)?;
+        let file = std::io::BufWriter::new(std::fs::File::create(md_path)?);
+        let mut writer = MarkdownWriter::new(file).with_state_comparison(state_comparison.as_ref());
+        write_report(&mut writer, &report, &config.messages)?;
         if verbose {
             eprintln!("{} {}", "Markdown report saved to:".green(), md_path.display());
         }
     }
 
+    // Save CSV export if requested
+    if let Some(csv_path) = csv {
+        report.to_csv(std::io::BufWriter::new(std::fs::File::create(csv_path)?))?;
+        if verbose {
+            eprintln!("{} {}", "CSV report saved to:".green(), csv_path.display());
+        }
+    }
+
     // Output PR comment if requested
     if pr_comment {
         let pr_markdown = generate_pr_comment(&report, state_comparison.as_ref());
         println!("{}", pr_markdown);
     } else {
         // Print summary (only if not in PR comment mode)
-        print_report_summary(&report);
+        let mut writer = writer_for(format, std::io::stdout().lock());
+        write_report(writer.as_mut(), &report, &config.messages)?;
+    }
+
+    if profile {
+        eprintln!();
+        eprint!("{}", timer.render());
     }
 
-    // Exit with error code if there are errors
-    if report.has_errors() {
+    // Exit with error code if there are errors (restricted to `fail_on_codes` when set)
+    if report.fails_on(&config.fail_on_codes) {
         std::process::exit(1);
     }
 
@@ -475,7 +934,7 @@ fn impact_command(_config: &Config, model: &str, manifest_path: &Path, verbose:
     }
 
     // Load manifest
-    let manifest = Manifest::from_file(manifest_path)
+    let manifest = load_manifest(manifest_path)
         .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
 
     if verbose {
@@ -503,6 +962,12 @@ fn impact_command(_config: &Config, model: &str, manifest_path: &Path, verbose:
 
     println!("{} {}", "Model:".bold(), node_id.green());
     println!("{} {}", "Downstream models:".bold(), downstream.len());
+    println!(
+        "{} max depth: {}, direct children: {}",
+        "DAG:".bold(),
+        dag.max_downstream_depth(&node_id),
+        dag.direct_children(&node_id)
+    );
     println!();
 
     if downstream.is_empty() {
@@ -531,6 +996,106 @@ fn impact_command(_config: &Config, model: &str, manifest_path: &Path, verbose:
     Ok(())
 }
 
+/// Compare-versions command - report breaking and additive contract changes between model versions
+fn compare_versions_command(
+    config: &Config,
+    model: &str,
+    from: &str,
+    to: &str,
+    manifest_path: &Path,
+    verbose: bool,
+) -> Result<()> {
+    if verbose {
+        eprintln!("{} {}", "Loading manifest from:".cyan(), manifest_path.display());
+    }
+
+    let manifest = load_manifest(manifest_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
+
+    let contract = |version: &str| -> Result<Schema> {
+        let node = manifest.find_model_version(model, version)
+            .ok_or_else(|| anyhow::anyhow!("Version '{}' of model '{}' not found in manifest", version, model))?;
+        ContractExtractor::extract_from_node(node)
+            .map(|contract| contract.schema)
+            .ok_or_else(|| anyhow::anyhow!("'{}' has no enforced contract with typed columns", node.unique_id))
+    };
+
+    let diff = VersionDiff::compare(
+        model,
+        from,
+        to,
+        &contract(from)?,
+        &contract(to)?,
+        CompareOptions::from_config(config).identifier_case,
+    );
+
+    println!("\n{}", "=".repeat(60).bright_blue());
+    println!("{}", "Model Version Comparison".bold().bright_blue());
+    println!("{}", "=".repeat(60).bright_blue());
+    println!();
+    println!("{} {} ({} -> {})", "Model:".bold(), model.green(), from, to);
+    println!();
+
+    if diff.changes.is_empty() {
+        println!("{}", "✓ Contracts are identical".green());
+    }
+
+    let breaking: Vec<_> = diff.breaking().collect();
+    if !breaking.is_empty() {
+        println!("{} {}", "Breaking changes:".bold(), breaking.len());
+        for change in &breaking {
+            println!("  {} {}", "✗".red(), change);
+        }
+        println!();
+    }
+
+    let additive: Vec<_> = diff.additive().collect();
+    if !additive.is_empty() {
+        println!("{} {}", "Additive changes:".bold(), additive.len());
+        for change in &additive {
+            println!("  {} {}", "+".green(), change);
+        }
+        println!();
+    }
+
+    println!("{}", "=".repeat(60).bright_blue());
+
+    if diff.has_breaking() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Locate a model's SQL file from its `original_file_path`
+///
+/// Relative paths are tried under the project root, then under its `models/`,
+/// so commands run from a subdirectory find the same files.
+fn resolve_model_sql_path(project_root: &Path, original_file_path: &str) -> Option<PathBuf> {
+    let sql_path = Path::new(original_file_path);
+    if sql_path.is_absolute() {
+        return Some(sql_path.to_path_buf());
+    }
+
+    [project_root.join(sql_path), project_root.join("models").join(sql_path)]
+        .into_iter()
+        .find(|p| p.exists())
+}
+
+/// Whether a `--manifest` argument asks for stdin
+fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Load a manifest from a file (plain or gzipped), or from stdin for `-`
+fn load_manifest(path: &Path) -> Result<Manifest, schemarefly_dbt::manifest::ManifestError> {
+    if is_stdin(path) {
+        Manifest::from_reader(std::io::stdin().lock())
+    } else {
+        Manifest::from_file(path)
+    }
+}
+
 /// Find node ID from short name or unique_id
 fn find_node_id(manifest: &Manifest, name: &str) -> Result<String> {
     // If it's already a unique_id (contains dots), use it directly
@@ -560,22 +1125,518 @@ fn find_node_id(manifest: &Manifest, name: &str) -> Result<String> {
     ))
 }
 
-/// Drift command - detect warehouse schema changes
-async fn drift_command(config: &Config, output: &Path, verbose: bool) -> Result<()> {
-    // Load .env file if present (for environment variable configuration)
-    if let Err(e) = dotenvy::dotenv() {
-        // Only warn if verbose - it's okay if .env doesn't exist
-        if verbose && !matches!(e, dotenvy::Error::Io(_)) {
-            eprintln!("{} Failed to load .env file: {}", "⚠".yellow(), e);
-        }
-    }
+/// Graph command - export the DAG (optionally a model's slice of it) as DOT or Mermaid
+fn graph_command(
+    _config: &Config,
+    format: GraphFormat,
+    select: Option<&str>,
+    manifest_path: &Path,
+    output: Option<&Path>,
+    verbose: bool,
+) -> Result<()> {
+    use schemarefly_dbt::{GraphExport, GraphSelector};
 
     if verbose {
-        eprintln!("{}", "Detecting schema drift...".cyan());
+        eprintln!("{} {}", "Loading manifest from:".cyan(), manifest_path.display());
     }
 
-    // Check warehouse configuration
-    let warehouse_config = config.warehouse.as_ref()
+    let manifest = load_manifest(manifest_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
+    let dag = DependencyGraph::from_manifest(&manifest);
+
+    let mut export = GraphExport::new(&manifest, &dag);
+    if let Some(select) = select {
+        let selector = GraphSelector::parse(select);
+        let node_id = find_node_id(&manifest, &selector.model)?;
+        export = export.with_selection(&node_id, selector.upstream, selector.downstream);
+    }
+
+    let graph = match format {
+        GraphFormat::Dot => export.to_dot(),
+        GraphFormat::Mermaid => export.to_mermaid(),
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, &graph)?;
+            if verbose {
+                eprintln!("{} {}", "Graph saved to:".green(), path.display());
+            }
+        }
+        None => print!("{}", graph),
+    }
+
+    Ok(())
+}
+
+/// Query command - filter diagnostics from an existing report
+fn query_command(
+    report_path: &Path,
+    codes: &[String],
+    severities: &[String],
+    path_glob: Option<&str>,
+    output: Option<&Path>,
+    verbose: bool,
+) -> Result<()> {
+    use schemarefly_core::{ReportFilter, Severity};
+
+    let report = Report::from_file(report_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load report {}: {}", report_path.display(), e))?;
+
+    let mut criteria = ReportFilter::new();
+    for code in codes {
+        let code = DiagnosticCode::parse(code)
+            .ok_or_else(|| anyhow::anyhow!("Unknown diagnostic code '{}'", code))?;
+        criteria = criteria.with_code(code);
+    }
+    for severity in severities {
+        let severity = Severity::parse(severity)
+            .ok_or_else(|| anyhow::anyhow!("Unknown severity '{}'. Expected: error, warn, info", severity))?;
+        criteria = criteria.with_severity(severity);
+    }
+    if let Some(pattern) = path_glob {
+        criteria = criteria.with_path_glob(pattern);
+    }
+
+    let filtered = report.filter(|d| criteria.matches(d));
+
+    if verbose {
+        eprintln!(
+            "{} {} of {} diagnostics",
+            "Matched".cyan(),
+            filtered.summary.total,
+            report.summary.total
+        );
+    }
+
+    match output {
+        Some(path) => {
+            filtered.save_to_file(path)?;
+            if verbose {
+                eprintln!("{} {}", "Filtered report saved to:".green(), path.display());
+            }
+        }
+        None => println!("{}", filtered.to_json()?),
+    }
+
+    Ok(())
+}
+
+fn validate_manifest_command(
+    config: &Config,
+    manifest_path: &Path,
+    output: Option<&Path>,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    if verbose {
+        eprintln!("{} {}", "Validating manifest:".cyan(), manifest_path.display());
+    }
+
+    let manifest = load_manifest(manifest_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
+
+    let diagnostics = manifest
+        .validate()
+        .into_iter()
+        .map(|mut d| {
+            d.severity = config.severity.get_severity(d.code, d.severity);
+            d
+        })
+        .collect();
+
+    let mut report = Report::from_diagnostics_with_config(diagnostics, config);
+    report.summary.models_checked = manifest.models_iter().count();
+
+    if let Some(path) = output {
+        report.save_to_file(path)?;
+        if verbose {
+            eprintln!("{} {}", "Report saved to:".green(), path.display());
+        }
+    }
+
+    let mut writer = writer_for(format, std::io::stdout().lock());
+    write_report(writer.as_mut(), &report, &config.messages)?;
+
+    if report.fails_on(&config.fail_on_codes) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Refs command - inventory of dbt references and their resolution
+fn refs_command(config: &Config, manifest_path: &Path, json: bool, verbose: bool) -> Result<()> {
+    use refs::ResolutionStatus;
+
+    if verbose {
+        eprintln!("{} {}", "Loading manifest from:".cyan(), manifest_path.display());
+    }
+
+    let manifest = load_manifest(manifest_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
+
+    let (usages, unreadable) = refs::collect(&manifest, |node| read_model_sql(&config.project_root, node).ok().map(|(_, sql)| sql));
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&usages)?);
+    } else {
+        for usage in &usages {
+            match &usage.resolved_to {
+                Some(target) => println!("{} {} → {} ({})", "✓".green(), usage.model, usage.call(), target.dimmed()),
+                None => println!("{} {} → {} {}", "✗".red(), usage.model, usage.call(), "unresolved".red()),
+            }
+        }
+
+        let unresolved = usages.iter().filter(|u| u.status == ResolutionStatus::Unresolved).count();
+        println!();
+        println!("{} references, {} unresolved", usages.len(), unresolved);
+    }
+
+    if !unreadable.is_empty() {
+        eprintln!("{} SQL not found for {} models; their references are not listed", "⚠".yellow(), unreadable.len());
+        if verbose {
+            for node_id in &unreadable {
+                eprintln!("  {}", node_id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn lint_contracts_command(
+    config: &Config,
+    manifest_path: &Path,
+    output: Option<&Path>,
+    format: OutputFormat,
+    verbose: bool,
+) -> Result<()> {
+    if verbose {
+        eprintln!("{} {}", "Linting contracts in:".cyan(), manifest_path.display());
+    }
+
+    let manifest = load_manifest(manifest_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
+
+    let diagnostics = ContractExtractor::lint_all(&manifest)
+        .into_iter()
+        .map(|mut d| {
+            d.severity = config.severity.get_severity(d.code, d.severity);
+            d
+        })
+        .collect();
+
+    let mut report = Report::from_diagnostics_with_config(diagnostics, config);
+    report.summary.models_checked = manifest
+        .models()
+        .values()
+        .filter(|node| node.config.contract.as_ref().is_some_and(|c| c.enforced))
+        .count();
+
+    if let Some(path) = output {
+        report.save_to_file(path)?;
+        if verbose {
+            eprintln!("{} {}", "Report saved to:".green(), path.display());
+        }
+    }
+
+    let mut writer = writer_for(format, std::io::stdout().lock());
+    write_report(writer.as_mut(), &report, &config.messages)?;
+
+    if report.fails_on(&config.fail_on_codes) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Explain command - show how refs/sources resolve before inference
+fn explain_command(config: &Config, model: &str, manifest_path: &Path, verbose: bool) -> Result<()> {
+    use schemarefly_sql::{InferenceContext, InferenceExplanation, SchemaInference, SqlParser};
+
+    if verbose {
+        eprintln!("{} {}", "Loading manifest from:".cyan(), manifest_path.display());
+    }
+
+    let manifest = load_manifest(manifest_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
+
+    let node_id = find_node_id(&manifest, model)?;
+    let node = manifest.get_node(&node_id)
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a model", node_id))?;
+
+    let (sql_file_path, sql_content) = read_model_sql(&config.project_root, node)?;
+
+    let context = InferenceContext::from_manifest(&manifest);
+    let explanation = InferenceExplanation::explain_with_vars(&sql_content, &manifest, &context, &config.vars);
+
+    println!("\n{}", "=".repeat(60).bright_blue());
+    println!("{}", "Inference Explain Plan".bold().bright_blue());
+    println!("{}", "=".repeat(60).bright_blue());
+    println!();
+
+    println!("{} {}", "Model:".bold(), node_id.green());
+    println!("{} {}", "File:".bold(), sql_file_path.display());
+    println!();
+
+    println!("{}", "References:".bold());
+    print!("{}", explanation.render());
+    println!();
+
+    if verbose {
+        println!("{}", "Preprocessed SQL:".bold());
+        println!("{}", explanation.preprocessed_sql.trim().dimmed());
+        println!();
+    }
+
+    // Run inference so the plan ends with the actual outcome
+    let parser = SqlParser::from_dialect(&config.dialect);
+    let result = parser
+        .parse(&explanation.preprocessed_sql, Some(&sql_file_path))
+        .map_err(|e| e.to_string())
+        .and_then(|parsed| {
+            let stmt = parsed.model_statement()
+                .ok_or_else(|| "No SQL statement found".to_string())?;
+            SchemaInference::new(&context)
+                .with_max_depth(config.inference.max_depth)
+                .with_nullability_from_where(config.inference.nullability_from_where)
+                .infer_statement(stmt)
+                .map_err(|e| e.to_string())
+        });
+
+    println!("{}", "Inference:".bold());
+    match result {
+        Ok(schema) => {
+            println!("  {} {} columns inferred", "✓".green(), schema.columns.len());
+            for col in &schema.columns {
+                println!("    - {}: {}", col.name, col.logical_type);
+            }
+        }
+        Err(e) => {
+            println!("  {} {}", "✗".red(), e.red());
+        }
+    }
+
+    let unresolved = explanation.unresolved();
+    if !unresolved.is_empty() {
+        println!();
+        println!(
+            "{}",
+            format!("⚠ {} unresolved reference(s) - check model/source names in the manifest", unresolved.len())
+                .yellow()
+                .bold()
+        );
+    }
+
+    println!();
+    println!("{}", "=".repeat(60).bright_blue());
+
+    Ok(())
+}
+
+/// Find and read a model's SQL file with the check command's lookup
+fn read_model_sql(project_root: &Path, node: &schemarefly_dbt::ManifestNode) -> Result<(PathBuf, String)> {
+    let sql_file_path = resolve_model_sql_path(project_root, &node.original_file_path)
+        .ok_or_else(|| anyhow::anyhow!("SQL file not found: {}", node.original_file_path))?;
+
+    let sql_content = std::fs::read_to_string(&sql_file_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read SQL file {}: {}", sql_file_path.display(), e))?;
+
+    Ok((sql_file_path, sql_content))
+}
+
+/// Repro command - bundle a model's SQL and upstream schemas for replaying inference
+fn repro_command(
+    config: &Config,
+    model: &str,
+    manifest_path: &Path,
+    output: Option<&Path>,
+    redact: bool,
+    verbose: bool,
+) -> Result<()> {
+    use schemarefly_sql::{InferenceContext, ReproBundle};
+
+    if verbose {
+        eprintln!("{} {}", "Loading manifest from:".cyan(), manifest_path.display());
+    }
+
+    let manifest = load_manifest(manifest_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
+
+    let node_id = find_node_id(&manifest, model)?;
+    let node = manifest.get_node(&node_id)
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a model", node_id))?;
+
+    let (_, sql_content) = read_model_sql(&config.project_root, node)?;
+
+    let context = InferenceContext::from_manifest(&manifest);
+    let mut bundle = ReproBundle::build(&node_id, &sql_content, config.dialect.clone(), &manifest, &context, &config.vars);
+    if redact || config.redact_sensitive_data {
+        bundle = bundle.redacted()
+            .map_err(|e| anyhow::anyhow!("Failed to redact bundle: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(&bundle)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, &json)?;
+            if verbose {
+                eprintln!(
+                    "{} {} ({} upstream tables)",
+                    "Repro bundle saved to:".green(),
+                    path.display(),
+                    bundle.tables.len()
+                );
+            }
+        }
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
+
+/// Snapshot command - infer every model's schema and save them for later comparison
+fn snapshot_command(config: &Config, output: &Path, manifest_path: &Path, verbose: bool) -> Result<()> {
+    use schemarefly_sql::{InferenceContext, SchemaInference, SqlParser};
+
+    if verbose {
+        eprintln!("{} {}", "Loading manifest from:".cyan(), manifest_path.display());
+    }
+
+    let manifest = load_manifest(manifest_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
+
+    let context = InferenceContext::from_manifest(&manifest);
+    let parser = SqlParser::from_dialect(&config.dialect);
+    let mut snapshot = SchemaSnapshot::new();
+    let mut failed = Vec::new();
+
+    for (node_id, node) in manifest.models() {
+        let result = read_model_sql(&config.project_root, node)
+            .map_err(|e| e.to_string())
+            .and_then(|(sql_file_path, sql_content)| {
+                let (preprocessed_sql, _) = DbtFunctionExtractor::preprocess_with_vars(&sql_content, Some(&manifest), &config.vars);
+                let parsed = parser
+                    .parse(&preprocessed_sql, Some(&sql_file_path))
+                    .map_err(|e| e.to_string())?;
+                let stmt = parsed.model_statement()
+                    .ok_or_else(|| "No SQL statement found".to_string())?;
+                SchemaInference::new(&context)
+                    .with_max_depth(config.inference.max_depth)
+                    .with_nullability_from_where(config.inference.nullability_from_where)
+                    .infer_statement(stmt)
+                    .map_err(|e| e.to_string())
+            });
+
+        match result {
+            Ok(schema) => snapshot.insert(node_id, schema),
+            Err(e) => failed.push((node_id, e)),
+        }
+    }
+
+    std::fs::write(output, serde_json::to_string_pretty(&snapshot)?)?;
+
+    println!(
+        "{} {} ({} models)",
+        "Schema snapshot saved to:".green(),
+        output.display(),
+        snapshot.models.len()
+    );
+    if !failed.is_empty() {
+        eprintln!("{} {} models could not be inferred and are not in the snapshot", "⚠".yellow(), failed.len());
+        if verbose {
+            for (node_id, e) in &failed {
+                eprintln!("  {} {}: {}", "✗".red(), node_id, e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Snapshot diff command - report schema changes between two snapshots
+fn snapshot_diff_command(old: &Path, new: &Path) -> Result<()> {
+    let load = |path: &Path| -> Result<SchemaSnapshot> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read snapshot {}: {}", path.display(), e))?;
+        let snapshot: SchemaSnapshot = serde_json::from_str(&json)
+            .map_err(|e| anyhow::anyhow!("Failed to parse snapshot {}: {}", path.display(), e))?;
+        if snapshot.version != SCHEMA_SNAPSHOT_VERSION {
+            anyhow::bail!(
+                "Snapshot {} has format version {}, expected {}",
+                path.display(),
+                snapshot.version,
+                SCHEMA_SNAPSHOT_VERSION
+            );
+        }
+        Ok(snapshot)
+    };
+
+    let diff = load(old)?.diff(&load(new)?);
+    if diff.is_empty() {
+        println!("{}", "✓ No schema changes".green());
+        return Ok(());
+    }
+
+    for model_id in &diff.added_models {
+        println!("{} {} (new model)", "+".green(), model_id);
+    }
+    for model_id in &diff.removed_models {
+        println!("{} {} (model removed)", "-".red(), model_id);
+    }
+    for (model_id, changes) in &diff.changed {
+        println!("{} {}", "~".yellow(), model_id.bold());
+        for col in &changes.added {
+            println!("    {} {}: {}", "+".green(), col.name, col.logical_type);
+        }
+        for col in &changes.removed {
+            println!("    {} {}: {}", "-".red(), col.name, col.logical_type);
+        }
+        for change in &changes.type_changed {
+            println!("    {} {}: {} → {}", "~".yellow(), change.after.name, change.before.logical_type, change.after.logical_type);
+        }
+        for change in &changes.nullability_changed {
+            println!(
+                "    {} {}: nullable {:?} → {:?}",
+                "~".yellow(),
+                change.after.name,
+                change.before.nullable,
+                change.after.nullable
+            );
+        }
+    }
+
+    println!();
+    println!(
+        "{} models added, {} removed, {} changed",
+        diff.added_models.len(),
+        diff.removed_models.len(),
+        diff.changed.len()
+    );
+
+    Ok(())
+}
+
+/// Drift command - detect warehouse schema changes
+///
+/// Cancelling `cancel` stops the run between models (or during a schema
+/// fetch); the report then covers the models completed so far.
+async fn drift_command(config: &Config, output: &Path, full: bool, cache_stats: bool, verbose: bool, cancel: CancellationToken) -> Result<()> {
+    // Load .env file if present (for environment variable configuration)
+    if let Err(e) = dotenvy::dotenv() {
+        // Only warn if verbose - it's okay if .env doesn't exist
+        if verbose && !matches!(e, dotenvy::Error::Io(_)) {
+            eprintln!("{} Failed to load .env file: {}", "⚠".yellow(), e);
+        }
+    }
+
+    if verbose {
+        eprintln!("{}", "Detecting schema drift...".cyan());
+    }
+
+    // Check warehouse configuration
+    let warehouse_config = config.warehouse.as_ref()
         .ok_or_else(|| anyhow::anyhow!(
             "No warehouse configuration found in schemarefly.toml. \
              Add a [warehouse] section with type and connection settings.\n\n\
@@ -587,56 +1648,67 @@ async fn drift_command(config: &Config, output: &Path, verbose: bool) -> Result<
              project_id = \"my-gcp-project\""
         ))?;
 
-    // Find manifest path
-    let manifest_path = Path::new("target/manifest.json");
-    if !manifest_path.exists() {
-        return Err(anyhow::anyhow!(
-            "Manifest not found at {}. Run 'dbt compile' or 'dbt build' first.",
-            manifest_path.display()
-        ));
-    }
+    // Secrets missing from the config come from SCHEMAREFLY_<KEY> variables
+    let warehouse_config = &resolve_secrets(warehouse_config, &EnvCredentialProvider::new())?;
+
+    // Validate warehouse type and required settings before doing any work
+    let warehouse_kind = WarehouseKind::from_config(warehouse_config)?;
+
+    // Load manifest (local target directory or dbt Cloud)
+    let loaded = load_manifest_json(config).await?;
 
     if verbose {
-        eprintln!("{} {}", "Loading manifest from:".cyan(), manifest_path.display());
+        eprintln!("{} {}", "Loaded manifest from:".cyan(), loaded.source);
     }
 
-    // Load manifest
-    let manifest = Manifest::from_file(manifest_path)?;
+    let manifest = loaded.parse()?;
 
     // Create warehouse adapter based on config
     if verbose {
-        eprintln!("{} {}...", "Connecting to".cyan(), warehouse_config.warehouse_type);
+        eprintln!("{} {}...", "Connecting to".cyan(), warehouse_kind);
         if warehouse_config.use_env_vars {
             eprintln!("{}", "  (environment variable lookup enabled)".dimmed());
         }
     }
 
-    let adapter: Box<dyn WarehouseAdapter> = match warehouse_config.warehouse_type.to_lowercase().as_str() {
-        "bigquery" => {
+    // House type names are rewritten before each adapter maps them
+    let type_aliases = TypeAliases::from(config.drift.type_aliases.clone());
+
+    let adapter: Box<dyn WarehouseAdapter> = match warehouse_kind {
+        WarehouseKind::BigQuery => {
             let project_id = warehouse_config.require_setting("project_id")
                 .map_err(|e| anyhow::anyhow!("BigQuery configuration error: {}", e))?;
 
-            if let Some(credentials) = warehouse_config.get_setting("credentials") {
-                // Check if it's a file path or JSON content
-                if credentials.starts_with('{') {
-                    Box::new(BigQueryAdapter::from_service_account_json(&project_id, &credentials).await?)
-                } else {
-                    Box::new(BigQueryAdapter::from_service_account_file(&project_id, &credentials).await?)
-                }
+            let adapter = if let Some(credentials) = warehouse_config.get_setting("credentials") {
+                // A key file path or the key's JSON content (e.g. "${GCP_SA_KEY}")
+                BigQueryAdapter::from_credentials(&project_id, &credentials).await?
             } else {
                 // Use Application Default Credentials
-                Box::new(BigQueryAdapter::with_adc(&project_id).await?)
+                BigQueryAdapter::with_adc(&project_id).await?
+            };
+            let adapter = adapter.with_type_normalizer(type_aliases);
+
+            // Region for non-US datasets (e.g. "EU", "europe-west2")
+            match warehouse_config.get_setting("location") {
+                Some(location) => Box::new(adapter.with_location(location)),
+                None => Box::new(adapter),
             }
         }
-        "snowflake" => {
+        WarehouseKind::Snowflake => {
             let account = warehouse_config.require_setting("account")
                 .map_err(|e| anyhow::anyhow!("Snowflake configuration error: {}", e))?;
             let username = warehouse_config.require_setting("username")
                 .map_err(|e| anyhow::anyhow!("Snowflake configuration error: {}", e))?;
-            let password = warehouse_config.require_setting("password")
-                .map_err(|e| anyhow::anyhow!("Snowflake configuration error: {}", e))?;
 
-            let mut builder = SnowflakeAdapterBuilder::with_password(&account, &username, &password);
+            // Key-pair (JWT) authentication takes precedence over password
+            let mut builder = if let Some(key_path) = warehouse_config.get_setting("private_key_path") {
+                let passphrase = warehouse_config.get_setting("private_key_passphrase");
+                SnowflakeAdapterBuilder::with_private_key(&account, &username, key_path, passphrase)
+            } else {
+                let password = warehouse_config.require_setting("password")
+                    .map_err(|e| anyhow::anyhow!("Snowflake configuration error: {}", e))?;
+                SnowflakeAdapterBuilder::with_password(&account, &username, &password)
+            };
 
             if let Some(warehouse) = warehouse_config.get_setting("warehouse") {
                 builder = builder.with_warehouse(&warehouse);
@@ -648,13 +1720,16 @@ async fn drift_command(config: &Config, output: &Path, verbose: bool) -> Result<
                 builder = builder.with_database(&database);
             }
 
-            Box::new(builder.build()?)
+            Box::new(builder.build()?.with_type_normalizer(type_aliases))
         }
-        "postgres" | "postgresql" | "redshift" => {
+        WarehouseKind::Postgres | WarehouseKind::Redshift => {
+            let is_redshift = warehouse_kind == WarehouseKind::Redshift;
+            let default_port = if is_redshift { "5439" } else { "5432" };
+
             let host = warehouse_config.require_setting("host")
                 .map_err(|e| anyhow::anyhow!("PostgreSQL configuration error: {}", e))?;
             let port: u16 = warehouse_config.get_setting("port")
-                .unwrap_or_else(|| "5432".to_string())
+                .unwrap_or_else(|| default_port.to_string())
                 .parse()
                 .map_err(|_| anyhow::anyhow!("Invalid port number in warehouse settings"))?;
             let database = warehouse_config.require_setting("database")
@@ -670,41 +1745,287 @@ async fn drift_command(config: &Config, output: &Path, verbose: bool) -> Result<
                 .map(|v| v != "disable" && v != "false")
                 .unwrap_or(false);
 
-            if use_tls {
-                Box::new(PostgresAdapter::connect_with_tls(&host, port, &database, &username, &password).await?)
-            } else {
-                Box::new(PostgresAdapter::connect(&host, port, &database, &username, &password).await?)
+            match (is_redshift, use_tls) {
+                (true, true) => Box::new(RedshiftAdapter::connect_with_tls(&host, port, &database, &username, &password).await?.with_type_normalizer(type_aliases)),
+                (true, false) => Box::new(RedshiftAdapter::connect(&host, port, &database, &username, &password).await?.with_type_normalizer(type_aliases)),
+                (false, true) => Box::new(PostgresAdapter::connect_with_tls(&host, port, &database, &username, &password).await?.with_type_normalizer(type_aliases)),
+                (false, false) => Box::new(PostgresAdapter::connect(&host, port, &database, &username, &password).await?.with_type_normalizer(type_aliases)),
             }
         }
-        _ => {
-            return Err(anyhow::anyhow!(
-                "Unsupported warehouse type '{}'. Supported: bigquery, snowflake, postgres",
-                warehouse_config.warehouse_type
-            ));
-        }
     };
 
+    // Retry rate limits and network errors, honoring server-provided backoff
+    let adapter = RetryingAdapter::new(adapter);
+
     // Test connection
     if verbose {
         eprintln!("{}", "Testing warehouse connection...".cyan());
     }
 
-    adapter.test_connection().await
-        .map_err(|e| anyhow::anyhow!("Failed to connect to warehouse: {}", e))?;
+    adapter.test_connection().await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to warehouse: {}", e))?;
+
+    if verbose {
+        eprintln!("{}", "✓ Connection successful".green());
+        eprintln!("{}", "Checking models with contracts...".cyan());
+    }
+
+    // Tables that were clean last run and whose schemas haven't changed are skipped
+    let fingerprint_path = config.target_dir().join(DRIFT_FINGERPRINTS_FILE);
+    let mut fingerprints = if full {
+        FingerprintCache::new()
+    } else {
+        FingerprintCache::load(&fingerprint_path)
+    };
+
+    let DriftRun {
+        detections: all_drift_detections,
+        checked_models,
+        models_with_drift,
+        unchanged_models,
+        skipped_models,
+        ignored_models,
+        warehouse_cache,
+        cancelled,
+    } = detect_drift(config, &manifest, &adapter, &mut fingerprints, &cancel, verbose).await;
+
+    if cancelled {
+        eprintln!(
+            "{} Drift run cancelled; reporting the {} models checked so far",
+            "⚠".yellow(),
+            checked_models
+        );
+    }
+
+    if let Err(e) = fingerprints.save(&fingerprint_path) {
+        eprintln!("{} Failed to save drift fingerprints: {}", "⚠".yellow(), e);
+    }
+
+    if verbose || !skipped_models.is_empty() {
+        eprintln!();
+        eprintln!(
+            "Checked {} models ({} unchanged since last run), {} with drift detected, {} skipped, {} ignored",
+            checked_models, unchanged_models, models_with_drift, skipped_models.len(), ignored_models.len()
+        );
+    }
+
+    // Collect all diagnostics from drift detections, summarized per table if configured
+    let mut all_diagnostics = drift_diagnostics(&all_drift_detections, config.drift.summary);
+
+    // The exit status is decided on the individual findings, before any are summarized away
+    let detailed = drift_diagnostics(&all_drift_detections, DriftSummaryMode::Off);
+    let drift_fails = Report::from_diagnostics(detailed).fails_on(&config.fail_on_codes);
+
+    // Add diagnostics for skipped models (so they appear in the report)
+    for (model_name, reason, file_path) in &skipped_models {
+        let mut diag = Diagnostic::new(
+            schemarefly_core::DiagnosticCode::DriftModelSkipped,
+            schemarefly_core::Severity::Warn,
+            format!("Model '{}' was skipped: {}", model_name, reason),
+        );
+        if let Some(path) = file_path {
+            diag = diag.with_location(schemarefly_core::Location::new(path.clone()));
+        }
+        all_diagnostics.push(diag);
+    }
+
+    // Build drift report
+    let report = Report::from_diagnostics(all_diagnostics);
+
+    // Save JSON report
+    report.save_to_file(output)?;
+
+    if verbose {
+        eprintln!("{} {}", "Drift report saved to:".green(), output.display());
+    }
+
+    // Print summary
+    print_drift_summary(&report, checked_models, models_with_drift, skipped_models.len(), &ignored_models);
+
+    if cache_stats {
+        // A fingerprint hit is a table skipped as unchanged since the last clean run
+        let fingerprint_cache = CacheStats {
+            hits: unchanged_models as u64,
+            misses: (checked_models - unchanged_models) as u64,
+        };
+        eprintln!();
+        eprintln!("{} {}", "Fingerprint cache:".bold(), fingerprint_cache);
+        eprintln!("{} {}", "Warehouse cache:".bold(), warehouse_cache);
+    }
+
+    // An interrupted run exits like any process stopped by SIGINT
+    if cancelled {
+        std::process::exit(130);
+    }
+
+    // Exit with error code if there are errors (restricted to `fail_on_codes` when set)
+    if drift_fails || report.fails_on(&config.fail_on_codes) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Diagnostics of every table with drift, summarized per table as configured
+///
+/// A summary shown instead of the findings is as severe as the worst of them.
+fn drift_diagnostics(detections: &[DriftDetection], mode: DriftSummaryMode) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for detection in detections.iter().filter(|d| !d.diagnostics.is_empty()) {
+        match mode {
+            DriftSummaryMode::Off => diagnostics.extend(detection.diagnostics.iter().cloned()),
+            DriftSummaryMode::Alongside => {
+                diagnostics.extend(detection.diagnostics.iter().cloned());
+                diagnostics.push(detection.summary());
+            }
+            DriftSummaryMode::Instead => diagnostics.push(detection.replacement_summary()),
+        }
+    }
+    diagnostics
+}
+
+/// Outcome of comparing contracts against warehouse schemas
+#[derive(Default)]
+struct DriftRun {
+    /// Comparisons that were run (excludes unchanged and skipped tables)
+    detections: Vec<DriftDetection>,
+    checked_models: usize,
+    models_with_drift: usize,
+    /// Checked tables whose fingerprints matched the last clean run
+    unchanged_models: usize,
+    /// (model_name, reason, file_path) for models that could not be checked
+    skipped_models: Vec<(String, String, Option<String>)>,
+    /// (model_name, why) for models deliberately left out of the run
+    ignored_models: Vec<(String, IgnoreReason)>,
+    /// Schema lookups answered without asking the warehouse again
+    warehouse_cache: CacheStats,
+    /// The run was cancelled before every model was checked
+    cancelled: bool,
+}
+
+/// Why a model was left out of a drift run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IgnoreReason {
+    /// Ephemeral models have no warehouse table
+    Ephemeral,
+    /// `meta: {schemarefly: {ignore_drift: true}}`
+    Meta,
+    /// The table matches `[drift] ignore_tables` / `ignore_schemas`
+    Config,
+}
+
+/// One summary line per kind of ignored model, e.g. `Models ignored by [drift] config: 2`
+fn ignored_models_summary(ignored: &[(String, IgnoreReason)]) -> Vec<String> {
+    [
+        (IgnoreReason::Ephemeral, "Ephemeral models ignored"),
+        (IgnoreReason::Meta, "Models ignored by ignore_drift meta"),
+        (IgnoreReason::Config, "Models ignored by [drift] config"),
+    ]
+    .into_iter()
+    .filter_map(|(reason, label)| {
+        let count = ignored.iter().filter(|(_, r)| *r == reason).count();
+        (count > 0).then(|| format!("{}: {}", label, count))
+    })
+    .collect()
+}
+
+/// Warehouse tables `detect_drift` will fetch: one per contracted model that
+/// is not ephemeral or ignored, without duplicates
+fn drift_tables(config: &Config, manifest: &Manifest) -> Vec<TableIdentifier> {
+    let mut tables = Vec::new();
+    for (node_id, node) in manifest.models() {
+        if node.is_ephemeral()
+            || MetaPolicy::for_node(node).skips_drift()
+            || ContractExtractor::extract_from_node(node).is_none()
+        {
+            continue;
+        }
+        let Ok(table_id) = parse_table_identifier(&node_id, &node.database, &node.schema, &node.name) else {
+            continue;
+        };
+        if !config.drift.is_ignored(&table_id.database, &table_id.schema, &table_id.table) && !tables.contains(&table_id) {
+            tables.push(table_id);
+        }
+    }
+    tables
+}
+
+/// Compare every model with a contract against its warehouse table
+///
+/// `cancel` is checked before each model and aborts a schema fetch in
+/// flight; models completed up to that point are kept in the result.
+async fn detect_drift(
+    config: &Config,
+    manifest: &Manifest,
+    adapter: &dyn WarehouseAdapter,
+    fingerprints: &mut FingerprintCache,
+    cancel: &CancellationToken,
+    verbose: bool,
+) -> DriftRun {
+    let mut run = DriftRun::default();
+    // Checks the adapter cannot back are skipped up front, with a note
+    let capabilities = adapter.capabilities();
+    if !capabilities.supports_constraints {
+        eprintln!("{} Nullability and default checks are not supported by {}; skipping them", "ℹ".cyan(), adapter.name());
+    }
+    let check_view_definitions = config.drift.check_view_definitions && capabilities.supports_view_defs;
+    if config.drift.check_view_definitions && !check_view_definitions {
+        eprintln!("{} View definition checks are not supported by {}; skipping them", "ℹ".cyan(), adapter.name());
+    }
+    let compare_options = CompareOptions::from_config(config).with_ignore_constraints(!capabilities.supports_constraints);
+
+    // Models built into the same relation share one fetch
+    let schemas = WarehouseCache::new(DRIFT_SCHEMA_TTL);
+
+    // Adapters that batch fetch every table up front; tables missing from the
+    // batch (or all of them, if it fails) are fetched one by one below
+    if capabilities.supports_batch_fetch {
+        let tables = drift_tables(config, manifest);
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => {
+                run.cancelled = true;
+                return run;
+            }
+            fetched = adapter.fetch_schemas(&tables) => match fetched {
+                Ok(fetched) => {
+                    for (table, schema) in fetched {
+                        schemas.insert(table, schema);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{} Batched schema fetch failed, fetching tables one by one: {}", "⚠".yellow(), e);
+                }
+            },
+        }
+    }
+
+    // Check each model with a contract
+    for (node_id, node) in manifest.models() {
+        if cancel.is_cancelled() {
+            run.cancelled = true;
+            break;
+        }
 
-    if verbose {
-        eprintln!("{}", "✓ Connection successful".green());
-        eprintln!("{}", "Checking models with contracts...".cyan());
-    }
+        // Ephemeral models are inlined into their children and have no table to fetch
+        if node.is_ephemeral() {
+            if verbose {
+                eprintln!("  {} {} (ephemeral, no warehouse table)", "Ignoring".dimmed(), node.name);
+            }
+            run.ignored_models.push((node.name.clone(), IgnoreReason::Ephemeral));
+            continue;
+        }
 
-    // Collect drift detections for all models with contracts
-    let mut all_drift_detections = Vec::new();
-    let mut checked_models = 0;
-    let mut models_with_drift = 0;
-    let mut skipped_models: Vec<(String, String, Option<String>)> = Vec::new(); // (model_name, reason, file_path)
+        // `meta: {schemarefly: {ignore_drift: true}}` on the model or some of its columns
+        let meta_policy = MetaPolicy::for_node(node);
+        if meta_policy.skips_drift() {
+            if verbose {
+                eprintln!("  {} {} (ignore_drift in meta)", "Ignoring".dimmed(), node.name);
+            }
+            run.ignored_models.push((node.name.clone(), IgnoreReason::Meta));
+            continue;
+        }
 
-    // Check each model with a contract
-    for (node_id, node) in manifest.models() {
         // Check if model has an enforced contract
         let has_enforced_contract = node.config.contract
             .as_ref()
@@ -720,7 +2041,7 @@ async fn drift_command(config: &Config, output: &Path, verbose: bool) -> Result<
                 if has_enforced_contract {
                     let reason = "Contract enforced but no columns with data_type specified".to_string();
                     eprintln!("  {} {} - {}", "⚠ Skipped:".yellow(), node.name, reason);
-                    skipped_models.push((
+                    run.skipped_models.push((
                         node.name.clone(),
                         reason,
                         Some(node.original_file_path.clone()),
@@ -741,7 +2062,7 @@ async fn drift_command(config: &Config, output: &Path, verbose: bool) -> Result<
             Err(e) => {
                 let reason = format!("Missing table identifier: {}", e);
                 eprintln!("  {} {} - {}", "⚠ Skipped:".yellow(), node.name, reason);
-                skipped_models.push((
+                run.skipped_models.push((
                     node.name.clone(),
                     reason,
                     Some(node.original_file_path.clone()),
@@ -750,13 +2071,49 @@ async fn drift_command(config: &Config, output: &Path, verbose: bool) -> Result<
             }
         };
 
-        // Fetch actual schema from warehouse
-        let actual_schema = match adapter.fetch_schema(&table_id).await {
-            Ok(schema) => schema,
+        // Tables excluded by [drift] ignore_tables / ignore_schemas are never fetched
+        if config.drift.is_ignored(&table_id.database, &table_id.schema, &table_id.table) {
+            if verbose {
+                eprintln!("    {}", "Ignored by [drift] config".dimmed());
+            }
+            run.ignored_models.push((node.name.clone(), IgnoreReason::Config));
+            continue;
+        }
+
+        let mut expected_schema = meta_policy.drift_schema(&contract.schema, compare_options.identifier_case);
+
+        // Declared accepted values are compared with the warehouse's enum labels
+        for (column, values) in manifest.accepted_values(node) {
+            if let Some(col) = expected_schema
+                .columns
+                .iter_mut()
+                .find(|c| compare_options.identifier_case.matches(&c.name, &column))
+            {
+                col.accepted_values = Some(values);
+            }
+        }
+
+        // Fetch actual schema from warehouse, dropping the request on cancellation
+        let fetched = match schemas.get(&table_id) {
+            Some(schema) => Ok(Schema::clone(&schema)),
+            None => tokio::select! {
+                biased;
+                _ = cancel.cancelled() => {
+                    run.cancelled = true;
+                    break;
+                }
+                fetched = adapter.fetch_schema(&table_id) => fetched,
+            },
+        };
+        let actual_schema = match fetched {
+            Ok(schema) => {
+                schemas.insert(table_id.clone(), schema.clone());
+                meta_policy.drift_schema(&schema, compare_options.identifier_case)
+            }
             Err(e) => {
                 let reason = format!("Failed to fetch schema: {}", e);
                 eprintln!("  {} {} - {}", "⚠ Skipped:".yellow(), node.name, reason);
-                skipped_models.push((
+                run.skipped_models.push((
                     node.name.clone(),
                     reason,
                     Some(node.original_file_path.clone()),
@@ -765,23 +2122,59 @@ async fn drift_command(config: &Config, output: &Path, verbose: bool) -> Result<
             }
         };
 
+        // Views whose stored SQL no longer matches the compiled model
+        let is_view = node.config.materialized.as_deref() == Some("view");
+        let view_drift = match &node.compiled_code {
+            Some(model_sql) if is_view && check_view_definitions => {
+                match adapter.fetch_view_definition(&table_id).await {
+                    Ok(Some(view_sql)) => detect_view_definition_drift(
+                        &node_id,
+                        &view_sql,
+                        model_sql,
+                        Some(&node.original_file_path),
+                    ),
+                    Ok(None) => None,
+                    Err(e) => {
+                        eprintln!("  {} {} - Failed to fetch view definition: {}", "⚠".yellow(), node.name, e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        if view_drift.is_none() && fingerprints.is_unchanged(&node_id, &expected_schema, &actual_schema, &compare_options) {
+            run.checked_models += 1;
+            run.unchanged_models += 1;
+            if verbose {
+                eprintln!("    {}", "✓ Unchanged since last run".green());
+            }
+            continue;
+        }
+
         // Compare expected (contract) vs actual (warehouse)
-        let drift = DriftDetection::detect(
+        let mut drift = DriftDetection::detect_with_options(
             node_id,
-            &contract.schema,
+            &expected_schema,
             &actual_schema,
             Some(node.original_file_path.clone()),
+            &compare_options,
         );
+        drift.diagnostics.extend(view_drift);
+        for diag in &mut drift.diagnostics {
+            diag.model_id = Some(drift.table_id.clone());
+        }
 
         let has_errors = drift.has_errors();
         let has_warnings = drift.has_warnings();
         let has_info = drift.has_info();
 
         if has_errors || has_warnings || has_info {
-            models_with_drift += 1;
+            run.models_with_drift += 1;
         }
 
-        checked_models += 1;
+        fingerprints.record(&drift.table_id, &expected_schema, &actual_schema, &compare_options, has_errors || has_warnings || has_info);
+        run.checked_models += 1;
 
         if verbose {
             if has_errors {
@@ -795,52 +2188,85 @@ async fn drift_command(config: &Config, output: &Path, verbose: bool) -> Result<
             }
         }
 
-        all_drift_detections.push(drift);
+        run.detections.push(drift);
     }
 
-    if verbose || !skipped_models.is_empty() {
-        eprintln!();
-        eprintln!(
-            "Checked {} models, {} with drift detected, {} skipped",
-            checked_models, models_with_drift, skipped_models.len()
-        );
-    }
+    run.warehouse_cache = schemas.cache_stats();
+    run
+}
 
-    // Collect all diagnostics from drift detections
-    let mut all_diagnostics: Vec<Diagnostic> = all_drift_detections
-        .iter()
-        .flat_map(|d| d.diagnostics.clone())
-        .collect();
+/// File in the dbt target directory where `drift` stores fingerprints of tables that had no drift
+const DRIFT_FINGERPRINTS_FILE: &str = "schemarefly-drift-fingerprints.json";
 
-    // Add diagnostics for skipped models (so they appear in the report)
-    for (model_name, reason, file_path) in &skipped_models {
-        let mut diag = Diagnostic::new(
-            schemarefly_core::DiagnosticCode::DriftModelSkipped,
-            schemarefly_core::Severity::Warn,
-            format!("Model '{}' was skipped: {}", model_name, reason),
-        );
-        if let Some(path) = file_path {
-            diag = diag.with_location(schemarefly_core::Location::new(path.clone()));
-        }
-        all_diagnostics.push(diag);
-    }
+/// How long a fetched warehouse schema is reused within one drift run
+const DRIFT_SCHEMA_TTL: std::time::Duration = std::time::Duration::from_secs(300);
 
-    // Build drift report
-    let report = Report::from_diagnostics(all_diagnostics);
+/// Accept-drift command - rewrite contract YAML to match accepted warehouse drift
+fn accept_drift_command(
+    config: &Config,
+    report_path: &Path,
+    manifest_path: &Path,
+    write: bool,
+    include_breaking: bool,
+    verbose: bool,
+) -> Result<()> {
+    use accept_drift::{ReconciliationPlan, reconcile_file};
 
-    // Save JSON report
-    report.save_to_file(output)?;
+    let report = Report::from_file(report_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read drift report {}: {}", report_path.display(), e))?;
+
+    if !is_stdin(manifest_path) && !manifest_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Manifest not found at {}. Run 'dbt compile' or 'dbt build' first.",
+            manifest_path.display()
+        ));
+    }
+    let manifest = load_manifest(manifest_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
 
     if verbose {
-        eprintln!("{} {}", "Drift report saved to:".green(), output.display());
+        eprintln!("{} {}", "Loaded drift report from:".cyan(), report_path.display());
     }
 
-    // Print summary
-    print_drift_summary(&report, checked_models, models_with_drift, skipped_models.len());
+    let plan = ReconciliationPlan::from_report(&report, &manifest, include_breaking)
+        .map_err(|e| anyhow::anyhow!("Cannot accept drift from {}: {}", report_path.display(), e))?;
 
-    // Exit with error code if there are errors
-    if report.has_errors() {
-        std::process::exit(1);
+    for model in &plan.models {
+        let path = config.project_root.join(&model.yaml_path);
+        println!("{} {} ({})", "Model".bold(), model.model_name, model.yaml_path.display());
+        for change in &model.changes {
+            println!("  {}", change.describe());
+        }
+
+        let diff = reconcile_file(&path, model, write)
+            .map_err(|e| anyhow::anyhow!("Failed to update contract for {}: {}", model.model_name, e))?;
+        if !diff.is_empty() {
+            println!();
+            for line in diff.lines() {
+                match line.chars().next() {
+                    Some('-') => println!("{}", line.red()),
+                    Some('+') => println!("{}", line.green()),
+                    _ => println!("{}", line.dimmed()),
+                }
+            }
+        }
+        println!();
+    }
+
+    if !plan.skipped.is_empty() {
+        println!("{}", "Not accepted:".yellow().bold());
+        for reason in &plan.skipped {
+            println!("  {} {}", "⚠".yellow(), reason);
+        }
+        println!();
+    }
+
+    if plan.is_empty() {
+        println!("{}", "No drift to accept.".green());
+    } else if write {
+        println!("{} {} contract(s)", "Updated".green().bold(), plan.models.len());
+    } else {
+        println!("{}", "Dry run: re-run with --write to apply these changes.".cyan());
     }
 
     Ok(())
@@ -869,7 +2295,7 @@ fn parse_table_identifier(
 }
 
 /// Print drift detection summary
-fn print_drift_summary(report: &Report, checked_models: usize, models_with_drift: usize, skipped_models: usize) {
+fn print_drift_summary(report: &Report, checked_models: usize, models_with_drift: usize, skipped_models: usize, ignored_models: &[(String, IgnoreReason)]) {
     println!("\n{}", "=".repeat(60).bright_blue());
     println!("{}", "Schema Drift Detection Report".bold().bright_blue());
     println!("{}", "=".repeat(60).bright_blue());
@@ -880,6 +2306,9 @@ fn print_drift_summary(report: &Report, checked_models: usize, models_with_drift
     if skipped_models > 0 {
         println!("Models skipped: {}", format!("{}", skipped_models).yellow());
     }
+    for line in ignored_models_summary(ignored_models) {
+        println!("{}", line);
+    }
     println!();
 
     println!("{}", "Summary:".bold());
@@ -1028,12 +2457,73 @@ fn generate_config_template(dialect: DialectConfig) -> String {
 # SQL dialect for your dbt project
 dialect = "{dialect_str}"
 
+# dbt target directory holding manifest.json
+# Defaults to $DBT_TARGET_PATH, then "target"; --target-path overrides both
+# target_path = "target"
+
+# Only fail CI on errors with these codes; everything else is still reported
+# Defaults to failing on any error; --fail-on CODE overrides
+# fail_on_codes = ["DRIFT_COLUMN_DROPPED"]
+
+# Cap diagnostics per model so broken models don't flood the report
+# max_diagnostics_per_model = 20
+
 # Severity overrides for specific diagnostic codes
 # Uncomment to change default severities
 [severity.overrides]
 # CONTRACT_EXTRA_COLUMN = "warn"
 # SQL_SELECT_STAR_UNEXPANDABLE = "info"
 
+# Text appended to messages for specific codes when rendering ({{model}}, {{column}})
+[messages]
+# CONTRACT_TYPE_MISMATCH = "See runbook: https://wiki.example.com/contracts#{{column}}"
+
+# Contract enforcement
+[contract]
+# How model columns not declared in the contract are treated:
+# "warn" (default), "strict" (error) or "additive" (info)
+extra_columns = "warn"
+# Warn when output column order differs from the contract (for positional loaders)
+enforce_column_order = false
+# Models whose path matches one of these globs must enforce a contract
+# require_contract_paths = ["models/marts/*"]
+# Warn when fewer than this fraction of contract columns infer to a concrete type
+# min_inference_coverage = 0.8
+
+[inference]
+# Maximum nesting depth of subqueries and expressions before inference fails
+max_depth = 256
+# Treat columns filtered by `col = <literal>` or `col IS NOT NULL` in WHERE as non-nullable
+nullability_from_where = false
+
+# dbt variables for var() in model Jinja (--vars overrides)
+[vars]
+# include_deleted = false
+
+# Drift detection: tables and schemas that are never fetched (glob patterns)
+[drift]
+ignore_tables = [
+    # "analytics.tmp_*"
+]
+ignore_schemas = [
+    # "staging"
+]
+# Loader-managed columns that are never reported as drift (glob patterns)
+ignore_columns = [
+    # "_fivetran_*"
+]
+# Per-table drift summary: "off", "alongside" or "instead" of per-column findings
+summary = "off"
+# Compare view models' warehouse SQL with their compiled SQL (PostgreSQL)
+check_view_definitions = false
+# Report timestamp <-> timestamptz changes as type drift
+timezone_aware = false
+# Treat NUMERIC(p,0) and integer columns as the same type
+treat_integral_decimal_as_int = false
+# Map warehouse type names (domains, vendor aliases) to known types
+# [drift.type_aliases]
+# money_t = "NUMERIC(19,4)"
+
 # Allowlist rules (glob patterns)
 [allowlist]
 # Allow type widening for specific models
@@ -1052,6 +2542,13 @@ skip_models = [
     # "test_*"
 ]
 
+# Per-environment overlays, applied with --env <name>
+# [env.prod]
+# strict = true
+# fail_on_codes = []
+# [env.prod.severity.overrides]
+# SQL_SELECT_STAR_UNEXPANDABLE = "error"
+
 # Warehouse connection (for drift detection)
 # Uncomment and configure for your warehouse
 # [warehouse]
@@ -1060,12 +2557,14 @@ skip_models = [
 # [warehouse.settings]
 # # BigQuery settings
 # # project_id = "your-project"
-# # credentials = "path/to/credentials.json"  # Or use ADC (Application Default Credentials)
+# # credentials = "path/to/credentials.json"  # Or "${{GCP_SA_KEY}}" holding the key JSON, or use ADC
+# # location = "EU"  # Dataset region, for datasets outside the US multi-region
 #
 # # Snowflake settings
 # # account = "your-account"
 # # username = "your-username"
 # # password = "${{SNOWFLAKE_PASSWORD}}"  # Use environment variable
+# # private_key_path = "path/to/rsa_key.p8"  # Or key-pair (JWT) authentication
 # # warehouse = "your-warehouse"
 # # role = "your-role"
 "#)
@@ -1184,7 +2683,7 @@ jobs:
 /// Init contracts command - generate contracts from current schemas
 #[allow(clippy::too_many_arguments)]
 fn init_contracts_command(
-    _config: &Config,
+    config: &Config,
     models: &[String],
     output_dir: &Path,
     manifest_path: &Path,
@@ -1198,14 +2697,14 @@ fn init_contracts_command(
     }
 
     // Load manifest
-    if !manifest_path.exists() {
+    if !is_stdin(manifest_path) && !manifest_path.exists() {
         return Err(anyhow::anyhow!(
             "Manifest not found at {}. Run 'dbt compile' first.",
             manifest_path.display()
         ));
     }
 
-    let manifest = Manifest::from_file(manifest_path)
+    let manifest = load_manifest(manifest_path)
         .map_err(|e| anyhow::anyhow!("Failed to load manifest: {}", e))?;
 
     if verbose {
@@ -1283,7 +2782,7 @@ fn init_contracts_command(
         }
 
         // Generate contract YAML
-        let contract_yaml = generate_contract_yaml(node_id, node, &catalog_data, &manifest)?;
+        let contract_yaml = generate_contract_yaml(config, node_id, node, &catalog_data, &manifest)?;
 
         // Write to file
         std::fs::write(&contract_file, contract_yaml)?;
@@ -1315,6 +2814,7 @@ fn init_contracts_command(
 
 /// Generate contract YAML for a model
 fn generate_contract_yaml(
+    config: &Config,
     node_id: &str,
     node: &schemarefly_dbt::ManifestNode,
     catalog_data: &Option<serde_json::Value>,
@@ -1337,21 +2837,28 @@ fn generate_contract_yaml(
     yaml.push_str("  columns:\n");
 
     // Try to get columns from catalog first
-    let columns = get_columns_for_model(node_id, node, catalog_data, manifest);
+    let columns = get_columns_for_model(config, node_id, node, catalog_data, manifest);
 
     for (col_name, col_type, description) in columns {
-        yaml.push_str(&format!("    - name: {}\n", col_name));
-        yaml.push_str(&format!("      data_type: {}\n", col_type));
-        if let Some(desc) = description {
-            yaml.push_str(&format!("      description: \"{}\"\n", desc));
-        }
+        yaml.push_str(&contract_column_yaml("    ", &col_name, &col_type, description.as_deref()));
     }
 
     Ok(yaml)
 }
 
+/// YAML for one contract column entry, with the `- name:` line at `indent`
+fn contract_column_yaml(indent: &str, name: &str, data_type: &str, description: Option<&str>) -> String {
+    let mut yaml = format!("{}- name: {}\n", indent, name);
+    yaml.push_str(&format!("{}  data_type: {}\n", indent, data_type));
+    if let Some(desc) = description {
+        yaml.push_str(&format!("{}  description: \"{}\"\n", indent, desc));
+    }
+    yaml
+}
+
 /// Get columns for a model from catalog or SQL inference
 fn get_columns_for_model(
+    config: &Config,
     node_id: &str,
     node: &schemarefly_dbt::ManifestNode,
     catalog_data: &Option<serde_json::Value>,
@@ -1399,15 +2906,12 @@ fn get_columns_for_model(
     }
 
     // Fall back to SQL inference
-    let sql_path = Path::new(&node.original_file_path);
-    if sql_path.exists() {
-        if let Ok(sql_content) = std::fs::read_to_string(sql_path) {
-            let (preprocessed, _) = DbtFunctionExtractor::preprocess(&sql_content, Some(manifest));
-
-            // Simple column extraction from SELECT statement
-            if let Some(inferred) = infer_columns_from_sql(&preprocessed) {
-                return inferred;
-            }
+    if let Ok((_, sql_content)) = read_model_sql(&config.project_root, node) {
+        let (preprocessed, _) = DbtFunctionExtractor::preprocess_with_vars(&sql_content, Some(manifest), &config.vars);
+
+        // Simple column extraction from SELECT statement
+        if let Some(inferred) = infer_columns_from_sql(&preprocessed) {
+            return inferred;
         }
     }
 
@@ -1466,181 +2970,6 @@ fn infer_columns_from_sql(sql: &str) -> Option<Vec<(String, String, Option<Strin
     }
 }
 
-/// Print report summary to stdout
-fn print_report_summary(report: &Report) {
-    println!("\n{}", "=".repeat(60).bright_blue());
-    println!("{}", "Schema Contract Check Report".bold().bright_blue());
-    println!("{}", "=".repeat(60).bright_blue());
-    println!();
-
-    println!("Version: {}", report.version);
-    println!("Timestamp: {}", report.timestamp);
-    println!();
-
-    println!("{}", "Summary:".bold());
-    println!("  Total diagnostics: {}", report.summary.total);
-
-    if report.summary.errors > 0 {
-        println!("  Errors:   {}", format!("{}", report.summary.errors).red().bold());
-    } else {
-        println!("  Errors:   {}", format!("{}", report.summary.errors).green());
-    }
-
-    if report.summary.warnings > 0 {
-        println!("  Warnings: {}", format!("{}", report.summary.warnings).yellow());
-    } else {
-        println!("  Warnings: {}", format!("{}", report.summary.warnings).green());
-    }
-
-    println!("  Info:     {}", report.summary.info);
-    println!();
-
-    if report.diagnostics.is_empty() {
-        println!("{}", "✓ No issues found!".green().bold());
-    } else {
-        println!("{}", "Diagnostics:".bold());
-        for diag in &report.diagnostics {
-            let severity_str = match diag.severity {
-                schemarefly_core::Severity::Error => "ERROR".red().bold(),
-                schemarefly_core::Severity::Warn => "WARN".yellow().bold(),
-                schemarefly_core::Severity::Info => "INFO".cyan(),
-            };
-
-            println!("  [{}] {}: {}", severity_str, diag.code, diag.message);
-
-            if let Some(loc) = &diag.location {
-                print!("    at {}:", loc.file);
-                if let Some(line) = loc.line {
-                    print!("{}", line);
-                }
-                println!();
-            }
-
-            if diag.expected.is_some() || diag.actual.is_some() {
-                if let Some(exp) = &diag.expected {
-                    println!("    Expected: {}", exp);
-                }
-                if let Some(act) = &diag.actual {
-                    println!("    Actual:   {}", act);
-                }
-            }
-
-            if !diag.impact.is_empty() {
-                println!("    Impact: {} downstream models", diag.impact.len());
-                for model in &diag.impact {
-                    println!("      - {}", model);
-                }
-            }
-        }
-    }
-
-    println!();
-    println!("{}", "=".repeat(60).bright_blue());
-}
-
-/// Generate markdown report
-fn generate_markdown_report(report: &Report, state_comparison: Option<&StateComparisonResult>) -> String {
-    let mut md = String::new();
-
-    md.push_str("# Schema Contract Check Report\n\n");
-    md.push_str(&format!("**Version:** {}\n\n", report.version));
-    md.push_str(&format!("**Timestamp:** {}\n\n", report.timestamp));
-
-    // Add Slim CI section if state comparison was performed
-    if let Some(comparison) = state_comparison {
-        md.push_str("## Slim CI Analysis\n\n");
-        md.push_str("This report was generated in **Slim CI mode**, comparing against a production state manifest.\n\n");
-
-        md.push_str("### Change Summary\n\n");
-        md.push_str("| Metric | Count |\n");
-        md.push_str("|--------|-------|\n");
-        md.push_str(&format!("| Modified models | {} |\n", comparison.modified_models.len()));
-        md.push_str(&format!("| New models | {} |\n", comparison.new_models.len()));
-        md.push_str(&format!("| Deleted models | {} |\n", comparison.deleted_models.len()));
-        md.push_str(&format!("| Total blast radius | {} |\n", comparison.total_blast_radius));
-        md.push('\n');
-
-        if !comparison.modified_models.is_empty() {
-            md.push_str("### Modified Models\n\n");
-            for modified in &comparison.modified_models {
-                let reasons: Vec<String> = modified.reasons.iter().map(|r| r.to_string()).collect();
-                md.push_str(&format!("#### `{}`\n\n", modified.name));
-                md.push_str(&format!("- **Unique ID:** `{}`\n", modified.unique_id));
-                md.push_str(&format!("- **Reason:** {}\n", reasons.join(", ")));
-                md.push_str(&format!("- **Downstream impact:** {} models\n", modified.downstream_count));
-
-                if !modified.downstream_impact.is_empty() {
-                    md.push_str("\n**Affected downstream models:**\n\n");
-                    for downstream in &modified.downstream_impact {
-                        md.push_str(&format!("- `{}`\n", downstream));
-                    }
-                }
-                md.push('\n');
-            }
-        }
-
-        if !comparison.deleted_models.is_empty() {
-            md.push_str("### Deleted Models\n\n");
-            md.push_str("⚠️ The following models were removed:\n\n");
-            for deleted in &comparison.deleted_models {
-                md.push_str(&format!("- `{}`\n", deleted));
-            }
-            md.push('\n');
-        }
-
-        md.push_str("---\n\n");
-    }
-
-    md.push_str("## Summary\n\n");
-    md.push_str(&format!("- Total diagnostics: {}\n", report.summary.total));
-    md.push_str(&format!("- Errors: {}\n", report.summary.errors));
-    md.push_str(&format!("- Warnings: {}\n", report.summary.warnings));
-    md.push_str(&format!("- Info: {}\n", report.summary.info));
-    md.push('\n');
-
-    if report.diagnostics.is_empty() {
-        md.push_str("✅ **No issues found!**\n");
-    } else {
-        md.push_str("## Diagnostics\n\n");
-
-        for diag in &report.diagnostics {
-            let severity_emoji = match diag.severity {
-                schemarefly_core::Severity::Error => "❌",
-                schemarefly_core::Severity::Warn => "⚠️",
-                schemarefly_core::Severity::Info => "ℹ️",
-            };
-
-            md.push_str(&format!("### {} {} - {}\n\n", severity_emoji, diag.severity, diag.code));
-            md.push_str(&format!("{}\n\n", diag.message));
-
-            if let Some(loc) = &diag.location {
-                md.push_str(&format!("**Location:** {}", loc.file));
-                if let Some(line) = loc.line {
-                    md.push_str(&format!(":{}", line));
-                }
-                md.push_str("\n\n");
-            }
-
-            if let Some(exp) = &diag.expected {
-                md.push_str(&format!("**Expected:** `{}`\n\n", exp));
-            }
-            if let Some(act) = &diag.actual {
-                md.push_str(&format!("**Actual:** `{}`\n\n", act));
-            }
-
-            if !diag.impact.is_empty() {
-                md.push_str(&format!("**Impact:** {} downstream models\n\n", diag.impact.len()));
-                for model in &diag.impact {
-                    md.push_str(&format!("- {}\n", model));
-                }
-                md.push('\n');
-            }
-        }
-    }
-
-    md
-}
-
 /// Generate PR comment markdown (optimized for GitHub PRs)
 /// Includes status badge, collapsible details, and concise summary
 fn generate_pr_comment(report: &Report, state_comparison: Option<&StateComparisonResult>) -> String {
@@ -1756,10 +3085,289 @@ fn generate_pr_comment(report: &Report, state_comparison: Option<&StateCompariso
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    /// Manifest holding `nodes`, keyed by their `unique_id`
+    pub(crate) fn test_manifest(nodes: &[serde_json::Value]) -> Manifest {
+        let nodes: serde_json::Map<String, serde_json::Value> = nodes
+            .iter()
+            .map(|node| (node["unique_id"].as_str().unwrap().to_string(), node.clone()))
+            .collect();
+        let manifest = json!({
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": nodes,
+            "sources": {}
+        });
+        Manifest::from_str(&manifest.to_string()).unwrap()
+    }
+
+    /// Contracted model `model.shop.<name>` built as `db.analytics.<name>` with an integer `id`
+    pub(crate) fn test_model(name: &str) -> serde_json::Value {
+        json!({
+            "unique_id": format!("model.shop.{name}"), "name": name, "resource_type": "model",
+            "package_name": "shop", "path": format!("{name}.sql"), "original_file_path": format!("models/{name}.sql"),
+            "database": "db", "schema": "analytics",
+            "config": {"contract": {"enforced": true}},
+            "columns": {"id": {"name": "id", "data_type": "integer"}}
+        })
+    }
 
     #[test]
     fn verify_cli() {
         use clap::CommandFactory;
         Cli::command().debug_assert();
     }
+
+    #[tokio::test]
+    async fn drift_skips_ignored_schemas_without_fetching() {
+        use schemarefly_catalog::{FetchError, MockAdapter};
+        use schemarefly_core::{Column, LogicalType};
+
+        let mut stg_users = test_model("stg_users");
+        stg_users["schema"] = json!("staging");
+        let manifest = test_manifest(&[test_model("users"), stg_users]);
+
+        let adapter = MockAdapter::new();
+        adapter
+            .add_schema(
+                TableIdentifier::new("db", "analytics", "users"),
+                Schema::from_columns(vec![Column::new("id", LogicalType::Int)]),
+            )
+            .await;
+        // Fetching the staging table would fail and mark the model as skipped
+        adapter
+            .add_error_for_table(
+                TableIdentifier::new("db", "staging", "stg_users"),
+                FetchError::QueryError("should not be fetched".to_string()),
+            )
+            .await;
+
+        let mut config = Config::default();
+        config.drift.ignore_schemas = vec!["staging".to_string()];
+
+        let run = detect_drift(&config, &manifest, &adapter, &mut FingerprintCache::new(), &CancellationToken::new(), false).await;
+
+        assert_eq!(run.ignored_models, vec![("stg_users".to_string(), IgnoreReason::Config)]);
+        assert!(run.skipped_models.is_empty(), "{:?}", run.skipped_models);
+        assert_eq!(run.checked_models, 1);
+        assert_eq!(run.detections.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn drift_ignores_ephemeral_models() {
+        use schemarefly_catalog::{FetchError, MockAdapter};
+        use schemarefly_core::{Column, LogicalType};
+
+        let mut int_users = test_model("int_users");
+        int_users["config"]["materialized"] = json!("ephemeral");
+        let manifest = test_manifest(&[test_model("users"), int_users]);
+
+        let adapter = MockAdapter::new();
+        adapter
+            .add_schema(
+                TableIdentifier::new("db", "analytics", "users"),
+                Schema::from_columns(vec![Column::new("id", LogicalType::Int)]),
+            )
+            .await;
+        // There is no table behind an ephemeral model
+        adapter
+            .add_error_for_table(
+                TableIdentifier::new("db", "analytics", "int_users"),
+                FetchError::TableNotFound("db.analytics.int_users".to_string()),
+            )
+            .await;
+
+        let run = detect_drift(&Config::default(), &manifest, &adapter, &mut FingerprintCache::new(), &CancellationToken::new(), false).await;
+
+        assert_eq!(run.ignored_models, vec![("int_users".to_string(), IgnoreReason::Ephemeral)]);
+        assert!(run.skipped_models.is_empty(), "{:?}", run.skipped_models);
+        assert_eq!(run.checked_models, 1);
+    }
+
+    #[tokio::test]
+    async fn drift_summary_counts_each_kind_of_ignored_model() {
+        use schemarefly_catalog::MockAdapter;
+        use schemarefly_core::{Column, LogicalType};
+
+        let mut int_users = test_model("int_users");
+        int_users["config"]["materialized"] = json!("ephemeral");
+        let mut legacy = test_model("legacy");
+        legacy["meta"] = json!({"schemarefly": {"ignore_drift": true}});
+        let mut stg_users = test_model("stg_users");
+        stg_users["schema"] = json!("staging");
+        let mut stg_orders = test_model("stg_orders");
+        stg_orders["schema"] = json!("staging");
+        let manifest = test_manifest(&[test_model("users"), int_users, legacy, stg_users, stg_orders]);
+
+        let adapter = MockAdapter::new();
+        adapter
+            .add_schema(
+                TableIdentifier::new("db", "analytics", "users"),
+                Schema::from_columns(vec![Column::new("id", LogicalType::Int)]),
+            )
+            .await;
+
+        let mut config = Config::default();
+        config.drift.ignore_schemas = vec!["staging".to_string()];
+
+        let run = detect_drift(&config, &manifest, &adapter, &mut FingerprintCache::new(), &CancellationToken::new(), false).await;
+
+        assert_eq!(
+            ignored_models_summary(&run.ignored_models),
+            vec![
+                "Ephemeral models ignored: 1".to_string(),
+                "Models ignored by ignore_drift meta: 1".to_string(),
+                "Models ignored by [drift] config: 2".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn summarized_dropped_column_still_fails_drift() {
+        use schemarefly_catalog::MockAdapter;
+        use schemarefly_core::{Column, LogicalType};
+
+        let mut users = test_model("users");
+        users["columns"]["email"] = json!({"name": "email", "data_type": "varchar"});
+        let manifest = test_manifest(&[users]);
+
+        let adapter = MockAdapter::new();
+        adapter
+            .add_schema(
+                TableIdentifier::new("db", "analytics", "users"),
+                Schema::from_columns(vec![Column::new("id", LogicalType::Int)]),
+            )
+            .await;
+
+        let run = detect_drift(&Config::default(), &manifest, &adapter, &mut FingerprintCache::new(), &CancellationToken::new(), false).await;
+
+        let summarized = drift_diagnostics(&run.detections, DriftSummaryMode::Instead);
+        assert_eq!(summarized.len(), 1);
+        assert_eq!(summarized[0].code, schemarefly_core::DiagnosticCode::DriftSummary);
+        assert!(Report::from_diagnostics(summarized).fails_on(&[]));
+
+        // `fail_on_codes` still sees the finding the summary replaced
+        let detailed = drift_diagnostics(&run.detections, DriftSummaryMode::Off);
+        assert!(Report::from_diagnostics(detailed).fails_on(&[schemarefly_core::DiagnosticCode::DriftColumnDropped]));
+    }
+
+    #[tokio::test]
+    async fn drift_fetches_a_shared_table_once() {
+        use schemarefly_catalog::MockAdapter;
+        use schemarefly_core::{Column, LogicalType};
+
+        // Two packages build the same relation
+        let mut legacy = test_model("users");
+        legacy["unique_id"] = json!("model.legacy.users");
+        legacy["package_name"] = json!("legacy");
+        let manifest = test_manifest(&[test_model("users"), legacy]);
+
+        let adapter = MockAdapter::new();
+        adapter
+            .add_schema(
+                TableIdentifier::new("db", "analytics", "users"),
+                Schema::from_columns(vec![Column::new("id", LogicalType::Int)]),
+            )
+            .await;
+
+        let run = detect_drift(&Config::default(), &manifest, &adapter, &mut FingerprintCache::new(), &CancellationToken::new(), false).await;
+
+        assert_eq!(run.checked_models, 2);
+        assert_eq!(run.warehouse_cache, CacheStats { hits: 1, misses: 1 });
+    }
+
+    #[tokio::test]
+    async fn drift_prefetches_tables_when_the_adapter_batches() {
+        use schemarefly_catalog::{AdapterCapabilities, MockAdapter};
+        use schemarefly_core::{Column, LogicalType};
+
+        let manifest = test_manifest(&[test_model("users"), test_model("orders"), test_model("missing")]);
+
+        let adapter = MockAdapter::new().with_capabilities(AdapterCapabilities {
+            supports_batch_fetch: true,
+            ..AdapterCapabilities::default()
+        });
+        for name in ["users", "orders"] {
+            adapter
+                .add_schema(
+                    TableIdentifier::new("db", "analytics", name),
+                    Schema::from_columns(vec![Column::new("id", LogicalType::Int)]),
+                )
+                .await;
+        }
+
+        let run = detect_drift(&Config::default(), &manifest, &adapter, &mut FingerprintCache::new(), &CancellationToken::new(), false).await;
+
+        // Prefetched tables are served from the cache; the missing one falls back to a single fetch
+        assert_eq!(run.checked_models, 2);
+        assert_eq!(run.warehouse_cache, CacheStats { hits: 2, misses: 1 });
+        assert_eq!(run.skipped_models.len(), 1);
+        assert!(run.skipped_models[0].1.contains("Table not found"), "{}", run.skipped_models[0].1);
+    }
+
+    #[tokio::test]
+    async fn drift_skips_constraint_checks_the_adapter_does_not_support() {
+        use schemarefly_catalog::{AdapterCapabilities, MockAdapter};
+        use schemarefly_core::{Column, LogicalType, Nullability};
+
+        let manifest = test_manifest(&[test_model("users")]);
+
+        // A NOT NULL column without a default is a warning only when constraints are known
+        let warehouse = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("tenant_id", LogicalType::Int)
+                .with_nullability(Nullability::No)
+                .with_has_default(false),
+        ]);
+
+        let mut severities = Vec::new();
+        for supports_constraints in [true, false] {
+            let adapter = MockAdapter::new().with_capabilities(AdapterCapabilities {
+                supports_constraints,
+                ..AdapterCapabilities::default()
+            });
+            adapter.add_schema(TableIdentifier::new("db", "analytics", "users"), warehouse.clone()).await;
+
+            let run = detect_drift(&Config::default(), &manifest, &adapter, &mut FingerprintCache::new(), &CancellationToken::new(), false).await;
+            let added = run.detections[0].diagnostics.iter().find(|d| d.code == DiagnosticCode::DriftColumnAdded).unwrap();
+            severities.push(added.severity);
+        }
+
+        assert_eq!(severities, vec![schemarefly_core::Severity::Warn, schemarefly_core::Severity::Info]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelled_drift_run_keeps_completed_models() {
+        use schemarefly_catalog::MockAdapter;
+        use schemarefly_core::{Column, LogicalType};
+
+        let names = ["orders", "payments", "users"];
+        let models: Vec<serde_json::Value> = names.iter().map(|name| test_model(name)).collect();
+        let manifest = test_manifest(&models);
+
+        // Every fetch takes 100ms; cancelling at 150ms interrupts the second one
+        let adapter = MockAdapter::new().with_latency(100);
+        for name in names {
+            adapter
+                .add_schema(
+                    TableIdentifier::new("db", "analytics", name),
+                    Schema::from_columns(vec![Column::new("id", LogicalType::Int)]),
+                )
+                .await;
+        }
+
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+            trigger.cancel();
+        });
+
+        let run = detect_drift(&Config::default(), &manifest, &adapter, &mut FingerprintCache::new(), &cancel, false).await;
+
+        assert!(run.cancelled);
+        assert_eq!(run.checked_models, 1);
+        assert_eq!(run.detections.len(), 1);
+        assert!(run.skipped_models.is_empty(), "{:?}", run.skipped_models);
+    }
 }
@@ -0,0 +1,238 @@
+//! Locating the dbt manifest for `check`, `drift` and Slim CI runs
+//!
+//! `locate_manifest` looks in the configured target directory first, then in
+//! the standard dbt locations. The file it finds is read once and parsed
+//! once; a file that can't be read or doesn't parse is reported with its own
+//! process exit code so CI scripts can tell "run dbt compile first" apart
+//! from a broken artifact. With `[manifest_source]` configured,
+//! `load_manifest_json` downloads the manifest from dbt Cloud instead.
+
+use std::path::{Path, PathBuf};
+
+use schemarefly_core::{Config, ManifestSourceConfig};
+use schemarefly_dbt::manifest::ManifestError;
+use schemarefly_dbt::{DbtCloudClient, Manifest};
+
+/// Exit code when no manifest exists at any searched location
+pub const EXIT_MANIFEST_NOT_FOUND: i32 = 3;
+
+/// Exit code when the manifest exists but cannot be read
+pub const EXIT_MANIFEST_UNREADABLE: i32 = 4;
+
+/// Exit code when the manifest is not valid JSON
+pub const EXIT_MANIFEST_INVALID_JSON: i32 = 5;
+
+/// Why a manifest could not be located
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ManifestLocateError {
+    #[error("Manifest not found (searched {}). Run 'dbt compile' or 'dbt build' first.", format_paths(.searched))]
+    NotFound { searched: Vec<PathBuf> },
+
+    #[error("Manifest at {} could not be read: {reason}", .path.display())]
+    Unreadable { path: PathBuf, reason: String },
+
+    #[error("Manifest at {} is not valid JSON: {reason}", .path.display())]
+    InvalidJson { path: PathBuf, reason: String },
+}
+
+impl ManifestLocateError {
+    /// Process exit code for this failure
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ManifestLocateError::NotFound { .. } => EXIT_MANIFEST_NOT_FOUND,
+            ManifestLocateError::Unreadable { .. } => EXIT_MANIFEST_UNREADABLE,
+            ManifestLocateError::InvalidJson { .. } => EXIT_MANIFEST_INVALID_JSON,
+        }
+    }
+}
+
+fn format_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Candidate manifest paths, most specific first
+///
+/// The configured target directory (config, then `DBT_TARGET_PATH`, then
+/// `target`) comes first; dbt's default `target/` directory is searched as a
+/// fallback when the configured one differs. Gzipped manifests are accepted.
+pub fn manifest_candidates(config: &Config) -> Vec<PathBuf> {
+    let mut dirs = vec![config.target_dir()];
+    let default_dir = config.project_root.join(schemarefly_core::config::DEFAULT_TARGET_PATH);
+    if !dirs.contains(&default_dir) {
+        dirs.push(default_dir);
+    }
+
+    dirs.iter()
+        .flat_map(|dir| [dir.join("manifest.json"), dir.join("manifest.json.gz")])
+        .collect()
+}
+
+/// Find the manifest for `config`
+pub fn locate_manifest(config: &Config) -> Result<PathBuf, ManifestLocateError> {
+    let candidates = manifest_candidates(config);
+
+    match candidates.iter().find(|path| path.exists()) {
+        Some(path) => Ok(path.clone()),
+        None => Err(ManifestLocateError::NotFound { searched: candidates }),
+    }
+}
+
+/// Manifest JSON text and where it was read from
+pub struct LoadedManifest {
+    /// File path or download URL, for messages
+    pub source: String,
+
+    /// Manifest JSON (decompressed)
+    pub json: String,
+}
+
+impl LoadedManifest {
+    /// Parse the manifest, reporting a malformed one as invalid JSON
+    pub fn parse(&self) -> Result<Manifest, ManifestLocateError> {
+        parse_manifest(Path::new(&self.source), &self.json)
+    }
+}
+
+/// Read the manifest JSON used by `check` and `drift`
+///
+/// Downloads from dbt Cloud when `[manifest_source]` is configured,
+/// otherwise reads the file found by [`locate_manifest`].
+pub async fn load_manifest_json(config: &Config) -> anyhow::Result<LoadedManifest> {
+    match &config.manifest_source {
+        Some(ManifestSourceConfig::DbtCloud(cloud)) => {
+            let client = DbtCloudClient::new(cloud.clone());
+            let json = client.fetch_manifest_json().await?;
+            Ok(LoadedManifest { source: client.artifact_url("manifest.json"), json })
+        }
+        None => {
+            let path = locate_manifest(config)?;
+            let json = read_manifest_json(&path)?;
+            Ok(LoadedManifest { source: path.display().to_string(), json })
+        }
+    }
+}
+
+/// Read and parse the manifest at `path`, e.g. a Slim CI state manifest
+pub fn read_manifest(path: &Path) -> Result<Manifest, ManifestLocateError> {
+    parse_manifest(path, &read_manifest_json(path)?)
+}
+
+/// Read the (possibly gzipped) manifest JSON at `path` without parsing it
+fn read_manifest_json(path: &Path) -> Result<String, ManifestLocateError> {
+    if !path.exists() {
+        return Err(ManifestLocateError::NotFound { searched: vec![path.to_path_buf()] });
+    }
+
+    Manifest::read_json_file(path).map_err(|e| ManifestLocateError::Unreadable {
+        path: path.to_path_buf(),
+        reason: error_reason(e),
+    })
+}
+
+/// Parse manifest JSON read from `path`
+fn parse_manifest(path: &Path, json: &str) -> Result<Manifest, ManifestLocateError> {
+    Manifest::from_str_partial(json).map_err(|e| ManifestLocateError::InvalidJson {
+        path: path.to_path_buf(),
+        reason: error_reason(e),
+    })
+}
+
+/// Underlying reason of a manifest error, without its variant prefix
+fn error_reason(error: ManifestError) -> String {
+    match error {
+        ManifestError::IoError(_, reason)
+        | ManifestError::ParseError(reason)
+        | ManifestError::DownloadError(_, reason) => reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_for(root: &Path) -> Config {
+        Config {
+            project_root: root.to_path_buf(),
+            target_path: Some(PathBuf::from("target")),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn finds_manifest_in_configured_target() {
+        let root = tempfile::tempdir().unwrap();
+        let mut config = config_for(root.path());
+        config.target_path = Some(PathBuf::from("build/dbt"));
+
+        let dir = root.path().join("build/dbt");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("manifest.json"), "{}").unwrap();
+
+        assert_eq!(locate_manifest(&config).unwrap(), dir.join("manifest.json"));
+    }
+
+    #[test]
+    fn falls_back_to_default_target_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let mut config = config_for(root.path());
+        config.target_path = Some(PathBuf::from("build/dbt"));
+
+        let dir = root.path().join("target");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("manifest.json.gz"), "{}").unwrap();
+
+        assert_eq!(locate_manifest(&config).unwrap(), dir.join("manifest.json.gz"));
+    }
+
+    #[test]
+    fn missing_manifest_is_not_found() {
+        let root = tempfile::tempdir().unwrap();
+        let config = config_for(root.path());
+
+        let err = locate_manifest(&config).unwrap_err();
+        assert!(matches!(err, ManifestLocateError::NotFound { ref searched } if searched.len() == 2));
+        assert_eq!(err.exit_code(), EXIT_MANIFEST_NOT_FOUND);
+        assert!(err.to_string().contains("dbt compile"));
+    }
+
+    #[test]
+    fn unreadable_manifest_is_reported() {
+        let root = tempfile::tempdir().unwrap();
+        let config = config_for(root.path());
+
+        // A directory where the file should be exists but cannot be read
+        std::fs::create_dir_all(root.path().join("target/manifest.json")).unwrap();
+
+        let path = locate_manifest(&config).unwrap();
+        let err = read_manifest(&path).unwrap_err();
+        assert!(matches!(err, ManifestLocateError::Unreadable { .. }));
+        assert_eq!(err.exit_code(), EXIT_MANIFEST_UNREADABLE);
+    }
+
+    #[test]
+    fn invalid_json_manifest_is_reported() {
+        let root = tempfile::tempdir().unwrap();
+        let config = config_for(root.path());
+
+        std::fs::create_dir_all(root.path().join("target")).unwrap();
+        std::fs::write(root.path().join("target/manifest.json"), "{ \"nodes\": ").unwrap();
+
+        let path = locate_manifest(&config).unwrap();
+        let err = read_manifest(&path).unwrap_err();
+        assert!(matches!(err, ManifestLocateError::InvalidJson { .. }));
+        assert_eq!(err.exit_code(), EXIT_MANIFEST_INVALID_JSON);
+    }
+
+    #[test]
+    fn read_reports_missing_state_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("prod/manifest.json");
+
+        let err = read_manifest(&path).unwrap_err();
+        assert_eq!(err, ManifestLocateError::NotFound { searched: vec![path] });
+    }
+}
@@ -0,0 +1,643 @@
+//! Report rendering
+//!
+//! Rendering is decoupled from stdout through the `OutputWriter` trait so each
+//! format can be unit-tested against an in-memory buffer. The CLI picks a
+//! writer based on `--format` and drives it with `write_report`.
+//!
+//! Configured `[messages]` templates are appended here, at render time, so the
+//! stored diagnostic messages (and the report's content hash) stay stable.
+
+use colored::Colorize;
+use schemarefly_core::{Diagnostic, MessageTemplates, Report, Severity};
+use schemarefly_engine::StateComparisonResult;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+/// Output format for the check report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Colored, human-readable summary
+    Human,
+
+    /// One aligned row per diagnostic
+    Table,
+
+    /// The full report.json document
+    Json,
+}
+
+/// Renders a report incrementally
+///
+/// Writers are driven in order: `write_summary` once, `write_diagnostic` for
+/// each diagnostic, then `finish`.
+pub trait OutputWriter {
+    /// Write the report header and summary counts
+    fn write_summary(&mut self, report: &Report) -> io::Result<()>;
+
+    /// Write a single diagnostic
+    fn write_diagnostic(&mut self, diagnostic: &Diagnostic) -> io::Result<()>;
+
+    /// Write any trailing output and flush
+    fn finish(&mut self, report: &Report) -> io::Result<()>;
+}
+
+/// Drive a writer over a complete report, appending configured message templates
+///
+/// Only per-diagnostic output is affected; writers that serialize the whole
+/// report (JSON) keep the original messages.
+pub fn write_report(
+    writer: &mut dyn OutputWriter,
+    report: &Report,
+    messages: &MessageTemplates,
+) -> io::Result<()> {
+    writer.write_summary(report)?;
+    for diagnostic in &report.diagnostics {
+        match messages.get(diagnostic.code) {
+            Some(template) => {
+                let mut rendered = diagnostic.clone();
+                rendered.message = format!("{} {}", diagnostic.message, expand_template(template, diagnostic));
+                writer.write_diagnostic(&rendered)?;
+            }
+            None => writer.write_diagnostic(diagnostic)?,
+        }
+    }
+    writer.finish(report)
+}
+
+/// Fill `{model}` and `{column}` placeholders from a diagnostic
+///
+/// The model is the file stem of the diagnostic's location and the column is
+/// the first quoted name in its message; missing values render as empty.
+fn expand_template(template: &str, diagnostic: &Diagnostic) -> String {
+    let model = diagnostic
+        .location
+        .as_ref()
+        .and_then(|loc| std::path::Path::new(&loc.file).file_stem())
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let column = diagnostic
+        .message
+        .split('\'')
+        .nth(1)
+        .unwrap_or_default();
+
+    template.replace("{model}", model).replace("{column}", column)
+}
+
+/// Create the writer for an output format
+pub fn writer_for<'a, W: Write + 'a>(format: OutputFormat, out: W) -> Box<dyn OutputWriter + 'a> {
+    match format {
+        OutputFormat::Human => Box::new(HumanWriter::new(out)),
+        OutputFormat::Table => Box::new(TableWriter::new(out)),
+        OutputFormat::Json => Box::new(JsonWriter::new(out)),
+    }
+}
+
+/// Colored terminal output (the default)
+pub struct HumanWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> HumanWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+}
+
+impl<W: Write> OutputWriter for HumanWriter<W> {
+    fn write_summary(&mut self, report: &Report) -> io::Result<()> {
+        let out = &mut self.out;
+
+        writeln!(out, "\n{}", "=".repeat(60).bright_blue())?;
+        writeln!(out, "{}", "Schema Contract Check Report".bold().bright_blue())?;
+        writeln!(out, "{}", "=".repeat(60).bright_blue())?;
+        writeln!(out)?;
+
+        writeln!(out, "Version: {}", report.version)?;
+        writeln!(out, "Timestamp: {}", report.timestamp)?;
+        writeln!(out)?;
+
+        writeln!(out, "{}", "Summary:".bold())?;
+        writeln!(out, "  Total diagnostics: {}", report.summary.total)?;
+
+        if report.summary.errors > 0 {
+            writeln!(out, "  Errors:   {}", format!("{}", report.summary.errors).red().bold())?;
+        } else {
+            writeln!(out, "  Errors:   {}", format!("{}", report.summary.errors).green())?;
+        }
+
+        if report.summary.warnings > 0 {
+            writeln!(out, "  Warnings: {}", format!("{}", report.summary.warnings).yellow())?;
+        } else {
+            writeln!(out, "  Warnings: {}", format!("{}", report.summary.warnings).green())?;
+        }
+
+        writeln!(out, "  Info:     {}", report.summary.info)?;
+        if report.summary.suppressed > 0 {
+            writeln!(out, "  Suppressed: {}", report.summary.suppressed)?;
+        }
+        writeln!(out)?;
+
+        if report.diagnostics.is_empty() {
+            writeln!(out, "{}", "✓ No issues found!".green().bold())?;
+        } else {
+            writeln!(out, "{}", "Diagnostics:".bold())?;
+        }
+
+        Ok(())
+    }
+
+    fn write_diagnostic(&mut self, diag: &Diagnostic) -> io::Result<()> {
+        let out = &mut self.out;
+
+        let severity_str = match diag.severity {
+            Severity::Error => "ERROR".red().bold(),
+            Severity::Warn => "WARN".yellow().bold(),
+            Severity::Info => "INFO".cyan(),
+        };
+
+        writeln!(out, "  [{}] {}: {}", severity_str, diag.code, diag.message)?;
+
+        if let Some(loc) = &diag.location {
+            write!(out, "    at {}:", loc.file)?;
+            if let Some(line) = loc.line {
+                write!(out, "{}", line)?;
+            }
+            writeln!(out)?;
+        }
+
+        for related in &diag.related {
+            write!(out, "    see {}", related.file)?;
+            if let Some(line) = related.line {
+                write!(out, ":{}", line)?;
+            }
+            writeln!(out)?;
+        }
+
+        if let Some(exp) = &diag.expected {
+            writeln!(out, "    Expected: {}", exp)?;
+        }
+        if let Some(act) = &diag.actual {
+            writeln!(out, "    Actual:   {}", act)?;
+        }
+
+        if !diag.impact.is_empty() {
+            writeln!(out, "    Impact: {} downstream models", diag.impact.len())?;
+            for model in &diag.impact {
+                writeln!(out, "      - {}", model)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self, _report: &Report) -> io::Result<()> {
+        writeln!(self.out)?;
+        writeln!(self.out, "{}", "=".repeat(60).bright_blue())?;
+        self.out.flush()
+    }
+}
+
+/// Plain aligned table, one row per diagnostic
+pub struct TableWriter<W: Write> {
+    out: W,
+    rows: Vec<[String; 4]>,
+}
+
+impl<W: Write> TableWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out, rows: Vec::new() }
+    }
+}
+
+impl<W: Write> OutputWriter for TableWriter<W> {
+    fn write_summary(&mut self, report: &Report) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "{} diagnostics ({} errors, {} warnings, {} info)",
+            report.summary.total, report.summary.errors, report.summary.warnings, report.summary.info
+        )
+    }
+
+    fn write_diagnostic(&mut self, diag: &Diagnostic) -> io::Result<()> {
+        let location = match &diag.location {
+            Some(loc) => match loc.line {
+                Some(line) => format!("{}:{}", loc.file, line),
+                None => loc.file.clone(),
+            },
+            None => "-".to_string(),
+        };
+
+        self.rows.push([
+            diag.severity.to_string().to_uppercase(),
+            diag.code.to_string(),
+            location,
+            diag.message.clone(),
+        ]);
+
+        Ok(())
+    }
+
+    fn finish(&mut self, _report: &Report) -> io::Result<()> {
+        if self.rows.is_empty() {
+            return self.out.flush();
+        }
+
+        let header = ["SEVERITY", "CODE", "LOCATION", "MESSAGE"].map(String::from);
+        let mut widths = header.clone().map(|h| h.len());
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for row in std::iter::once(&header).chain(&self.rows) {
+            writeln!(
+                self.out,
+                "{:<w0$}  {:<w1$}  {:<w2$}  {}",
+                row[0], row[1], row[2], row[3],
+                w0 = widths[0], w1 = widths[1], w2 = widths[2],
+            )?;
+        }
+
+        self.out.flush()
+    }
+}
+
+/// The full report as JSON (same shape as report.json)
+pub struct JsonWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> JsonWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+}
+
+impl<W: Write> OutputWriter for JsonWriter<W> {
+    fn write_summary(&mut self, _report: &Report) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_diagnostic(&mut self, _diagnostic: &Diagnostic) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn finish(&mut self, report: &Report) -> io::Result<()> {
+        let json = report.to_json().map_err(io::Error::other)?;
+        writeln!(self.out, "{}", json)?;
+        self.out.flush()
+    }
+}
+
+/// Markdown report (used for `--markdown`)
+///
+/// Diagnostics are grouped by model: a table of per-model counts comes
+/// first, then one collapsible section per model (expanded when the model
+/// has errors). Diagnostics without a model id are listed last.
+pub struct MarkdownWriter<'a, W: Write> {
+    out: W,
+    state_comparison: Option<&'a StateComparisonResult>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a, W: Write> MarkdownWriter<'a, W> {
+    pub fn new(out: W) -> Self {
+        Self { out, state_comparison: None, diagnostics: Vec::new() }
+    }
+
+    /// Include a Slim CI section for the given state comparison
+    pub fn with_state_comparison(mut self, comparison: Option<&'a StateComparisonResult>) -> Self {
+        self.state_comparison = comparison;
+        self
+    }
+}
+
+impl<W: Write> OutputWriter for MarkdownWriter<'_, W> {
+    fn write_summary(&mut self, report: &Report) -> io::Result<()> {
+        let md = &mut self.out;
+
+        write!(md, "# Schema Contract Check Report\n\n")?;
+        write!(md, "**Version:** {}\n\n", report.version)?;
+        write!(md, "**Timestamp:** {}\n\n", report.timestamp)?;
+
+        // Add Slim CI section if state comparison was performed
+        if let Some(comparison) = self.state_comparison {
+            write!(md, "## Slim CI Analysis\n\n")?;
+            write!(md, "This report was generated in **Slim CI mode**, comparing against a production state manifest.\n\n")?;
+
+            write!(md, "### Change Summary\n\n")?;
+            writeln!(md, "| Metric | Count |")?;
+            writeln!(md, "|--------|-------|")?;
+            writeln!(md, "| Modified models | {} |", comparison.modified_models.len())?;
+            writeln!(md, "| New models | {} |", comparison.new_models.len())?;
+            writeln!(md, "| Deleted models | {} |", comparison.deleted_models.len())?;
+            writeln!(md, "| Total blast radius | {} |", comparison.total_blast_radius)?;
+            writeln!(md)?;
+
+            if !comparison.modified_models.is_empty() {
+                write!(md, "### Modified Models\n\n")?;
+                for modified in &comparison.modified_models {
+                    let reasons: Vec<String> = modified.reasons.iter().map(|r| r.to_string()).collect();
+                    write!(md, "#### `{}`\n\n", modified.name)?;
+                    writeln!(md, "- **Unique ID:** `{}`", modified.unique_id)?;
+                    writeln!(md, "- **Reason:** {}", reasons.join(", "))?;
+                    writeln!(md, "- **Downstream impact:** {} models", modified.downstream_count)?;
+
+                    if !modified.downstream_impact.is_empty() {
+                        write!(md, "\n**Affected downstream models:**\n\n")?;
+                        for downstream in &modified.downstream_impact {
+                            writeln!(md, "- `{}`", downstream)?;
+                        }
+                    }
+                    writeln!(md)?;
+                }
+            }
+
+            if !comparison.deleted_models.is_empty() {
+                write!(md, "### Deleted Models\n\n")?;
+                write!(md, "⚠️ The following models were removed:\n\n")?;
+                for deleted in &comparison.deleted_models {
+                    writeln!(md, "- `{}`", deleted)?;
+                }
+                writeln!(md)?;
+            }
+
+            write!(md, "---\n\n")?;
+        }
+
+        write!(md, "## Summary\n\n")?;
+        writeln!(md, "- Total diagnostics: {}", report.summary.total)?;
+        writeln!(md, "- Errors: {}", report.summary.errors)?;
+        writeln!(md, "- Warnings: {}", report.summary.warnings)?;
+        writeln!(md, "- Info: {}", report.summary.info)?;
+        if report.summary.suppressed > 0 {
+            writeln!(md, "- Suppressed: {}", report.summary.suppressed)?;
+        }
+        writeln!(md)?;
+
+        if report.diagnostics.is_empty() {
+            writeln!(md, "✅ **No issues found!**")?;
+        } else {
+            write!(md, "## Diagnostics\n\n")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_diagnostic(&mut self, diag: &Diagnostic) -> io::Result<()> {
+        // Rendered in `finish`, once every model's diagnostics are known
+        self.diagnostics.push(diag.clone());
+        Ok(())
+    }
+
+    fn finish(&mut self, _report: &Report) -> io::Result<()> {
+        let mut by_model: BTreeMap<&str, Vec<&Diagnostic>> = BTreeMap::new();
+        let mut unattributed = Vec::new();
+        for diag in &self.diagnostics {
+            match diag.model_id.as_deref() {
+                Some(model) => by_model.entry(model).or_default().push(diag),
+                None => unattributed.push(diag),
+            }
+        }
+
+        let md = &mut self.out;
+        let mut groups: Vec<(String, Vec<&Diagnostic>)> = by_model
+            .into_iter()
+            .map(|(model, diags)| (format!("`{}`", model), diags))
+            .collect();
+        if !unattributed.is_empty() {
+            groups.push(("Other".to_string(), unattributed));
+        }
+
+        if !groups.is_empty() {
+            writeln!(md, "| Model | Status | Errors | Warnings | Info |")?;
+            writeln!(md, "|-------|--------|--------|----------|------|")?;
+            for (model, diags) in &groups {
+                let [errors, warnings, info] = severity_counts(diags);
+                writeln!(md, "| {} | {} | {} | {} | {} |", model, status_emoji(diags), errors, warnings, info)?;
+            }
+            writeln!(md)?;
+        }
+
+        for (model, diags) in &groups {
+            let [errors, warnings, info] = severity_counts(diags);
+            writeln!(md, "{}", if errors > 0 { "<details open>" } else { "<details>" })?;
+            write!(
+                md,
+                "<summary>{} {} ({} errors, {} warnings, {} info)</summary>\n\n",
+                status_emoji(diags), model, errors, warnings, info
+            )?;
+            for diag in diags {
+                write_markdown_diagnostic(md, diag)?;
+            }
+            write!(md, "</details>\n\n")?;
+        }
+
+        md.flush()
+    }
+}
+
+/// Error, warning and info counts of a model's diagnostics
+fn severity_counts(diags: &[&Diagnostic]) -> [usize; 3] {
+    let count = |severity| diags.iter().filter(|d| d.severity == severity).count();
+    [count(Severity::Error), count(Severity::Warn), count(Severity::Info)]
+}
+
+/// Badge for the most severe diagnostic of a model
+fn status_emoji(diags: &[&Diagnostic]) -> &'static str {
+    match diags.iter().map(|d| d.severity).max() {
+        Some(severity) => severity_emoji(severity),
+        None => "✅",
+    }
+}
+
+fn severity_emoji(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "❌",
+        Severity::Warn => "⚠️",
+        Severity::Info => "ℹ️",
+    }
+}
+
+/// One diagnostic as a markdown section
+fn write_markdown_diagnostic(md: &mut impl Write, diag: &Diagnostic) -> io::Result<()> {
+    write!(md, "### {} {} - {}\n\n", severity_emoji(diag.severity), diag.severity, diag.code)?;
+    write!(md, "{}\n\n", diag.message)?;
+
+    if let Some(loc) = &diag.location {
+        write!(md, "**Location:** {}", loc.file)?;
+        if let Some(line) = loc.line {
+            write!(md, ":{}", line)?;
+        }
+        write!(md, "\n\n")?;
+    }
+
+    if !diag.related.is_empty() {
+        let related: Vec<String> = diag.related.iter()
+            .map(|loc| match loc.line {
+                Some(line) => format!("{}:{}", loc.file, line),
+                None => loc.file.clone(),
+            })
+            .collect();
+        write!(md, "**Related:** {}\n\n", related.join(", "))?;
+    }
+
+    if let Some(exp) = &diag.expected {
+        write!(md, "**Expected:** `{}`\n\n", exp)?;
+    }
+    if let Some(act) = &diag.actual {
+        write!(md, "**Actual:** `{}`\n\n", act)?;
+    }
+
+    if !diag.impact.is_empty() {
+        write!(md, "**Impact:** {} downstream models\n\n", diag.impact.len())?;
+        for model in &diag.impact {
+            writeln!(md, "- {}", model)?;
+        }
+        writeln!(md)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemarefly_core::{DiagnosticCode, Location};
+
+    fn sample_report() -> Report {
+        Report::from_diagnostics(vec![
+            Diagnostic::new(DiagnosticCode::ContractTypeMismatch, Severity::Error, "Column 'id' type mismatch")
+                .with_location(Location::with_line("models/users.sql", 3))
+                .with_comparison("INT", "STRING"),
+            Diagnostic::new(DiagnosticCode::ContractExtraColumn, Severity::Warn, "Column 'extra' not in contract"),
+        ])
+    }
+
+    fn render(writer: &mut dyn OutputWriter, report: &Report) {
+        write_report(writer, report, &MessageTemplates::default()).unwrap();
+    }
+
+    #[test]
+    fn human_writer_includes_summary_and_diagnostics() {
+        let report = sample_report();
+        let mut buf = Vec::new();
+        render(&mut HumanWriter::new(&mut buf), &report);
+        let out = String::from_utf8(buf).unwrap();
+
+        assert!(out.contains("Schema Contract Check Report"));
+        assert!(out.contains("Total diagnostics: 2"));
+        assert!(out.contains("CONTRACT_TYPE_MISMATCH: Column 'id' type mismatch"));
+        assert!(out.contains("at models/users.sql:3"));
+        assert!(out.contains("Expected: INT"));
+        assert!(!out.contains("No issues found"));
+    }
+
+    #[test]
+    fn table_writer_aligns_rows() {
+        let report = sample_report();
+        let mut buf = Vec::new();
+        render(&mut TableWriter::new(&mut buf), &report);
+        let out = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[0], "2 diagnostics (1 errors, 1 warnings, 0 info)");
+        assert!(lines[1].starts_with("SEVERITY"));
+        assert!(lines[2].starts_with("ERROR"));
+        assert!(lines[2].contains("models/users.sql:3"));
+        assert!(lines[3].starts_with("WARN"));
+        // Message column starts at the same offset on every row
+        let offset = lines[1].find("MESSAGE").unwrap();
+        assert_eq!(&lines[2][offset..], "Column 'id' type mismatch");
+    }
+
+    #[test]
+    fn json_writer_round_trips_report() {
+        let report = sample_report();
+        let mut buf = Vec::new();
+        render(&mut JsonWriter::new(&mut buf), &report);
+
+        let parsed: Report = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(parsed, report);
+    }
+
+    #[test]
+    fn markdown_writer_renders_sections() {
+        let report = sample_report();
+        let mut buf = Vec::new();
+        render(&mut MarkdownWriter::new(&mut buf), &report);
+        let out = String::from_utf8(buf).unwrap();
+
+        assert!(out.starts_with("# Schema Contract Check Report"));
+        assert!(out.contains("- Errors: 1"));
+        assert!(out.contains("### ❌ error - CONTRACT_TYPE_MISMATCH"));
+        assert!(out.contains("**Location:** models/users.sql:3"));
+        assert!(!out.contains("Slim CI Analysis"));
+    }
+
+    #[test]
+    fn markdown_writer_groups_diagnostics_by_model() {
+        let for_model = |code, severity, model: &str| {
+            let mut diag = Diagnostic::new(code, severity, "finding");
+            diag.model_id = Some(model.to_string());
+            diag
+        };
+        let report = Report::from_diagnostics(vec![
+            for_model(DiagnosticCode::ContractTypeMismatch, Severity::Error, "model.shop.users"),
+            for_model(DiagnosticCode::ContractExtraColumn, Severity::Warn, "model.shop.orders"),
+            for_model(DiagnosticCode::ContractMissingColumn, Severity::Error, "model.shop.users"),
+            for_model(DiagnosticCode::ContractExtraColumn, Severity::Warn, "model.shop.users"),
+            Diagnostic::new(DiagnosticCode::Info, Severity::Info, "no model"),
+        ]);
+
+        let mut buf = Vec::new();
+        render(&mut MarkdownWriter::new(&mut buf), &report);
+        let out = String::from_utf8(buf).unwrap();
+
+        assert!(out.contains("| `model.shop.orders` | ⚠️ | 0 | 1 | 0 |"));
+        assert!(out.contains("| `model.shop.users` | ❌ | 2 | 1 | 0 |"));
+        assert!(out.contains("| Other | ℹ️ | 0 | 0 | 1 |"));
+        assert!(out.contains("<details open>\n<summary>❌ `model.shop.users` (2 errors, 1 warnings, 0 info)</summary>"));
+        assert!(out.contains("<details>\n<summary>⚠️ `model.shop.orders` (0 errors, 1 warnings, 0 info)</summary>"));
+
+        // Each model's diagnostics are listed under its own header
+        let users = out.find("<summary>❌ `model.shop.users`").unwrap();
+        let other = out.find("<summary>ℹ️ Other").unwrap();
+        assert_eq!(out[users..other].matches("### ").count(), 3);
+    }
+
+    #[test]
+    fn message_template_appended_for_configured_code() {
+        let report = sample_report();
+        let mut messages = MessageTemplates::default();
+        messages.set(DiagnosticCode::ContractTypeMismatch, "See runbook: https://wiki/{model}#{column}");
+
+        let mut buf = Vec::new();
+        write_report(&mut HumanWriter::new(&mut buf), &report, &messages).unwrap();
+        let out = String::from_utf8(buf).unwrap();
+
+        assert!(out.contains("Column 'id' type mismatch See runbook: https://wiki/users#id"));
+        assert!(out.contains("Column 'extra' not in contract\n"));
+
+        // The report itself keeps the original message
+        assert_eq!(report.diagnostics[0].message, "Column 'id' type mismatch");
+        let mut buf = Vec::new();
+        write_report(&mut JsonWriter::new(&mut buf), &report, &messages).unwrap();
+        assert!(!String::from_utf8(buf).unwrap().contains("See runbook"));
+    }
+
+    #[test]
+    fn empty_report_reports_no_issues() {
+        let report = Report::from_diagnostics(Vec::new());
+
+        let mut buf = Vec::new();
+        render(writer_for(OutputFormat::Human, &mut buf).as_mut(), &report);
+        assert!(String::from_utf8(buf).unwrap().contains("No issues found"));
+
+        let mut buf = Vec::new();
+        render(&mut MarkdownWriter::new(&mut buf), &report);
+        assert!(String::from_utf8(buf).unwrap().contains("No issues found"));
+    }
+}
@@ -0,0 +1,135 @@
+//! Panic isolation for per-model processing
+//!
+//! A bug in inference or an unexpected manifest node must not abort a check
+//! run over thousands of models. `run_model` catches a panic raised while
+//! processing one model and turns it into an `INTERNAL_ERROR` diagnostic for
+//! that model, so the remaining models are still checked and reported;
+//! `check_models` is the per-model loop of `check` built on it.
+
+// The error is the model's diagnostic, produced at most once per model
+#![allow(clippy::result_large_err)]
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+use schemarefly_core::{Diagnostic, DiagnosticCode, Location, Severity};
+use schemarefly_dbt::ManifestNode;
+
+/// Run `process` for one model, converting a panic into a diagnostic
+///
+/// `path` is the model's `original_file_path`, used as the diagnostic location.
+pub fn run_model<T>(model: &str, path: &str, process: impl FnOnce() -> T) -> Result<T, Diagnostic> {
+    panic::catch_unwind(AssertUnwindSafe(process)).map_err(|payload| {
+        Diagnostic::new(
+            DiagnosticCode::InternalError,
+            Severity::Error,
+            format!("Internal error while checking model '{}': {}", model, panic_message(&*payload)),
+        )
+        .with_location(Location::new(path))
+    })
+}
+
+/// Run `check` for each model, isolating panics per model
+///
+/// `check` pushes the model's findings onto the diagnostics it is handed. A
+/// panic becomes the model's `INTERNAL_ERROR` and the next model is checked.
+/// Every diagnostic pushed while checking a model is tagged with its id.
+pub fn check_models<'m>(
+    models: impl IntoIterator<Item = (&'m String, &'m ManifestNode)>,
+    diagnostics: &mut Vec<Diagnostic>,
+    mut check: impl FnMut(&'m String, &'m ManifestNode, &mut Vec<Diagnostic>),
+) {
+    for (node_id, node) in models {
+        let first = diagnostics.len();
+
+        if let Err(diag) = run_model(&node.name, &node.original_file_path, || check(node_id, node, diagnostics)) {
+            diagnostics.push(diag);
+        }
+        for diag in &mut diagnostics[first..] {
+            diag.model_id.get_or_insert_with(|| node_id.clone());
+        }
+    }
+}
+
+/// Message carried by a panic payload, if it is a string
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{test_manifest, test_model};
+
+    #[test]
+    fn panicking_model_becomes_internal_error_and_run_continues() {
+        let models = ["stg_users", "fct_orders", "dim_users"];
+        let mut checked = Vec::new();
+        let mut diagnostics = Vec::new();
+
+        for model in models {
+            let path = format!("models/{}.sql", model);
+            let outcome = run_model(model, &path, || {
+                if model == "fct_orders" {
+                    panic!("unexpected node shape");
+                }
+                checked.push(model);
+            });
+
+            if let Err(diag) = outcome {
+                diagnostics.push(diag);
+            }
+        }
+
+        assert_eq!(checked, vec!["stg_users", "dim_users"]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::InternalError);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            diagnostics[0].message,
+            "Internal error while checking model 'fct_orders': unexpected node shape"
+        );
+        assert_eq!(diagnostics[0].location.as_ref().unwrap().file, "models/fct_orders.sql");
+    }
+
+    #[test]
+    fn check_loop_continues_after_a_panicking_model() {
+        let manifest = test_manifest(&[test_model("a_users"), test_model("b_orders"), test_model("c_revenue")]);
+
+        let mut models: Vec<_> = manifest.models_iter().collect();
+        models.sort_by_key(|(id, _)| id.as_str());
+
+        let mut diagnostics = Vec::new();
+        check_models(models, &mut diagnostics, |_, node, diagnostics| {
+            diagnostics.push(Diagnostic::new(DiagnosticCode::ContractTypeMismatch, Severity::Warn, node.name.clone()));
+            if node.name == "b_orders" {
+                panic!("unexpected node shape");
+            }
+        });
+
+        let summary: Vec<(DiagnosticCode, Option<&str>)> =
+            diagnostics.iter().map(|d| (d.code, d.model_id.as_deref())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (DiagnosticCode::ContractTypeMismatch, Some("model.shop.a_users")),
+                (DiagnosticCode::ContractTypeMismatch, Some("model.shop.b_orders")),
+                (DiagnosticCode::InternalError, Some("model.shop.b_orders")),
+                (DiagnosticCode::ContractTypeMismatch, Some("model.shop.c_revenue")),
+            ]
+        );
+        assert!(diagnostics[2].message.ends_with("unexpected node shape"));
+    }
+
+    #[test]
+    fn formatted_panic_message_is_kept() {
+        let diag = run_model("m", "models/m.sql", || panic!("column {} missing", 3)).unwrap_err();
+        assert!(diag.message.ends_with("column 3 missing"));
+    }
+}
@@ -0,0 +1,154 @@
+//! Parse preflight for `check --preflight`
+//!
+//! Parses every selected model with the dialect-aware parser before any
+//! inference runs, so all syntax errors are reported together and up front.
+//! Parsing one model does not depend on any other, so the models are split
+//! across worker threads. A model whose SQL file cannot be found is reported
+//! rather than skipped, and a parser panic becomes that model's
+//! `INTERNAL_ERROR` instead of aborting the run.
+
+use std::path::{Path, PathBuf};
+
+use crate::panic_boundary;
+
+use schemarefly_core::{Config, Diagnostic, DiagnosticCode, Location, Severity};
+use schemarefly_dbt::Manifest;
+use schemarefly_sql::{DbtFunctionExtractor, SqlParser};
+
+/// A model to parse: its `original_file_path` and its SQL file, if found
+pub type PreflightModel = (String, Option<PathBuf>);
+
+/// Parse every model's SQL file and return one diagnostic per file that fails
+///
+/// Diagnostics are ordered by file path, so the result does not depend on
+/// the order of `models` or on thread scheduling.
+pub fn parse_all(models: &[PreflightModel], config: &Config, manifest: Option<&Manifest>) -> Vec<Diagnostic> {
+    if models.is_empty() {
+        return Vec::new();
+    }
+
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get()).min(models.len());
+    let chunk_size = models.len().div_ceil(workers);
+
+    let mut diagnostics: Vec<Diagnostic> = std::thread::scope(|scope| {
+        let handles: Vec<_> = models
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let handle = scope.spawn(move || {
+                    let parser = SqlParser::from_dialect(&config.dialect);
+                    chunk.iter().filter_map(|model| check_model(&parser, model, config, manifest)).collect::<Vec<_>>()
+                });
+                (chunk, handle)
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|(chunk, handle)| handle.join().unwrap_or_else(|_| chunk.iter().map(thread_failed).collect()))
+            .collect()
+    });
+
+    diagnostics.sort_by(|a, b| {
+        let file = |d: &Diagnostic| d.location.as_ref().map(|l| l.file.clone());
+        file(a).cmp(&file(b)).then_with(|| a.message.cmp(&b.message))
+    });
+    diagnostics
+}
+
+/// Parse one model, reporting a missing file or a parser panic as its diagnostic
+fn check_model(parser: &SqlParser, model: &PreflightModel, config: &Config, manifest: Option<&Manifest>) -> Option<Diagnostic> {
+    let (original_file_path, sql_path) = model;
+    let Some(sql_path) = sql_path else {
+        return Some(
+            Diagnostic::new(
+                DiagnosticCode::SqlParseError,
+                Severity::Error,
+                format!("SQL file not found: {}", original_file_path),
+            )
+            .with_location(Location::new(original_file_path.clone())),
+        );
+    };
+
+    panic_boundary::run_model(original_file_path, original_file_path, || parse_model(parser, sql_path, config, manifest))
+        .unwrap_or_else(Some)
+}
+
+/// `INTERNAL_ERROR` for a model whose parser thread died outside the per-model boundary
+fn thread_failed(model: &PreflightModel) -> Diagnostic {
+    let (original_file_path, _) = model;
+    Diagnostic::new(
+        DiagnosticCode::InternalError,
+        Severity::Error,
+        format!("Internal error while parsing '{}' in preflight", original_file_path),
+    )
+    .with_location(Location::new(original_file_path.clone()))
+}
+
+/// Parse one model the way `check` does, returning its parse error
+fn parse_model(parser: &SqlParser, sql_path: &Path, config: &Config, manifest: Option<&Manifest>) -> Option<Diagnostic> {
+    let sql = match std::fs::read_to_string(sql_path) {
+        Ok(sql) => sql,
+        Err(e) => {
+            return Some(
+                Diagnostic::new(
+                    DiagnosticCode::SqlParseError,
+                    Severity::Error,
+                    format!("Failed to read SQL file {}: {}", sql_path.display(), e),
+                )
+                .with_location(Location::new(sql_path.display().to_string())),
+            );
+        }
+    };
+
+    let (preprocessed, _) = DbtFunctionExtractor::preprocess_with_vars(&sql, manifest, &config.vars);
+    parser.parse(&preprocessed, Some(sql_path)).err().map(|e| e.to_diagnostic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemarefly_core::DialectConfig;
+
+    #[test]
+    fn reports_every_broken_model_regardless_of_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let model = |name: &str, sql: &str| {
+            let sql_path = dir.path().join(format!("{}.sql", name));
+            std::fs::write(&sql_path, sql).unwrap();
+            (sql_path.display().to_string(), Some(sql_path))
+        };
+
+        let mut models = vec![
+            model("broken_a", "SELECT id, FROM users WHERE"),
+            model("ok", "SELECT id FROM {{ ref('users') }}"),
+            model("broken_b", "SELECT (id FROM orders"),
+        ];
+
+        let config = Config { dialect: DialectConfig::Postgres, ..Config::default() };
+        let forward = parse_all(&models, &config, None);
+        models.reverse();
+        let reverse = parse_all(&models, &config, None);
+
+        let files: Vec<String> = forward.iter().map(|d| d.location.as_ref().unwrap().file.clone()).collect();
+        assert_eq!(
+            files,
+            vec![
+                dir.path().join("broken_a.sql").display().to_string(),
+                dir.path().join("broken_b.sql").display().to_string(),
+            ]
+        );
+        assert!(forward.iter().all(|d| d.code == DiagnosticCode::SqlParseError));
+        assert_eq!(forward, reverse);
+    }
+
+    #[test]
+    fn reports_models_without_a_sql_file() {
+        let models = vec![("models/gone.sql".to_string(), None)];
+
+        let diagnostics = parse_all(&models, &Config::default(), None);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::SqlParseError);
+        assert_eq!(diagnostics[0].message, "SQL file not found: models/gone.sql");
+        assert_eq!(diagnostics[0].location.as_ref().unwrap().file, "models/gone.sql");
+    }
+}
@@ -0,0 +1,203 @@
+//! Wall-time profiling for `schemarefly check --profile`
+//!
+//! `PhaseTimer` accumulates time per named phase (manifest parse, DAG build,
+//! per-model parse/infer/compare) and per model, then renders a breakdown
+//! with the slowest models. Phases are listed in the order first recorded.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+/// Number of models listed in the "slowest models" section
+pub const SLOWEST_MODELS: usize = 10;
+
+/// Accumulates wall time per phase and per model
+pub struct PhaseTimer {
+    /// When timing started
+    started: Instant,
+
+    /// Total time per phase, in first-recorded order
+    phases: Vec<(&'static str, Duration)>,
+
+    /// Total time per model across its phases
+    models: HashMap<String, Duration>,
+}
+
+impl PhaseTimer {
+    /// Start timing
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            phases: Vec::new(),
+            models: HashMap::new(),
+        }
+    }
+
+    /// Run `f`, adding its wall time to `phase`
+    pub fn time<T>(&mut self, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(phase, start.elapsed());
+        result
+    }
+
+    /// Run `f` for `model`, adding its wall time to both `phase` and the model
+    pub fn time_model<T>(&mut self, model: &str, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record_model(model, phase, start.elapsed());
+        result
+    }
+
+    /// Add `elapsed` to both `phase` and `model`
+    pub fn record_model(&mut self, model: &str, phase: &'static str, elapsed: Duration) {
+        self.record(phase, elapsed);
+        *self.models.entry(model.to_string()).or_default() += elapsed;
+    }
+
+    /// Add `elapsed` to `phase`
+    pub fn record(&mut self, phase: &'static str, elapsed: Duration) {
+        match self.phases.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => self.phases.push((phase, elapsed)),
+        }
+    }
+
+    /// Total time recorded for `phase`
+    #[cfg(test)]
+    pub fn phase(&self, phase: &str) -> Duration {
+        self.phases
+            .iter()
+            .find(|(name, _)| *name == phase)
+            .map(|(_, total)| *total)
+            .unwrap_or_default()
+    }
+
+    /// Sum of all phase times
+    pub fn phases_total(&self) -> Duration {
+        self.phases.iter().map(|(_, total)| *total).sum()
+    }
+
+    /// Wall time since the timer started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Models sorted by total time, slowest first (ties by name)
+    pub fn slowest_models(&self, limit: usize) -> Vec<(&str, Duration)> {
+        let mut models: Vec<(&str, Duration)> =
+            self.models.iter().map(|(name, total)| (name.as_str(), *total)).collect();
+        models.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        models.truncate(limit);
+        models
+    }
+
+    /// Render the breakdown as plain text
+    pub fn render(&self) -> String {
+        let total = self.phases_total();
+        let share = |d: Duration| {
+            if total.is_zero() {
+                0.0
+            } else {
+                d.as_secs_f64() / total.as_secs_f64() * 100.0
+            }
+        };
+
+        let mut out = String::from("Profile (wall time)\n");
+        for (name, elapsed) in &self.phases {
+            let _ = writeln!(out, "  {:<16} {:>10} {:>6.1}%", name, format_duration(*elapsed), share(*elapsed));
+        }
+        let _ = writeln!(out, "  {:<16} {:>10}", "total", format_duration(total));
+        let _ = writeln!(out, "  {:<16} {:>10}", "wall", format_duration(self.elapsed()));
+
+        let slowest = self.slowest_models(SLOWEST_MODELS);
+        if !slowest.is_empty() {
+            let _ = writeln!(out, "\nSlowest models");
+            for (rank, (name, elapsed)) in slowest.iter().enumerate() {
+                let _ = writeln!(out, "  {:>2}. {:<40} {:>10}", rank + 1, name, format_duration(*elapsed));
+            }
+        }
+
+        out
+    }
+}
+
+impl Default for PhaseTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Format a duration in milliseconds with two decimals
+fn format_duration(d: Duration) -> String {
+    format!("{:.2} ms", d.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn render_lists_phases_in_recorded_order_and_slowest_models() {
+        let mut timer = PhaseTimer::new();
+        timer.record("manifest parse", ms(2));
+        timer.record("dag build", ms(1));
+        for (model, n) in [("users", 1), ("orders", 3)] {
+            timer.record_model(model, "parse", ms(n));
+            timer.record_model(model, "infer", ms(n));
+            timer.record_model(model, "compare", ms(n));
+        }
+
+        let output = timer.render();
+        let lines: Vec<&str> = output.lines().collect();
+        let names: Vec<&str> = lines[1..8].iter().map(|l| l.split_whitespace().next().unwrap()).collect();
+        assert_eq!(names, vec!["manifest", "dag", "parse", "infer", "compare", "total", "wall"]);
+        assert!(lines[1].contains("2.00 ms") && lines[1].contains("13.3%"), "{}", lines[1]);
+        assert!(lines[3].contains("4.00 ms"), "{}", lines[3]);
+        assert!(lines[6].contains("15.00 ms"), "{}", lines[6]);
+
+        assert_eq!(lines[9], "Slowest models");
+        assert!(lines[10].contains("1. orders") && lines[10].contains("9.00 ms"), "{}", lines[10]);
+        assert!(lines[11].contains("2. users") && lines[11].contains("3.00 ms"), "{}", lines[11]);
+    }
+
+    #[test]
+    fn phases_accumulate_and_total_is_their_sum() {
+        let mut timer = PhaseTimer::new();
+        timer.record("manifest parse", ms(5));
+        timer.record_model("orders", "infer", ms(5));
+        timer.record_model("orders", "infer", ms(5));
+
+        assert_eq!(timer.phase("infer"), ms(10));
+        assert_eq!(timer.phases_total(), ms(15));
+        assert_eq!(timer.phase("compare"), Duration::ZERO);
+        assert_eq!(timer.slowest_models(SLOWEST_MODELS), vec![("orders", ms(10))]);
+    }
+
+    #[test]
+    fn slowest_models_break_ties_by_name_and_truncate() {
+        let mut timer = PhaseTimer::new();
+        for model in ["c", "a", "b"] {
+            timer.record_model(model, "infer", ms(1));
+        }
+        timer.record_model("d", "infer", ms(2));
+
+        let names: Vec<&str> = timer.slowest_models(3).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["d", "a", "b"]);
+    }
+
+    #[test]
+    fn time_returns_the_closure_result_and_records_the_phase() {
+        let mut timer = PhaseTimer::new();
+        assert_eq!(timer.time("dag build", || 42), 42);
+        assert_eq!(timer.time_model("orders", "parse", || "ok"), "ok");
+
+        assert!(timer.render().contains("dag build"));
+        assert_eq!(timer.slowest_models(1)[0].0, "orders");
+        assert!(timer.phases_total() <= timer.elapsed());
+    }
+}
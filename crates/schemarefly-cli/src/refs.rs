@@ -0,0 +1,155 @@
+//! Reference inventory for `schemarefly refs`
+//!
+//! Lists every `ref()` and `source()` in the project's model SQL together with
+//! the manifest node it resolves to, so broken lineage across a large project
+//! shows up in one place. A `ref()` resolves to a model, seed or snapshot.
+
+use schemarefly_dbt::{Manifest, ManifestNode};
+use schemarefly_sql::{DbtFunctionExtractor, DbtReference};
+use serde::Serialize;
+
+/// Kind of dbt reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceKind {
+    Ref,
+    Source,
+}
+
+/// Whether a reference names a node in the manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResolutionStatus {
+    Resolved,
+    Unresolved,
+}
+
+/// One `ref()`/`source()` used by a model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReferenceUsage {
+    /// unique_id of the model whose SQL holds the reference
+    pub model: String,
+
+    /// `ref` or `source`
+    pub kind: ReferenceKind,
+
+    /// Referenced name (`customers`, or `raw.orders` for a source)
+    pub target: String,
+
+    /// unique_id the reference resolves to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_to: Option<String>,
+
+    /// Whether `resolved_to` was found
+    pub status: ResolutionStatus,
+}
+
+/// Collect the references of every model, ordered by model and target
+///
+/// `read_sql` returns a model's SQL; models it returns `None` for are
+/// listed in the second return value instead. A reference used several
+/// times by one model is listed once.
+pub fn collect(
+    manifest: &Manifest,
+    read_sql: impl Fn(&ManifestNode) -> Option<String>,
+) -> (Vec<ReferenceUsage>, Vec<String>) {
+    let mut usages = Vec::new();
+    let mut unreadable = Vec::new();
+
+    for (node_id, node) in manifest.models() {
+        let Some(sql) = read_sql(node) else {
+            unreadable.push(node_id);
+            continue;
+        };
+
+        for reference in DbtFunctionExtractor::extract(&sql) {
+            let resolved_to = DbtFunctionExtractor::resolved_target(&reference, manifest);
+            let usage = match reference {
+                DbtReference::Ref { model_name, .. } => {
+                    ReferenceUsage::new(&node_id, ReferenceKind::Ref, model_name, resolved_to)
+                }
+                DbtReference::Source { source_name, table_name, .. } => ReferenceUsage::new(
+                    &node_id,
+                    ReferenceKind::Source,
+                    format!("{}.{}", source_name, table_name),
+                    resolved_to,
+                ),
+            };
+            if !usages.contains(&usage) {
+                usages.push(usage);
+            }
+        }
+    }
+
+    usages.sort_by(|a, b| a.model.cmp(&b.model).then_with(|| a.target.cmp(&b.target)));
+    unreadable.sort();
+    (usages, unreadable)
+}
+
+impl ReferenceUsage {
+    fn new(model: &str, kind: ReferenceKind, target: String, resolved_to: Option<String>) -> Self {
+        let status = if resolved_to.is_some() { ResolutionStatus::Resolved } else { ResolutionStatus::Unresolved };
+        Self { model: model.to_string(), kind, target, resolved_to, status }
+    }
+
+    /// The reference as written in SQL (`ref('customers')`, `source('raw', 'orders')`)
+    pub fn call(&self) -> String {
+        match self.kind {
+            ReferenceKind::Ref => format!("ref('{}')", self.target),
+            ReferenceKind::Source => {
+                let (source, table) = self.target.split_once('.').unwrap_or_default();
+                format!("source('{}', '{}')", source, table)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{test_manifest, test_model};
+    use std::collections::HashMap;
+
+    #[test]
+    fn reports_resolved_and_unresolved_references() {
+        let manifest = test_manifest(&[test_model("customers"), test_model("report"), test_model("orphan")]);
+
+        let sql = HashMap::from([
+            ("models/customers.sql", "SELECT 1 AS id"),
+            (
+                "models/report.sql",
+                "SELECT * FROM {{ ref('customers') }} JOIN {{ ref('ordrs') }} USING (id) \
+                 UNION ALL SELECT * FROM {{ ref('customers') }}",
+            ),
+        ]);
+        let (usages, unreadable) = collect(&manifest, |node| sql.get(node.original_file_path.as_str()).map(|s| s.to_string()));
+
+        assert_eq!(unreadable, vec!["model.shop.orphan"]);
+        assert_eq!(
+            usages,
+            vec![
+                ReferenceUsage {
+                    model: "model.shop.report".to_string(),
+                    kind: ReferenceKind::Ref,
+                    target: "customers".to_string(),
+                    resolved_to: Some("model.shop.customers".to_string()),
+                    status: ResolutionStatus::Resolved,
+                },
+                ReferenceUsage {
+                    model: "model.shop.report".to_string(),
+                    kind: ReferenceKind::Ref,
+                    target: "ordrs".to_string(),
+                    resolved_to: None,
+                    status: ResolutionStatus::Unresolved,
+                },
+            ]
+        );
+
+        let json = serde_json::to_value(&usages[1]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"model": "model.shop.report", "kind": "ref", "target": "ordrs", "status": "unresolved"})
+        );
+        assert_eq!(usages[1].call(), "ref('ordrs')");
+    }
+}
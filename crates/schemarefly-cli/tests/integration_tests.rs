@@ -0,0 +1,49 @@
+//! Integration tests running the schemarefly binary
+
+use std::path::Path;
+use std::process::Command;
+
+/// A dbt project with one contracted model whose SQL selects an extra column
+fn write_project(root: &Path) {
+    std::fs::create_dir_all(root.join(".git")).unwrap();
+    std::fs::create_dir_all(root.join("models/staging")).unwrap();
+    std::fs::create_dir_all(root.join("target")).unwrap();
+
+    std::fs::write(root.join("schemarefly.toml"), "dialect = \"postgres\"\n").unwrap();
+    std::fs::write(root.join("models/users.sql"), "select 1 as id, 'a' as email\n").unwrap();
+    std::fs::write(
+        root.join("target/manifest.json"),
+        r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.shop.users": {
+                    "unique_id": "model.shop.users", "name": "users", "resource_type": "model",
+                    "package_name": "shop", "path": "users.sql", "original_file_path": "models/users.sql",
+                    "config": {"contract": {"enforced": true}},
+                    "columns": {"id": {"name": "id", "data_type": "integer"}}
+                }
+            },
+            "sources": {}
+        }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn check_from_a_subdirectory_reads_model_sql_from_the_project_root() {
+    let project = tempfile::tempdir().unwrap();
+    write_project(project.path());
+    let nested = project.path().join("models/staging");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_schemarefly"))
+        .args(["check", "--output", "report.json"])
+        .current_dir(&nested)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let report = std::fs::read_to_string(nested.join("report.json")).unwrap();
+    assert!(!report.contains("SQL file not found"), "{}", report);
+    // The extra column is only found if the SQL was read and checked
+    assert!(report.contains("CONTRACT_EXTRA_COLUMN"), "{}", report);
+}
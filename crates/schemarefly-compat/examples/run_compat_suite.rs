@@ -41,8 +41,18 @@ fn main() -> anyhow::Result<()> {
         dialect,
         severity: Default::default(),
         allowlist: Default::default(),
+        contract: Default::default(),
+        inference: Default::default(),
+        drift: Default::default(),
         warehouse: None,
+        messages: Default::default(),
+        manifest_source: None,
+        target_path: None,
+        fail_on_codes: Vec::new(),
+        max_diagnostics_per_model: None,
         redact_sensitive_data: false,
+        vars: Default::default(),
+        env: Default::default(),
         project_root: project_path.clone(),
     };
 
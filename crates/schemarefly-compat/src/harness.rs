@@ -1,11 +1,11 @@
 //! Test harness for running SchemaRefly against real dbt projects
 
-use crate::metrics::{CompatMetrics, ModelResult, ModelOutcome, FailureDetail};
-use crate::model_detection::detect_model_type;
+use crate::metrics::{CompatMetrics, ModelResult, ModelOutcome, FailureDetail, DEFAULT_SAMPLES_PER_CODE};
+use crate::model_detection::{detect_model_type, is_macro_file, is_non_model_resource, UnsupportedReason};
 
 use schemarefly_core::config::Config;
 use schemarefly_dbt::manifest::{Manifest, ManifestNode};
-use schemarefly_sql::SqlParser;
+use schemarefly_sql::{InferenceContext, ParsedSql, SchemaInference, SqlParser};
 
 use anyhow::{Context, Result};
 use std::path::PathBuf;
@@ -21,6 +21,9 @@ pub struct CompatTestHarness {
 
     /// dbt manifest
     manifest: Option<Manifest>,
+
+    /// Number of failure samples kept per code in the metrics
+    samples_per_code: usize,
 }
 
 impl CompatTestHarness {
@@ -30,9 +33,16 @@ impl CompatTestHarness {
             project_root: project_root.into(),
             config,
             manifest: None,
+            samples_per_code: DEFAULT_SAMPLES_PER_CODE,
         }
     }
 
+    /// Set how many failure samples are kept per code
+    pub fn with_samples_per_code(mut self, n: usize) -> Self {
+        self.samples_per_code = n;
+        self
+    }
+
     /// Load dbt manifest from target/manifest.json
     pub fn load_manifest(&mut self) -> Result<()> {
         let manifest_path = self.project_root.join("target/manifest.json");
@@ -65,16 +75,22 @@ impl CompatTestHarness {
                 .and_then(|s| s.to_str())
                 .unwrap_or("unknown"),
             format!("{:?}", self.config.dialect).to_lowercase(),
-        );
+        )
+        .with_samples_per_code(self.samples_per_code);
+
+        let context = InferenceContext::from_manifest(manifest);
 
-        // Check each model
-        for (unique_id, node) in &manifest.nodes {
-            // Only process models
-            if !unique_id.starts_with("model.") {
+        // Check each model in unique_id order so results are reproducible
+        let mut nodes: Vec<_> = manifest.nodes.iter().collect();
+        nodes.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (unique_id, node) in nodes {
+            // Only process models; macros, analyses and tests are never SELECTs
+            if !unique_id.starts_with("model.") || is_non_model_resource(&node.resource_type) {
                 continue;
             }
 
-            let result = self.check_model(node);
+            let result = self.check_model(node, &context);
             metrics.add_model_result(result);
         }
 
@@ -88,7 +104,8 @@ impl CompatTestHarness {
                 .and_then(|s| s.to_str())
                 .unwrap_or("unknown"),
             format!("{:?}", self.config.dialect).to_lowercase(),
-        );
+        )
+        .with_samples_per_code(self.samples_per_code);
 
         // Discover all SQL model files
         let sql_files = self.discover_models()?;
@@ -98,8 +115,9 @@ impl CompatTestHarness {
         }
 
         // Check each discovered model
+        let context = InferenceContext::new();
         for sql_path in sql_files {
-            let result = self.check_model_file(&sql_path);
+            let result = self.check_model_file(&sql_path, &context);
             metrics.add_model_result(result);
         }
 
@@ -107,7 +125,7 @@ impl CompatTestHarness {
     }
 
     /// Check a single model and return result
-    fn check_model(&self, node: &ManifestNode) -> ModelResult {
+    fn check_model(&self, node: &ManifestNode, context: &InferenceContext) -> ModelResult {
         let model_name = node.name.clone();
         let file_path = node.original_file_path.clone();
 
@@ -139,6 +157,7 @@ impl CompatTestHarness {
                         code: "SR000".to_string(),
                         message: format!("Failed to read SQL file: {}", err),
                         context: None,
+                        unsupported_kind: None,
                     }),
                 };
             }
@@ -156,22 +175,19 @@ impl CompatTestHarness {
                         code: diag.code.as_str().to_string(),
                         message: diag.message.clone(),
                         context: Some(extract_error_context(&sql_content, 0)),
+                        unsupported_kind: None,
                     }),
                 };
             }
         };
 
-        // For basic compat testing, we just check if we got at least one statement
-        // More advanced inference would use the SchemaInference engine
         let has_statements = !parsed_sql.statements.is_empty();
 
         if has_statements {
             ModelResult {
                 model_name,
                 file_path,
-                outcome: ModelOutcome::Success {
-                    schema_inferred: true,
-                },
+                outcome: self.inference_outcome(&parsed_sql, context),
             }
         } else {
             ModelResult {
@@ -181,13 +197,14 @@ impl CompatTestHarness {
                     code: "SR010".to_string(),
                     message: "No SQL statements found in file".to_string(),
                     context: Some(sql_content[..std::cmp::min(200, sql_content.len())].to_string()),
+                    unsupported_kind: None,
                 }),
             }
         }
     }
 
     /// Check a single model file directly (without manifest metadata)
-    fn check_model_file(&self, sql_path: &PathBuf) -> ModelResult {
+    fn check_model_file(&self, sql_path: &PathBuf, context: &InferenceContext) -> ModelResult {
         // Extract model name from file path
         let model_name = sql_path
             .file_stem()
@@ -213,11 +230,23 @@ impl CompatTestHarness {
                         code: "SR000".to_string(),
                         message: format!("Failed to read SQL file: {}", err),
                         context: None,
+                        unsupported_kind: None,
                     }),
                 };
             }
         };
 
+        // Macro files under models/ aren't models; note and skip them
+        if is_macro_file(&sql_content) {
+            return ModelResult {
+                model_name,
+                file_path,
+                outcome: ModelOutcome::Unsupported {
+                    reason: UnsupportedReason::Macro.diagnostic_message(),
+                },
+            };
+        }
+
         // Try to parse SQL (with Jinja template preprocessing)
         let parser = SqlParser::from_dialect(&self.config.dialect);
         let parsed_sql = match parser.parse_with_jinja(&sql_content, Some(sql_path), None) {
@@ -230,6 +259,7 @@ impl CompatTestHarness {
                         code: diag.code.as_str().to_string(),
                         message: diag.message.clone(),
                         context: Some(extract_error_context(&sql_content, 0)),
+                        unsupported_kind: None,
                     }),
                 };
             }
@@ -242,9 +272,7 @@ impl CompatTestHarness {
             ModelResult {
                 model_name,
                 file_path,
-                outcome: ModelOutcome::Success {
-                    schema_inferred: true,
-                },
+                outcome: self.inference_outcome(&parsed_sql, context),
             }
         } else {
             ModelResult {
@@ -254,11 +282,32 @@ impl CompatTestHarness {
                     code: "SR010".to_string(),
                     message: "No SQL statements found in file".to_string(),
                     context: Some(sql_content[..std::cmp::min(200, sql_content.len())].to_string()),
+                    unsupported_kind: None,
                 }),
             }
         }
     }
 
+    /// Outcome for a model whose SQL parsed
+    ///
+    /// Only unsupported constructs count as inference failures: compat runs
+    /// rarely know every upstream schema, so unknown tables and columns are
+    /// not held against the model.
+    fn inference_outcome(&self, parsed_sql: &ParsedSql, context: &InferenceContext) -> ModelOutcome {
+        let inference = SchemaInference::new(context).with_max_depth(self.config.inference.max_depth);
+        let error = parsed_sql.first_statement().and_then(|statement| inference.infer_statement(statement).err());
+
+        match error.as_ref().and_then(|e| e.unsupported_kind().map(|kind| (e, kind))) {
+            Some((error, kind)) => ModelOutcome::InferenceFailure(FailureDetail {
+                code: inference.create_diagnostic(error).code.as_str().to_string(),
+                message: error.to_string(),
+                context: None,
+                unsupported_kind: Some(kind.as_str().to_string()),
+            }),
+            None => ModelOutcome::Success { schema_inferred: true },
+        }
+    }
+
     /// Discover all SQL model files in the project
     pub fn discover_models(&self) -> Result<Vec<PathBuf>> {
         let models_dir = self.project_root.join("models");
@@ -281,6 +330,9 @@ impl CompatTestHarness {
             }
         }
 
+        // Sort for a stable order across filesystems
+        sql_files.sort();
+
         Ok(sql_files)
     }
 }
@@ -313,4 +365,73 @@ mod tests {
         assert!(context.len() <= 203); // 200 + "..."
         assert!(context.ends_with("..."));
     }
+
+    #[test]
+    fn repeated_runs_produce_identical_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let models_dir = dir.path().join("models");
+        std::fs::create_dir_all(&models_dir).unwrap();
+
+        std::fs::write(models_dir.join("good.sql"), "SELECT 1 AS one").unwrap();
+        for name in ["zeta", "alpha", "mid", "beta"] {
+            std::fs::write(models_dir.join(format!("{}.sql", name)), "SELECT FROM WHERE").unwrap();
+        }
+
+        let harness = CompatTestHarness::new(dir.path(), Config::default()).with_samples_per_code(2);
+
+        let first = harness.run_checks().unwrap();
+        let second = harness.run_checks().unwrap();
+
+        let first_json = serde_json::to_string(&first.failure_samples).unwrap();
+        let second_json = serde_json::to_string(&second.failure_samples).unwrap();
+        assert_eq!(first_json, second_json);
+
+        // Samples are capped and come from the alphabetically first models
+        let samples: Vec<&Vec<String>> = first.failure_samples.values().collect();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].len(), 2);
+        assert_eq!(first.parse_failures, 4);
+    }
+
+    #[test]
+    fn macro_file_is_skipped_without_parse_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let models_dir = dir.path().join("models");
+        std::fs::create_dir_all(&models_dir).unwrap();
+
+        std::fs::write(models_dir.join("good.sql"), "SELECT 1 AS one").unwrap();
+        std::fs::write(
+            models_dir.join("helpers.sql"),
+            "{% macro cents_to_dollars(col) %}\n  ({{ col }} / 100)::numeric(16, 2)\n{% endmacro %}\n",
+        )
+        .unwrap();
+
+        let metrics = CompatTestHarness::new(dir.path(), Config::default()).run_checks().unwrap();
+
+        assert_eq!(metrics.parse_failures, 0);
+        assert_eq!(metrics.unsupported_models, 1);
+        let helpers = metrics.model_results.iter().find(|r| r.model_name == "helpers").unwrap();
+        assert!(matches!(helpers.outcome, ModelOutcome::Unsupported { ref reason } if reason.contains("macros")));
+    }
+
+    #[test]
+    fn unsupported_constructs_are_tallied_by_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        let models_dir = dir.path().join("models");
+        std::fs::create_dir_all(&models_dir).unwrap();
+
+        std::fs::write(models_dir.join("good.sql"), "SELECT 1 AS one").unwrap();
+        let recursive = "WITH RECURSIVE t(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 5) SELECT n FROM t";
+        std::fs::write(models_dir.join("counter.sql"), recursive).unwrap();
+        std::fs::write(models_dir.join("steps.sql"), recursive).unwrap();
+        std::fs::write(models_dir.join("listed.sql"), "SELECT x FROM (VALUES (1), (2)) AS v(x)").unwrap();
+
+        let metrics = CompatTestHarness::new(dir.path(), Config::default()).run_checks().unwrap();
+
+        assert_eq!(metrics.inference_failures, 3);
+        assert_eq!(metrics.unsupported_kinds["RECURSIVE_CTE"], 2);
+        assert_eq!(metrics.unsupported_kinds["VALUES"], 1);
+        assert_eq!(metrics.failure_codes["SQL_UNSUPPORTED_SYNTAX"], 3);
+        assert_eq!(metrics.schema_inferred, 1);
+    }
 }
@@ -14,5 +14,5 @@ pub mod report;
 
 pub use harness::CompatTestHarness;
 pub use metrics::{CompatMetrics, ModelResult, FailureDetail};
-pub use model_detection::{ModelType, UnsupportedReason, detect_model_type};
+pub use model_detection::{ModelType, UnsupportedReason, detect_model_type, is_macro_file, is_non_model_resource};
 pub use report::CompatReport;
@@ -1,7 +1,10 @@
 //! Compatibility metrics collection and tracking
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+/// Default number of failure samples kept per code
+pub const DEFAULT_SAMPLES_PER_CODE: usize = 3;
 
 /// Overall compatibility metrics for a dbt project
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,10 +37,34 @@ pub struct CompatMetrics {
     pub model_results: Vec<ModelResult>,
 
     /// Top failure codes with counts
-    pub failure_codes: HashMap<String, usize>,
+    pub failure_codes: BTreeMap<String, usize>,
 
     /// Failure samples (code -> example messages)
-    pub failure_samples: HashMap<String, Vec<String>>,
+    ///
+    /// Samples are chosen deterministically: failures for a code are ordered by
+    /// model name, then file path, then message, and the first
+    /// `samples_per_code` are kept, regardless of the order models were
+    /// checked in.
+    pub failure_samples: BTreeMap<String, Vec<String>>,
+
+    /// Sort keys of the kept samples, parallel to `failure_samples`
+    #[serde(skip)]
+    sample_keys: BTreeMap<String, Vec<SampleKey>>,
+
+    /// Models blocked by each unsupported SQL construct (e.g. `PIVOT`)
+    #[serde(default)]
+    pub unsupported_kinds: BTreeMap<String, usize>,
+
+    /// Maximum number of samples kept per failure code
+    #[serde(default = "default_samples_per_code")]
+    pub samples_per_code: usize,
+}
+
+/// Model name, file path and message of a failure sample
+type SampleKey = (String, String, String);
+
+fn default_samples_per_code() -> usize {
+    DEFAULT_SAMPLES_PER_CODE
 }
 
 impl CompatMetrics {
@@ -52,11 +79,20 @@ impl CompatMetrics {
             inference_failures: 0,
             unsupported_models: 0,
             model_results: Vec::new(),
-            failure_codes: HashMap::new(),
-            failure_samples: HashMap::new(),
+            failure_codes: BTreeMap::new(),
+            failure_samples: BTreeMap::new(),
+            sample_keys: BTreeMap::new(),
+            unsupported_kinds: BTreeMap::new(),
+            samples_per_code: DEFAULT_SAMPLES_PER_CODE,
         }
     }
 
+    /// Set the maximum number of failure samples kept per code
+    pub fn with_samples_per_code(mut self, n: usize) -> Self {
+        self.samples_per_code = n;
+        self
+    }
+
     /// Calculate parse success rate (0.0 to 1.0)
     pub fn parse_success_rate(&self) -> f64 {
         if self.total_models == 0 {
@@ -78,7 +114,8 @@ impl CompatMetrics {
         let mut codes: Vec<(String, usize)> = self.failure_codes.iter()
             .map(|(k, v)| (k.clone(), *v))
             .collect();
-        codes.sort_by_key(|c| std::cmp::Reverse(c.1));
+        // Ties are broken by code so the ordering is stable across runs
+        codes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         codes.into_iter().take(n).collect()
     }
 
@@ -86,40 +123,75 @@ impl CompatMetrics {
     pub fn add_model_result(&mut self, result: ModelResult) {
         self.total_models += 1;
 
-        match &result.outcome {
+        let failure_code = match &result.outcome {
             ModelOutcome::Success { schema_inferred } => {
                 self.parsed_successfully += 1;
                 if *schema_inferred {
                     self.schema_inferred += 1;
                 }
+                None
             }
             ModelOutcome::ParseFailure(detail) => {
                 self.parse_failures += 1;
-                self.record_failure(detail);
+                Some(detail.code.clone())
             }
             ModelOutcome::InferenceFailure(detail) => {
                 self.parsed_successfully += 1; // Parsing succeeded
                 self.inference_failures += 1;
-                self.record_failure(detail);
+                if let Some(kind) = &detail.unsupported_kind {
+                    *self.unsupported_kinds.entry(kind.clone()).or_insert(0) += 1;
+                }
+                Some(detail.code.clone())
             }
             ModelOutcome::Unsupported { reason: _ } => {
                 self.unsupported_models += 1;
+                None
             }
+        };
+
+        if let Some(code) = failure_code {
+            self.record_failure(&code, &result);
         }
 
         self.model_results.push(result);
     }
 
-    /// Record a failure code and sample
-    fn record_failure(&mut self, detail: &FailureDetail) {
-        // Increment failure code count
-        *self.failure_codes.entry(detail.code.clone()).or_insert(0) += 1;
+    /// Count a failure and keep it as a sample if it sorts among the first
+    fn record_failure(&mut self, code: &str, result: &ModelResult) {
+        *self.failure_codes.entry(code.to_string()).or_insert(0) += 1;
+
+        // Metrics loaded from JSON carry samples but not their keys
+        if !self.sample_keys.contains_key(code) && self.failure_samples.contains_key(code) {
+            let keys = self.failure_keys(code);
+            self.sample_keys.insert(code.to_string(), keys);
+        }
+
+        let Some(detail) = result.failure_detail() else { return };
+        let key = (result.model_name.clone(), result.file_path.clone(), detail.message.clone());
 
-        // Store sample (up to 3 samples per code)
-        let samples = self.failure_samples.entry(detail.code.clone()).or_default();
-        if samples.len() < 3 {
-            samples.push(detail.message.clone());
+        let keys = self.sample_keys.entry(code.to_string()).or_default();
+        let position = keys.partition_point(|kept| *kept <= key);
+        if position >= self.samples_per_code {
+            return;
         }
+        keys.insert(position, key);
+        keys.truncate(self.samples_per_code);
+
+        let samples = keys.iter().map(|(_, _, message)| message.clone()).collect();
+        self.failure_samples.insert(code.to_string(), samples);
+    }
+
+    /// Sorted sample keys of the recorded failures for `code`
+    fn failure_keys(&self, code: &str) -> Vec<SampleKey> {
+        let mut keys: Vec<SampleKey> = self.model_results.iter()
+            .filter_map(|r| {
+                let detail = r.failure_detail().filter(|d| d.code == code)?;
+                Some((r.model_name.clone(), r.file_path.clone(), detail.message.clone()))
+            })
+            .collect();
+        keys.sort();
+        keys.truncate(self.samples_per_code);
+        keys
     }
 }
 
@@ -136,6 +208,16 @@ pub struct ModelResult {
     pub outcome: ModelOutcome,
 }
 
+impl ModelResult {
+    /// Failure detail, if this model failed to parse or infer
+    pub fn failure_detail(&self) -> Option<&FailureDetail> {
+        match &self.outcome {
+            ModelOutcome::ParseFailure(detail) | ModelOutcome::InferenceFailure(detail) => Some(detail),
+            _ => None,
+        }
+    }
+}
+
 /// Outcome of testing a model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -168,4 +250,90 @@ pub struct FailureDetail {
 
     /// Optional context (e.g., SQL snippet)
     pub context: Option<String>,
+
+    /// Unsupported construct that stopped inference (e.g., "PIVOT")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unsupported_kind: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(model: &str, code: &str) -> ModelResult {
+        ModelResult {
+            model_name: model.to_string(),
+            file_path: format!("models/{}.sql", model),
+            outcome: ModelOutcome::ParseFailure(FailureDetail {
+                code: code.to_string(),
+                message: format!("{} failed", model),
+                context: None,
+                unsupported_kind: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn samples_are_independent_of_insertion_order() {
+        let models = ["e", "b", "d", "a", "c"];
+
+        let mut forward = CompatMetrics::new("p", "ansi").with_samples_per_code(2);
+        for m in models {
+            forward.add_model_result(failure(m, "SQL_PARSE_ERROR"));
+        }
+
+        let mut reverse = CompatMetrics::new("p", "ansi").with_samples_per_code(2);
+        for m in models.iter().rev() {
+            reverse.add_model_result(failure(m, "SQL_PARSE_ERROR"));
+        }
+
+        assert_eq!(forward.failure_samples, reverse.failure_samples);
+        assert_eq!(
+            forward.failure_samples["SQL_PARSE_ERROR"],
+            vec!["a failed".to_string(), "b failed".to_string()]
+        );
+    }
+
+    #[test]
+    fn samples_with_the_same_model_are_ordered_by_message() {
+        let same_model = |message: &str| {
+            let mut result = failure("a", "SQL_PARSE_ERROR");
+            if let ModelOutcome::ParseFailure(detail) = &mut result.outcome {
+                detail.message = message.to_string();
+            }
+            result
+        };
+        let results = [same_model("z"), same_model("x"), same_model("y")];
+
+        let mut forward = CompatMetrics::new("p", "ansi").with_samples_per_code(2);
+        results.iter().cloned().for_each(|r| forward.add_model_result(r));
+        let mut reverse = CompatMetrics::new("p", "ansi").with_samples_per_code(2);
+        results.iter().rev().cloned().for_each(|r| reverse.add_model_result(r));
+
+        assert_eq!(forward.failure_samples["SQL_PARSE_ERROR"], vec!["x".to_string(), "y".to_string()]);
+        assert_eq!(forward.failure_samples, reverse.failure_samples);
+    }
+
+    #[test]
+    fn samples_continue_after_a_round_trip() {
+        let mut metrics = CompatMetrics::new("p", "ansi").with_samples_per_code(2);
+        metrics.add_model_result(failure("c", "SQL_PARSE_ERROR"));
+        metrics.add_model_result(failure("b", "SQL_PARSE_ERROR"));
+
+        let mut loaded: CompatMetrics = serde_json::from_str(&serde_json::to_string(&metrics).unwrap()).unwrap();
+        loaded.add_model_result(failure("a", "SQL_PARSE_ERROR"));
+
+        assert_eq!(loaded.failure_samples["SQL_PARSE_ERROR"], vec!["a failed".to_string(), "b failed".to_string()]);
+    }
+
+    #[test]
+    fn top_failure_codes_break_ties_by_code() {
+        let mut metrics = CompatMetrics::new("p", "ansi");
+        metrics.add_model_result(failure("a", "B_CODE"));
+        metrics.add_model_result(failure("b", "A_CODE"));
+
+        let top = metrics.top_failure_codes(2);
+        assert_eq!(top[0].0, "A_CODE");
+        assert_eq!(top[1].0, "B_CODE");
+    }
 }
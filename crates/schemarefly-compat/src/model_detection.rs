@@ -43,6 +43,9 @@ pub enum UnsupportedReason {
     /// Snapshots don't support dbt contracts
     Snapshot,
 
+    /// Macro definitions are not models
+    Macro,
+
     /// Other reason
     Other(String),
 }
@@ -71,6 +74,10 @@ impl UnsupportedReason {
                  dbt contracts are only available for standard models. \
                  SchemaRefly will skip schema inference for this model.".to_string()
             }
+            UnsupportedReason::Macro => {
+                "File only defines Jinja macros and is not a model. \
+                 SchemaRefly will skip parsing for this file.".to_string()
+            }
             UnsupportedReason::Other(reason) => {
                 format!("This model type is not supported for dbt contracts: {}", reason)
             }
@@ -103,6 +110,9 @@ pub fn detect_model_type(node: &ManifestNode) -> Result<ModelType, UnsupportedRe
         "source" => {
             Err(UnsupportedReason::Other("Sources are not models".to_string()))
         }
+        "macro" => {
+            Err(UnsupportedReason::Macro)
+        }
         "test" => {
             Err(UnsupportedReason::Other("Tests are not models".to_string()))
         }
@@ -115,6 +125,71 @@ pub fn detect_model_type(node: &ManifestNode) -> Result<ModelType, UnsupportedRe
     }
 }
 
+/// Resource types that are never parsed as SELECT statements
+pub fn is_non_model_resource(resource_type: &str) -> bool {
+    matches!(resource_type, "macro" | "analysis" | "test")
+}
+
+/// Detect a file that only defines `{% macro %}` blocks
+///
+/// Anything outside the macro bodies other than whitespace and `{# #}`
+/// comments means the file renders SQL of its own and is treated as a model.
+pub fn is_macro_file(sql: &str) -> bool {
+    let mut rest = sql;
+    let mut found_macro = false;
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return found_macro;
+        }
+
+        if let Some(comment) = rest.strip_prefix("{#") {
+            match comment.find("#}") {
+                Some(end) => rest = &comment[end + 2..],
+                None => return false,
+            }
+            continue;
+        }
+
+        if !block_tag_is(rest, "macro") {
+            return false;
+        }
+
+        match find_endmacro(rest) {
+            Some(end) => {
+                rest = &rest[end..];
+                found_macro = true;
+            }
+            None => return false,
+        }
+    }
+}
+
+/// Whether `text` starts with a `{% <keyword> ... %}` tag
+fn block_tag_is(text: &str, keyword: &str) -> bool {
+    let Some(tag) = text.strip_prefix("{%") else {
+        return false;
+    };
+    let tag = tag.strip_prefix('-').unwrap_or(tag).trim_start();
+    tag.strip_prefix(keyword)
+        .is_some_and(|after| after.starts_with(|c: char| c.is_whitespace() || c == '%' || c == '-'))
+}
+
+/// Byte offset just past the `{% endmacro %}` tag closing the macro at the start of `text`
+fn find_endmacro(text: &str) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("{%") {
+        let tag_start = offset + start;
+        let tag_end = tag_start + text[tag_start..].find("%}")? + 2;
+        if block_tag_is(&text[tag_start..], "endmacro") {
+            return Some(tag_end);
+        }
+        offset = tag_end;
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,6 +204,7 @@ mod tests {
             package_name: "my_project".to_string(),
             path: "test.sql".to_string(),
             original_file_path: "models/test.sql".to_string(),
+            patch_path: None,
             database: None,
             schema: None,
             alias: None,
@@ -136,11 +212,18 @@ mod tests {
                 enabled: true,
                 materialized,
                 contract: None,
+                meta: HashMap::new(),
             },
             description: String::new(),
             columns: HashMap::new(),
+            meta: HashMap::new(),
             depends_on: Default::default(),
             fqn: vec!["my_project".to_string(), "test".to_string()],
+            compiled_code: None,
+            version: None,
+            test_metadata: None,
+            column_name: None,
+            attached_node: None,
         }
     }
 
@@ -167,4 +250,27 @@ mod tests {
         let node = create_test_node("snapshot", None);
         assert_eq!(detect_model_type(&node), Err(UnsupportedReason::Snapshot));
     }
+
+    #[test]
+    fn test_detect_macro_and_non_model_resources() {
+        let node = create_test_node("macro", None);
+        assert_eq!(detect_model_type(&node), Err(UnsupportedReason::Macro));
+
+        assert!(is_non_model_resource("macro"));
+        assert!(is_non_model_resource("analysis"));
+        assert!(is_non_model_resource("test"));
+        assert!(!is_non_model_resource("model"));
+    }
+
+    #[test]
+    fn test_is_macro_file() {
+        let macros = "{# helpers #}\n{% macro cents(col) %}\n  ({{ col }} / 100)\n{% endmacro %}\n\n\
+                      {%- macro dollars(col) -%}{{ col }}{%- endmacro -%}\n";
+        assert!(is_macro_file(macros));
+
+        assert!(!is_macro_file("{% macro m() %}1{% endmacro %}\nSELECT {{ m() }} AS one"));
+        assert!(!is_macro_file("{% set x = 1 %}SELECT {{ x }} AS one"));
+        assert!(!is_macro_file("SELECT 1 AS one"));
+        assert!(!is_macro_file(""));
+    }
 }
@@ -111,6 +111,15 @@ impl CompatReport {
                     }
                 }
             }
+
+            if !metrics.unsupported_kinds.is_empty() {
+                println!("    Unsupported Constructs:");
+                let mut kinds: Vec<_> = metrics.unsupported_kinds.iter().collect();
+                kinds.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+                for (kind, count) in kinds {
+                    println!("      {} - {} models", kind.red(), count);
+                }
+            }
         }
 
         // Summary
@@ -1,8 +1,9 @@
 //! Configuration schema (schemarefly.toml)
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use crate::diagnostic::{DiagnosticCode, Severity};
+use crate::schema::{EnforcementPolicy, ExtraColumnPolicy, IdentifierCase};
 
 /// SQL dialect configuration
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -23,6 +24,19 @@ pub enum DialectConfig {
     Ansi,
 }
 
+impl DialectConfig {
+    /// How the dialect compares unquoted identifiers
+    ///
+    /// Snowflake folds unquoted identifiers to upper case, so `user_id` and
+    /// `USER_ID` name the same column; the other dialects are case-sensitive.
+    pub fn identifier_case(&self) -> IdentifierCase {
+        match self {
+            Self::Snowflake => IdentifierCase::Insensitive,
+            Self::BigQuery | Self::Postgres | Self::Ansi => IdentifierCase::Sensitive,
+        }
+    }
+}
+
 
 /// Severity threshold overrides for specific diagnostic codes
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -48,6 +62,40 @@ impl SeverityThreshold {
     }
 }
 
+/// Text appended to rendered diagnostic messages, keyed by diagnostic code
+///
+/// Templates may use `{model}` and `{column}` placeholders. They only affect
+/// how diagnostics are displayed; the stored `message` (used for hashing and
+/// baselines) is never changed. Keys must be known diagnostic codes.
+///
+/// ```toml
+/// [messages]
+/// CONTRACT_TYPE_MISMATCH = "See runbook: https://wiki.example.com/contracts#{column}"
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MessageTemplates {
+    /// Map of diagnostic code to message template
+    pub templates: HashMap<DiagnosticCode, String>,
+}
+
+impl MessageTemplates {
+    /// Template configured for a diagnostic code
+    pub fn get(&self, code: DiagnosticCode) -> Option<&str> {
+        self.templates.get(&code).map(String::as_str)
+    }
+
+    /// Set the template for a code
+    pub fn set(&mut self, code: DiagnosticCode, template: impl Into<String>) {
+        self.templates.insert(code, template.into());
+    }
+
+    /// Whether no templates are configured
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+}
+
 /// Warehouse connection configuration for drift detection
 ///
 /// Supports environment variable interpolation for sensitive settings like passwords.
@@ -64,6 +112,7 @@ impl SeverityThreshold {
 /// [warehouse.settings]
 /// project_id = "my-gcp-project"
 /// # password will be read from SCHEMAREFLY_PASSWORD env var
+/// dataset = "${BQ_DATASET}"  # expanded when the config is loaded
 /// ```
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WarehouseConfig {
@@ -252,6 +301,29 @@ impl WarehouseConfig {
         })
     }
 
+    /// Replace `${VAR}` references in the warehouse type and settings values
+    /// with values from the process environment
+    ///
+    /// Fails if a referenced variable is not set, so a missing secret is
+    /// reported at load time instead of as a confusing connection error.
+    pub fn interpolate_env(&mut self) -> Result<(), ConfigError> {
+        self.interpolate_with(|name| std::env::var(name).ok())
+    }
+
+    /// Replace `${VAR}` references using a custom variable lookup
+    pub fn interpolate_with(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), ConfigError> {
+        self.warehouse_type = interpolate_vars("type", &self.warehouse_type, &lookup)?;
+
+        for (key, value) in self.settings.iter_mut() {
+            *value = interpolate_vars(key, value, &lookup)?;
+        }
+
+        Ok(())
+    }
+
     /// Check if a setting exists (either in config or environment)
     pub fn has_setting(&self, key: &str) -> bool {
         self.get_setting(key).is_some()
@@ -278,6 +350,107 @@ impl WarehouseConfig {
     }
 }
 
+/// Supported warehouse kinds for drift detection
+///
+/// Parsed from `[warehouse] type` and validated up front so that missing
+/// settings are reported before any connection is attempted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WarehouseKind {
+    /// Google BigQuery
+    BigQuery,
+
+    /// Snowflake
+    Snowflake,
+
+    /// PostgreSQL (also accepts "postgresql")
+    Postgres,
+
+    /// Amazon Redshift (PostgreSQL wire protocol)
+    Redshift,
+}
+
+impl WarehouseKind {
+    /// Parse a warehouse type string (case-insensitive)
+    pub fn parse(warehouse_type: &str) -> Option<Self> {
+        match warehouse_type.to_lowercase().as_str() {
+            "bigquery" => Some(WarehouseKind::BigQuery),
+            "snowflake" => Some(WarehouseKind::Snowflake),
+            "postgres" | "postgresql" => Some(WarehouseKind::Postgres),
+            "redshift" => Some(WarehouseKind::Redshift),
+            _ => None,
+        }
+    }
+
+    /// Canonical lowercase name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarehouseKind::BigQuery => "bigquery",
+            WarehouseKind::Snowflake => "snowflake",
+            WarehouseKind::Postgres => "postgres",
+            WarehouseKind::Redshift => "redshift",
+        }
+    }
+
+    /// Settings that must be present for this warehouse kind
+    pub fn required_settings(&self) -> &'static [&'static str] {
+        match self {
+            WarehouseKind::BigQuery => &["project_id"],
+            // Plus either `password` or `private_key_path` (checked in `from_config`)
+            WarehouseKind::Snowflake => &["account", "username"],
+            WarehouseKind::Postgres | WarehouseKind::Redshift => {
+                &["host", "database", "username", "password"]
+            }
+        }
+    }
+
+    /// Determine the warehouse kind from config and validate its settings
+    ///
+    /// Returns an error naming the first missing required setting, or an
+    /// invalid `port` value for PostgreSQL-compatible warehouses.
+    pub fn from_config(config: &WarehouseConfig) -> Result<Self, ConfigError> {
+        let kind = Self::parse(&config.warehouse_type)
+            .ok_or_else(|| ConfigError::UnsupportedWarehouse(config.warehouse_type.clone()))?;
+
+        for key in kind.required_settings() {
+            config.require_setting(key).map_err(|message| ConfigError::MissingSetting {
+                warehouse: kind.as_str().to_string(),
+                key: key.to_string(),
+                message,
+            })?;
+        }
+
+        if kind == WarehouseKind::Snowflake
+            && config.get_setting("password").is_none()
+            && config.get_setting("private_key_path").is_none()
+        {
+            return Err(ConfigError::MissingSetting {
+                warehouse: kind.as_str().to_string(),
+                key: "password".to_string(),
+                message: "Missing 'password' or 'private_key_path' in [warehouse.settings] \
+                          (key-pair authentication uses private_key_path)"
+                    .to_string(),
+            });
+        }
+
+        if matches!(kind, WarehouseKind::Postgres | WarehouseKind::Redshift) {
+            if let Some(port) = config.get_setting("port") {
+                port.parse::<u16>().map_err(|_| ConfigError::InvalidSetting {
+                    key: "port".to_string(),
+                    message: format!("'{}' is not a valid port number", port),
+                })?;
+            }
+        }
+
+        Ok(kind)
+    }
+}
+
+impl std::fmt::Display for WarehouseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Allowlist rules for specific models or patterns
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[derive(Default)]
@@ -325,6 +498,243 @@ impl AllowlistRules {
     }
 }
 
+/// Contract enforcement settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Default)]
+pub struct ContractConfig {
+    /// How extra columns are treated: "warn" (default), "strict" or "additive"
+    #[serde(default)]
+    pub extra_columns: ExtraColumnPolicy,
+
+    /// Require inferred column order to match the contract's declared order
+    #[serde(default)]
+    pub enforce_column_order: bool,
+
+    /// Models whose file path matches one of these globs must have an
+    /// enforced contract (e.g. `models/marts/*`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub require_contract_paths: Vec<String>,
+
+    /// Minimum fraction (0.0 to 1.0) of contract columns whose inferred type
+    /// must be concrete rather than unknown; reported as a warning unless
+    /// `[severity.overrides]` sets `SQL_INFERENCE_LOW_COVERAGE`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_inference_coverage: Option<f64>,
+}
+
+impl ContractConfig {
+    /// Whether a model at `path` (its `original_file_path`) must have an enforced contract
+    pub fn requires_contract(&self, path: &str) -> bool {
+        self.require_contract_paths.iter().any(|pattern| glob_match(pattern, path))
+    }
+}
+
+/// Drift detection settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Default)]
+pub struct DriftConfig {
+    /// Skip these tables (glob patterns matched against `table`, `schema.table`
+    /// and `database.schema.table`, case-insensitively)
+    #[serde(default)]
+    pub ignore_tables: Vec<String>,
+
+    /// Skip every table in these schemas (glob patterns matched against
+    /// `schema` and `database.schema`, case-insensitively)
+    #[serde(default)]
+    pub ignore_schemas: Vec<String>,
+
+    /// Warehouse-managed columns (glob patterns such as `_fivetran_*`, matched
+    /// case-insensitively) whose additions and changes are not reported
+    #[serde(default)]
+    pub ignore_columns: Vec<String>,
+
+    /// Whether to report one summary diagnostic per drifted table
+    #[serde(default)]
+    pub summary: DriftSummaryMode,
+
+    /// Warehouse type names mapped to known ones before comparison
+    /// (e.g. a `MONEY_T` domain to `NUMERIC(19,4)`), matched case-insensitively
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub type_aliases: HashMap<String, String>,
+
+    /// Compare the stored SQL of view models with their compiled SQL
+    /// (adapters that cannot read view definitions skip the check)
+    #[serde(default)]
+    pub check_view_definitions: bool,
+
+    /// Report `timestamp` ↔ `timestamptz` changes as type drift
+    /// (only where both sides say whether the column has a time zone)
+    #[serde(default)]
+    pub timezone_aware: bool,
+
+    /// Treat `DECIMAL(p,0)`/`NUMERIC(p,0)` and integer columns as the same type
+    /// (contract checks already accept integers for any decimal)
+    #[serde(default)]
+    pub treat_integral_decimal_as_int: bool,
+}
+
+/// How per-table drift summaries are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum DriftSummaryMode {
+    /// Per-column findings only
+    #[default]
+    Off,
+
+    /// A summary per table in addition to the per-column findings
+    Alongside,
+
+    /// A summary per table replacing the per-column findings
+    Instead,
+}
+
+impl DriftConfig {
+    /// Check if a warehouse table is excluded from drift detection
+    pub fn is_ignored(&self, database: &str, schema: &str, table: &str) -> bool {
+        let matches = |patterns: &[String], candidates: &[String]| {
+            patterns.iter().any(|pattern| {
+                let pattern = pattern.to_lowercase();
+                candidates.iter().any(|candidate| glob_match(&pattern, candidate))
+            })
+        };
+
+        let schemas = [schema.to_lowercase(), format!("{}.{}", database, schema).to_lowercase()];
+        let tables = [
+            table.to_lowercase(),
+            format!("{}.{}", schema, table).to_lowercase(),
+            format!("{}.{}.{}", database, schema, table).to_lowercase(),
+        ];
+
+        matches(&self.ignore_schemas, &schemas) || matches(&self.ignore_tables, &tables)
+    }
+}
+
+/// Expand `${VAR}` references in a setting value
+fn interpolate_vars(
+    key: &str,
+    value: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<String, ConfigError> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        let end = after.find('}').ok_or_else(|| ConfigError::InvalidSetting {
+            key: key.to_string(),
+            message: "unterminated '${' in value".to_string(),
+        })?;
+
+        let var = &after[..end];
+        let resolved = lookup(var).ok_or_else(|| ConfigError::UndefinedEnvVar {
+            key: key.to_string(),
+            var: var.to_string(),
+        })?;
+        result.push_str(&resolved);
+        rest = &after[end + 1..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// dbt Cloud host used when `[manifest_source]` doesn't set one
+pub const DEFAULT_DBT_CLOUD_HOST: &str = "cloud.getdbt.com";
+
+/// Where `check` and `drift` read the dbt manifest from
+///
+/// When `[manifest_source]` is absent the manifest is read from the local
+/// target directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ManifestSourceConfig {
+    /// Artifacts of the latest run of a dbt Cloud job
+    DbtCloud(DbtCloudConfig),
+}
+
+impl ManifestSourceConfig {
+    /// Replace `${VAR}` references with environment variable values
+    pub fn interpolate_env(&mut self) -> Result<(), ConfigError> {
+        self.interpolate_with(|name| std::env::var(name).ok())
+    }
+
+    /// Replace `${VAR}` references using a custom variable lookup
+    pub fn interpolate_with(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), ConfigError> {
+        match self {
+            ManifestSourceConfig::DbtCloud(cloud) => {
+                cloud.account_id = interpolate_vars("account_id", &cloud.account_id, &lookup)?;
+                cloud.job_id = interpolate_vars("job_id", &cloud.job_id, &lookup)?;
+                cloud.token = interpolate_vars("token", &cloud.token, &lookup)?;
+                cloud.host = interpolate_vars("host", &cloud.host, &lookup)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// dbt Cloud job whose latest run provides `manifest.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbtCloudConfig {
+    /// dbt Cloud account ID
+    pub account_id: String,
+
+    /// Job whose most recent successful run is used
+    pub job_id: String,
+
+    /// API token (use `${VAR}` to read it from the environment)
+    pub token: String,
+
+    /// Access URL host, e.g. `emea.dbt.com` for multi-region accounts
+    #[serde(default = "default_dbt_cloud_host")]
+    pub host: String,
+}
+
+fn default_dbt_cloud_host() -> String {
+    DEFAULT_DBT_CLOUD_HOST.to_string()
+}
+
+/// Schema inference settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InferenceConfig {
+    /// Maximum nesting depth of queries and expressions before inference fails
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+
+    /// Mark projected columns as non-nullable when the WHERE clause filters
+    /// them with `col = <literal>` or `col IS NOT NULL`
+    #[serde(default)]
+    pub nullability_from_where: bool,
+}
+
+fn default_max_depth() -> usize {
+    256
+}
+
+impl Default for InferenceConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: default_max_depth(),
+            nullability_from_where: false,
+        }
+    }
+}
+
+/// File name of the SchemaRefly config file
+pub const CONFIG_FILE_NAME: &str = "schemarefly.toml";
+
+/// dbt target directory used when neither config nor `DBT_TARGET_PATH` sets one
+pub const DEFAULT_TARGET_PATH: &str = "target";
+
+/// Environment variable dbt reads to override its target directory
+pub const DBT_TARGET_PATH_ENV: &str = "DBT_TARGET_PATH";
+
 /// Main configuration structure
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Config {
@@ -340,15 +750,60 @@ pub struct Config {
     #[serde(default)]
     pub allowlist: AllowlistRules,
 
+    /// Contract enforcement settings
+    #[serde(default)]
+    pub contract: ContractConfig,
+
+    /// Schema inference settings
+    #[serde(default)]
+    pub inference: InferenceConfig,
+
+    /// Drift detection settings
+    #[serde(default)]
+    pub drift: DriftConfig,
+
     /// Warehouse connection configuration (for drift detection)
     #[serde(default)]
     pub warehouse: Option<WarehouseConfig>,
 
+    /// Per-code text appended to messages when rendering reports
+    #[serde(default, skip_serializing_if = "MessageTemplates::is_empty")]
+    pub messages: MessageTemplates,
+
+    /// Remote manifest location (defaults to the local target directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_source: Option<ManifestSourceConfig>,
+
+    /// dbt target directory holding `manifest.json` and other artifacts
+    ///
+    /// Relative paths are resolved against the project root. When unset,
+    /// `DBT_TARGET_PATH` is used, then `target`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_path: Option<std::path::PathBuf>,
+
+    /// Diagnostic codes that fail CI when reported at Error severity
+    ///
+    /// When empty, any error fails. Diagnostics with other codes are still reported.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fail_on_codes: Vec<DiagnosticCode>,
+
+    /// Maximum diagnostics reported per model; extras collapse into one summary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_diagnostics_per_model: Option<usize>,
+
     /// Redact sensitive data (schema names, column names, table names) in diagnostics and logs
     /// This is useful for privacy/security when sharing reports or logs
     #[serde(default)]
     pub redact_sensitive_data: bool,
 
+    /// dbt project variables for `var()` when rendering model Jinja (`[vars]`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub vars: HashMap<String, serde_json::Value>,
+
+    /// Per-environment overlays (`[env.prod]`), applied with `--env <name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, EnvOverlay>,
+
     /// Project root path (for resolving relative paths)
     #[serde(skip)]
     pub project_root: std::path::PathBuf,
@@ -360,8 +815,18 @@ impl Default for Config {
             dialect: DialectConfig::default(),
             severity: SeverityThreshold::default(),
             allowlist: AllowlistRules::default(),
+            contract: ContractConfig::default(),
+            inference: InferenceConfig::default(),
+            drift: DriftConfig::default(),
             warehouse: None,
+            messages: MessageTemplates::default(),
+            manifest_source: None,
+            target_path: None,
+            fail_on_codes: Vec::new(),
+            max_diagnostics_per_model: None,
             redact_sensitive_data: false,
+            vars: HashMap::new(),
+            env: BTreeMap::new(),
             project_root: std::env::current_dir().unwrap_or_default(),
         }
     }
@@ -373,21 +838,152 @@ impl Config {
         let contents = std::fs::read_to_string(path)
             .map_err(|e| ConfigError::IoError(e.to_string()))?;
 
-        let mut config: Config = toml::from_str(&contents)
-            .map_err(|e| ConfigError::ParseError(e.to_string()))?;
+        let mut config = Self::from_toml(&contents)?;
 
-        // Set project root to parent of config file
+        // Set project root to parent of config file (`.` for a bare file name)
         if let Some(parent) = path.parent() {
-            config.project_root = parent.to_path_buf();
+            config.project_root = if parent.as_os_str().is_empty() {
+                std::path::PathBuf::from(".")
+            } else {
+                parent.to_path_buf()
+            };
         }
 
         Ok(config)
     }
 
+    /// Find `schemarefly.toml` in `start_dir` or one of its ancestors
+    ///
+    /// The search stops at the first directory containing `.git` (the
+    /// repository root) or at the filesystem root.
+    pub fn find_config_file(start_dir: &std::path::Path) -> Option<std::path::PathBuf> {
+        for dir in start_dir.ancestors() {
+            let candidate = dir.join(CONFIG_FILE_NAME);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+
+        None
+    }
+
+    /// Discover and load the nearest `schemarefly.toml` from `start_dir` upwards
+    ///
+    /// Returns `None` if no config file is found or it fails to load; use
+    /// [`Config::find_config_file`] with [`Config::from_file`] to surface errors.
+    pub fn discover(start_dir: &std::path::Path) -> Option<(std::path::PathBuf, Config)> {
+        let path = Self::find_config_file(start_dir)?;
+        let config = Self::from_file(&path).ok()?;
+        Some((path, config))
+    }
+
     /// Load config from TOML string
+    ///
+    /// `${VAR}` references in `[warehouse]` settings are expanded from the environment.
     pub fn from_toml(toml: &str) -> Result<Self, ConfigError> {
-        toml::from_str(toml)
-            .map_err(|e| ConfigError::ParseError(e.to_string()))
+        let mut config: Config = toml::from_str(toml)
+            .map_err(|e| ConfigError::ParseError(e.to_string()))?;
+
+        if let Some(warehouse) = config.warehouse.as_mut() {
+            warehouse.interpolate_env()?;
+        }
+
+        if let Some(source) = config.manifest_source.as_mut() {
+            source.interpolate_env()?;
+        }
+
+        if let Some(min) = config.contract.min_inference_coverage {
+            if !(0.0..=1.0).contains(&min) {
+                return Err(ConfigError::InvalidSetting {
+                    key: "contract.min_inference_coverage".to_string(),
+                    message: format!("{} is not a fraction between 0.0 and 1.0", min),
+                });
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Layer the `[env.<name>]` overlay over this config
+    ///
+    /// Severity overrides are merged code by code, the overlay's winning.
+    /// `fail_on_codes` and `strict` replace the base settings when the overlay
+    /// sets them; anything the overlay leaves out keeps its base value.
+    /// Applying the same overlay twice gives the same config.
+    pub fn apply_env(&mut self, name: &str) -> Result<(), ConfigError> {
+        let overlay = self.env.get(name).cloned().ok_or_else(|| {
+            let defined: Vec<&str> = self.env.keys().map(String::as_str).collect();
+            ConfigError::InvalidSetting {
+                key: "env".to_string(),
+                message: format!("no [env.{}] section (defined: {})", name, defined.join(", ")),
+            }
+        })?;
+
+        self.severity.overrides.extend(overlay.severity.overrides);
+
+        if let Some(fail_on_codes) = overlay.fail_on_codes {
+            self.fail_on_codes = fail_on_codes;
+        }
+
+        match overlay.strict {
+            Some(true) => self.contract.extra_columns = ExtraColumnPolicy::Strict,
+            // Relaxing only undoes strict; an additive base stays additive
+            Some(false) if self.contract.extra_columns == ExtraColumnPolicy::Strict => {
+                self.contract.extra_columns = ExtraColumnPolicy::Warn;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Directory holding dbt artifacts (`manifest.json`, `catalog.json`, ...)
+    pub fn target_dir(&self) -> std::path::PathBuf {
+        self.target_dir_with(|name| std::env::var(name).ok())
+    }
+
+    /// Resolve the target directory using a custom environment lookup
+    ///
+    /// Precedence: `target_path` from config, then `DBT_TARGET_PATH`, then `target`.
+    pub fn target_dir_with(&self, lookup: impl Fn(&str) -> Option<String>) -> std::path::PathBuf {
+        let target = self
+            .target_path
+            .clone()
+            .or_else(|| {
+                lookup(DBT_TARGET_PATH_ENV)
+                    .filter(|v| !v.is_empty())
+                    .map(std::path::PathBuf::from)
+            })
+            .unwrap_or_else(|| std::path::PathBuf::from(DEFAULT_TARGET_PATH));
+
+        self.project_root.join(target)
+    }
+
+    /// Path of the dbt manifest inside the target directory
+    pub fn manifest_path(&self) -> std::path::PathBuf {
+        self.target_dir().join("manifest.json")
+    }
+
+    /// Enforcement policy for a model's contract
+    ///
+    /// Starts from `[contract]` settings; models matched by
+    /// `allowlist.allow_extra_columns` are treated as additive.
+    pub fn enforcement_policy_for(&self, model: &str) -> EnforcementPolicy {
+        let extra_columns = if self.allowlist.are_extra_columns_allowed(model) {
+            ExtraColumnPolicy::Additive
+        } else {
+            self.contract.extra_columns
+        };
+
+        EnforcementPolicy {
+            allow_widening: self.allowlist.is_widening_allowed(model),
+            ..EnforcementPolicy::default()
+        }
+        .with_extra_columns(extra_columns)
     }
 
     /// Save config to TOML file
@@ -402,8 +998,27 @@ impl Config {
     }
 }
 
+/// Settings layered over the base config for one environment (`[env.<name>]`)
+///
+/// See [`Config::apply_env`] for how they are merged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvOverlay {
+    /// Severity overrides merged over `[severity.overrides]`
+    #[serde(default)]
+    pub severity: SeverityThreshold,
+
+    /// Replaces the base `fail_on_codes` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_on_codes: Option<Vec<DiagnosticCode>>,
+
+    /// Strict contracts: `true` sets `[contract] extra_columns = "strict"`,
+    /// `false` turns a strict base into "warn" and leaves any other policy alone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+}
+
 /// Simple glob matching (supports * and **)
-fn glob_match(pattern: &str, text: &str) -> bool {
+pub fn glob_match(pattern: &str, text: &str) -> bool {
     // Very simple implementation - just handle basic * wildcard
     if pattern == "*" || pattern == "**" {
         return true;
@@ -419,6 +1034,59 @@ fn glob_match(pattern: &str, text: &str) -> bool {
     }
 }
 
+/// Parse a `--vars` value into dbt variables
+///
+/// Accepts a JSON object (`{"start_date": "2024-01-01"}`) or comma-separated
+/// `key=value` pairs (`start_date=2024-01-01,full=true`). Pair values that
+/// parse as JSON (numbers, booleans, quoted strings) keep that type; anything
+/// else is a string.
+pub fn parse_vars(input: &str) -> Result<HashMap<String, serde_json::Value>, ConfigError> {
+    let input = input.trim();
+
+    if input.starts_with('{') {
+        return serde_json::from_str(input)
+            .map_err(|e| ConfigError::ParseError(format!("Invalid --vars JSON: {}", e)));
+    }
+
+    split_top_level(input)
+        .into_iter()
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').ok_or_else(|| {
+                ConfigError::ParseError(format!("Invalid --vars entry '{}': expected key=value", pair.trim()))
+            })?;
+            let value = value.trim();
+            let value = serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+            Ok((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// Split `key=value` pairs on commas outside brackets, braces and quotes
+fn split_top_level(input: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                parts.push(&input[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
 /// Config error types
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -430,12 +1098,150 @@ pub enum ConfigError {
 
     #[error("Serialize error: {0}")]
     SerializeError(String),
+
+    #[error("Unsupported warehouse type '{0}'. Supported: bigquery, snowflake, postgres, redshift")]
+    UnsupportedWarehouse(String),
+
+    #[error("{warehouse} configuration error: {message}")]
+    MissingSetting {
+        warehouse: String,
+        key: String,
+        message: String,
+    },
+
+    #[error("Invalid setting '{key}': {message}")]
+    InvalidSetting {
+        key: String,
+        message: String,
+    },
+
+    #[error("Setting '{key}' references environment variable '{var}', which is not set")]
+    UndefinedEnvVar {
+        key: String,
+        var: String,
+    },
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn discover_config_in_parent_directory() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join(".git")).unwrap();
+        std::fs::write(root.path().join(CONFIG_FILE_NAME), "dialect = \"snowflake\"\n").unwrap();
+
+        let nested = root.path().join("models").join("staging");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let (path, config) = Config::discover(&nested).unwrap();
+        assert_eq!(path, root.path().join(CONFIG_FILE_NAME));
+        assert_eq!(config.dialect, DialectConfig::Snowflake);
+        assert_eq!(config.project_root, root.path());
+    }
+
+    #[test]
+    fn discover_stops_at_repository_root() {
+        let outer = tempfile::tempdir().unwrap();
+        std::fs::write(outer.path().join(CONFIG_FILE_NAME), "dialect = \"snowflake\"\n").unwrap();
+
+        // The config above the repository root must not be picked up
+        let repo = outer.path().join("repo");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        let nested = repo.join("models");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert!(Config::discover(&nested).is_none());
+    }
+
+    #[test]
+    fn configured_target_path_locates_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join(CONFIG_FILE_NAME),
+            "target_path = \"build/dbt\"\n",
+        ).unwrap();
+
+        let config = Config::from_file(&root.path().join(CONFIG_FILE_NAME)).unwrap();
+        assert_eq!(config.manifest_path(), root.path().join("build/dbt/manifest.json"));
+
+        // Config wins over DBT_TARGET_PATH
+        let dir = config.target_dir_with(|_| Some("env-target".to_string()));
+        assert_eq!(dir, root.path().join("build/dbt"));
+    }
+
+    #[test]
+    fn target_path_falls_back_to_env_then_default() {
+        let config = Config {
+            project_root: std::path::PathBuf::from("/project"),
+            ..Config::default()
+        };
+
+        let from_env = config.target_dir_with(|name| {
+            (name == DBT_TARGET_PATH_ENV).then(|| "dbt-out".to_string())
+        });
+        assert_eq!(from_env, std::path::PathBuf::from("/project/dbt-out"));
+
+        assert_eq!(config.target_dir_with(|_| None), std::path::PathBuf::from("/project/target"));
+        assert_eq!(config.target_dir_with(|_| Some(String::new())), std::path::PathBuf::from("/project/target"));
+    }
+
+    #[test]
+    fn dbt_cloud_manifest_source_interpolates_token() {
+        std::env::set_var("SCHEMAREFLY_TEST_DBT_CLOUD_TOKEN", "dbtc_secret");
+
+        let config = Config::from_toml(r#"
+            [manifest_source]
+            type = "dbt_cloud"
+            account_id = "12345"
+            job_id = "678"
+            token = "${SCHEMAREFLY_TEST_DBT_CLOUD_TOKEN}"
+        "#).unwrap();
+
+        let Some(ManifestSourceConfig::DbtCloud(cloud)) = config.manifest_source else {
+            panic!("expected a dbt Cloud manifest source");
+        };
+        assert_eq!(cloud.account_id, "12345");
+        assert_eq!(cloud.job_id, "678");
+        assert_eq!(cloud.token, "dbtc_secret");
+        assert_eq!(cloud.host, DEFAULT_DBT_CLOUD_HOST);
+    }
+
+    #[test]
+    fn warehouse_settings_interpolate_env_vars() {
+        std::env::set_var("SCHEMAREFLY_TEST_INTERP_PASSWORD", "s3cret");
+
+        let config = Config::from_toml(r#"
+            [warehouse]
+            type = "snowflake"
+
+            [warehouse.settings]
+            account = "acme"
+            password = "${SCHEMAREFLY_TEST_INTERP_PASSWORD}"
+            role = "role_${SCHEMAREFLY_TEST_INTERP_PASSWORD}_x"
+        "#).unwrap();
+
+        let warehouse = config.warehouse.unwrap();
+        assert_eq!(warehouse.settings["password"], "s3cret");
+        assert_eq!(warehouse.settings["role"], "role_s3cret_x");
+        assert_eq!(warehouse.settings["account"], "acme");
+    }
+
+    #[test]
+    fn warehouse_settings_undefined_env_var_errors() {
+        let mut warehouse = WarehouseConfig::new("postgres")
+            .with_setting("password", "${PG_SECRET_THAT_IS_UNSET}");
+
+        let err = warehouse.interpolate_with(|_| None).unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::UndefinedEnvVar { ref key, ref var }
+                if key == "password" && var == "PG_SECRET_THAT_IS_UNSET"
+        ));
+        assert!(err.to_string().contains("PG_SECRET_THAT_IS_UNSET"));
+    }
+
     #[test]
     fn default_config() {
         let config = Config::default();
@@ -455,8 +1261,10 @@ mod tests {
 
     #[test]
     fn allowlist_pattern_matching() {
-        let mut rules = AllowlistRules::default();
-        rules.allow_extra_columns = vec!["staging.*".to_string()];
+        let rules = AllowlistRules {
+            allow_extra_columns: vec!["staging.*".to_string()],
+            ..Default::default()
+        };
 
         assert!(rules.are_extra_columns_allowed("staging.users"));
         assert!(!rules.are_extra_columns_allowed("prod.users"));
@@ -597,4 +1405,259 @@ mod tests {
         assert_eq!(warehouse.settings.get("account"), Some(&"xy12345".to_string()));
         assert_eq!(warehouse.settings.get("warehouse"), Some(&"COMPUTE_WH".to_string()));
     }
+
+    fn assert_missing(config: &WarehouseConfig, expected_key: &str) {
+        match WarehouseKind::from_config(config) {
+            Err(ConfigError::MissingSetting { key, .. }) => assert_eq!(key, expected_key),
+            other => panic!("expected missing '{}', got {:?}", expected_key, other),
+        }
+    }
+
+    #[test]
+    fn warehouse_kind_bigquery_requires_project_id() {
+        let config = WarehouseConfig::new("BigQuery").with_env_vars(false);
+        assert_missing(&config, "project_id");
+
+        let config = config.with_setting("project_id", "my-project");
+        assert_eq!(WarehouseKind::from_config(&config).unwrap(), WarehouseKind::BigQuery);
+    }
+
+    #[test]
+    fn warehouse_kind_snowflake_requires_credentials() {
+        let config = WarehouseConfig::new("snowflake")
+            .with_setting("account", "xy12345")
+            .with_env_vars(false);
+        assert_missing(&config, "username");
+
+        let config = config.with_setting("username", "user");
+        assert_missing(&config, "password");
+
+        let key_pair = config.clone().with_setting("private_key_path", "/keys/rsa_key.p8");
+        assert_eq!(WarehouseKind::from_config(&key_pair).unwrap(), WarehouseKind::Snowflake);
+
+        let config = config.with_setting("password", "secret");
+        assert_eq!(WarehouseKind::from_config(&config).unwrap(), WarehouseKind::Snowflake);
+    }
+
+    #[test]
+    fn warehouse_kind_postgres_requires_connection_settings() {
+        let config = WarehouseConfig::new("postgresql")
+            .with_setting("host", "localhost")
+            .with_setting("username", "user")
+            .with_setting("password", "secret")
+            .with_env_vars(false);
+        assert_missing(&config, "database");
+
+        let config = config.with_setting("database", "analytics");
+        assert_eq!(WarehouseKind::from_config(&config).unwrap(), WarehouseKind::Postgres);
+
+        let bad_port = config.with_setting("port", "not-a-port");
+        assert!(matches!(
+            WarehouseKind::from_config(&bad_port),
+            Err(ConfigError::InvalidSetting { .. })
+        ));
+    }
+
+    #[test]
+    fn warehouse_kind_redshift_requires_connection_settings() {
+        let config = WarehouseConfig::new("redshift").with_env_vars(false);
+        assert_missing(&config, "host");
+
+        let config = config
+            .with_setting("host", "cluster.example.com")
+            .with_setting("database", "dev")
+            .with_setting("username", "user")
+            .with_setting("password", "secret");
+        assert_eq!(WarehouseKind::from_config(&config).unwrap(), WarehouseKind::Redshift);
+    }
+
+    #[test]
+    fn warehouse_kind_rejects_unknown_type() {
+        let config = WarehouseConfig::new("oracle").with_env_vars(false);
+        assert!(matches!(
+            WarehouseKind::from_config(&config),
+            Err(ConfigError::UnsupportedWarehouse(t)) if t == "oracle"
+        ));
+    }
+
+    #[test]
+    fn test_contract_extra_columns_policy() {
+        let toml = r#"
+[contract]
+extra_columns = "strict"
+
+[allowlist]
+allow_extra_columns = ["staging_*"]
+"#;
+        let config = Config::from_toml(toml).unwrap();
+        assert_eq!(config.contract.extra_columns, ExtraColumnPolicy::Strict);
+        assert_eq!(
+            config.enforcement_policy_for("orders").extra_column_policy(),
+            ExtraColumnPolicy::Strict
+        );
+        assert_eq!(
+            config.enforcement_policy_for("staging_orders").extra_column_policy(),
+            ExtraColumnPolicy::Additive
+        );
+        assert_eq!(Config::default().contract.extra_columns, ExtraColumnPolicy::Warn);
+    }
+
+    #[test]
+    fn test_message_templates() {
+        let config = Config::from_toml(r#"
+[messages]
+CONTRACT_TYPE_MISMATCH = "See runbook: https://wiki/{model}"
+"#).unwrap();
+        assert_eq!(
+            config.messages.get(DiagnosticCode::ContractTypeMismatch),
+            Some("See runbook: https://wiki/{model}")
+        );
+        assert_eq!(config.messages.get(DiagnosticCode::ContractExtraColumn), None);
+        assert!(Config::default().messages.is_empty());
+
+        let saved = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(Config::from_toml(&saved).unwrap().messages, config.messages);
+
+        // A misspelled code would silently never match, so it is rejected like in fail_on_codes
+        assert!(Config::from_toml("[messages]\nCONTRACT_TYPE_MISMATC = \"typo\"").is_err());
+    }
+
+    #[test]
+    fn test_min_inference_coverage_range() {
+        let config = Config::from_toml("[contract]\nmin_inference_coverage = 0.8").unwrap();
+        assert_eq!(config.contract.min_inference_coverage, Some(0.8));
+
+        // 80 instead of 0.8 would otherwise flag every model
+        for invalid in ["80", "-0.1", "nan"] {
+            let toml = format!("[contract]\nmin_inference_coverage = {}", invalid);
+            assert!(matches!(
+                Config::from_toml(&toml),
+                Err(ConfigError::InvalidSetting { ref key, .. }) if key == "contract.min_inference_coverage"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_fail_on_codes() {
+        let config = Config::from_toml(r#"fail_on_codes = ["DRIFT_COLUMN_DROPPED", "DRIFT_TYPE_CHANGE"]"#).unwrap();
+        assert_eq!(
+            config.fail_on_codes,
+            vec![DiagnosticCode::DriftColumnDropped, DiagnosticCode::DriftTypeChange]
+        );
+        assert!(Config::default().fail_on_codes.is_empty());
+        assert!(Config::from_toml(r#"fail_on_codes = ["NOT_A_CODE"]"#).is_err());
+    }
+
+    #[test]
+    fn test_env_overlay_merges_over_base() {
+        let config = Config::from_toml(r#"
+            fail_on_codes = ["DRIFT_COLUMN_DROPPED"]
+
+            [severity.overrides]
+            CONTRACT_EXTRA_COLUMN = "info"
+            SQL_INFERENCE_LOW_COVERAGE = "info"
+
+            [env.prod]
+            fail_on_codes = []
+            strict = true
+
+            [env.prod.severity.overrides]
+            SQL_SELECT_STAR_UNEXPANDABLE = "error"
+            CONTRACT_EXTRA_COLUMN = "error"
+
+            [env.dev.severity.overrides]
+            SQL_SELECT_STAR_UNEXPANDABLE = "info"
+        "#).unwrap();
+
+        // Base leaves the warning as a warning
+        let code = DiagnosticCode::SqlSelectStarUnexpandable;
+        assert_eq!(config.severity.get_severity(code, Severity::Warn), Severity::Warn);
+        assert_eq!(config.contract.extra_columns, ExtraColumnPolicy::Warn);
+
+        // prod escalates it, wins over the base per code and keeps the rest
+        let mut prod = config.clone();
+        prod.apply_env("prod").unwrap();
+        assert_eq!(prod.severity.get_severity(code, Severity::Warn), Severity::Error);
+        assert_eq!(prod.severity.get_severity(DiagnosticCode::ContractExtraColumn, Severity::Warn), Severity::Error);
+        assert_eq!(prod.severity.get_severity(DiagnosticCode::SqlInferenceLowCoverage, Severity::Warn), Severity::Info);
+        assert!(prod.fail_on_codes.is_empty());
+        assert_eq!(prod.contract.extra_columns, ExtraColumnPolicy::Strict);
+
+        let mut again = prod.clone();
+        again.apply_env("prod").unwrap();
+        assert_eq!(again, prod);
+
+        // dev only touches severities
+        let mut dev = config.clone();
+        dev.apply_env("dev").unwrap();
+        assert_eq!(dev.severity.get_severity(code, Severity::Warn), Severity::Info);
+        assert_eq!(dev.fail_on_codes, vec![DiagnosticCode::DriftColumnDropped]);
+        assert_eq!(dev.contract.extra_columns, ExtraColumnPolicy::Warn);
+
+        let err = config.clone().apply_env("staging").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid setting 'env': no [env.staging] section (defined: dev, prod)");
+    }
+
+    #[test]
+    fn test_env_overlay_strict_false_only_relaxes_strict() {
+        let overlay = "[env.dev]\nstrict = false";
+        for (base, expected) in [
+            ("strict", ExtraColumnPolicy::Warn),
+            ("warn", ExtraColumnPolicy::Warn),
+            ("additive", ExtraColumnPolicy::Additive),
+        ] {
+            let toml = format!("[contract]\nextra_columns = \"{}\"\n{}", base, overlay);
+            let mut config = Config::from_toml(&toml).unwrap();
+            config.apply_env("dev").unwrap();
+            assert_eq!(config.contract.extra_columns, expected, "base {}", base);
+        }
+    }
+
+    #[test]
+    fn test_drift_ignore_patterns() {
+        let config = Config::from_toml(r#"
+            [drift]
+            ignore_schemas = ["staging", "*_temp"]
+            ignore_tables = ["analytics.tmp_*", "audit_log"]
+            ignore_columns = ["_fivetran_*", "_loaded_at"]
+        "#).unwrap();
+        let drift = &config.drift;
+        assert_eq!(drift.ignore_columns, vec!["_fivetran_*", "_loaded_at"]);
+
+        assert!(drift.is_ignored("db", "STAGING", "users"));
+        assert!(drift.is_ignored("db", "scratch_temp", "users"));
+        assert!(drift.is_ignored("db", "analytics", "tmp_orders"));
+        assert!(drift.is_ignored("db", "reporting", "audit_log"));
+        assert!(!drift.is_ignored("db", "analytics", "orders"));
+        assert!(!drift.is_ignored("db", "reporting", "tmp_orders"));
+        assert!(!Config::default().drift.is_ignored("db", "staging", "users"));
+    }
+
+    #[test]
+    fn test_vars_from_config_and_flag() {
+        let config = Config::from_toml(r#"
+            [vars]
+            include_deleted = false
+            start_date = "2024-01-01"
+        "#).unwrap();
+        assert_eq!(config.vars["include_deleted"], serde_json::json!(false));
+        assert_eq!(config.vars["start_date"], serde_json::json!("2024-01-01"));
+
+        let json = parse_vars(r#"{"include_deleted": true, "lookback": 7}"#).unwrap();
+        assert_eq!(json["include_deleted"], serde_json::json!(true));
+        assert_eq!(json["lookback"], serde_json::json!(7));
+
+        let pairs = parse_vars("include_deleted=true, region=eu-west-1,lookback=7").unwrap();
+        assert_eq!(pairs["include_deleted"], serde_json::json!(true));
+        assert_eq!(pairs["region"], serde_json::json!("eu-west-1"));
+        assert_eq!(pairs["lookback"], serde_json::json!(7));
+
+        let nested = parse_vars(r#"ids=[1,2], opts={"a":1,"b":2}, label="x,y""#).unwrap();
+        assert_eq!(nested["ids"], serde_json::json!([1, 2]));
+        assert_eq!(nested["opts"], serde_json::json!({"a": 1, "b": 2}));
+        assert_eq!(nested["label"], serde_json::json!("x,y"));
+
+        assert!(parse_vars("include_deleted").is_err());
+        assert!(parse_vars("{not json").is_err());
+    }
 }
@@ -26,6 +26,24 @@ pub enum DiagnosticCode {
     /// Contract is missing but model references other contracts
     ContractMissing,
 
+    /// Inferred column order differs from the contract's declared order
+    ContractColumnOrderMismatch,
+
+    /// A contract column has no `data_type`, so it is left out of the contract
+    ContractColumnMissingType,
+
+    /// A contract column's `data_type` is not a recognized type
+    ContractUnknownType,
+
+    /// A contract declares the same column more than once
+    ContractDuplicateColumn,
+
+    /// A strict contract's column count differs from the model's (summary of missing/extra columns)
+    ContractArityMismatch,
+
+    /// A dbt unit test's `expect` rows use columns the model does not have
+    UnitTestSchemaMismatch,
+
     // Drift detection (2xxx)
     /// Warehouse table schema has changed (column dropped)
     DriftColumnDropped,
@@ -42,6 +60,18 @@ pub enum DiagnosticCode {
     /// Warehouse column nullability changed
     DriftNullabilityChange,
 
+    /// Warehouse column default expression differs from the known default
+    DriftDefaultChanged,
+
+    /// Per-table count of drift findings
+    DriftSummary,
+
+    /// Warehouse view SQL differs from the model's compiled SQL
+    DriftViewDefinitionChanged,
+
+    /// Warehouse enum values differ from the column's declared accepted values
+    DriftAcceptedValuesChanged,
+
     // SQL inference issues (3xxx)
     /// SELECT * encountered but cannot expand (no catalog)
     SqlSelectStarUnexpandable,
@@ -58,6 +88,24 @@ pub enum DiagnosticCode {
     /// Aggregate function in GROUP BY without explicit alias
     SqlGroupByAggregateUnaliased,
 
+    /// Too few contract columns have a concrete inferred type
+    SqlInferenceLowCoverage,
+
+    /// Two output columns of a query share a name
+    SqlDuplicateColumnName,
+
+    /// Schema inferred for a model without a contract (`check --emit-inferred`)
+    SqlInferredSchema,
+
+    /// ORDER BY references a column that is neither an output alias nor a source column
+    SqlOrderByUnknownColumn,
+
+    /// `ref()` or `source()` names nothing in the manifest
+    SqlUnresolvedRef,
+
+    /// Model SQL holds more than one statement; only one is analyzed
+    SqlMultipleStatements,
+
     // Jinja template issues (4xxx)
     /// Failed to render Jinja template
     JinjaRenderError,
@@ -68,6 +116,16 @@ pub enum DiagnosticCode {
     /// Invalid Jinja syntax
     JinjaSyntaxError,
 
+    // Manifest issues (5xxx)
+    /// A node's `depends_on` references a node missing from the manifest
+    ManifestDanglingDependency,
+
+    /// Several models share the same name across packages
+    ManifestDuplicateName,
+
+    /// A model has no `database` or `schema`
+    ManifestMissingRelation,
+
     // Internal errors (8xxx)
     /// Internal error (should not happen)
     InternalError,
@@ -76,6 +134,9 @@ pub enum DiagnosticCode {
     /// General informational message
     Info,
 
+    /// Diagnostics beyond the per-model cap were left out of the report
+    DiagnosticsSuppressed,
+
     /// General warning message
     Warning,
 }
@@ -88,26 +149,53 @@ impl DiagnosticCode {
             Self::ContractTypeMismatch => "CONTRACT_TYPE_MISMATCH",
             Self::ContractExtraColumn => "CONTRACT_EXTRA_COLUMN",
             Self::ContractMissing => "CONTRACT_MISSING",
+            Self::ContractColumnOrderMismatch => "CONTRACT_COLUMN_ORDER_MISMATCH",
+            Self::ContractColumnMissingType => "CONTRACT_COLUMN_MISSING_TYPE",
+            Self::ContractUnknownType => "CONTRACT_UNKNOWN_TYPE",
+            Self::ContractDuplicateColumn => "CONTRACT_DUPLICATE_COLUMN",
+            Self::ContractArityMismatch => "CONTRACT_ARITY_MISMATCH",
+            Self::UnitTestSchemaMismatch => "UNIT_TEST_SCHEMA_MISMATCH",
             Self::DriftColumnDropped => "DRIFT_COLUMN_DROPPED",
             Self::DriftTypeChange => "DRIFT_TYPE_CHANGE",
             Self::DriftColumnAdded => "DRIFT_COLUMN_ADDED",
             Self::DriftModelSkipped => "DRIFT_MODEL_SKIPPED",
             Self::DriftNullabilityChange => "DRIFT_NULLABILITY_CHANGE",
+            Self::DriftDefaultChanged => "DRIFT_DEFAULT_CHANGED",
+            Self::DriftSummary => "DRIFT_SUMMARY",
+            Self::DriftViewDefinitionChanged => "DRIFT_VIEW_DEFINITION_CHANGED",
+            Self::DriftAcceptedValuesChanged => "DRIFT_ACCEPTED_VALUES_CHANGED",
             Self::SqlSelectStarUnexpandable => "SQL_SELECT_STAR_UNEXPANDABLE",
             Self::SqlUnsupportedSyntax => "SQL_UNSUPPORTED_SYNTAX",
             Self::SqlParseError => "SQL_PARSE_ERROR",
             Self::SqlInferenceError => "SQL_INFERENCE_ERROR",
             Self::SqlGroupByAggregateUnaliased => "SQL_GROUP_BY_AGGREGATE_UNALIASED",
+            Self::SqlInferenceLowCoverage => "SQL_INFERENCE_LOW_COVERAGE",
+            Self::SqlDuplicateColumnName => "SQL_DUPLICATE_COLUMN_NAME",
+            Self::SqlInferredSchema => "SQL_INFERRED_SCHEMA",
+            Self::SqlOrderByUnknownColumn => "SQL_ORDER_BY_UNKNOWN_COLUMN",
+            Self::SqlUnresolvedRef => "SQL_UNRESOLVED_REF",
+            Self::SqlMultipleStatements => "SQL_MULTIPLE_STATEMENTS",
             Self::JinjaRenderError => "JINJA_RENDER_ERROR",
             Self::JinjaUndefinedVariable => "JINJA_UNDEFINED_VARIABLE",
             Self::JinjaSyntaxError => "JINJA_SYNTAX_ERROR",
+            Self::ManifestDanglingDependency => "MANIFEST_DANGLING_DEPENDENCY",
+            Self::ManifestDuplicateName => "MANIFEST_DUPLICATE_NAME",
+            Self::ManifestMissingRelation => "MANIFEST_MISSING_RELATION",
             Self::InternalError => "INTERNAL_ERROR",
             Self::Info => "INFO",
+            Self::DiagnosticsSuppressed => "DIAGNOSTICS_SUPPRESSED",
             Self::Warning => "WARNING",
         }
     }
 }
 
+impl DiagnosticCode {
+    /// Parse a code from its stable string form (e.g. `CONTRACT_TYPE_MISMATCH`)
+    pub fn parse(s: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(s.trim().to_uppercase())).ok()
+    }
+}
+
 impl std::fmt::Display for DiagnosticCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_str())
@@ -128,6 +216,18 @@ pub enum Severity {
     Error,
 }
 
+impl Severity {
+    /// Parse a severity name (`info`, `warn`/`warning`, `error`)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
 impl std::fmt::Display for Severity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -215,6 +315,22 @@ pub struct Diagnostic {
 
     /// List of downstream nodes impacted by this issue
     pub impact: Vec<String>,
+
+    /// Other locations involved in this issue (e.g. the contract's YAML declaration)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<Location>,
+
+    /// Unique id of the model this diagnostic belongs to, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+
+    /// Column the diagnostic is about, when it concerns a single column
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+
+    /// The column's type as the warehouse declares it (e.g. `NUMBER(38,0)`), for drift findings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warehouse_type: Option<String>,
 }
 
 impl Diagnostic {
@@ -228,6 +344,10 @@ impl Diagnostic {
             expected: None,
             actual: None,
             impact: Vec::new(),
+            related: Vec::new(),
+            model_id: None,
+            column: None,
+            warehouse_type: None,
         }
     }
 
@@ -244,12 +364,62 @@ impl Diagnostic {
         self
     }
 
+    /// Add a related location
+    pub fn with_related(mut self, location: Location) -> Self {
+        self.related.push(location);
+        self
+    }
+
+    /// Set the model this diagnostic belongs to
+    pub fn with_model_id(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = Some(model_id.into());
+        self
+    }
+
+    /// Set the column this diagnostic is about
+    pub fn with_column(mut self, column: impl Into<String>) -> Self {
+        self.column = Some(column.into());
+        self
+    }
+
     /// Set downstream impact
     pub fn with_impact(mut self, impact: Vec<String>) -> Self {
         self.impact = impact;
         self
     }
 
+    /// Keep at most `max` of one model's diagnostics, most severe first
+    ///
+    /// When more are present, the rest are replaced by a single
+    /// `DIAGNOSTICS_SUPPRESSED` summary carrying the most severe suppressed
+    /// severity, so a capped model still fails CI if it had errors.
+    pub fn cap(mut diagnostics: Vec<Diagnostic>, max: usize, model: &str) -> Vec<Diagnostic> {
+        if diagnostics.len() <= max {
+            return diagnostics;
+        }
+
+        diagnostics.sort();
+        let suppressed = diagnostics.split_off(max);
+
+        let mut summary = Diagnostic::new(
+            DiagnosticCode::DiagnosticsSuppressed,
+            suppressed[0].severity,
+            format!(
+                "{} more diagnostics suppressed for '{}' (max_diagnostics_per_model = {})",
+                suppressed.len(),
+                model,
+                max
+            ),
+        );
+        if let Some(location) = suppressed.iter().find_map(|d| d.location.as_ref()) {
+            summary = summary.with_location(Location::new(location.file.clone()));
+        }
+        summary.model_id = suppressed.iter().find_map(|d| d.model_id.clone());
+
+        diagnostics.push(summary);
+        diagnostics
+    }
+
     /// Redact sensitive data from diagnostic messages
     ///
     /// Replaces schema names, column names, and table names with `<REDACTED>`.
@@ -275,6 +445,8 @@ impl Diagnostic {
 
         // Redact downstream impact (model names)
         self.impact = self.impact.iter().map(|_| "<REDACTED>".to_string()).collect();
+        self.model_id = self.model_id.map(|_| "<REDACTED>".to_string());
+        self.column = self.column.map(|_| "<REDACTED>".to_string());
 
         self
     }
@@ -297,6 +469,10 @@ impl Diagnostic {
 /// 1. Severity (Error > Warn > Info) - most severe first
 /// 2. Diagnostic code (alphabetically)
 /// 3. Location (file path, then line, then column)
+/// 4. Message, then expected/actual, impact, related locations and model id
+///
+/// Every field takes part, so the order is total and never depends on the
+/// order diagnostics were produced in (e.g. by parallel workers).
 impl Ord for Diagnostic {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         // Reverse severity order (Error > Warn > Info)
@@ -310,6 +486,20 @@ impl Ord for Diagnostic {
             .cmp(&severity_order(&other.severity))
             .then_with(|| self.code.as_str().cmp(other.code.as_str()))
             .then_with(|| self.location.cmp(&other.location))
+            .then_with(|| self.tiebreak_cmp(other))
+    }
+}
+
+impl Diagnostic {
+    /// Compare the fields not covered by severity, code and location
+    fn tiebreak_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.message
+            .cmp(&other.message)
+            .then_with(|| self.expected.cmp(&other.expected))
+            .then_with(|| self.actual.cmp(&other.actual))
+            .then_with(|| self.impact.cmp(&other.impact))
+            .then_with(|| self.related.cmp(&other.related))
+            .then_with(|| self.model_id.cmp(&other.model_id))
     }
 }
 
@@ -330,6 +520,38 @@ mod tests {
         assert_eq!(DiagnosticCode::DriftTypeChange.as_str(), "DRIFT_TYPE_CHANGE");
     }
 
+    #[test]
+    fn cap_appends_suppression_summary() {
+        let diagnostics: Vec<Diagnostic> = (0..10)
+            .map(|i| {
+                Diagnostic::new(DiagnosticCode::ContractMissingColumn, Severity::Warn, format!("Missing col_{}", i))
+                    .with_location(Location::new("models/broken.sql"))
+            })
+            .chain(std::iter::once(Diagnostic::new(
+                DiagnosticCode::ContractTypeMismatch,
+                Severity::Error,
+                "Type mismatch",
+            )))
+            .collect();
+
+        let capped = Diagnostic::cap(diagnostics, 3, "broken");
+
+        assert_eq!(capped.len(), 4);
+        assert_eq!(capped[0].code, DiagnosticCode::ContractTypeMismatch);
+
+        let summary = capped.last().unwrap();
+        assert_eq!(summary.code, DiagnosticCode::DiagnosticsSuppressed);
+        assert_eq!(summary.severity, Severity::Warn);
+        assert!(summary.message.starts_with("8 more diagnostics suppressed for 'broken'"));
+        assert_eq!(summary.location.as_ref().unwrap().file, "models/broken.sql");
+    }
+
+    #[test]
+    fn cap_keeps_diagnostics_within_limit() {
+        let diagnostics = vec![Diagnostic::new(DiagnosticCode::Info, Severity::Info, "ok")];
+        assert_eq!(Diagnostic::cap(diagnostics.clone(), 1, "m"), diagnostics);
+    }
+
     #[test]
     fn diagnostic_serialization() {
         let diag = Diagnostic::new(
@@ -347,7 +569,7 @@ mod tests {
     #[test]
     fn diagnostic_ordering_is_deterministic() {
         // Create diagnostics in random order
-        let mut diagnostics = vec![
+        let mut diagnostics = [
             Diagnostic::new(DiagnosticCode::Info, Severity::Info, "Info message")
                 .with_location(Location::new("models/c.sql")),
             Diagnostic::new(DiagnosticCode::ContractMissingColumn, Severity::Error, "Error 1")
@@ -7,8 +7,12 @@ pub mod diagnostic;
 pub mod schema;
 pub mod report;
 pub mod config;
+pub mod suppression;
+pub mod snapshot;
 
 pub use diagnostic::{Diagnostic, DiagnosticCode, Severity, Location};
-pub use schema::{LogicalType, Column, Schema, Contract, Nullability, ColumnRef, EnforcementPolicy};
-pub use report::{Report, ReportVersion};
-pub use config::{Config, DialectConfig, SeverityThreshold, AllowlistRules};
+pub use schema::{LogicalType, Column, timestamp_time_zone, Schema, SchemaDiff, ColumnChange, Contract, Nullability, ColumnRef, EnforcementPolicy, ExtraColumnPolicy, IdentifierCase};
+pub use report::{Report, ReportVersion, ReportFilter, SortMode, ModelContractDiff, DiffColumn, ColumnTypeChange};
+pub use suppression::Suppression;
+pub use snapshot::{SchemaSnapshot, SnapshotDiff, SCHEMA_SNAPSHOT_VERSION};
+pub use config::{Config, DialectConfig, SeverityThreshold, AllowlistRules, ContractConfig, InferenceConfig, DriftConfig, DriftSummaryMode, MessageTemplates, ManifestSourceConfig, DbtCloudConfig, EnvOverlay};
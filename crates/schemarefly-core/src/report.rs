@@ -4,7 +4,8 @@
 //! Breaking changes require a new version.
 
 use serde::{Deserialize, Serialize};
-use crate::diagnostic::Diagnostic;
+use std::collections::HashSet;
+use crate::diagnostic::{Diagnostic, DiagnosticCode, Severity};
 
 /// Report schema version
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -18,7 +19,9 @@ pub struct ReportVersion {
 
 impl ReportVersion {
     /// Current report schema version
-    pub const CURRENT: ReportVersion = ReportVersion { major: 1, minor: 0 };
+    ///
+    /// 1.1 added the optional `model_diffs` field; 1.2 added `summary.suppressed`.
+    pub const CURRENT: ReportVersion = ReportVersion { major: 1, minor: 2 };
 }
 
 impl std::fmt::Display for ReportVersion {
@@ -48,8 +51,173 @@ pub struct ReportSummary {
 
     /// Number of contracts validated
     pub contracts_validated: usize,
+
+    /// Number of diagnostics dropped by inline `schemarefly: ignore` directives (added in 1.2)
+    #[serde(default)]
+    pub suppressed: usize,
+}
+
+
+/// A column and its type, as listed in a model diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffColumn {
+    /// Column name
+    pub name: String,
+
+    /// Logical type (e.g. `INT`, `DECIMAL(10, 2)`)
+    pub data_type: String,
+}
+
+/// A column whose type differs between contract and inferred schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnTypeChange {
+    /// Column name
+    pub name: String,
+
+    /// Type declared in the contract
+    pub from: String,
+
+    /// Type inferred from the SQL
+    pub to: String,
+}
+
+/// Structured inferred-vs-contract diff for one model
+///
+/// "Added" columns are produced by the SQL but not declared in the contract;
+/// "removed" columns are declared in the contract but missing from the SQL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelContractDiff {
+    /// Model unique_id
+    pub model_id: String,
+
+    /// Columns in the inferred schema but not in the contract
+    #[serde(default)]
+    pub added: Vec<DiffColumn>,
+
+    /// Columns in the contract but not in the inferred schema
+    #[serde(default)]
+    pub removed: Vec<DiffColumn>,
+
+    /// Columns present in both with incompatible types
+    #[serde(default)]
+    pub type_changed: Vec<ColumnTypeChange>,
+}
+
+impl ModelContractDiff {
+    /// Create an empty diff for a model
+    pub fn new(model_id: impl Into<String>) -> Self {
+        Self {
+            model_id: model_id.into(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            type_changed: Vec::new(),
+        }
+    }
+
+    /// Whether the contract and inferred schema agree
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.type_changed.is_empty()
+    }
+}
+
+/// Criteria for selecting diagnostics from a report
+///
+/// Empty criteria match everything; multiple codes or severities are OR-ed,
+/// while different kinds of criteria are AND-ed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReportFilter {
+    /// Diagnostic codes to keep
+    pub codes: Vec<DiagnosticCode>,
+
+    /// Severities to keep
+    pub severities: Vec<Severity>,
+
+    /// Glob pattern matched against the diagnostic's file path
+    pub path_glob: Option<String>,
+}
+
+impl ReportFilter {
+    /// Create an empty filter (matches all diagnostics)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep diagnostics with this code
+    pub fn with_code(mut self, code: DiagnosticCode) -> Self {
+        self.codes.push(code);
+        self
+    }
+
+    /// Keep diagnostics with this severity
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severities.push(severity);
+        self
+    }
+
+    /// Keep diagnostics whose file path matches this glob
+    pub fn with_path_glob(mut self, pattern: impl Into<String>) -> Self {
+        self.path_glob = Some(pattern.into());
+        self
+    }
+
+    /// Check whether a diagnostic satisfies all criteria
+    pub fn matches(&self, diagnostic: &Diagnostic) -> bool {
+        if !self.codes.is_empty() && !self.codes.contains(&diagnostic.code) {
+            return false;
+        }
+
+        if !self.severities.is_empty() && !self.severities.contains(&diagnostic.severity) {
+            return false;
+        }
+
+        if let Some(ref pattern) = self.path_glob {
+            match diagnostic.location {
+                Some(ref location) => {
+                    if !crate::config::glob_match(pattern, &location.file) {
+                        return false;
+                    }
+                }
+                None => return false,
+            }
+        }
+
+        true
+    }
 }
 
+/// Order in which a report lists its diagnostics
+///
+/// Every mode is total: ties on the primary keys fall back to the full
+/// `Diagnostic` ordering, so the same set of diagnostics always sorts the same.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    /// Most severe first, then code, then location (the `Diagnostic` ordering)
+    #[default]
+    BySeverity,
+
+    /// File path and position first; diagnostics without a location last
+    ByFile,
+
+    /// Diagnostic code first, then severity
+    ByCode,
+}
+
+impl SortMode {
+    /// Compare two diagnostics under this mode
+    pub fn compare(self, a: &Diagnostic, b: &Diagnostic) -> std::cmp::Ordering {
+        match self {
+            Self::BySeverity => a.cmp(b),
+            Self::ByFile => match (&a.location, &b.location) {
+                (Some(x), Some(y)) => x.cmp(y).then_with(|| a.cmp(b)),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => a.cmp(b),
+            },
+            Self::ByCode => a.code.as_str().cmp(b.code.as_str()).then_with(|| a.cmp(b)),
+        }
+    }
+}
 
 /// Check report (report.json v1)
 ///
@@ -74,6 +242,10 @@ pub struct Report {
     /// All diagnostics
     pub diagnostics: Vec<Diagnostic>,
 
+    /// Per-model contract diffs (added in 1.1; absent when not computed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_diffs: Option<Vec<ModelContractDiff>>,
+
     /// Metadata
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>,
@@ -88,6 +260,7 @@ impl Report {
             content_hash: None,
             summary: ReportSummary::default(),
             diagnostics: Vec::new(),
+            model_diffs: None,
             metadata: None,
         }
     }
@@ -113,8 +286,6 @@ impl Report {
 
     /// Create a report from diagnostics
     pub fn from_diagnostics(mut diagnostics: Vec<Diagnostic>) -> Self {
-        use crate::diagnostic::Severity;
-
         // Sort diagnostics for deterministic output
         // Order: Error > Warn > Info, then by code, then by location
         diagnostics.sort();
@@ -129,6 +300,7 @@ impl Report {
             info: diagnostics.iter().filter(|d| d.severity == Severity::Info).count(),
             models_checked: 0,
             contracts_validated: 0,
+            suppressed: 0,
         };
 
         Self {
@@ -137,14 +309,13 @@ impl Report {
             content_hash: Some(content_hash),
             summary,
             diagnostics,
+            model_diffs: None,
             metadata: None,
         }
     }
 
     /// Create a report from diagnostics with optional redaction
     pub fn from_diagnostics_with_config(mut diagnostics: Vec<Diagnostic>, config: &crate::Config) -> Self {
-        use crate::diagnostic::Severity;
-
         // Sort diagnostics for deterministic output
         // Order: Error > Warn > Info, then by code, then by location
         diagnostics.sort();
@@ -164,6 +335,7 @@ impl Report {
             info: diagnostics.iter().filter(|d| d.severity == Severity::Info).count(),
             models_checked: 0,
             contracts_validated: 0,
+            suppressed: 0,
         };
 
         Self {
@@ -172,14 +344,29 @@ impl Report {
             content_hash: Some(content_hash),
             summary,
             diagnostics,
+            model_diffs: None,
             metadata: None,
         }
     }
 
+    /// Attach per-model contract diffs, sorted by model id
+    ///
+    /// Diffs with no changes are dropped.
+    pub fn with_model_diffs(mut self, diffs: impl IntoIterator<Item = ModelContractDiff>) -> Self {
+        let mut diffs: Vec<ModelContractDiff> = diffs.into_iter().filter(|d| !d.is_empty()).collect();
+        diffs.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+        self.model_diffs = Some(diffs);
+        self
+    }
+
+    /// Record the number of diagnostics dropped by inline suppression directives
+    pub fn with_suppressed(mut self, suppressed: usize) -> Self {
+        self.summary.suppressed = suppressed;
+        self
+    }
+
     /// Add a diagnostic to the report
     pub fn add_diagnostic(&mut self, diagnostic: Diagnostic) {
-        use crate::diagnostic::Severity;
-
         match diagnostic.severity {
             Severity::Error => self.summary.errors += 1,
             Severity::Warn => self.summary.warnings += 1,
@@ -190,11 +377,80 @@ impl Report {
         self.diagnostics.push(diagnostic);
     }
 
+    /// Create a new report containing only diagnostics matching the predicate
+    ///
+    /// The result keeps the `Report` shape: summary counts and the content
+    /// hash are recomputed, while version, timestamp and metadata are preserved.
+    pub fn filter(&self, predicate: impl Fn(&Diagnostic) -> bool) -> Self {
+        let diagnostics: Vec<Diagnostic> = self
+            .diagnostics
+            .iter()
+            .filter(|d| predicate(d))
+            .cloned()
+            .collect();
+
+        let summary = ReportSummary {
+            total: diagnostics.len(),
+            errors: diagnostics.iter().filter(|d| d.severity == Severity::Error).count(),
+            warnings: diagnostics.iter().filter(|d| d.severity == Severity::Warn).count(),
+            info: diagnostics.iter().filter(|d| d.severity == Severity::Info).count(),
+            ..self.summary.clone()
+        };
+
+        Self {
+            version: self.version,
+            timestamp: self.timestamp.clone(),
+            content_hash: Some(Self::compute_content_hash(&diagnostics)),
+            summary,
+            diagnostics,
+            model_diffs: self.model_diffs.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    /// Create a new report with only the diagnostics and contract diffs of these models
+    ///
+    /// Models are matched by unique id against `Diagnostic::model_id`, so
+    /// diagnostics that aren't tied to a model are left out.
+    pub fn for_models(&self, ids: &HashSet<String>) -> Self {
+        let mut report = self.filter(|d| d.model_id.as_ref().is_some_and(|id| ids.contains(id)));
+        if let Some(diffs) = &mut report.model_diffs {
+            diffs.retain(|diff| ids.contains(&diff.model_id));
+        }
+        report
+    }
+
+    /// Reorder diagnostics by `mode` and refresh the content hash
+    pub fn sort_by(&mut self, mode: SortMode) {
+        self.diagnostics.sort_by(|a, b| mode.compare(a, b));
+        self.content_hash = Some(Self::compute_content_hash(&self.diagnostics));
+    }
+
+    /// Load a report from a JSON file
+    pub fn from_file(path: &std::path::Path) -> Result<Self, std::io::Error> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(std::io::Error::other)
+    }
+
     /// Check if the report has any errors
     pub fn has_errors(&self) -> bool {
         self.summary.errors > 0
     }
 
+    /// Check if the report should fail CI
+    ///
+    /// With an empty `fail_on_codes`, any error fails. Otherwise only errors
+    /// whose code is listed fail; other errors are reported but non-fatal.
+    pub fn fails_on(&self, fail_on_codes: &[DiagnosticCode]) -> bool {
+        if fail_on_codes.is_empty() {
+            return self.has_errors();
+        }
+
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error && fail_on_codes.contains(&d.code))
+    }
+
     /// Serialize to JSON string
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(self)
@@ -206,6 +462,48 @@ impl Report {
             .map_err(std::io::Error::other)?;
         std::fs::write(path, json)
     }
+
+    /// Write diagnostics as CSV, one row per diagnostic, for spreadsheet triage
+    ///
+    /// Columns: code, severity, message, file, line, expected, actual,
+    /// impact_count. Missing values are empty fields. Fields are quoted per
+    /// RFC 4180 when they contain commas, quotes or line breaks.
+    pub fn to_csv<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writeln!(writer, "{}", CSV_COLUMNS.join(","))?;
+
+        for diag in &self.diagnostics {
+            let location = diag.location.as_ref();
+            let fields = [
+                diag.code.as_str().to_string(),
+                diag.severity.to_string(),
+                diag.message.clone(),
+                location.map(|l| l.file.clone()).unwrap_or_default(),
+                location.and_then(|l| l.line).map(|l| l.to_string()).unwrap_or_default(),
+                diag.expected.clone().unwrap_or_default(),
+                diag.actual.clone().unwrap_or_default(),
+                diag.impact.len().to_string(),
+            ];
+
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            writeln!(writer, "{}", row.join(","))?;
+        }
+
+        writer.flush()
+    }
+}
+
+/// Header row of [`Report::to_csv`]
+const CSV_COLUMNS: [&str; 8] = [
+    "code", "severity", "message", "file", "line", "expected", "actual", "impact_count",
+];
+
+/// Quote a CSV field if it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
 }
 
 impl Default for Report {
@@ -220,7 +518,7 @@ impl Default for Report {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::diagnostic::{Diagnostic, DiagnosticCode, Severity};
+    use crate::diagnostic::Location;
 
     #[test]
     fn empty_report() {
@@ -244,6 +542,95 @@ mod tests {
         assert!(report.has_errors());
     }
 
+    fn unordered_diagnostics() -> Vec<Diagnostic> {
+        vec![
+            Diagnostic::new(DiagnosticCode::ContractMissingColumn, Severity::Error, "Missing column 'b'")
+                .with_location(Location::new("models/orders.sql")),
+            Diagnostic::new(DiagnosticCode::DriftColumnAdded, Severity::Info, "Column added")
+                .with_location(Location::new("models/a.sql")),
+            Diagnostic::new(DiagnosticCode::ContractMissingColumn, Severity::Error, "Missing column 'a'")
+                .with_location(Location::new("models/orders.sql")),
+            Diagnostic::new(DiagnosticCode::ContractExtraColumn, Severity::Warn, "Extra column"),
+            Diagnostic::new(DiagnosticCode::ContractTypeMismatch, Severity::Error, "Type mismatch")
+                .with_location(Location::new("models/customers.sql")),
+        ]
+    }
+
+    #[test]
+    fn ordering_is_independent_of_input_order() {
+        let forward = unordered_diagnostics();
+        let mut reversed = forward.clone();
+        reversed.reverse();
+
+        let a = Report::from_diagnostics(forward.clone());
+        let b = Report::from_diagnostics(reversed.clone());
+        assert_eq!(a.diagnostics, b.diagnostics);
+        assert_eq!(a.content_hash, b.content_hash);
+
+        // Same code and location: the message breaks the tie
+        assert_eq!(a.diagnostics[0].message, "Missing column 'a'");
+        assert_eq!(a.diagnostics[1].message, "Missing column 'b'");
+
+        for mode in [SortMode::BySeverity, SortMode::ByFile, SortMode::ByCode] {
+            let mut a = Report::from_diagnostics(forward.clone());
+            let mut b = Report::from_diagnostics(reversed.clone());
+            a.sort_by(mode);
+            b.sort_by(mode);
+            assert_eq!(a.diagnostics, b.diagnostics, "{:?}", mode);
+            assert_eq!(a.content_hash, b.content_hash, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn sort_modes_order_by_primary_key() {
+        let mut report = Report::from_diagnostics(unordered_diagnostics());
+
+        report.sort_by(SortMode::ByFile);
+        let files: Vec<Option<&str>> = report.diagnostics.iter()
+            .map(|d| d.location.as_ref().map(|l| l.file.as_str()))
+            .collect();
+        assert_eq!(files, vec![
+            Some("models/a.sql"),
+            Some("models/customers.sql"),
+            Some("models/orders.sql"),
+            Some("models/orders.sql"),
+            None,
+        ]);
+
+        report.sort_by(SortMode::ByCode);
+        let codes: Vec<&str> = report.diagnostics.iter().map(|d| d.code.as_str()).collect();
+        assert_eq!(codes, vec![
+            "CONTRACT_EXTRA_COLUMN",
+            "CONTRACT_MISSING_COLUMN",
+            "CONTRACT_MISSING_COLUMN",
+            "CONTRACT_TYPE_MISMATCH",
+            "DRIFT_COLUMN_ADDED",
+        ]);
+
+        report.sort_by(SortMode::BySeverity);
+        assert_eq!(report.diagnostics, Report::from_diagnostics(unordered_diagnostics()).diagnostics);
+    }
+
+    #[test]
+    fn fails_only_on_listed_codes() {
+        let contract_errors = Report::from_diagnostics(vec![
+            Diagnostic::new(DiagnosticCode::ContractTypeMismatch, Severity::Error, "Type mismatch"),
+            Diagnostic::new(DiagnosticCode::DriftColumnDropped, Severity::Warn, "Dropped (downgraded)"),
+        ]);
+        let fail_on = [DiagnosticCode::DriftColumnDropped];
+
+        // Contract errors are still reported but no longer fatal
+        assert!(contract_errors.has_errors());
+        assert!(!contract_errors.fails_on(&fail_on));
+        assert!(contract_errors.fails_on(&[]));
+
+        let drift_error = Report::from_diagnostics(vec![
+            Diagnostic::new(DiagnosticCode::ContractTypeMismatch, Severity::Error, "Type mismatch"),
+            Diagnostic::new(DiagnosticCode::DriftColumnDropped, Severity::Error, "Column dropped"),
+        ]);
+        assert!(drift_error.fails_on(&fail_on));
+    }
+
     #[test]
     fn report_serialization() {
         let report = Report::new();
@@ -292,4 +679,149 @@ mod tests {
         // Different diagnostics should produce different hashes
         assert_ne!(report1.content_hash, report2.content_hash);
     }
+
+    fn mixed_report() -> Report {
+        Report::from_diagnostics(vec![
+            Diagnostic::new(DiagnosticCode::ContractTypeMismatch, Severity::Error, "Type mismatch")
+                .with_location(Location::new("models/marts/orders.sql")),
+            Diagnostic::new(DiagnosticCode::ContractMissingColumn, Severity::Error, "Missing column")
+                .with_location(Location::new("models/staging/stg_users.sql")),
+            Diagnostic::new(DiagnosticCode::ContractExtraColumn, Severity::Warn, "Extra column")
+                .with_location(Location::new("models/marts/orders.sql")),
+        ])
+    }
+
+    #[test]
+    fn filter_by_code() {
+        let report = mixed_report();
+        let criteria = ReportFilter::new().with_code(DiagnosticCode::ContractTypeMismatch);
+
+        let filtered = report.filter(|d| criteria.matches(d));
+
+        assert_eq!(filtered.summary.total, 1);
+        assert_eq!(filtered.diagnostics[0].code, DiagnosticCode::ContractTypeMismatch);
+        assert_eq!(filtered.timestamp, report.timestamp);
+        assert_ne!(filtered.content_hash, report.content_hash);
+    }
+
+    #[test]
+    fn filter_by_severity_and_path() {
+        let report = mixed_report();
+
+        let errors = report.filter(|d| ReportFilter::new().with_severity(Severity::Error).matches(d));
+        assert_eq!(errors.summary.total, 2);
+        assert_eq!(errors.summary.errors, 2);
+        assert_eq!(errors.summary.warnings, 0);
+
+        let criteria = ReportFilter::new()
+            .with_severity(Severity::Error)
+            .with_path_glob("models/marts/*");
+        let marts_errors = report.filter(|d| criteria.matches(d));
+        assert_eq!(marts_errors.summary.total, 1);
+        assert_eq!(marts_errors.diagnostics[0].code, DiagnosticCode::ContractTypeMismatch);
+    }
+
+    #[test]
+    fn for_models_keeps_only_those_models() {
+        let diagnostics = vec![
+            Diagnostic::new(DiagnosticCode::ContractMissingColumn, Severity::Error, "Missing column 'b'")
+                .with_model_id("model.shop.orders"),
+            Diagnostic::new(DiagnosticCode::ContractTypeMismatch, Severity::Error, "Type mismatch")
+                .with_model_id("model.shop.users"),
+            Diagnostic::new(DiagnosticCode::ContractExtraColumn, Severity::Warn, "Extra column")
+                .with_model_id("model.shop.orders"),
+            Diagnostic::new(DiagnosticCode::ManifestDanglingDependency, Severity::Error, "Dangling dependency"),
+        ];
+        let report = Report::from_diagnostics(diagnostics).with_model_diffs(vec![
+            ModelContractDiff { added: vec![DiffColumn { name: "b".to_string(), data_type: "INT".to_string() }], ..ModelContractDiff::new("model.shop.orders") },
+            ModelContractDiff { added: vec![DiffColumn { name: "c".to_string(), data_type: "INT".to_string() }], ..ModelContractDiff::new("model.shop.users") },
+        ]);
+
+        let ids: HashSet<String> = ["model.shop.orders".to_string()].into();
+        let orders = report.for_models(&ids);
+
+        assert_eq!(orders.summary.total, 2);
+        assert_eq!(orders.summary.errors, 1);
+        assert_eq!(orders.summary.warnings, 1);
+        assert!(orders.diagnostics.iter().all(|d| d.model_id.as_deref() == Some("model.shop.orders")));
+        let diff_ids: Vec<&str> = orders.model_diffs.as_ref().unwrap().iter().map(|d| d.model_id.as_str()).collect();
+        assert_eq!(diff_ids, vec!["model.shop.orders"]);
+
+        assert_eq!(report.for_models(&HashSet::new()).summary.total, 0);
+    }
+
+    #[test]
+    fn parse_code_and_severity() {
+        assert_eq!(DiagnosticCode::parse("CONTRACT_TYPE_MISMATCH"), Some(DiagnosticCode::ContractTypeMismatch));
+        assert_eq!(DiagnosticCode::parse("not_a_code"), None);
+        assert_eq!(Severity::parse("error"), Some(Severity::Error));
+        assert_eq!(Severity::parse("Warning"), Some(Severity::Warn));
+    }
+
+    /// Split CSV text into records, honouring quoted fields
+    fn parse_csv(text: &str) -> Vec<Vec<String>> {
+        let mut records = Vec::new();
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = text.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match (ch, in_quotes) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                ('"', _) => in_quotes = !in_quotes,
+                (',', false) => record.push(std::mem::take(&mut field)),
+                ('\n', false) => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                _ => field.push(ch),
+            }
+        }
+
+        records
+    }
+
+    #[test]
+    fn csv_round_trip_escapes_fields() {
+        let report = Report::from_diagnostics(vec![
+            Diagnostic::new(
+                DiagnosticCode::ContractTypeMismatch,
+                Severity::Error,
+                "Column 'amount' type mismatch: expected DECIMAL(10, 2), got \"FLOAT\"",
+            )
+            .with_location(Location::with_line("models/marts/fct_orders.sql", 12))
+            .with_comparison("DECIMAL(10, 2)", "FLOAT")
+            .with_impact(vec!["model.p.a".to_string(), "model.p.b".to_string()]),
+            Diagnostic::new(DiagnosticCode::ContractExtraColumn, Severity::Warn, "Extra column\nsecond line"),
+        ]);
+
+        let mut csv = Vec::new();
+        report.to_csv(&mut csv).unwrap();
+        let records = parse_csv(&String::from_utf8(csv).unwrap());
+
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|r| r.len() == 8));
+        assert_eq!(
+            records[0],
+            vec!["code", "severity", "message", "file", "line", "expected", "actual", "impact_count"]
+        );
+        assert_eq!(
+            records[1],
+            vec![
+                "CONTRACT_TYPE_MISMATCH",
+                "error",
+                "Column 'amount' type mismatch: expected DECIMAL(10, 2), got \"FLOAT\"",
+                "models/marts/fct_orders.sql",
+                "12",
+                "DECIMAL(10, 2)",
+                "FLOAT",
+                "2",
+            ]
+        );
+        assert_eq!(records[2], vec!["CONTRACT_EXTRA_COLUMN", "warn", "Extra column\nsecond line", "", "", "", "", "0"]);
+    }
 }
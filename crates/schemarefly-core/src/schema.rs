@@ -1,6 +1,8 @@
 //! Schema types and canonical type system
 
+use crate::diagnostic::{Location, Severity};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Portable logical type system
 ///
@@ -111,6 +113,35 @@ pub struct Column {
 
     /// Provenance - where this column comes from
     pub provenance: Vec<ColumnRef>,
+
+    /// Whether the column has a default value (None if not reported by the source)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_default: Option<bool>,
+
+    /// Default expression as reported by the warehouse (e.g. `'active'`, `now()`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+
+    /// Per-column contract enforcement (None defers to the model's policy)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforced: Option<bool>,
+
+    /// Whether a timestamp column stores a time zone (None if not reported by the source)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<bool>,
+
+    /// Whether the warehouse computes the column (`GENERATED ALWAYS AS`; None if not reported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_generated: Option<bool>,
+
+    /// Values the column may hold: declared by `accepted_values` tests or
+    /// `IN (...)` checks, or the labels of a warehouse enum type (None if unknown)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accepted_values: Option<Vec<String>>,
+
+    /// Type as the warehouse declares it (e.g. `NUMBER(38,0)`; None if not read from a warehouse)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warehouse_type: Option<String>,
 }
 
 impl Column {
@@ -121,9 +152,22 @@ impl Column {
             logical_type,
             nullable: Nullability::Unknown,
             provenance: Vec::new(),
+            has_default: None,
+            default: None,
+            enforced: None,
+            time_zone: None,
+            is_generated: None,
+            accepted_values: None,
+            warehouse_type: None,
         }
     }
 
+    /// Override contract enforcement for this column
+    pub fn with_enforced(mut self, enforced: bool) -> Self {
+        self.enforced = Some(enforced);
+        self
+    }
+
     /// Set nullability
     pub fn with_nullability(mut self, nullable: Nullability) -> Self {
         self.nullable = nullable;
@@ -135,6 +179,120 @@ impl Column {
         self.provenance = provenance;
         self
     }
+
+    /// Set whether the column has a default value
+    pub fn with_has_default(mut self, has_default: bool) -> Self {
+        self.has_default = Some(has_default);
+        self
+    }
+
+    /// Set the default expression (also marks the column as having a default)
+    pub fn with_default(mut self, default: impl Into<String>) -> Self {
+        self.default = Some(default.into());
+        self.has_default = Some(true);
+        self
+    }
+
+    /// Set whether the timestamp column stores a time zone
+    pub fn with_time_zone(mut self, time_zone: bool) -> Self {
+        self.time_zone = Some(time_zone);
+        self
+    }
+
+    /// Set the type as the warehouse declares it
+    pub fn with_warehouse_type(mut self, warehouse_type: impl Into<String>) -> Self {
+        self.warehouse_type = Some(warehouse_type.into());
+        self
+    }
+
+    /// Set whether the warehouse computes the column
+    pub fn with_generated(mut self, is_generated: bool) -> Self {
+        self.is_generated = Some(is_generated);
+        self
+    }
+
+    /// Set the values the column may hold
+    pub fn with_accepted_values(mut self, values: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.accepted_values = Some(values.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Record the time zone awareness spelled out by a SQL type name
+    ///
+    /// Types that say nothing about a time zone leave the column unchanged.
+    pub fn with_time_zone_of(self, data_type: &str) -> Self {
+        match timestamp_time_zone(data_type) {
+            Some(time_zone) => self.with_time_zone(time_zone),
+            None => self,
+        }
+    }
+}
+
+/// Whether a timestamp type name includes a time zone
+///
+/// `timestamptz`, `timestamp with time zone`, `TIMESTAMP_TZ` and
+/// `TIMESTAMP_LTZ` do; `timestamp`, `timestamp without time zone` and
+/// `TIMESTAMP_NTZ` don't. Returns `None` for non-timestamp types.
+pub fn timestamp_time_zone(data_type: &str) -> Option<bool> {
+    let normalized = data_type.trim().to_lowercase();
+    // Drop a precision such as `timestamp(6) with time zone`
+    let normalized = match (normalized.find('('), normalized.find(')')) {
+        (Some(open), Some(close)) if open < close => {
+            format!("{}{}", &normalized[..open], &normalized[close + 1..])
+        }
+        _ => normalized,
+    };
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+
+    match words.as_slice() {
+        ["timestamptz" | "timestamp_tz" | "timestamp_ltz"] => Some(true),
+        ["timestamp" | "timestamp_ntz"] => Some(false),
+        ["timestamp", "with", "time", "zone"] | ["timestamp", "with", "local", "time", "zone"] => Some(true),
+        ["timestamp", "without", "time", "zone"] => Some(false),
+        _ => None,
+    }
+}
+
+/// How unquoted identifiers are compared
+///
+/// Quoted identifiers (`"Name"` or `` `Name` ``) are always compared exactly,
+/// with the quotes stripped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentifierCase {
+    /// Identifiers must match exactly (BigQuery, Postgres, ANSI)
+    #[default]
+    Sensitive,
+
+    /// Unquoted identifiers match regardless of case (Snowflake)
+    Insensitive,
+}
+
+impl IdentifierCase {
+    /// Check if two identifiers refer to the same column
+    pub fn matches(&self, a: &str, b: &str) -> bool {
+        let (a, a_quoted) = unquote(a);
+        let (b, b_quoted) = unquote(b);
+
+        if a_quoted || b_quoted {
+            return a == b;
+        }
+
+        match self {
+            Self::Sensitive => a == b,
+            Self::Insensitive => a.eq_ignore_ascii_case(b),
+        }
+    }
+}
+
+/// Strip surrounding identifier quotes, reporting whether any were present
+fn unquote(ident: &str) -> (&str, bool) {
+    for quote in ['"', '`'] {
+        if ident.len() >= 2 && ident.starts_with(quote) && ident.ends_with(quote) {
+            return (&ident[1..ident.len() - 1], true);
+        }
+    }
+    (ident, false)
 }
 
 /// An ordered collection of columns
@@ -162,10 +320,118 @@ impl Schema {
         self.columns.iter().find(|c| c.name == name)
     }
 
+    /// Find a column by name using the given identifier case policy
+    pub fn find_column_with(&self, name: &str, case: IdentifierCase) -> Option<&Column> {
+        self.columns.iter().find(|c| case.matches(&c.name, name))
+    }
+
     /// Get column names
     pub fn column_names(&self) -> Vec<&str> {
         self.columns.iter().map(|c| c.name.as_str()).collect()
     }
+
+    /// Names shared by more than one column, in order of first appearance
+    ///
+    /// Each duplicated name is listed once, spelled as its first occurrence.
+    pub fn duplicate_column_names(&self, case: IdentifierCase) -> Vec<&str> {
+        let mut duplicates: Vec<&str> = Vec::new();
+        for (i, col) in self.columns.iter().enumerate() {
+            let seen_before = self.columns[..i].iter().any(|c| case.matches(&c.name, &col.name));
+            if seen_before && !duplicates.iter().any(|d| case.matches(d, &col.name)) {
+                duplicates.push(self.find_column_with(&col.name, case).map_or(&col.name, |c| &c.name));
+            }
+        }
+        duplicates
+    }
+
+    /// Pairs of same-named columns, in this schema's column order
+    pub fn matched_columns<'a>(
+        &'a self,
+        other: &'a Schema,
+        case: IdentifierCase,
+    ) -> impl Iterator<Item = (&'a Column, &'a Column)> + 'a {
+        self.columns
+            .iter()
+            .filter_map(move |col| other.find_column_with(&col.name, case).map(|o| (col, o)))
+    }
+
+    /// Compare this schema (before) against `other` (after)
+    ///
+    /// Columns match by exact name and types must be equal. Use `diff_with`
+    /// for case-insensitive matching or a more lenient type comparison.
+    pub fn diff(&self, other: &Schema) -> SchemaDiff {
+        self.diff_with(other, IdentifierCase::Sensitive, |a, b| a == b)
+    }
+
+    /// Compare schemas with an identifier case policy and a type comparison
+    ///
+    /// `types_match(before, after)` decides whether a matched column's type
+    /// changed. Nullability changes are only reported when both sides are known.
+    pub fn diff_with(
+        &self,
+        other: &Schema,
+        case: IdentifierCase,
+        types_match: impl Fn(&LogicalType, &LogicalType) -> bool,
+    ) -> SchemaDiff {
+        let mut diff = SchemaDiff::default();
+
+        for col in &self.columns {
+            if other.find_column_with(&col.name, case).is_none() {
+                diff.removed.push(col.clone());
+            }
+        }
+
+        for (before, after) in self.matched_columns(other, case) {
+            let change = || ColumnChange { before: before.clone(), after: after.clone() };
+
+            if !types_match(&before.logical_type, &after.logical_type) {
+                diff.type_changed.push(change());
+            }
+
+            let both_known = before.nullable != Nullability::Unknown && after.nullable != Nullability::Unknown;
+            if both_known && before.nullable != after.nullable {
+                diff.nullability_changed.push(change());
+            }
+        }
+
+        for col in &other.columns {
+            if self.find_column_with(&col.name, case).is_none() {
+                diff.added.push(col.clone());
+            }
+        }
+
+        diff
+    }
+
+    /// Order-sensitive fingerprint over column names, types, nullability,
+    /// time zones, defaults, generated flags and accepted values (when known)
+    ///
+    /// Stable across runs and Rust versions, so it can be persisted and
+    /// compared later to tell whether a schema changed.
+    pub fn fingerprint(&self) -> u64 {
+        use sha2::{Sha256, Digest};
+
+        let mut hasher = Sha256::new();
+        for col in &self.columns {
+            hasher.update(col.name.as_bytes());
+            hasher.update([0]);
+            hasher.update(col.logical_type.to_string().as_bytes());
+            hasher.update([0]);
+            hasher.update(format!("{:?}/{:?}", col.nullable, col.time_zone).as_bytes());
+            hasher.update([0]);
+            hasher.update(format!("{:?}/{:?}/{:?}", col.has_default, col.default, col.is_generated).as_bytes());
+            for value in col.accepted_values.iter().flatten() {
+                hasher.update([0]);
+                hasher.update(value.as_bytes());
+            }
+            hasher.update([0xff]);
+        }
+
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        u64::from_be_bytes(bytes)
+    }
 }
 
 impl Default for Schema {
@@ -174,17 +440,124 @@ impl Default for Schema {
     }
 }
 
+/// A column present in both schemas whose definition changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnChange {
+    /// The column in the first (before) schema
+    pub before: Column,
+
+    /// The column in the second (after) schema
+    pub after: Column,
+}
+
+/// Column-level differences between two schemas
+///
+/// Each list follows the column order of the schema the columns come from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaDiff {
+    /// Columns only in the second schema
+    pub added: Vec<Column>,
+
+    /// Columns only in the first schema
+    pub removed: Vec<Column>,
+
+    /// Matched columns whose types differ
+    pub type_changed: Vec<ColumnChange>,
+
+    /// Matched columns whose (known) nullability differs
+    pub nullability_changed: Vec<ColumnChange>,
+}
+
+impl SchemaDiff {
+    /// Whether the schemas have no column-level differences
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.type_changed.is_empty()
+            && self.nullability_changed.is_empty()
+    }
+}
+
+/// How a contract treats columns produced by the model but not declared in it
+///
+/// Mirrors dbt's strict vs additive contracts; `Warn` is the historical default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtraColumnPolicy {
+    /// Report extra columns as warnings
+    #[default]
+    Warn,
+
+    /// Reject extra columns (contract must match exactly)
+    Strict,
+
+    /// Allow extra columns, reporting them for information only
+    Additive,
+}
+
 /// Enforcement policy for contracts
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[derive(Default)]
 pub struct EnforcementPolicy {
-    /// Allow extra columns not in contract
+    /// Allow extra columns not in contract (equivalent to `ExtraColumnPolicy::Additive`)
     pub allow_extra_columns: bool,
 
     /// Allow type widening (e.g., INT -> BIGINT)
     pub allow_widening: bool,
+
+    /// How extra columns are treated
+    #[serde(default)]
+    pub extra_columns: ExtraColumnPolicy,
 }
 
+impl EnforcementPolicy {
+    /// Strict policy: extra columns are errors
+    pub fn strict() -> Self {
+        Self::default().with_extra_columns(ExtraColumnPolicy::Strict)
+    }
+
+    /// Additive policy: extra columns are allowed
+    pub fn additive() -> Self {
+        Self::default().with_extra_columns(ExtraColumnPolicy::Additive)
+    }
+
+    /// Set how extra columns are treated
+    pub fn with_extra_columns(mut self, extra_columns: ExtraColumnPolicy) -> Self {
+        self.extra_columns = extra_columns;
+        self
+    }
+
+    /// Effective extra-column policy, honoring the `allow_extra_columns` flag
+    pub fn extra_column_policy(&self) -> ExtraColumnPolicy {
+        if self.allow_extra_columns && self.extra_columns == ExtraColumnPolicy::Warn {
+            ExtraColumnPolicy::Additive
+        } else {
+            self.extra_columns
+        }
+    }
+
+    /// Severity for a column present in the model but missing from the contract
+    pub fn extra_column_severity(&self) -> Severity {
+        match self.extra_column_policy() {
+            ExtraColumnPolicy::Warn => Severity::Warn,
+            ExtraColumnPolicy::Strict => Severity::Error,
+            ExtraColumnPolicy::Additive => Severity::Info,
+        }
+    }
+
+    /// Severity for a contract column that is missing or has an incompatible type
+    ///
+    /// Columns follow the enforced contract and are errors unless their own
+    /// `enforced` flag is `false`. Such lenient columns are info, but a strict
+    /// policy, which promises an exact match, still warns about them.
+    pub fn column_violation_severity(&self, column: &Column) -> Severity {
+        match (column.enforced, self.extra_column_policy()) {
+            (Some(false), ExtraColumnPolicy::Strict) => Severity::Warn,
+            (Some(false), ExtraColumnPolicy::Warn | ExtraColumnPolicy::Additive) => Severity::Info,
+            (Some(true) | None, _) => Severity::Error,
+        }
+    }
+}
 
 /// A contract defines expected schema with enforcement policy
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -197,6 +570,10 @@ pub struct Contract {
 
     /// Whether this contract is enforced
     pub enforced: bool,
+
+    /// Where each contract column is declared (column name -> YAML location)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub column_locations: HashMap<String, Location>,
 }
 
 impl Contract {
@@ -206,6 +583,7 @@ impl Contract {
             schema,
             policy: EnforcementPolicy::default(),
             enforced: true,
+            column_locations: HashMap::new(),
         }
     }
 
@@ -220,6 +598,20 @@ impl Contract {
         self.enforced = enforced;
         self
     }
+
+    /// Record where a contract column is declared
+    pub fn with_column_location(mut self, column: impl Into<String>, location: Location) -> Self {
+        self.column_locations.insert(column.into(), location);
+        self
+    }
+
+    /// Where a contract column is declared, if known
+    pub fn column_location(&self, column: &str, case: IdentifierCase) -> Option<&Location> {
+        self.column_locations
+            .iter()
+            .find(|(name, _)| case.matches(name, column))
+            .map(|(_, location)| location)
+    }
 }
 
 #[cfg(test)]
@@ -247,6 +639,68 @@ mod tests {
         assert!(schema.find_column("nonexistent").is_none());
     }
 
+    #[test]
+    fn schema_fingerprint() {
+        let schema = || Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int).with_nullability(Nullability::No),
+            Column::new("name", LogicalType::String),
+        ]);
+
+        assert_eq!(schema().fingerprint(), schema().fingerprint());
+
+        let mut retyped = schema();
+        retyped.columns[1].logical_type = LogicalType::Int;
+        assert_ne!(schema().fingerprint(), retyped.fingerprint());
+
+        let mut reordered = schema();
+        reordered.columns.reverse();
+        assert_ne!(schema().fingerprint(), reordered.fingerprint());
+    }
+
+    #[test]
+    fn schema_diff_categories() {
+        let before = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int).with_nullability(Nullability::No),
+            Column::new("email", LogicalType::String).with_nullability(Nullability::Yes),
+            Column::new("legacy", LogicalType::String),
+            Column::new("amount", LogicalType::Int),
+            Column::new("notes", LogicalType::String),
+        ]);
+        let after = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int).with_nullability(Nullability::No),
+            Column::new("email", LogicalType::String).with_nullability(Nullability::No),
+            Column::new("amount", LogicalType::Float),
+            Column::new("notes", LogicalType::String).with_nullability(Nullability::No),
+            Column::new("created_at", LogicalType::Timestamp),
+        ]);
+
+        let diff = before.diff(&after);
+
+        assert_eq!(diff.added.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["created_at"]);
+        assert_eq!(diff.removed.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["legacy"]);
+        assert_eq!(diff.type_changed.len(), 1);
+        assert_eq!(diff.type_changed[0].before.logical_type, LogicalType::Int);
+        assert_eq!(diff.type_changed[0].after.logical_type, LogicalType::Float);
+        // Unknown -> NOT NULL on `notes` is not a known change
+        assert_eq!(diff.nullability_changed.len(), 1);
+        assert_eq!(diff.nullability_changed[0].before.name, "email");
+
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn schema_diff_with_case_and_type_policy() {
+        let before = Schema::from_columns(vec![Column::new("user_id", LogicalType::Int)]);
+        let after = Schema::from_columns(vec![Column::new("USER_ID", LogicalType::Float)]);
+
+        let exact = before.diff(&after);
+        assert_eq!(exact.removed.len(), 1);
+        assert_eq!(exact.added.len(), 1);
+
+        let lenient = before.diff_with(&after, IdentifierCase::Insensitive, |_, _| true);
+        assert!(lenient.is_empty());
+    }
+
     #[test]
     fn contract_creation() {
         let schema = Schema::from_columns(vec![
@@ -257,10 +711,50 @@ mod tests {
             .with_policy(EnforcementPolicy {
                 allow_extra_columns: true,
                 allow_widening: false,
+                extra_columns: ExtraColumnPolicy::Warn,
             });
 
         assert!(contract.enforced);
         assert!(contract.policy.allow_extra_columns);
         assert!(!contract.policy.allow_widening);
+        assert_eq!(contract.policy.extra_column_policy(), ExtraColumnPolicy::Additive);
+    }
+
+    #[test]
+    fn extra_column_severity_by_policy() {
+        assert_eq!(EnforcementPolicy::default().extra_column_severity(), Severity::Warn);
+        assert_eq!(EnforcementPolicy::strict().extra_column_severity(), Severity::Error);
+        assert_eq!(EnforcementPolicy::additive().extra_column_severity(), Severity::Info);
+    }
+
+    #[test]
+    fn duplicate_column_names() {
+        let schema = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::String),
+            Column::new("ID", LogicalType::Int),
+            Column::new("id", LogicalType::Int),
+        ]);
+
+        assert_eq!(schema.duplicate_column_names(IdentifierCase::Sensitive), vec!["id"]);
+        assert_eq!(schema.duplicate_column_names(IdentifierCase::Insensitive), vec!["id"]);
+        assert!(Schema::from_columns(vec![Column::new("id", LogicalType::Int)])
+            .duplicate_column_names(IdentifierCase::Sensitive)
+            .is_empty());
+    }
+
+    #[test]
+    fn identifier_case_matching() {
+        let schema = Schema::from_columns(vec![
+            Column::new("USER_ID", LogicalType::Int),
+            Column::new("\"MixedCase\"", LogicalType::String),
+        ]);
+
+        assert!(schema.find_column_with("user_id", IdentifierCase::Insensitive).is_some());
+        assert!(schema.find_column_with("user_id", IdentifierCase::Sensitive).is_none());
+
+        // Quoted identifiers stay exact even when folding is enabled
+        assert!(schema.find_column_with("MixedCase", IdentifierCase::Insensitive).is_some());
+        assert!(schema.find_column_with("mixedcase", IdentifierCase::Insensitive).is_none());
     }
 }
@@ -0,0 +1,147 @@
+//! Schema snapshots for tracking inferred schemas over time
+//!
+//! A `SchemaSnapshot` records the schema inferred for every model in one
+//! run. Stored as a CI artifact and compared with the next run's snapshot,
+//! it shows schema changes on every model, including uncontracted ones.
+
+use crate::schema::{Schema, SchemaDiff};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Snapshot format version, bumped on incompatible changes
+pub const SCHEMA_SNAPSHOT_VERSION: u32 = 1;
+
+/// Inferred schemas of a project's models at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaSnapshot {
+    /// Snapshot format version
+    pub version: u32,
+
+    /// Timestamp (ISO 8601)
+    pub generated_at: String,
+
+    /// Model unique_id → inferred schema
+    pub models: BTreeMap<String, Schema>,
+}
+
+impl SchemaSnapshot {
+    /// Create an empty snapshot stamped with the current time
+    pub fn new() -> Self {
+        Self {
+            version: SCHEMA_SNAPSHOT_VERSION,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            models: BTreeMap::new(),
+        }
+    }
+
+    /// Record the schema of a model
+    pub fn insert(&mut self, model_id: impl Into<String>, schema: Schema) {
+        self.models.insert(model_id.into(), schema);
+    }
+
+    /// Changes from this snapshot to a newer one
+    ///
+    /// Models in both snapshots are compared with `Schema::diff`; only those
+    /// with column-level differences are listed in `changed`.
+    pub fn diff(&self, newer: &SchemaSnapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+
+        for (model_id, before) in &self.models {
+            match newer.models.get(model_id) {
+                Some(after) => {
+                    let changes = before.diff(after);
+                    if !changes.is_empty() {
+                        diff.changed.insert(model_id.clone(), changes);
+                    }
+                }
+                None => diff.removed_models.push(model_id.clone()),
+            }
+        }
+
+        diff.added_models = newer
+            .models
+            .keys()
+            .filter(|model_id| !self.models.contains_key(*model_id))
+            .cloned()
+            .collect();
+
+        diff
+    }
+}
+
+impl Default for SchemaSnapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Differences between two schema snapshots
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SnapshotDiff {
+    /// Models only in the newer snapshot
+    pub added_models: Vec<String>,
+
+    /// Models only in the older snapshot
+    pub removed_models: Vec<String>,
+
+    /// Models in both snapshots whose schema changed
+    pub changed: BTreeMap<String, SchemaDiff>,
+}
+
+impl SnapshotDiff {
+    /// Whether the snapshots hold the same models with the same schemas
+    pub fn is_empty(&self) -> bool {
+        self.added_models.is_empty() && self.removed_models.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{Column, LogicalType};
+
+    fn snapshot(models: &[(&str, Vec<Column>)]) -> SchemaSnapshot {
+        let mut snapshot = SchemaSnapshot::new();
+        for (model_id, columns) in models {
+            snapshot.insert(*model_id, Schema::from_columns(columns.clone()));
+        }
+        snapshot
+    }
+
+    #[test]
+    fn snapshot_round_trips_through_json() {
+        let snapshot = snapshot(&[(
+            "model.shop.users",
+            vec![Column::new("id", LogicalType::Int), Column::new("email", LogicalType::String)],
+        )]);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let parsed: SchemaSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, snapshot);
+        assert_eq!(parsed.version, SCHEMA_SNAPSHOT_VERSION);
+        assert_eq!(parsed.models["model.shop.users"].column_names(), vec!["id", "email"]);
+    }
+
+    #[test]
+    fn diff_reports_type_changes_and_model_set_changes() {
+        let old = snapshot(&[
+            ("model.shop.users", vec![Column::new("id", LogicalType::Int)]),
+            ("model.shop.legacy", vec![Column::new("id", LogicalType::Int)]),
+        ]);
+        let new = snapshot(&[
+            ("model.shop.users", vec![Column::new("id", LogicalType::String)]),
+            ("model.shop.orders", vec![Column::new("order_id", LogicalType::Int)]),
+        ]);
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added_models, vec!["model.shop.orders"]);
+        assert_eq!(diff.removed_models, vec!["model.shop.legacy"]);
+
+        let users = &diff.changed["model.shop.users"];
+        assert_eq!(users.type_changed.len(), 1);
+        assert_eq!(users.type_changed[0].before.logical_type, LogicalType::Int);
+        assert_eq!(users.type_changed[0].after.logical_type, LogicalType::String);
+
+        assert!(old.diff(&old).is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+//! Inline suppression directives
+//!
+//! Model SQL can silence specific findings at the source with a comment:
+//!
+//! ```sql
+//! -- schemarefly: ignore CONTRACT_EXTRA_COLUMN
+//! -- schemarefly: ignore CONTRACT_EXTRA_COLUMN, CONTRACT_TYPE_MISMATCH -- legacy export
+//! {# schemarefly: ignore CONTRACT_MISSING_COLUMN -- dropped next release #}
+//! ```
+//!
+//! A directive applies to every diagnostic with a listed code for the model
+//! file it appears in. Text after a second `--` is kept as the reason.
+
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+
+/// Marker that introduces a directive inside a comment
+const DIRECTIVE: &str = "schemarefly:";
+
+/// An `ignore` directive found in model SQL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    /// Codes to drop
+    pub codes: Vec<DiagnosticCode>,
+
+    /// Tokens that did not name a known diagnostic code
+    pub unknown_codes: Vec<String>,
+
+    /// Why the findings are suppressed, if given
+    pub reason: Option<String>,
+
+    /// 1-based line of the directive
+    pub line: usize,
+}
+
+impl Suppression {
+    /// Find all `schemarefly: ignore` directives in SQL (raw or preprocessed)
+    pub fn parse_sql(sql: &str) -> Vec<Suppression> {
+        sql.lines()
+            .enumerate()
+            .filter_map(|(index, line)| Self::parse_line(line, index + 1))
+            .collect()
+    }
+
+    /// Parse a single line, returning a directive if it contains one
+    fn parse_line(line: &str, line_number: usize) -> Option<Suppression> {
+        let comment = Self::comment_body(line)?;
+        let rest = comment.trim_start().strip_prefix(DIRECTIVE)?.trim_start();
+        let rest = rest.strip_prefix("ignore")?;
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+
+        let (codes_part, reason) = match rest.split_once("--") {
+            Some((codes, reason)) => (codes, Some(reason.trim()).filter(|r| !r.is_empty())),
+            None => (rest, None),
+        };
+
+        let mut codes = Vec::new();
+        let mut unknown_codes = Vec::new();
+        for token in codes_part.split(|c: char| c == ',' || c.is_whitespace()).filter(|t| !t.is_empty()) {
+            match DiagnosticCode::parse(token) {
+                Some(code) if !codes.contains(&code) => codes.push(code),
+                Some(_) => {}
+                None => unknown_codes.push(token.to_string()),
+            }
+        }
+
+        Some(Suppression {
+            codes,
+            unknown_codes,
+            reason: reason.map(str::to_string),
+            line: line_number,
+        })
+    }
+
+    /// Text of the comment on a line (`--`, `/* */` or `{# #}`), if any
+    fn comment_body(line: &str) -> Option<&str> {
+        if let Some((_, body)) = line.split_once("{#") {
+            return Some(body.split("#}").next().unwrap_or(body));
+        }
+        if let Some((_, body)) = line.split_once("/*") {
+            return Some(body.split("*/").next().unwrap_or(body));
+        }
+        line.split_once("--").map(|(_, body)| body)
+    }
+
+    /// Whether this directive suppresses `diagnostic`
+    pub fn matches(&self, diagnostic: &Diagnostic) -> bool {
+        self.codes.contains(&diagnostic.code)
+    }
+
+    /// Drop diagnostics matched by any directive, returning the rest and the
+    /// number suppressed
+    pub fn apply(suppressions: &[Suppression], diagnostics: Vec<Diagnostic>) -> (Vec<Diagnostic>, usize) {
+        if suppressions.is_empty() {
+            return (diagnostics, 0);
+        }
+
+        let before = diagnostics.len();
+        let kept: Vec<Diagnostic> = diagnostics
+            .into_iter()
+            .filter(|d| !suppressions.iter().any(|s| s.matches(d)))
+            .collect();
+        let suppressed = before - kept.len();
+        (kept, suppressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::Severity;
+
+    fn diag(code: DiagnosticCode) -> Diagnostic {
+        Diagnostic::new(code, Severity::Error, code.as_str())
+    }
+
+    #[test]
+    fn parses_codes_and_reason() {
+        let sql = "-- schemarefly: ignore CONTRACT_EXTRA_COLUMN, contract_type_mismatch -- legacy export\n\
+                   select 1 as id\n\
+                   {# schemarefly: ignore CONTRACT_MISSING_COLUMN #}\n\
+                   /* schemarefly: ignore NOT_A_CODE */";
+
+        let suppressions = Suppression::parse_sql(sql);
+
+        assert_eq!(suppressions.len(), 3);
+        assert_eq!(
+            suppressions[0].codes,
+            vec![DiagnosticCode::ContractExtraColumn, DiagnosticCode::ContractTypeMismatch]
+        );
+        assert_eq!(suppressions[0].reason.as_deref(), Some("legacy export"));
+        assert_eq!(suppressions[0].line, 1);
+        assert_eq!(suppressions[1].codes, vec![DiagnosticCode::ContractMissingColumn]);
+        assert_eq!(suppressions[1].reason, None);
+        assert_eq!(suppressions[2].unknown_codes, vec!["NOT_A_CODE".to_string()]);
+    }
+
+    #[test]
+    fn ignores_ordinary_comments() {
+        let sql = "-- schemarefly is great\nselect 1 -- ignore this\n/* schemarefly: check later */";
+        assert!(Suppression::parse_sql(sql).is_empty());
+    }
+
+    #[test]
+    fn apply_drops_only_targeted_codes() {
+        let suppressions = Suppression::parse_sql("-- schemarefly: ignore CONTRACT_EXTRA_COLUMN\nselect 1");
+        let diagnostics = vec![
+            diag(DiagnosticCode::ContractExtraColumn),
+            diag(DiagnosticCode::ContractTypeMismatch),
+            diag(DiagnosticCode::ContractExtraColumn),
+        ];
+
+        let (kept, suppressed) = Suppression::apply(&suppressions, diagnostics);
+
+        assert_eq!(suppressed, 2);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].code, DiagnosticCode::ContractTypeMismatch);
+    }
+}
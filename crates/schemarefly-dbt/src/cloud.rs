@@ -0,0 +1,120 @@
+//! dbt Cloud artifact download
+//!
+//! Teams on dbt Cloud have no local `target/manifest.json`; the Admin API
+//! serves the artifacts of a job's most recent successful run instead.
+//! URL and header construction is always available; the HTTP download
+//! itself needs the `dbt-cloud` feature.
+
+use crate::manifest::ManifestError;
+use schemarefly_core::DbtCloudConfig;
+
+/// Client for the dbt Cloud Admin API (v2)
+#[derive(Debug, Clone)]
+pub struct DbtCloudClient {
+    config: DbtCloudConfig,
+}
+
+impl DbtCloudClient {
+    /// Create a client for a configured dbt Cloud job
+    pub fn new(config: DbtCloudConfig) -> Self {
+        Self { config }
+    }
+
+    /// Base URL of the Admin API, e.g. `https://cloud.getdbt.com/api/v2`
+    ///
+    /// A host given with a scheme (`http://localhost:8080`) is used as is.
+    pub fn api_base(&self) -> String {
+        let host = self.config.host.trim_end_matches('/');
+        if host.contains("://") {
+            format!("{}/api/v2", host)
+        } else {
+            format!("https://{}/api/v2", host)
+        }
+    }
+
+    /// URL of an artifact from the job's latest successful run
+    pub fn artifact_url(&self, artifact: &str) -> String {
+        format!(
+            "{}/accounts/{}/jobs/{}/artifacts/{}",
+            self.api_base(),
+            self.config.account_id,
+            self.config.job_id,
+            artifact
+        )
+    }
+
+    /// Request headers, including the API token
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Authorization", format!("Token {}", self.config.token)),
+            ("Accept", "application/json".to_string()),
+        ]
+    }
+
+    /// Download `manifest.json` from the job's latest successful run
+    #[cfg(feature = "dbt-cloud")]
+    pub async fn fetch_manifest_json(&self) -> Result<String, ManifestError> {
+        let url = self.artifact_url("manifest.json");
+        let download_error = |e: reqwest::Error| ManifestError::DownloadError(url.clone(), e.to_string());
+
+        let mut request = reqwest::Client::new().get(&url);
+        for (name, value) in self.headers() {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().await.map_err(download_error)?;
+        let response = response.error_for_status().map_err(download_error)?;
+        let bytes = response.bytes().await.map_err(download_error)?;
+
+        crate::Manifest::read_json(bytes.as_ref(), &url)
+    }
+
+    /// Download `manifest.json` (unavailable without the `dbt-cloud` feature)
+    #[cfg(not(feature = "dbt-cloud"))]
+    pub async fn fetch_manifest_json(&self) -> Result<String, ManifestError> {
+        Err(ManifestError::DownloadError(
+            self.artifact_url("manifest.json"),
+            "schemarefly was built without the `dbt-cloud` feature".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(host: &str) -> DbtCloudClient {
+        DbtCloudClient::new(DbtCloudConfig {
+            account_id: "12345".to_string(),
+            job_id: "678".to_string(),
+            token: "dbtc_secret".to_string(),
+            host: host.to_string(),
+        })
+    }
+
+    #[test]
+    fn builds_artifact_url_and_headers() {
+        let client = client("cloud.getdbt.com");
+
+        assert_eq!(
+            client.artifact_url("manifest.json"),
+            "https://cloud.getdbt.com/api/v2/accounts/12345/jobs/678/artifacts/manifest.json"
+        );
+        assert_eq!(
+            client.headers(),
+            vec![
+                ("Authorization", "Token dbtc_secret".to_string()),
+                ("Accept", "application/json".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn host_with_scheme_is_used_as_is() {
+        let client = client("http://localhost:8080/");
+        assert_eq!(
+            client.artifact_url("manifest.json"),
+            "http://localhost:8080/api/v2/accounts/12345/jobs/678/artifacts/manifest.json"
+        );
+    }
+}
@@ -2,9 +2,12 @@
 //!
 //! Extracts contract definitions (enforced schemas) from dbt model configurations.
 
-use schemarefly_core::{Contract, Schema, Column, LogicalType, EnforcementPolicy};
+use schemarefly_core::{
+    Contract, Schema, Column, LogicalType, EnforcementPolicy, Location, Diagnostic, DiagnosticCode, Severity,
+};
 use crate::manifest::{Manifest, ManifestNode};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 /// Extract contracts from manifest
 pub struct ContractExtractor;
@@ -41,7 +44,15 @@ impl ContractExtractor {
 
                 let logical_type = Self::parse_data_type(data_type);
 
-                Some(Column::new(col_def.name.clone(), logical_type))
+                let mut column = Column::new(col_def.name.clone(), logical_type).with_time_zone_of(data_type);
+                if let Some(default) = col_def.constraints.iter().find_map(|c| c.default_expression()) {
+                    column = column.with_default(default);
+                }
+
+                Some(match &col_def.config.contract {
+                    Some(column_contract) => column.with_enforced(column_contract.enforced),
+                    None => column,
+                })
             })
             .collect();
 
@@ -52,13 +63,150 @@ impl ContractExtractor {
         let schema = Schema::from_columns(columns);
 
         // Create contract with default enforcement policy
-        let contract = Contract::new(schema)
+        let mut contract = Contract::new(schema)
             .with_policy(EnforcementPolicy::default())
             .with_enforced(true);
 
+        // Columns are declared in the node's properties file (line unknown until resolved)
+        if let Some(yaml_file) = node.patch_file() {
+            let names: Vec<String> = contract.schema.columns.iter().map(|c| c.name.clone()).collect();
+            for name in names {
+                contract = contract.with_column_location(name, Location::new(yaml_file));
+            }
+        }
+
         Some(contract)
     }
 
+    /// Extract a contract and resolve the YAML line of each column
+    ///
+    /// Reads the node's properties file relative to `project_root`. If the
+    /// file can't be read, column locations stay file-level.
+    pub fn extract_with_locations(node: &ManifestNode, project_root: &Path) -> Option<Contract> {
+        let mut contract = Self::extract_from_node(node)?;
+
+        if let Some(yaml_file) = node.patch_file() {
+            if let Ok(yaml) = std::fs::read_to_string(project_root.join(yaml_file)) {
+                Self::resolve_column_lines(&mut contract, &node.name, yaml_file, &yaml);
+            }
+        }
+
+        Some(contract)
+    }
+
+    /// Fill in contract column line numbers from the properties YAML
+    ///
+    /// Finds the model's `- name: <model>` entry, then each `- name: <column>`
+    /// entry after it. This is a line scan rather than a YAML parse, so it
+    /// is best-effort: columns that aren't found keep a file-level location.
+    pub fn resolve_column_lines(contract: &mut Contract, model_name: &str, yaml_file: &str, yaml: &str) {
+        let name_of = |line: &str| {
+            let rest = line.trim_start().strip_prefix("- ")?.trim_start().strip_prefix("name:")?;
+            Some(rest.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+        };
+
+        let lines: Vec<&str> = yaml.lines().collect();
+        let Some(model_idx) = lines.iter().position(|l| name_of(l).as_deref() == Some(model_name)) else {
+            return;
+        };
+        let model_indent = lines[model_idx].len() - lines[model_idx].trim_start().len();
+
+        for (offset, line) in lines[model_idx + 1..].iter().enumerate() {
+            let indent = line.len() - line.trim_start().len();
+
+            // Stop at the next model entry
+            if indent <= model_indent && line.trim_start().starts_with("- ") {
+                break;
+            }
+
+            if let Some(column) = name_of(line) {
+                if contract.schema.find_column(&column).is_some() {
+                    let line_no = model_idx + offset + 2;
+                    let col_no = indent + 3;
+                    contract.column_locations.insert(
+                        column,
+                        Location::with_position(yaml_file, line_no, col_no),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check every enforced contract in the manifest for YAML mistakes
+    ///
+    /// Models are visited in `unique_id` order so the output is stable.
+    pub fn lint_all(manifest: &Manifest) -> Vec<Diagnostic> {
+        let mut models: Vec<&ManifestNode> = manifest.models().into_values().collect();
+        models.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+
+        models.into_iter().flat_map(Self::lint_node).collect()
+    }
+
+    /// Check one model's contract for YAML mistakes
+    ///
+    /// Reports columns without a `data_type` (silently left out by
+    /// `extract_from_node`), data types that parse to `Unknown` (never
+    /// compared), and column names declared more than once ignoring case.
+    /// Models without an enforced contract are skipped.
+    pub fn lint_node(node: &ManifestNode) -> Vec<Diagnostic> {
+        if !node.config.contract.as_ref().is_some_and(|c| c.enforced) {
+            return Vec::new();
+        }
+
+        let location = Location::new(node.patch_file().unwrap_or(&node.original_file_path));
+        let diagnostic = |code, severity, message: String| {
+            Diagnostic::new(code, severity, message).with_location(location.clone())
+        };
+
+        let mut columns: Vec<_> = node.columns.values().collect();
+        columns.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut diagnostics = Vec::new();
+        let mut by_name: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+
+        for column in &columns {
+            by_name.entry(column.name.to_lowercase()).or_default().push(&column.name);
+
+            match column.data_type.as_deref().map(str::trim) {
+                None | Some("") => diagnostics.push(diagnostic(
+                    DiagnosticCode::ContractColumnMissingType,
+                    Severity::Error,
+                    format!(
+                        "Contract column '{}' of '{}' has no data_type and is not checked",
+                        column.name, node.name
+                    ),
+                )),
+                Some(data_type) if Self::parse_data_type(data_type) == LogicalType::Unknown => {
+                    diagnostics.push(diagnostic(
+                        DiagnosticCode::ContractUnknownType,
+                        Severity::Warn,
+                        format!(
+                            "Contract column '{}' of '{}' has unrecognized data_type '{}'; its type is not checked",
+                            column.name, node.name, data_type
+                        ),
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+
+        for names in by_name.values().filter(|names| names.len() > 1) {
+            diagnostics.push(diagnostic(
+                DiagnosticCode::ContractDuplicateColumn,
+                Severity::Error,
+                format!(
+                    "Contract of '{}' declares column '{}' {} times ({})",
+                    node.name,
+                    names[0],
+                    names.len(),
+                    names.join(", ")
+                ),
+            ));
+        }
+
+        diagnostics
+    }
+
     /// Parse dbt data_type string to LogicalType
     ///
     /// This is a simple parser for common types. More sophisticated parsing
@@ -113,7 +261,10 @@ impl ContractExtractor {
             }
 
             // JSON/Variant
-            "json" | "jsonb" | "variant" | "object" => LogicalType::Json,
+            "json" | "jsonb" | "variant" => LogicalType::Json,
+
+            // Snowflake OBJECT carries no declared fields
+            "object" => LogicalType::Struct { fields: Vec::new() },
 
             // Arrays
             s if s.starts_with("array") => {
@@ -191,4 +342,138 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn extract_per_column_enforcement() {
+        let manifest = Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.p.users": {
+                    "unique_id": "model.p.users", "name": "users", "resource_type": "model",
+                    "package_name": "p", "path": "users.sql",
+                    "original_file_path": "models/users.sql",
+                    "config": {"contract": {"enforced": true}},
+                    "columns": {
+                        "id": {"name": "id", "data_type": "integer", "config": {"contract": {"enforced": true}}},
+                        "note": {"name": "note", "data_type": "varchar", "config": {"contract": {"enforced": false}}},
+                        "email": {"name": "email", "data_type": "varchar"}
+                    }
+                }
+            },
+            "sources": {}
+        }"#).unwrap();
+        let node = manifest.get_node("model.p.users").unwrap();
+
+        let contract = ContractExtractor::extract_from_node(node).unwrap();
+        assert_eq!(contract.schema.find_column("id").unwrap().enforced, Some(true));
+        assert_eq!(contract.schema.find_column("note").unwrap().enforced, Some(false));
+        assert_eq!(contract.schema.find_column("email").unwrap().enforced, None);
+    }
+
+    #[test]
+    fn extract_column_defaults_from_custom_constraints() {
+        let manifest = lint_manifest(r#"
+            "status": {"name": "status", "data_type": "varchar", "constraints": [{"type": "custom", "expression": "default 'placed'"}]},
+            "id": {"name": "id", "data_type": "integer", "constraints": [{"type": "not_null"}]}
+        "#);
+        let node = manifest.get_node("model.p.users").unwrap();
+
+        let contract = ContractExtractor::extract_from_node(node).unwrap();
+        let status = contract.schema.find_column("status").unwrap();
+        assert_eq!(status.default.as_deref(), Some("'placed'"));
+        assert_eq!(status.has_default, Some(true));
+        assert_eq!(contract.schema.find_column("id").unwrap().has_default, None);
+    }
+
+    fn lint_manifest(columns: &str) -> Manifest {
+        Manifest::from_str(&format!(r#"{{
+            "metadata": {{"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"}},
+            "nodes": {{
+                "model.p.users": {{
+                    "unique_id": "model.p.users", "name": "users", "resource_type": "model",
+                    "package_name": "p", "path": "users.sql",
+                    "original_file_path": "models/users.sql",
+                    "patch_path": "p://models/schema.yml",
+                    "config": {{"contract": {{"enforced": true}}}},
+                    "columns": {{{columns}}}
+                }}
+            }},
+            "sources": {{}}
+        }}"#)).unwrap()
+    }
+
+    #[test]
+    fn lint_column_without_data_type() {
+        let manifest = lint_manifest(r#""id": {"name": "id", "data_type": "integer"}, "email": {"name": "email"}"#);
+
+        let diagnostics = ContractExtractor::lint_all(&manifest);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::ContractColumnMissingType);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(diagnostics[0].message.contains("'email'"));
+        assert_eq!(diagnostics[0].location.as_ref().unwrap().file, "models/schema.yml");
+    }
+
+    #[test]
+    fn lint_unrecognized_data_type() {
+        let manifest = lint_manifest(r#""id": {"name": "id", "data_type": "integr"}, "email": {"name": "email", "data_type": "varchar"}"#);
+
+        let diagnostics = ContractExtractor::lint_all(&manifest);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::ContractUnknownType);
+        assert_eq!(diagnostics[0].severity, Severity::Warn);
+        assert!(diagnostics[0].message.contains("'integr'"));
+    }
+
+    #[test]
+    fn lint_duplicate_column() {
+        let manifest = lint_manifest(r#""id": {"name": "id", "data_type": "integer"}, "ID": {"name": "ID", "data_type": "integer"}"#);
+
+        let diagnostics = ContractExtractor::lint_all(&manifest);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::ContractDuplicateColumn);
+        assert_eq!(diagnostics[0].message, "Contract of 'users' declares column 'ID' 2 times (ID, id)");
+    }
+
+    #[test]
+    fn lint_skips_unenforced_contracts() {
+        let mut manifest = lint_manifest(r#""email": {"name": "email"}"#);
+        manifest.nodes.get_mut("model.p.users").unwrap().config.contract = None;
+
+        assert!(ContractExtractor::lint_all(&manifest).is_empty());
+    }
+
+    #[test]
+    fn resolve_contract_column_lines() {
+        let manifest = Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.p.users": {
+                    "unique_id": "model.p.users", "name": "users", "resource_type": "model",
+                    "package_name": "p", "path": "users.sql",
+                    "original_file_path": "models/users.sql",
+                    "patch_path": "p://models/schema.yml",
+                    "config": {"contract": {"enforced": true}},
+                    "columns": {
+                        "id": {"name": "id", "data_type": "integer"},
+                        "email": {"name": "email", "data_type": "varchar"}
+                    }
+                }
+            },
+            "sources": {}
+        }"#).unwrap();
+        let node = manifest.get_node("model.p.users").unwrap();
+
+        let mut contract = ContractExtractor::extract_from_node(node).unwrap();
+        assert_eq!(contract.column_locations["id"].file, "models/schema.yml");
+        assert_eq!(contract.column_locations["id"].line, None);
+
+        let yaml = "version: 2\nmodels:\n  - name: orders\n    columns:\n      - name: id\n  - name: users\n    columns:\n      - name: id\n        data_type: integer\n      - name: \"email\"\n";
+        ContractExtractor::resolve_column_lines(&mut contract, "users", "models/schema.yml", yaml);
+
+        // The `orders` model's `id` on line 5 must not be picked up
+        assert_eq!(contract.column_locations["id"].line, Some(8));
+        assert_eq!(contract.column_locations["id"].column, Some(9));
+        assert_eq!(contract.column_locations["email"].line, Some(10));
+    }
 }
@@ -176,6 +176,48 @@ impl DependencyGraph {
         result
     }
 
+    /// Number of distinct direct children (fan-out) of a node
+    pub fn direct_children(&self, node_id: &str) -> usize {
+        self.children
+            .get(node_id)
+            .map(|children| children.iter().collect::<HashSet<_>>().len())
+            .unwrap_or(0)
+    }
+
+    /// Length of the longest downstream chain from a node
+    ///
+    /// A node without children has depth 0; a node whose only child is a
+    /// leaf has depth 1. Edges that would close a cycle are ignored.
+    pub fn max_downstream_depth(&self, node_id: &str) -> usize {
+        let mut memo: HashMap<NodeId, usize> = HashMap::new();
+        let mut on_path: HashSet<NodeId> = HashSet::new();
+        self.depth_from(node_id, &mut memo, &mut on_path)
+    }
+
+    fn depth_from(
+        &self,
+        node_id: &str,
+        memo: &mut HashMap<NodeId, usize>,
+        on_path: &mut HashSet<NodeId>,
+    ) -> usize {
+        if let Some(&depth) = memo.get(node_id) {
+            return depth;
+        }
+
+        on_path.insert(node_id.to_string());
+
+        let mut depth = 0;
+        for child in self.children.get(node_id).into_iter().flatten() {
+            if !on_path.contains(child) {
+                depth = depth.max(1 + self.depth_from(child, memo, on_path));
+            }
+        }
+
+        on_path.remove(node_id);
+        memo.insert(node_id.to_string(), depth);
+        depth
+    }
+
     /// Check if there's a path from source to target
     pub fn has_path(&self, source: &str, target: &str) -> bool {
         let downstream = self.downstream(source);
@@ -278,4 +320,32 @@ mod tests {
             assert!(downstream.contains(&"model.mini_dbt_project.users".to_string()));
         }
     }
+
+    /// a -> b, a -> c, b -> d, c -> d, d -> e
+    fn diamond() -> DependencyGraph {
+        let manifest = Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {},
+            "sources": {},
+            "parent_map": {"a": [], "b": ["a"], "c": ["a"], "d": ["b", "c"], "e": ["d"]},
+            "child_map": {"a": ["b", "c"], "b": ["d"], "c": ["d"], "d": ["e"], "e": []}
+        }"#).unwrap();
+        DependencyGraph::from_manifest(&manifest)
+    }
+
+    #[test]
+    fn diamond_depth_and_fan_out() {
+        let dag = diamond();
+
+        assert_eq!(dag.max_downstream_depth("a"), 3);
+        assert_eq!(dag.max_downstream_depth("b"), 2);
+        assert_eq!(dag.max_downstream_depth("e"), 0);
+
+        assert_eq!(dag.direct_children("a"), 2);
+        assert_eq!(dag.direct_children("d"), 1);
+        assert_eq!(dag.direct_children("e"), 0);
+
+        // Shared descendants are only counted once downstream
+        assert_eq!(dag.downstream("a").len(), 4);
+    }
 }
@@ -0,0 +1,346 @@
+//! DAG export as Graphviz DOT or Mermaid
+//!
+//! Renders the dependency graph (or a model's upstream/downstream slice of it)
+//! for documentation and reviews. Nodes are labeled with model names and
+//! colored by whether the model declares a contract; sources get their own
+//! color. Test nodes are left out.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use crate::contract::ContractExtractor;
+use crate::dag::{DependencyGraph, NodeId};
+use crate::manifest::Manifest;
+
+/// Fill colors used by both formats
+const CONTRACT_FILL: &str = "#c8e6c9";
+const NO_CONTRACT_FILL: &str = "#eeeeee";
+const SOURCE_FILL: &str = "#bbdefb";
+
+/// How a node is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    Contract,
+    NoContract,
+    Source,
+}
+
+impl NodeKind {
+    fn fill(self) -> &'static str {
+        match self {
+            Self::Contract => CONTRACT_FILL,
+            Self::NoContract => NO_CONTRACT_FILL,
+            Self::Source => SOURCE_FILL,
+        }
+    }
+
+    /// Mermaid class name
+    fn class(self) -> &'static str {
+        match self {
+            Self::Contract => "contract",
+            Self::NoContract => "nocontract",
+            Self::Source => "source",
+        }
+    }
+}
+
+/// A `--select` expression: `model`, `+model`, `model+` or `+model+`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphSelector {
+    /// Model name or unique_id
+    pub model: String,
+
+    /// Include everything the model depends on
+    pub upstream: bool,
+
+    /// Include everything that depends on the model
+    pub downstream: bool,
+}
+
+impl GraphSelector {
+    /// Parse a dbt-style graph selector
+    pub fn parse(selector: &str) -> Self {
+        let selector = selector.trim();
+        let (upstream, rest) = match selector.strip_prefix('+') {
+            Some(rest) => (true, rest),
+            None => (false, selector),
+        };
+        let (downstream, model) = match rest.strip_suffix('+') {
+            Some(model) => (true, model),
+            None => (false, rest),
+        };
+
+        Self {
+            model: model.to_string(),
+            upstream,
+            downstream,
+        }
+    }
+}
+
+/// Renders a set of graph nodes and the edges between them
+pub struct GraphExport<'a> {
+    manifest: &'a Manifest,
+    dag: &'a DependencyGraph,
+
+    /// Nodes to draw, sorted for deterministic output
+    nodes: BTreeSet<NodeId>,
+}
+
+impl<'a> GraphExport<'a> {
+    /// Export every non-test node in the graph
+    pub fn new(manifest: &'a Manifest, dag: &'a DependencyGraph) -> Self {
+        let nodes = dag
+            .all_nodes()
+            .into_iter()
+            .filter(|id| !id.starts_with("test."))
+            .cloned()
+            .collect();
+
+        Self { manifest, dag, nodes }
+    }
+
+    /// Restrict the export to `node_id` plus its upstream and/or downstream nodes
+    pub fn with_selection(mut self, node_id: &str, upstream: bool, downstream: bool) -> Self {
+        let mut selected = BTreeSet::from([node_id.to_string()]);
+        if upstream {
+            selected.extend(self.dag.upstream(node_id));
+        }
+        if downstream {
+            selected.extend(self.dag.downstream(node_id));
+        }
+
+        self.nodes = selected.into_iter().filter(|id| !id.starts_with("test.")).collect();
+        self
+    }
+
+    /// Node ids included in the export
+    pub fn nodes(&self) -> impl Iterator<Item = &NodeId> {
+        self.nodes.iter()
+    }
+
+    /// Edges (parent, child) between included nodes, sorted
+    pub fn edges(&self) -> Vec<(&NodeId, &NodeId)> {
+        let mut edges: BTreeSet<(&NodeId, &NodeId)> = BTreeSet::new();
+        for child in &self.nodes {
+            for parent in self.dag.parents(child) {
+                if let Some(parent) = self.nodes.get(parent) {
+                    edges.insert((parent, child));
+                }
+            }
+        }
+        edges.into_iter().collect()
+    }
+
+    /// Render as a Graphviz `digraph`
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph schemarefly {\n");
+        out.push_str("  rankdir=LR;\n");
+        out.push_str("  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n");
+
+        for id in &self.nodes {
+            let _ = writeln!(
+                out,
+                "  \"{}\" [label=\"{}\", fillcolor=\"{}\"];",
+                escape_dot(id),
+                escape_dot(&self.label(id)),
+                self.kind(id).fill()
+            );
+        }
+
+        for (parent, child) in self.edges() {
+            let _ = writeln!(out, "  \"{}\" -> \"{}\";", escape_dot(parent), escape_dot(child));
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render as a Mermaid flowchart
+    ///
+    /// Mermaid ids must be plain identifiers, so nodes are numbered `n0`, `n1`, ...
+    /// in sorted unique_id order and labeled with the model name.
+    pub fn to_mermaid(&self) -> String {
+        let index: std::collections::HashMap<&NodeId, usize> =
+            self.nodes.iter().enumerate().map(|(i, id)| (id, i)).collect();
+
+        let mut out = String::from("graph LR\n");
+        for (i, id) in self.nodes.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "  n{}[\"{}\"]:::{}",
+                i,
+                escape_mermaid(&self.label(id)),
+                self.kind(id).class()
+            );
+        }
+
+        for (parent, child) in self.edges() {
+            let _ = writeln!(out, "  n{} --> n{}", index[parent], index[child]);
+        }
+
+        for kind in [NodeKind::Contract, NodeKind::NoContract, NodeKind::Source] {
+            let _ = writeln!(out, "  classDef {} fill:{},stroke:#555555", kind.class(), kind.fill());
+        }
+
+        out
+    }
+
+    /// Display name: model name, `source_name.table` for sources, else the id
+    fn label(&self, id: &str) -> String {
+        if let Some(node) = self.manifest.get_node(id) {
+            return node.name.clone();
+        }
+        if let Some(source) = self.manifest.get_source(id) {
+            return format!("{}.{}", source.source_name, source.name);
+        }
+        id.to_string()
+    }
+
+    fn kind(&self, id: &str) -> NodeKind {
+        if self.manifest.get_source(id).is_some() || id.starts_with("source.") {
+            return NodeKind::Source;
+        }
+
+        match self.manifest.get_node(id) {
+            Some(node) if ContractExtractor::extract_from_node(node).is_some() => NodeKind::Contract,
+            _ => NodeKind::NoContract,
+        }
+    }
+}
+
+/// Escape a string for a double-quoted DOT id or label
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escape a string for a double-quoted Mermaid label
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// raw.users -> users (contract) -> orders -> revenue, plus a test on orders
+    fn manifest() -> Manifest {
+        Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.shop.users": {
+                    "unique_id": "model.shop.users", "name": "users", "resource_type": "model",
+                    "package_name": "shop", "path": "users.sql", "original_file_path": "models/users.sql",
+                    "database": "db", "schema": "public",
+                    "config": {"contract": {"enforced": true}},
+                    "columns": {"id": {"name": "id", "data_type": "integer"}},
+                    "depends_on": {"nodes": ["source.shop.raw.users"]}
+                },
+                "model.shop.orders": {
+                    "unique_id": "model.shop.orders", "name": "orders", "resource_type": "model",
+                    "package_name": "shop", "path": "orders.sql", "original_file_path": "models/orders.sql",
+                    "database": "db", "schema": "public",
+                    "depends_on": {"nodes": ["model.shop.users"]}
+                },
+                "model.shop.revenue": {
+                    "unique_id": "model.shop.revenue", "name": "revenue", "resource_type": "model",
+                    "package_name": "shop", "path": "revenue.sql", "original_file_path": "models/revenue.sql",
+                    "database": "db", "schema": "public",
+                    "depends_on": {"nodes": ["model.shop.orders"]}
+                },
+                "test.shop.not_null_orders_id": {
+                    "unique_id": "test.shop.not_null_orders_id", "name": "not_null_orders_id", "resource_type": "test",
+                    "package_name": "shop", "path": "t.sql", "original_file_path": "models/schema.yml",
+                    "depends_on": {"nodes": ["model.shop.orders"]}
+                }
+            },
+            "sources": {
+                "source.shop.raw.users": {
+                    "unique_id": "source.shop.raw.users", "source_name": "raw", "name": "users",
+                    "schema": "raw", "resource_type": "source", "package_name": "shop",
+                    "path": "models/sources.yml", "original_file_path": "models/sources.yml"
+                }
+            }
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn selector_parsing() {
+        assert_eq!(
+            GraphSelector::parse("+orders+"),
+            GraphSelector { model: "orders".to_string(), upstream: true, downstream: true }
+        );
+        assert_eq!(
+            GraphSelector::parse("orders+"),
+            GraphSelector { model: "orders".to_string(), upstream: false, downstream: true }
+        );
+        assert!(!GraphSelector::parse("orders").upstream);
+    }
+
+    #[test]
+    fn dot_contains_expected_edges_and_colors() {
+        let manifest = manifest();
+        let dag = DependencyGraph::from_manifest(&manifest);
+        let dot = GraphExport::new(&manifest, &dag).to_dot();
+
+        assert!(dot.starts_with("digraph schemarefly {"));
+        assert!(dot.contains("\"source.shop.raw.users\" -> \"model.shop.users\";"), "{dot}");
+        assert!(dot.contains("\"model.shop.users\" -> \"model.shop.orders\";"), "{dot}");
+        assert!(dot.contains("\"model.shop.orders\" -> \"model.shop.revenue\";"), "{dot}");
+        assert!(dot.contains(&format!("\"model.shop.users\" [label=\"users\", fillcolor=\"{}\"]", CONTRACT_FILL)));
+        assert!(dot.contains(&format!("\"model.shop.orders\" [label=\"orders\", fillcolor=\"{}\"]", NO_CONTRACT_FILL)));
+        assert!(dot.contains("label=\"raw.users\""));
+        assert!(!dot.contains("not_null_orders_id"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn selection_limits_to_downstream() {
+        let manifest = manifest();
+        let dag = DependencyGraph::from_manifest(&manifest);
+        let export = GraphExport::new(&manifest, &dag).with_selection("model.shop.orders", false, true);
+
+        let nodes: Vec<&str> = export.nodes().map(String::as_str).collect();
+        assert_eq!(nodes, vec!["model.shop.orders", "model.shop.revenue"]);
+        assert_eq!(export.edges().len(), 1);
+    }
+
+    #[test]
+    fn mermaid_is_structurally_valid() {
+        let manifest = manifest();
+        let dag = DependencyGraph::from_manifest(&manifest);
+        let mermaid = GraphExport::new(&manifest, &dag).to_mermaid();
+
+        let mut lines = mermaid.lines();
+        assert_eq!(lines.next(), Some("graph LR"));
+
+        let mut defined = Vec::new();
+        let mut edges = Vec::new();
+        let mut classes = Vec::new();
+        for line in lines.map(str::trim) {
+            if let Some(def) = line.strip_prefix("classDef ") {
+                classes.push(def.split_whitespace().next().unwrap().to_string());
+            } else if let Some((from, to)) = line.split_once(" --> ") {
+                edges.push((from.to_string(), to.to_string()));
+            } else {
+                // n0["label"]:::class
+                let (id, rest) = line.split_once('[').expect(line);
+                let (label, class) = rest.split_once("]:::").expect(line);
+                assert!(label.starts_with('"') && label.ends_with('"'), "{line}");
+                defined.push((id.to_string(), class.to_string()));
+            }
+        }
+
+        assert_eq!(defined.len(), 4);
+        assert_eq!(edges.len(), 3);
+        for (from, to) in &edges {
+            assert!(defined.iter().any(|(id, _)| id == from), "undefined node {from}");
+            assert!(defined.iter().any(|(id, _)| id == to), "undefined node {to}");
+        }
+        for (_, class) in &defined {
+            assert!(classes.contains(class), "undefined class {class}");
+        }
+        assert!(mermaid.contains("[\"users\"]:::contract"));
+        assert!(mermaid.contains("[\"raw.users\"]:::source"));
+    }
+}
@@ -5,11 +5,17 @@
 //! - Building dependency graphs (DAG)
 //! - Extracting contract definitions from model YAMLs
 //! - Impact analysis (downstream dependencies)
+//! - Exporting the DAG as Graphviz DOT or Mermaid
+//! - Downloading artifacts from dbt Cloud (`dbt-cloud` feature)
 
 pub mod manifest;
 pub mod dag;
 pub mod contract;
+pub mod graph_export;
+pub mod cloud;
 
-pub use manifest::{Manifest, ManifestNode, ManifestSource, NodeConfig, ContractConfig, ColumnDefinition, DependsOn, ManifestMetadata};
+pub use manifest::{Manifest, ManifestNode, ManifestSource, NodeConfig, ContractConfig, ColumnConfig, ColumnDefinition, ColumnConstraint, DependsOn, ManifestMetadata, ManifestUnitTest, UnitTestFixture, SchemaReflyMeta, TestMetadata};
 pub use dag::{DependencyGraph, NodeId};
 pub use contract::ContractExtractor;
+pub use graph_export::{GraphExport, GraphSelector};
+pub use cloud::DbtCloudClient;
@@ -2,10 +2,17 @@
 //!
 //! Parses dbt-generated manifest.json to extract models, sources, and dependencies.
 
+use flate2::read::GzDecoder;
+use schemarefly_core::{Diagnostic, DiagnosticCode, Location, Severity};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::Read;
 use std::path::Path;
 
+/// Leading bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
 /// dbt manifest.json structure (subset of fields we care about)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Manifest {
@@ -25,15 +32,47 @@ pub struct Manifest {
     /// Child map (node -> list of child nodes)
     #[serde(default)]
     pub child_map: HashMap<String, Vec<String>>,
+
+    /// Unit tests (dbt 1.8+)
+    #[serde(default)]
+    pub unit_tests: HashMap<String, ManifestUnitTest>,
 }
 
 impl Manifest {
-    /// Load manifest from file
+    /// Load manifest from file (plain or gzip-compressed)
     pub fn from_file(path: &Path) -> Result<Self, ManifestError> {
-        let contents = std::fs::read_to_string(path)
-            .map_err(|e| ManifestError::IoError(path.display().to_string(), e.to_string()))?;
+        Self::from_str(&Self::read_json_file(path)?)
+    }
+
+    /// Load manifest from any reader, e.g. stdin (plain or gzip-compressed)
+    pub fn from_reader(reader: impl Read) -> Result<Self, ManifestError> {
+        Self::from_str(&Self::read_json(reader, "<reader>")?)
+    }
+
+    /// Read manifest JSON text from a file, decompressing gzip if needed
+    pub fn read_json_file(path: &Path) -> Result<String, ManifestError> {
+        let label = path.display().to_string();
+        let file = File::open(path).map_err(|e| ManifestError::IoError(label.clone(), e.to_string()))?;
+        Self::read_json(file, &label)
+    }
 
-        Self::from_str(&contents)
+    /// Read manifest JSON text from a reader, decompressing gzip if needed
+    ///
+    /// Compression is detected from the gzip magic bytes rather than the
+    /// file extension, so `manifest.json` and `manifest.json.gz` both work.
+    pub fn read_json(mut reader: impl Read, label: &str) -> Result<String, ManifestError> {
+        let io_error = |e: std::io::Error| ManifestError::IoError(label.to_string(), e.to_string());
+
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).map_err(io_error)?;
+
+        if bytes.starts_with(&GZIP_MAGIC) {
+            let mut json = Vec::new();
+            GzDecoder::new(bytes.as_slice()).read_to_end(&mut json).map_err(io_error)?;
+            bytes = json;
+        }
+
+        String::from_utf8(bytes).map_err(|e| ManifestError::IoError(label.to_string(), e.to_string()))
     }
 
     /// Parse manifest from JSON string
@@ -43,13 +82,82 @@ impl Manifest {
             .map_err(|e| ManifestError::ParseError(e.to_string()))
     }
 
+    /// Parse only the top-level sections SchemaRefly uses
+    ///
+    /// Reads `metadata`, `nodes`, `sources`, `parent_map`, `child_map` and
+    /// `unit_tests`, skips every other section without building it, and
+    /// stops parsing as soon as all six have been read. The sections after
+    /// them are only scanned for matching brackets, so syntax errors there go
+    /// unnoticed but a truncated manifest is still rejected. The result is
+    /// identical to [`Manifest::from_str`] for any valid manifest.
+    pub fn from_str_partial(json: &str) -> Result<Self, ManifestError> {
+        let mut reader = SectionReader::new(json);
+
+        let mut metadata = None;
+        let mut nodes = None;
+        let mut sources = None;
+        let mut parent_map = None;
+        let mut child_map = None;
+        let mut unit_tests = None;
+
+        reader.expect(b'{')?;
+        let mut more = !reader.consume(b'}');
+        while more {
+            let key: String = reader.value()?;
+            reader.expect(b':')?;
+
+            match key.as_str() {
+                "metadata" => metadata = Some(reader.value()?),
+                "nodes" => nodes = Some(reader.value()?),
+                "sources" => sources = Some(reader.value()?),
+                "parent_map" => parent_map = Some(reader.value()?),
+                "child_map" => child_map = Some(reader.value()?),
+                "unit_tests" => unit_tests = Some(reader.value()?),
+                _ => {
+                    reader.value::<serde::de::IgnoredAny>()?;
+                }
+            }
+
+            // Once every section is read the rest of the object is left unread
+            let complete = metadata.is_some()
+                && nodes.is_some()
+                && sources.is_some()
+                && parent_map.is_some()
+                && child_map.is_some()
+                && unit_tests.is_some();
+
+            more = !complete && reader.consume(b',');
+            if complete {
+                reader.skip_rest_of_object()?;
+            } else if !more {
+                reader.expect(b'}')?;
+            }
+        }
+        reader.finish()?;
+
+        let missing = |field: &str| ManifestError::ParseError(format!("missing field `{}`", field));
+
+        Ok(Self {
+            metadata: metadata.ok_or_else(|| missing("metadata"))?,
+            nodes: nodes.ok_or_else(|| missing("nodes"))?,
+            sources: sources.ok_or_else(|| missing("sources"))?,
+            parent_map: parent_map.unwrap_or_default(),
+            child_map: child_map.unwrap_or_default(),
+            unit_tests: unit_tests.unwrap_or_default(),
+        })
+    }
+
     /// Get all model nodes (filters out tests, seeds, etc.)
     pub fn models(&self) -> HashMap<String, &ManifestNode> {
-        self.nodes
-            .iter()
-            .filter(|(_, node)| node.resource_type == "model")
-            .map(|(id, node)| (id.clone(), node))
-            .collect()
+        self.models_iter().map(|(id, node)| (id.clone(), node)).collect()
+    }
+
+    /// Iterate over model nodes without collecting them
+    ///
+    /// Prefer this over `models()` when the nodes are only visited once:
+    /// it borrows from the manifest instead of copying every unique_id.
+    pub fn models_iter(&self) -> impl Iterator<Item = (&String, &ManifestNode)> {
+        self.nodes.iter().filter(|(_, node)| node.resource_type == "model")
     }
 
     /// Get a specific node by unique_id
@@ -57,10 +165,267 @@ impl Manifest {
         self.nodes.get(unique_id)
     }
 
+    /// Find a version of a versioned model by model name
+    pub fn find_model_version(&self, name: &str, version: &str) -> Option<&ManifestNode> {
+        self.nodes
+            .values()
+            .find(|node| node.resource_type == "model" && node.name == name && node.is_version(version))
+    }
+
     /// Get a specific source by unique_id
     pub fn get_source(&self, unique_id: &str) -> Option<&ManifestSource> {
         self.sources.get(unique_id)
     }
+
+    /// Unit tests of `model`, ordered by unique_id
+    ///
+    /// A unit test belongs to the model in its `depends_on`, or, for tests
+    /// without dependencies, to the model (and version) it names.
+    pub fn unit_tests_for(&self, model: &ManifestNode) -> Vec<&ManifestUnitTest> {
+        let mut tests: Vec<&ManifestUnitTest> = self
+            .unit_tests
+            .values()
+            .filter(|test| {
+                if test.depends_on.nodes.is_empty() {
+                    test.model == model.name && test.version == model.version
+                } else {
+                    test.depends_on.nodes.contains(&model.unique_id)
+                }
+            })
+            .collect();
+        tests.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+        tests
+    }
+
+    /// Values each column of `model` is declared to accept, keyed by column name
+    ///
+    /// Read from `accepted_values` tests on the model and from column `check`
+    /// constraints of the form `column IN (...)`. When both declare a column,
+    /// the test wins.
+    pub fn accepted_values(&self, model: &ManifestNode) -> BTreeMap<String, Vec<String>> {
+        let mut accepted: BTreeMap<String, Vec<String>> = model
+            .columns
+            .values()
+            .filter_map(|col| {
+                let values = col.constraints.iter().find_map(|c| c.in_list_values(&col.name))?;
+                Some((col.name.clone(), values))
+            })
+            .collect();
+
+        for test in self.nodes.values().filter(|node| node.resource_type == "test") {
+            let Some(metadata) = test.test_metadata.as_ref().filter(|m| m.name == "accepted_values") else {
+                continue;
+            };
+            let on_model = match &test.attached_node {
+                Some(attached) => *attached == model.unique_id,
+                None => test.depends_on.nodes.contains(&model.unique_id),
+            };
+            if !on_model {
+                continue;
+            }
+
+            let column = test
+                .column_name
+                .clone()
+                .or_else(|| metadata.kwargs.get("column_name").and_then(|c| c.as_str()).map(str::to_string));
+            let values = metadata.kwargs.get("values").and_then(|v| v.as_array());
+            if let (Some(column), Some(values)) = (column, values) {
+                let values = values
+                    .iter()
+                    .map(|value| match value {
+                        serde_json::Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect();
+                accepted.insert(column, values);
+            }
+        }
+
+        accepted
+    }
+
+    /// Check the manifest for structural problems before running checks
+    ///
+    /// Reports `depends_on` references to nodes that are not in the manifest,
+    /// model names shared across packages, and models without a `database`
+    /// or `schema`. Diagnostics are ordered by node id for stable output.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        let mut nodes: Vec<&ManifestNode> = self.nodes.values().collect();
+        nodes.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+
+        for node in &nodes {
+            for dependency in &node.depends_on.nodes {
+                if Self::is_tracked_node(dependency)
+                    && !self.nodes.contains_key(dependency)
+                    && !self.sources.contains_key(dependency)
+                {
+                    diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticCode::ManifestDanglingDependency,
+                            Severity::Error,
+                            format!(
+                                "'{}' depends on '{}', which is not in the manifest",
+                                node.unique_id, dependency
+                            ),
+                        )
+                        .with_location(Location::new(&node.original_file_path)),
+                    );
+                }
+            }
+        }
+
+        // Versions of one model share its name
+        let mut models_by_name: BTreeMap<(&str, Option<&str>), Vec<&ManifestNode>> = BTreeMap::new();
+        for node in nodes.iter().filter(|n| n.resource_type == "model") {
+            models_by_name.entry((node.name.as_str(), node.version.as_deref())).or_default().push(node);
+        }
+        for ((name, _), models) in &models_by_name {
+            if models.len() > 1 {
+                let ids: Vec<&str> = models.iter().map(|m| m.unique_id.as_str()).collect();
+                let mut diagnostic = Diagnostic::new(
+                    DiagnosticCode::ManifestDuplicateName,
+                    Severity::Warn,
+                    format!("Model name '{}' is defined {} times: {}", name, models.len(), ids.join(", ")),
+                )
+                .with_location(Location::new(&models[0].original_file_path));
+                for model in &models[1..] {
+                    diagnostic = diagnostic.with_related(Location::new(&model.original_file_path));
+                }
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        for node in nodes.iter().filter(|n| n.resource_type == "model") {
+            let missing: Vec<&str> = [("database", &node.database), ("schema", &node.schema)]
+                .into_iter()
+                .filter(|(_, value)| value.as_deref().unwrap_or_default().is_empty())
+                .map(|(field, _)| field)
+                .collect();
+            if !missing.is_empty() {
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticCode::ManifestMissingRelation,
+                        Severity::Warn,
+                        format!("Model '{}' is missing {}", node.unique_id, missing.join(" and ")),
+                    )
+                    .with_location(Location::new(&node.original_file_path)),
+                );
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Whether a `depends_on` id names a resource this manifest subset parses
+    ///
+    /// Metrics, semantic models and similar resources live in manifest
+    /// sections we do not load, so references to them cannot be checked.
+    fn is_tracked_node(unique_id: &str) -> bool {
+        ["model.", "seed.", "snapshot.", "source.", "analysis.", "test."]
+            .iter()
+            .any(|prefix| unique_id.starts_with(prefix))
+    }
+}
+
+/// Cursor over the top-level object of a manifest, one value at a time
+struct SectionReader<'a> {
+    json: &'a str,
+    pos: usize,
+}
+
+impl<'a> SectionReader<'a> {
+    fn new(json: &'a str) -> Self {
+        Self { json, pos: 0 }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.json.as_bytes()[self.pos..];
+        self.pos += rest.iter().take_while(|b| b.is_ascii_whitespace()).count();
+    }
+
+    /// Consume `byte` if it is the next non-whitespace character
+    fn consume(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        if self.json.as_bytes().get(self.pos) == Some(&byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ManifestError> {
+        if self.consume(byte) {
+            Ok(())
+        } else {
+            Err(ManifestError::ParseError(format!(
+                "expected `{}` at byte {}",
+                byte as char, self.pos
+            )))
+        }
+    }
+
+    /// Advance past the end of the enclosing object without parsing it
+    ///
+    /// Only strings and brackets are tracked, so this confirms that the
+    /// object closes, not that its contents are valid JSON.
+    fn skip_rest_of_object(&mut self) -> Result<(), ManifestError> {
+        let bytes = self.json.as_bytes();
+        let mut depth = 1usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        while let Some(&byte) = bytes.get(self.pos) {
+            self.pos += 1;
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Err(ManifestError::ParseError("unexpected end of manifest: top-level object is not closed".to_string()))
+    }
+
+    /// Fail unless only whitespace is left
+    fn finish(&mut self) -> Result<(), ManifestError> {
+        self.skip_whitespace();
+        if self.pos == self.json.len() {
+            Ok(())
+        } else {
+            Err(ManifestError::ParseError(format!("trailing characters at byte {}", self.pos)))
+        }
+    }
+
+    /// Deserialize the next JSON value and advance past it
+    fn value<T: serde::de::DeserializeOwned>(&mut self) -> Result<T, ManifestError> {
+        let mut stream = serde_json::Deserializer::from_str(&self.json[self.pos..]).into_iter::<T>();
+        let value = match stream.next() {
+            Some(Ok(value)) => value,
+            Some(Err(e)) => return Err(ManifestError::ParseError(e.to_string())),
+            None => return Err(ManifestError::ParseError("unexpected end of manifest".to_string())),
+        };
+        self.pos += stream.byte_offset();
+        Ok(value)
+    }
 }
 
 /// Manifest metadata
@@ -94,6 +459,10 @@ pub struct ManifestNode {
     /// Original file path
     pub original_file_path: String,
 
+    /// Properties file that documents this node (e.g., "my_project://models/schema.yml")
+    #[serde(default)]
+    pub patch_path: Option<String>,
+
     /// Database name
     #[serde(default)]
     pub database: Option<String>,
@@ -118,6 +487,10 @@ pub struct ManifestNode {
     #[serde(default)]
     pub columns: HashMap<String, ColumnDefinition>,
 
+    /// Free-form `meta` properties
+    #[serde(default)]
+    pub meta: HashMap<String, serde_json::Value>,
+
     /// Dependencies
     #[serde(default)]
     pub depends_on: DependsOn,
@@ -125,6 +498,84 @@ pub struct ManifestNode {
     /// Fully qualified name
     #[serde(default)]
     pub fqn: Vec<String>,
+
+    /// Compiled SQL (present once dbt has compiled the node)
+    #[serde(default)]
+    pub compiled_code: Option<String>,
+
+    /// Model version (`2` for `users_v2`); dbt writes numbers or strings
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: Option<String>,
+
+    /// Generic test name and arguments (test nodes only)
+    #[serde(default)]
+    pub test_metadata: Option<TestMetadata>,
+
+    /// Column a generic test is defined on (test nodes only)
+    #[serde(default)]
+    pub column_name: Option<String>,
+
+    /// Node a generic test is defined on (test nodes, dbt 1.5+)
+    #[serde(default)]
+    pub attached_node: Option<String>,
+}
+
+/// Generic test of a test node (`accepted_values`, `not_null`, ...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestMetadata {
+    /// Test name (e.g., "accepted_values")
+    pub name: String,
+
+    /// Package defining the test (None for dbt's built-in tests)
+    #[serde(default)]
+    pub namespace: Option<String>,
+
+    /// Test arguments (e.g., `values` and `column_name`)
+    #[serde(default)]
+    pub kwargs: HashMap<String, serde_json::Value>,
+}
+
+/// Read a model version written as a JSON number or string
+fn deserialize_version<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(version)) => Some(version),
+        Some(serde_json::Value::Number(version)) => Some(version.to_string()),
+        _ => None,
+    })
+}
+
+impl ManifestNode {
+    /// Whether this node is version `version` of its model
+    ///
+    /// A leading `v` is ignored on either side, so `v2` matches version `2`.
+    pub fn is_version(&self, version: &str) -> bool {
+        let strip = |v: &str| v.trim_start_matches(['v', 'V']).to_string();
+        self.version.as_deref().is_some_and(|own| strip(own) == strip(version))
+    }
+
+    /// SchemaRefly settings from the model's `meta` and `config.meta`
+    ///
+    /// Settings in `config.meta` win over the same settings in `meta`, as in
+    /// dbt, which merges the two.
+    pub fn schemarefly_meta(&self) -> SchemaReflyMeta {
+        SchemaReflyMeta::from_metas([&self.meta, &self.config.meta])
+    }
+
+    /// Whether the model is materialized as `ephemeral`
+    ///
+    /// Ephemeral models are inlined into downstream models as CTEs and have
+    /// no warehouse relation of their own.
+    pub fn is_ephemeral(&self) -> bool {
+        self.config.materialized.as_deref() == Some("ephemeral")
+    }
+
+    /// Project-relative path of the properties (YAML) file, without the
+    /// `package://` prefix dbt adds to `patch_path`
+    pub fn patch_file(&self) -> Option<&str> {
+        self.patch_path
+            .as_deref()
+            .map(|p| p.split_once("://").map_or(p, |(_, rest)| rest))
+    }
 }
 
 /// Node configuration (from dbt_project.yml or model config)
@@ -141,6 +592,10 @@ pub struct NodeConfig {
     /// Contract configuration
     #[serde(default)]
     pub contract: Option<ContractConfig>,
+
+    /// `meta` set through `config` (`{{ config(meta={...}) }}` or `+meta`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub meta: HashMap<String, serde_json::Value>,
 }
 
 fn default_true() -> bool {
@@ -167,6 +622,124 @@ pub struct ColumnDefinition {
     /// Data type (if specified in contract)
     #[serde(default)]
     pub data_type: Option<String>,
+
+    /// Column-level configuration
+    #[serde(default)]
+    pub config: ColumnConfig,
+
+    /// Free-form `meta` properties
+    #[serde(default)]
+    pub meta: HashMap<String, serde_json::Value>,
+
+    /// Column constraints (`not_null`, `check`, ...) declared in the contract
+    #[serde(default)]
+    pub constraints: Vec<ColumnConstraint>,
+}
+
+/// Column constraint (`columns[].constraints`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnConstraint {
+    /// Constraint type (e.g., "check", "not_null", "unique")
+    #[serde(rename = "type")]
+    pub constraint_type: String,
+
+    /// SQL expression of a `check` constraint
+    #[serde(default)]
+    pub expression: Option<String>,
+}
+
+impl ColumnConstraint {
+    /// Values allowed by a check of the form `column IN ('a', 'b')`
+    ///
+    /// Other expressions (ranges, `NOT IN`, function calls) yield `None`.
+    /// This is a plain text match, so values containing commas or
+    /// parentheses are not supported.
+    pub fn in_list_values(&self, column: &str) -> Option<Vec<String>> {
+        if self.constraint_type != "check" {
+            return None;
+        }
+        let expression = self.expression.as_deref()?.trim();
+
+        let at = expression.to_ascii_lowercase().find(" in ")?;
+        let subject = expression[..at].trim().trim_matches(|c| c == '"' || c == '`');
+        if !subject.eq_ignore_ascii_case(column) {
+            return None;
+        }
+
+        let list = expression[at + 4..].trim().strip_prefix('(')?.strip_suffix(')')?;
+        let values = list
+            .split(',')
+            .map(|value| value.trim().trim_matches('\'').to_string())
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<_>>();
+        (!values.is_empty()).then_some(values)
+    }
+
+    /// Default expression of a `custom` constraint of the form `default <expr>`
+    ///
+    /// dbt appends custom constraint expressions to the column DDL, so this
+    /// is how a contract declares a column default.
+    pub fn default_expression(&self) -> Option<&str> {
+        if self.constraint_type != "custom" {
+            return None;
+        }
+        let expression = self.expression.as_deref()?.trim();
+        let (keyword, rest) = expression.split_once(char::is_whitespace)?;
+        if !keyword.eq_ignore_ascii_case("default") {
+            return None;
+        }
+        let rest = rest.trim();
+        (!rest.is_empty()).then_some(rest)
+    }
+}
+
+impl ColumnDefinition {
+    /// SchemaRefly settings from the column's `meta`
+    pub fn schemarefly_meta(&self) -> SchemaReflyMeta {
+        SchemaReflyMeta::from_meta(&self.meta)
+    }
+}
+
+/// SchemaRefly settings kept in dbt `meta` (`meta: {schemarefly: {ignore_drift: true}}`)
+///
+/// Set on a model they apply to all its columns; set on a column, to that
+/// column only.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaReflyMeta {
+    /// Leave out of drift detection
+    #[serde(default)]
+    pub ignore_drift: bool,
+
+    /// Leave out of contract checks
+    #[serde(default)]
+    pub ignore_contract: bool,
+}
+
+impl SchemaReflyMeta {
+    /// Read the `schemarefly` entry of a `meta` map
+    ///
+    /// A missing or malformed entry yields the defaults (nothing ignored).
+    pub fn from_meta(meta: &HashMap<String, serde_json::Value>) -> Self {
+        Self::from_metas([meta])
+    }
+
+    /// Read the `schemarefly` entries of several `meta` maps, later maps winning per setting
+    pub fn from_metas<'a>(metas: impl IntoIterator<Item = &'a HashMap<String, serde_json::Value>>) -> Self {
+        let mut merged = serde_json::Map::new();
+        for entry in metas.into_iter().filter_map(|meta| meta.get("schemarefly")?.as_object()) {
+            merged.extend(entry.clone());
+        }
+
+        serde_json::from_value(serde_json::Value::Object(merged)).unwrap_or_default()
+    }
+}
+
+/// Column-level configuration (`columns[].config`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColumnConfig {
+    /// Per-column contract enforcement, overriding the model's contract
+    #[serde(default)]
+    pub contract: Option<ContractConfig>,
 }
 
 /// Dependencies structure
@@ -205,6 +778,85 @@ pub struct ManifestSource {
     pub columns: HashMap<String, ColumnDefinition>,
 }
 
+/// A dbt unit test (`unit_tests` section, dbt 1.8+)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestUnitTest {
+    /// Unique identifier (e.g., "unit_test.my_project.orders.test_totals")
+    pub unique_id: String,
+
+    /// Test name
+    pub name: String,
+
+    /// Name of the model under test
+    pub model: String,
+
+    /// Version of the model under test, for versioned models
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: Option<String>,
+
+    /// Path to the YAML file defining the test
+    #[serde(default)]
+    pub original_file_path: String,
+
+    /// Input fixtures
+    #[serde(default)]
+    pub given: Vec<UnitTestFixture>,
+
+    /// Expected output fixture
+    #[serde(default)]
+    pub expect: UnitTestFixture,
+
+    /// Dependencies (the model under test)
+    #[serde(default)]
+    pub depends_on: DependsOn,
+}
+
+/// Rows of a unit test input (`given`) or output (`expect`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnitTestFixture {
+    /// Input relation, e.g. `ref('stg_orders')` (`given` only)
+    #[serde(default)]
+    pub input: Option<String>,
+
+    /// Row data: a list of objects for `dict`, text for `csv` and `sql`
+    #[serde(default)]
+    pub rows: Option<serde_json::Value>,
+
+    /// Fixture format (`dict`, `csv` or `sql`; dbt defaults to `dict`)
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+impl UnitTestFixture {
+    /// Column names used by the fixture rows
+    ///
+    /// Reads the keys of `dict` rows and the header of `csv` rows. Returns
+    /// `None` for `sql` fixtures, external fixture files and fixtures without
+    /// rows, whose columns cannot be known from the manifest.
+    pub fn column_names(&self) -> Option<Vec<String>> {
+        let mut names = Vec::new();
+
+        match (self.format.as_deref().unwrap_or("dict"), self.rows.as_ref()?) {
+            ("dict", serde_json::Value::Array(rows)) => {
+                for row in rows.iter().filter_map(serde_json::Value::as_object) {
+                    for name in row.keys() {
+                        if !names.contains(name) {
+                            names.push(name.clone());
+                        }
+                    }
+                }
+            }
+            ("csv", serde_json::Value::String(text)) => {
+                let header = text.lines().find(|line| !line.trim().is_empty())?;
+                names.extend(header.split(',').map(|name| name.trim().to_string()));
+            }
+            _ => return None,
+        }
+
+        (!names.is_empty()).then_some(names)
+    }
+}
+
 /// Manifest parsing errors
 #[derive(Debug, thiserror::Error)]
 pub enum ManifestError {
@@ -213,6 +865,9 @@ pub enum ManifestError {
 
     #[error("Failed to parse manifest JSON: {0}")]
     ParseError(String),
+
+    #[error("Failed to download manifest from {0}: {1}")]
+    DownloadError(String, String),
 }
 
 #[cfg(test)]
@@ -251,4 +906,250 @@ mod tests {
             }
         }
     }
+
+    fn model(id: &str, name: &str, schema: Option<&str>, depends_on: &[&str]) -> String {
+        format!(
+            r#""{id}": {{
+                "unique_id": "{id}",
+                "name": "{name}",
+                "resource_type": "model",
+                "package_name": "{package}",
+                "path": "{name}.sql",
+                "original_file_path": "models/{name}.sql",
+                "database": "analytics",
+                "schema": {schema},
+                "depends_on": {{"nodes": {depends_on:?}}}
+            }}"#,
+            package = id.split('.').nth(1).unwrap(),
+            schema = schema.map(|s| format!("\"{}\"", s)).unwrap_or_else(|| "null".to_string()),
+        )
+    }
+
+    fn manifest_with(nodes: &[String]) -> Manifest {
+        Manifest::from_str(&format!(
+            r#"{{
+                "metadata": {{"dbt_schema_version": "v10", "dbt_version": "1.7.0", "generated_at": "2024-01-01T00:00:00Z"}},
+                "nodes": {{{}}},
+                "sources": {{}}
+            }}"#,
+            nodes.join(",")
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn validate_reports_dangling_dependency_and_duplicate_name() {
+        let manifest = manifest_with(&[
+            model("model.shop.orders", "orders", Some("core"), &["model.shop.customers", "metric.shop.revenue"]),
+            model("model.shop.users", "users", Some("core"), &[]),
+            model("model.crm.users", "users", Some("crm"), &["model.shop.orders"]),
+        ]);
+
+        let diagnostics = manifest.validate();
+        let codes: Vec<DiagnosticCode> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![DiagnosticCode::ManifestDanglingDependency, DiagnosticCode::ManifestDuplicateName]
+        );
+
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(diagnostics[0].message.contains("model.shop.customers"));
+        assert_eq!(diagnostics[0].location.as_ref().unwrap().file, "models/orders.sql");
+
+        assert!(diagnostics[1].message.contains("model.crm.users, model.shop.users"));
+        assert_eq!(diagnostics[1].related.len(), 1);
+    }
+
+    #[test]
+    fn models_iter_yields_the_same_nodes_as_models() {
+        let manifest = manifest_with(&[
+            model("model.shop.orders", "orders", Some("core"), &[]),
+            model("model.shop.users", "users", Some("core"), &[]),
+            model("seed.shop.countries", "countries", Some("core"), &[]).replace(r#""model""#, r#""seed""#),
+        ]);
+
+        let mut iterated: Vec<(&String, &ManifestNode)> = manifest.models_iter().collect();
+        iterated.sort_by_key(|(id, _)| *id);
+        let models = manifest.models();
+        let mut collected: Vec<(&String, &ManifestNode)> = models.iter().map(|(id, node)| (id, *node)).collect();
+        collected.sort_by_key(|(id, _)| *id);
+
+        assert_eq!(iterated, collected);
+        assert_eq!(iterated.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>(), vec!["model.shop.orders", "model.shop.users"]);
+        // Nodes are borrowed from the manifest, not copied
+        assert!(std::ptr::eq(iterated[0].1, manifest.get_node("model.shop.orders").unwrap()));
+    }
+
+    #[test]
+    fn accepted_values_from_tests_and_check_constraints() {
+        let manifest = Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.shop.orders": {
+                    "unique_id": "model.shop.orders", "name": "orders", "resource_type": "model",
+                    "package_name": "shop", "path": "orders.sql", "original_file_path": "models/orders.sql",
+                    "columns": {
+                        "status": {"name": "status", "data_type": "text"},
+                        "channel": {"name": "channel", "data_type": "text", "constraints": [
+                            {"type": "not_null"},
+                            {"type": "check", "expression": "channel IN ('web', 'store')"}
+                        ]},
+                        "amount": {"name": "amount", "data_type": "int", "constraints": [
+                            {"type": "check", "expression": "amount > 0"}
+                        ]}
+                    }
+                },
+                "test.shop.accepted_values_orders_status.1a2b": {
+                    "unique_id": "test.shop.accepted_values_orders_status.1a2b", "name": "accepted_values_orders_status",
+                    "resource_type": "test", "package_name": "shop", "path": "status.sql", "original_file_path": "models/schema.yml",
+                    "test_metadata": {"name": "accepted_values", "kwargs": {"column_name": "status", "values": ["placed", "shipped"]}},
+                    "column_name": "status", "attached_node": "model.shop.orders",
+                    "depends_on": {"nodes": ["model.shop.orders"]}
+                },
+                "test.shop.not_null_orders_status.3c4d": {
+                    "unique_id": "test.shop.not_null_orders_status.3c4d", "name": "not_null_orders_status",
+                    "resource_type": "test", "package_name": "shop", "path": "nn.sql", "original_file_path": "models/schema.yml",
+                    "test_metadata": {"name": "not_null", "kwargs": {"column_name": "status"}},
+                    "column_name": "status", "attached_node": "model.shop.orders"
+                }
+            },
+            "sources": {}
+        }"#).unwrap();
+
+        let orders = manifest.get_node("model.shop.orders").unwrap();
+        let accepted = manifest.accepted_values(orders);
+        assert_eq!(accepted.len(), 2);
+        assert_eq!(accepted["status"], vec!["placed", "shipped"]);
+        assert_eq!(accepted["channel"], vec!["web", "store"]);
+
+        let check = |expression: &str| ColumnConstraint {
+            constraint_type: "check".to_string(),
+            expression: Some(expression.to_string()),
+        };
+        assert_eq!(check(r#""status" in ('a','b')"#).in_list_values("status"), Some(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(check("status NOT IN ('a')").in_list_values("status"), None);
+        assert_eq!(check("other IN ('a')").in_list_values("status"), None);
+
+        let custom = |expression: &str| ColumnConstraint {
+            constraint_type: "custom".to_string(),
+            expression: Some(expression.to_string()),
+        };
+        assert_eq!(custom("DEFAULT 'placed'").default_expression(), Some("'placed'"));
+        assert_eq!(custom("default now()").default_expression(), Some("now()"));
+        assert_eq!(custom("defaults").default_expression(), None);
+        assert_eq!(check("default 1").default_expression(), None);
+    }
+
+    #[test]
+    fn validate_reports_missing_schema() {
+        let manifest = manifest_with(&[model("model.shop.orders", "orders", None, &[])]);
+
+        let diagnostics = manifest.validate();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::ManifestMissingRelation);
+        assert!(diagnostics[0].message.ends_with("is missing schema"));
+    }
+
+    #[test]
+    fn validate_accepts_healthy_manifest() {
+        let manifest = manifest_with(&[
+            model("model.shop.customers", "customers", Some("core"), &[]),
+            model("model.shop.orders", "orders", Some("core"), &["model.shop.customers"]),
+        ]);
+
+        assert!(manifest.validate().is_empty());
+    }
+
+    #[test]
+    fn loads_plain_and_gzipped_manifests_through_same_entry_point() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let json = format!(
+            r#"{{
+                "metadata": {{"dbt_schema_version": "v10", "dbt_version": "1.7.0", "generated_at": "2024-01-01T00:00:00Z"}},
+                "nodes": {{{}}},
+                "sources": {{}}
+            }}"#,
+            model("model.shop.orders", "orders", Some("core"), &[])
+        );
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(json.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let plain_path = dir.path().join("manifest.json");
+        let gz_path = dir.path().join("manifest.json.gz");
+        std::fs::write(&plain_path, &json).unwrap();
+        std::fs::write(&gz_path, &gzipped).unwrap();
+
+        let plain = Manifest::from_file(&plain_path).unwrap();
+        let compressed = Manifest::from_file(&gz_path).unwrap();
+        assert_eq!(plain, compressed);
+        assert!(!plain.models().is_empty());
+
+        assert_eq!(Manifest::from_reader(gzipped.as_slice()).unwrap(), plain);
+        assert_eq!(Manifest::from_reader(json.as_bytes()).unwrap(), plain);
+    }
+
+    #[test]
+    fn corrupt_gzip_is_an_io_error() {
+        let result = Manifest::from_reader(&[0x1f, 0x8b, 0x00, 0x01][..]);
+        assert!(matches!(result, Err(ManifestError::IoError(label, _)) if label == "<reader>"));
+    }
+
+    #[test]
+    fn partial_parse_matches_full_parse() {
+        let json = format!(
+            r#"{{
+                "metadata": {{"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"}},
+                "nodes": {{{}, {}}},
+                "sources": {{}},
+                "macros": {{"macro.shop.cents": {{"macro_sql": "{{% macro cents() %}}{{% endmacro %}}", "arguments": [1.5e3, null, true]}}}},
+                "docs": {{"doc.shop.overview": {{"block_contents": "Escaped \" quote and \u00e9"}}}},
+                "disabled": {{}},
+                "parent_map": {{"model.shop.orders": ["model.shop.users"]}},
+                "child_map": {{"model.shop.users": ["model.shop.orders"]}},
+                "unit_tests": {{}},
+                "semantic_models": {{"semantic_model.shop.orders": {{"measures": [1,,]}}}}
+            }}"#,
+            model("model.shop.orders", "orders", Some("core"), &["model.shop.users"]),
+            model("model.shop.users", "users", Some("core"), &[]),
+        );
+
+        let partial = Manifest::from_str_partial(&json).unwrap();
+        assert_eq!(partial.nodes.len(), 2);
+        assert_eq!(partial.parent_map["model.shop.orders"], vec!["model.shop.users"]);
+
+        // The full parse also validates the (malformed) trailing section
+        assert!(Manifest::from_str(&json).is_err());
+        let well_formed = json.replace(r#""measures": [1,,]"#, r#""measures": [1]"#);
+        assert_eq!(Manifest::from_str(&well_formed).unwrap(), partial);
+
+        // A manifest cut off in a skipped section is still rejected
+        let cut = well_formed.find("semantic_models").unwrap();
+        for truncated in [&well_formed[..cut], &well_formed[..well_formed.len() - 20]] {
+            let result = Manifest::from_str_partial(truncated);
+            assert!(matches!(result, Err(ManifestError::ParseError(ref msg)) if msg.contains("not closed")), "{result:?}");
+        }
+        assert!(Manifest::from_str_partial(&format!("{} {{}}", well_formed)).is_err());
+    }
+
+    #[test]
+    fn partial_parse_defaults_maps_and_requires_nodes() {
+        let json = format!(
+            r#"{{
+                "metadata": {{"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"}},
+                "nodes": {{{}}},
+                "sources": {{}}
+            }}"#,
+            model("model.shop.orders", "orders", Some("core"), &[])
+        );
+        assert_eq!(Manifest::from_str_partial(&json).unwrap(), Manifest::from_str(&json).unwrap());
+
+        let result = Manifest::from_str_partial(r#"{"metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "x"}, "sources": {}}"#);
+        assert!(matches!(result, Err(ManifestError::ParseError(msg)) if msg.contains("nodes")));
+
+        assert!(Manifest::from_str_partial(r#"{"nodes": {} "sources": {}}"#).is_err());
+    }
 }
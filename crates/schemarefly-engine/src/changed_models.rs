@@ -0,0 +1,221 @@
+//! Git-based selection of changed models
+//!
+//! Maps files changed since a git ref to manifest models so `check --since`
+//! can restrict checking to the models a branch touched plus everything
+//! downstream of them.
+
+use schemarefly_dbt::{DependencyGraph, Manifest};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Models selected from a set of changed files
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangedModels {
+    /// Models whose SQL or properties file changed directly
+    pub changed: BTreeSet<String>,
+
+    /// Changed models plus all of their downstream dependents
+    pub all_affected: HashSet<String>,
+
+    /// Changed files that did not map to any model
+    pub unmatched_files: Vec<PathBuf>,
+}
+
+impl ChangedModels {
+    /// Map changed file paths (relative to the dbt project root) to models
+    ///
+    /// `.sql` files match a model's `original_file_path`; `.yml`/`.yaml` files
+    /// match models whose `patch_path` points at them. Other files are ignored.
+    pub fn from_paths(manifest: &Manifest, dag: &DependencyGraph, paths: &[PathBuf]) -> Self {
+        let models = manifest.models();
+        let mut result = Self::default();
+
+        for path in paths {
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+            if !matches!(ext, "sql" | "yml" | "yaml") {
+                continue;
+            }
+
+            let path_str = normalize(path);
+            let mut matched = false;
+
+            for (node_id, node) in &models {
+                let hit = match ext {
+                    "sql" => normalize(Path::new(&node.original_file_path)) == path_str,
+                    _ => node
+                        .patch_file()
+                        .is_some_and(|p| normalize(Path::new(p)) == path_str),
+                };
+
+                if hit {
+                    result.changed.insert(node_id.clone());
+                    matched = true;
+                }
+            }
+
+            if !matched {
+                result.unmatched_files.push(path.clone());
+            }
+        }
+
+        for node_id in &result.changed {
+            result.all_affected.insert(node_id.clone());
+            result.all_affected.extend(dag.downstream(node_id));
+        }
+
+        result
+    }
+
+    /// Collect files changed since `since` and map them to models
+    pub fn from_git(
+        manifest: &Manifest,
+        dag: &DependencyGraph,
+        project_dir: &Path,
+        since: &str,
+    ) -> std::io::Result<Self> {
+        let paths = git_changed_files(project_dir, since)?;
+        Ok(Self::from_paths(manifest, dag, &paths))
+    }
+}
+
+/// List files changed since a git ref, relative to `project_dir`
+///
+/// Includes committed and uncommitted changes plus untracked files. Files
+/// outside `project_dir` are excluded by git's `--relative` handling.
+pub fn git_changed_files(project_dir: &Path, since: &str) -> std::io::Result<Vec<PathBuf>> {
+    let diff = run_git(project_dir, &["diff", "--name-only", "--relative", since, "--"])?;
+    let untracked = run_git(project_dir, &["ls-files", "--others", "--exclude-standard"])?;
+
+    let files: BTreeSet<PathBuf> = diff
+        .lines()
+        .chain(untracked.lines())
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(PathBuf::from)
+        .collect();
+
+    Ok(files.into_iter().collect())
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> std::io::Result<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+
+    if !output.status.success() {
+        return Err(std::io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Normalize a path for comparison (forward slashes, no leading `./`)
+fn normalize(path: &Path) -> String {
+    let s = path.to_string_lossy().replace('\\', "/");
+    s.trim_start_matches("./").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> Manifest {
+        Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.p.stg_users": {
+                    "unique_id": "model.p.stg_users", "name": "stg_users", "resource_type": "model",
+                    "package_name": "p", "path": "stg_users.sql",
+                    "original_file_path": "models/stg_users.sql",
+                    "patch_path": "p://models/schema.yml"
+                },
+                "model.p.users": {
+                    "unique_id": "model.p.users", "name": "users", "resource_type": "model",
+                    "package_name": "p", "path": "users.sql",
+                    "original_file_path": "models/users.sql",
+                    "depends_on": {"nodes": ["model.p.stg_users"]}
+                },
+                "model.p.orders": {
+                    "unique_id": "model.p.orders", "name": "orders", "resource_type": "model",
+                    "package_name": "p", "path": "orders.sql",
+                    "original_file_path": "models/orders.sql"
+                }
+            },
+            "sources": {}
+        }"#).unwrap()
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn maps_sql_and_yml_paths() {
+        let manifest = manifest();
+        let dag = DependencyGraph::from_manifest(&manifest);
+
+        let selected = ChangedModels::from_paths(
+            &manifest,
+            &dag,
+            &[PathBuf::from("models/schema.yml"), PathBuf::from("README.md")],
+        );
+
+        assert_eq!(selected.changed.len(), 1);
+        assert!(selected.changed.contains("model.p.stg_users"));
+        assert!(selected.all_affected.contains("model.p.users"));
+        assert!(!selected.all_affected.contains("model.p.orders"));
+        // Non-model files are skipped without being reported
+        assert!(selected.unmatched_files.is_empty());
+    }
+
+    #[test]
+    fn selects_changed_model_and_downstream_from_git() {
+        let repo = tempfile::tempdir().unwrap();
+        let root = repo.path();
+        let project = root.join("analytics");
+        std::fs::create_dir_all(project.join("models")).unwrap();
+
+        for name in ["stg_users", "users", "orders"] {
+            std::fs::write(project.join(format!("models/{}.sql", name)), "select 1").unwrap();
+        }
+        std::fs::write(root.join("outside.sql"), "select 1").unwrap();
+
+        git(root, &["init", "-q"]);
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "init"]);
+
+        // Change one model, plus a file outside the dbt project
+        std::fs::write(project.join("models/stg_users.sql"), "select 2").unwrap();
+        std::fs::write(root.join("outside.sql"), "select 2").unwrap();
+
+        let manifest = manifest();
+        let dag = DependencyGraph::from_manifest(&manifest);
+        let selected = ChangedModels::from_git(&manifest, &dag, &project, "HEAD").unwrap();
+
+        let mut affected: Vec<_> = selected.all_affected.iter().cloned().collect();
+        affected.sort();
+        assert_eq!(affected, vec!["model.p.stg_users", "model.p.users"]);
+        assert!(selected.unmatched_files.is_empty());
+    }
+
+    #[test]
+    fn unknown_ref_is_an_error() {
+        let repo = tempfile::tempdir().unwrap();
+        git(repo.path(), &["init", "-q"]);
+
+        assert!(git_changed_files(repo.path(), "does-not-exist").is_err());
+    }
+}
@@ -3,8 +3,11 @@
 //! This module implements the core contract validation logic that compares
 //! inferred SQL output schemas against declared dbt contracts.
 
-use schemarefly_core::{Schema, LogicalType, Diagnostic, DiagnosticCode, Severity, Location, Contract};
-use std::collections::HashSet;
+use crate::options::CompareOptions;
+use schemarefly_core::{
+    Schema, LogicalType, Diagnostic, DiagnosticCode, Severity, Location, Contract,
+    ModelContractDiff, DiffColumn, ColumnTypeChange, ExtraColumnPolicy,
+};
 
 /// Result of comparing an inferred schema against a contract
 #[derive(Debug, Clone)]
@@ -20,99 +23,172 @@ pub struct ContractDiff {
 
     /// Diagnostics produced by the comparison
     pub diagnostics: Vec<Diagnostic>,
+
+    /// Structured added/removed/type-changed columns, for report consumers
+    pub model_diff: ModelContractDiff,
+
+    /// Fraction of contract columns inferred to a concrete (non-unknown) type
+    pub inference_coverage: f64,
 }
 
 impl ContractDiff {
     /// Create a new contract diff by comparing inferred schema to contract
+    ///
+    /// Extra columns are reported at a severity determined by the contract's
+    /// `EnforcementPolicy` (strict → error, additive → info, default → warning).
+    /// Missing and mistyped columns are errors unless the contract column
+    /// sets `enforced = false`, which reports them as info. Under a strict
+    /// policy, missing or extra columns also get one info-level arity summary.
     pub fn compare(
         model_id: impl Into<String>,
         contract: &Contract,
         inferred: &Schema,
         file_path: Option<String>,
+    ) -> Self {
+        Self::compare_with_options(model_id, contract, inferred, file_path, &CompareOptions::default())
+    }
+
+    /// Compare inferred schema to contract using explicit comparison options
+    pub fn compare_with_options(
+        model_id: impl Into<String>,
+        contract: &Contract,
+        inferred: &Schema,
+        file_path: Option<String>,
+        options: &CompareOptions,
     ) -> Self {
         let model_id = model_id.into();
         let mut diagnostics = Vec::new();
-
-        // Track which columns we've seen from the contract
-        let mut seen_contract_cols = HashSet::new();
-
-        // Check each column in the contract
-        for expected_col in &contract.schema.columns {
-            seen_contract_cols.insert(&expected_col.name);
-
-            match inferred.find_column(&expected_col.name) {
-                Some(actual_col) => {
-                    // Column exists - check type match
-                    if !types_compatible(&expected_col.logical_type, &actual_col.logical_type) {
-                        let message = format!(
-                            "Column '{}' type mismatch: expected {}, got {}",
-                            expected_col.name,
-                            expected_col.logical_type,
-                            actual_col.logical_type
-                        );
-
-                        let mut diag = Diagnostic::new(
-                            DiagnosticCode::ContractTypeMismatch,
-                            Severity::Error,
-                            message,
-                        );
-
-                        if let Some(ref path) = file_path {
-                            diag = diag.with_location(Location::new(path.clone()));
-                        }
-
-                        diagnostics.push(diag);
-                    }
-                }
-                None => {
-                    // Column missing from inferred schema
-                    let message = format!(
-                        "Column '{}' required by contract but missing from inferred schema",
-                        expected_col.name
-                    );
-
-                    let mut diag = Diagnostic::new(
-                        DiagnosticCode::ContractMissingColumn,
-                        Severity::Error,
-                        message,
-                    );
-
-                    if let Some(ref path) = file_path {
-                        diag = diag.with_location(Location::new(path.clone()));
-                    }
-
-                    diagnostics.push(diag);
-                }
-            }
+        let mut model_diff = ModelContractDiff::new(model_id.clone());
+        let case = options.identifier_case;
+
+        let diff = contract.schema.diff_with(inferred, case, types_compatible);
+
+        // Columns whose inferred type is incompatible with the contract
+        for change in &diff.type_changed {
+            let (expected_col, actual_col) = (&change.before, &change.after);
+
+            model_diff.type_changed.push(ColumnTypeChange {
+                name: expected_col.name.clone(),
+                from: expected_col.logical_type.to_string(),
+                to: actual_col.logical_type.to_string(),
+            });
+
+            let message = format!(
+                "Column '{}' type mismatch: expected {}, got {}",
+                expected_col.name,
+                expected_col.logical_type,
+                actual_col.logical_type
+            );
+
+            diagnostics.push(column_diagnostic(
+                DiagnosticCode::ContractTypeMismatch,
+                contract.policy.column_violation_severity(expected_col),
+                message,
+                &expected_col.name,
+                contract,
+                file_path.as_deref(),
+                options,
+            ));
         }
 
-        // Check for extra columns in inferred schema
-        for actual_col in &inferred.columns {
-            if !seen_contract_cols.contains(&actual_col.name) {
+        // Compatible decimals whose inferred precision couldn't be checked
+        for (expected_col, actual_col) in contract.schema.matched_columns(inferred, case) {
+            if decimal_precision_unknown(&expected_col.logical_type, &actual_col.logical_type) {
                 let message = format!(
-                    "Column '{}' present in inferred schema but not declared in contract",
-                    actual_col.name
+                    "Column '{}' inferred decimal precision unknown: expected {}, got {}",
+                    expected_col.name,
+                    expected_col.logical_type,
+                    actual_col.logical_type
                 );
 
-                let mut diag = Diagnostic::new(
-                    DiagnosticCode::ContractExtraColumn,
-                    Severity::Warn,
+                diagnostics.push(column_diagnostic(
+                    DiagnosticCode::ContractTypeMismatch,
+                    Severity::Info,
                     message,
-                );
+                    &expected_col.name,
+                    contract,
+                    file_path.as_deref(),
+                    options,
+                ));
+            }
+        }
+
+        // Columns required by the contract but missing from the inferred schema
+        for expected_col in &diff.removed {
+            model_diff.removed.push(DiffColumn {
+                name: expected_col.name.clone(),
+                data_type: expected_col.logical_type.to_string(),
+            });
+
+            let message = format!(
+                "Column '{}' required by contract but missing from inferred schema",
+                expected_col.name
+            );
+
+            diagnostics.push(column_diagnostic(
+                DiagnosticCode::ContractMissingColumn,
+                contract.policy.column_violation_severity(expected_col),
+                message,
+                &expected_col.name,
+                contract,
+                file_path.as_deref(),
+                options,
+            ));
+        }
+
+        // Check for extra columns in inferred schema
+        let extra_severity = contract.policy.extra_column_severity();
+        for actual_col in &diff.added {
+            model_diff.added.push(DiffColumn {
+                name: actual_col.name.clone(),
+                data_type: actual_col.logical_type.to_string(),
+            });
+
+            let message = format!(
+                "Column '{}' present in inferred schema but not declared in contract",
+                actual_col.name
+            );
+
+            let mut diag = Diagnostic::new(
+                DiagnosticCode::ContractExtraColumn,
+                extra_severity,
+                message,
+            );
+
+            if let Some(ref path) = file_path {
+                diag = diag.with_location(Location::new(path.clone()));
+            }
+
+            diagnostics.push(diag);
+        }
 
-                if let Some(ref path) = file_path {
-                    diag = diag.with_location(Location::new(path.clone()));
-                }
+        if contract.policy.extra_column_policy() == ExtraColumnPolicy::Strict
+            && !(diff.removed.is_empty() && diff.added.is_empty())
+        {
+            diagnostics.push(arity_mismatch(contract, inferred, file_path.as_deref()));
+        }
 
+        if options.enforce_column_order {
+            if let Some(diag) = column_order_mismatch(contract, inferred, file_path.as_deref(), options) {
                 diagnostics.push(diag);
             }
         }
 
+        let coverage = inference_coverage(contract, inferred, options);
+        if let Some(min) = options.min_inference_coverage {
+            if coverage < min {
+                let severity = options.low_coverage_severity.unwrap_or(Severity::Warn);
+                diagnostics.push(low_coverage(coverage, min, severity, file_path.as_deref()));
+            }
+        }
+
         Self {
             model_id,
             expected: contract.schema.clone(),
             actual: inferred.clone(),
             diagnostics,
+            model_diff,
+            inference_coverage: coverage,
         }
     }
 
@@ -137,6 +213,136 @@ impl ContractDiff {
     }
 }
 
+/// Diagnostic about a contract column, located in the SQL and linked to the contract YAML
+fn column_diagnostic(
+    code: DiagnosticCode,
+    severity: Severity,
+    message: String,
+    column: &str,
+    contract: &Contract,
+    file_path: Option<&str>,
+    options: &CompareOptions,
+) -> Diagnostic {
+    let mut diag = Diagnostic::new(code, severity, message);
+
+    if let Some(path) = file_path {
+        diag = diag.with_location(Location::new(path.to_string()));
+    }
+
+    if let Some(yaml) = contract.column_location(column, options.identifier_case) {
+        diag = diag.with_related(yaml.clone());
+    }
+
+    diag
+}
+
+/// Summary of a strict contract's column count against the inferred one
+///
+/// Counts can match while names differ (one missing, one extra), so the
+/// summary is emitted whenever the column sets differ.
+fn arity_mismatch(contract: &Contract, inferred: &Schema, file_path: Option<&str>) -> Diagnostic {
+    let (expected, actual) = (contract.schema.columns.len(), inferred.columns.len());
+    let message = format!("contract: {} columns, inferred: {}", expected, actual);
+
+    let mut diag = Diagnostic::new(DiagnosticCode::ContractArityMismatch, Severity::Info, message)
+        .with_comparison(format!("{} columns", expected), format!("{} columns", actual));
+
+    if let Some(path) = file_path {
+        diag = diag.with_location(Location::new(path.to_string()));
+    }
+
+    diag
+}
+
+/// Compare the order of columns shared by the contract and the inferred schema
+///
+/// Missing and extra columns are reported separately, so only the relative
+/// order of columns present in both is checked. Reports the first position
+/// (1-based, within the shared columns) where the orders diverge.
+fn column_order_mismatch(
+    contract: &Contract,
+    inferred: &Schema,
+    file_path: Option<&str>,
+    options: &CompareOptions,
+) -> Option<Diagnostic> {
+    let case = options.identifier_case;
+
+    let expected: Vec<&str> = contract.schema.columns.iter()
+        .filter(|c| inferred.find_column_with(&c.name, case).is_some())
+        .map(|c| c.name.as_str())
+        .collect();
+    let actual: Vec<&str> = inferred.columns.iter()
+        .filter(|c| contract.schema.find_column_with(&c.name, case).is_some())
+        .map(|c| c.name.as_str())
+        .collect();
+
+    let (position, (expected_name, actual_name)) = expected.iter()
+        .zip(actual.iter())
+        .enumerate()
+        .find(|(_, (e, a))| !case.matches(e, a))?;
+
+    let message = format!(
+        "Column order differs from contract at position {}: expected '{}', got '{}'",
+        position + 1,
+        expected_name,
+        actual_name
+    );
+
+    let mut diag = Diagnostic::new(
+        DiagnosticCode::ContractColumnOrderMismatch,
+        Severity::Warn,
+        message,
+    );
+
+    if let Some(path) = file_path {
+        diag = diag.with_location(Location::new(path.to_string()));
+    }
+
+    if let Some(yaml) = contract.column_location(expected_name, case) {
+        diag = diag.with_related(yaml.clone());
+    }
+
+    Some(diag)
+}
+
+/// Fraction of contract columns whose inferred type is known
+///
+/// Contract columns missing from the inferred schema count as unknown. A
+/// contract without columns is fully covered.
+fn inference_coverage(contract: &Contract, inferred: &Schema, options: &CompareOptions) -> f64 {
+    let total = contract.schema.columns.len();
+    if total == 0 {
+        return 1.0;
+    }
+
+    let covered = contract.schema.columns.iter()
+        .filter(|c| {
+            inferred
+                .find_column_with(&c.name, options.identifier_case)
+                .is_some_and(|actual| actual.logical_type != LogicalType::Unknown)
+        })
+        .count();
+
+    covered as f64 / total as f64
+}
+
+/// Diagnostic for a model whose inferred coverage is below `min_inference_coverage`
+fn low_coverage(coverage: f64, min: f64, severity: Severity, file_path: Option<&str>) -> Diagnostic {
+    let message = format!(
+        "Only {:.0}% of contract columns have a concrete inferred type (minimum {:.0}%)",
+        coverage * 100.0,
+        min * 100.0
+    );
+
+    let mut diag = Diagnostic::new(DiagnosticCode::SqlInferenceLowCoverage, severity, message);
+
+    if let Some(path) = file_path {
+        diag = diag.with_location(Location::new(path.to_string()));
+    }
+
+    diag
+}
+
 /// Check if two types are compatible
 ///
 /// This implements a lenient type compatibility check that allows for
@@ -152,7 +358,13 @@ fn types_compatible(expected: &LogicalType, actual: &LogicalType) -> bool {
         // Numeric type compatibility
         (LogicalType::Int, LogicalType::Float) | (LogicalType::Float, LogicalType::Int) => true,
 
-        // Decimal compatibility - allow if precision/scale differ but are both decimals
+        // Decimal compatibility - the inferred value must fit the declared precision/scale
+        (
+            LogicalType::Decimal { precision: Some(p1), scale: s1 },
+            LogicalType::Decimal { precision: Some(p2), scale: s2 },
+        ) => decimal_fits(*p1, s1.unwrap_or(0), *p2, s2.unwrap_or(0)),
+
+        // Unspecified precision on either side can't be checked
         (LogicalType::Decimal { .. }, LogicalType::Decimal { .. }) => true,
 
         // Int can be decimal
@@ -171,10 +383,28 @@ fn types_compatible(expected: &LogicalType, actual: &LogicalType) -> bool {
     }
 }
 
+/// Whether a DECIMAL(actual_precision, actual_scale) value fits in DECIMAL(precision, scale)
+///
+/// Neither the fractional digits nor the integer digits may be narrowed.
+fn decimal_fits(precision: u16, scale: u16, actual_precision: u16, actual_scale: u16) -> bool {
+    let integer_digits = precision.saturating_sub(scale);
+    let actual_integer_digits = actual_precision.saturating_sub(actual_scale);
+
+    actual_scale <= scale && actual_integer_digits <= integer_digits
+}
+
+/// True when the contract declares a decimal precision but inference couldn't determine one
+fn decimal_precision_unknown(expected: &LogicalType, actual: &LogicalType) -> bool {
+    matches!(
+        (expected, actual),
+        (LogicalType::Decimal { precision: Some(_), .. }, LogicalType::Decimal { precision: None, .. })
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use schemarefly_core::Column;
+    use schemarefly_core::{Column, EnforcementPolicy, IdentifierCase};
 
     fn create_test_contract() -> Contract {
         Contract::new(Schema::from_columns(vec![
@@ -228,6 +458,82 @@ mod tests {
         assert!(diff.diagnostics[0].code == DiagnosticCode::ContractTypeMismatch);
     }
 
+    #[test]
+    fn test_per_column_enforcement_overrides_policy() {
+        let contract = Contract::new(Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int).with_enforced(true),
+            Column::new("name", LogicalType::String).with_enforced(false),
+            Column::new("amount", LogicalType::Decimal { precision: Some(10), scale: Some(2) }),
+        ]));
+        let inferred = Schema::from_columns(vec![
+            Column::new("id", LogicalType::String),
+            Column::new("name", LogicalType::Int),
+        ]);
+
+        let diff = ContractDiff::compare("test_model", &contract, &inferred, None);
+        let severity_of = |needle: &str| {
+            diff.diagnostics.iter().find(|d| d.message.contains(needle)).unwrap().severity
+        };
+
+        // Strict key column errors, lenient column is informational
+        assert_eq!(severity_of("'id' type mismatch"), Severity::Error);
+        assert_eq!(severity_of("'name' type mismatch"), Severity::Info);
+
+        // Columns without a flag fall back to the model policy
+        assert_eq!(severity_of("'amount' required by contract"), Severity::Error);
+        assert_eq!(diff.error_count(), 2);
+    }
+
+    #[test]
+    fn test_strict_policy_warns_on_lenient_columns() {
+        let contract = Contract::new(Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::String).with_enforced(false),
+        ]))
+        .with_policy(EnforcementPolicy::strict());
+        let inferred = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::Int),
+        ]);
+
+        let diff = ContractDiff::compare("test_model", &contract, &inferred, None);
+        assert_eq!(diff.diagnostics.len(), 1);
+        assert_eq!(diff.diagnostics[0].severity, Severity::Warn);
+
+        let additive = contract.clone().with_policy(EnforcementPolicy::additive());
+        let diff = ContractDiff::compare("test_model", &additive, &inferred, None);
+        assert_eq!(diff.diagnostics[0].severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_type_mismatch_populates_model_diff() {
+        let contract = create_test_contract();
+        let inferred = Schema::from_columns(vec![
+            Column::new("id", LogicalType::String),
+            Column::new("name", LogicalType::String),
+            Column::new("extra_col", LogicalType::Int),
+        ]);
+
+        let diff = ContractDiff::compare("model.test.orders", &contract, &inferred, None);
+        let model_diff = &diff.model_diff;
+
+        assert_eq!(model_diff.model_id, "model.test.orders");
+        assert_eq!(model_diff.type_changed, vec![ColumnTypeChange {
+            name: "id".to_string(),
+            from: "INT".to_string(),
+            to: "STRING".to_string(),
+        }]);
+        assert_eq!(model_diff.removed.len(), 1);
+        assert_eq!(model_diff.removed[0].name, "amount");
+        assert_eq!(model_diff.added, vec![DiffColumn { name: "extra_col".to_string(), data_type: "INT".to_string() }]);
+
+        let report = schemarefly_core::Report::from_diagnostics(diff.diagnostics.clone())
+            .with_model_diffs(vec![diff.model_diff.clone()]);
+        let json = report.to_json().unwrap();
+        assert!(json.contains("\"model_diffs\""));
+        assert!(json.contains("\"type_changed\""));
+    }
+
     #[test]
     fn test_extra_column() {
         let contract = create_test_contract();
@@ -246,16 +552,181 @@ mod tests {
         assert!(diff.diagnostics[0].code == DiagnosticCode::ContractExtraColumn);
     }
 
+    fn schema_with_extra_column() -> Schema {
+        Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::String),
+            Column::new("amount", LogicalType::Decimal { precision: Some(10), scale: Some(2) }),
+            Column::new("extra_col", LogicalType::String),
+        ])
+    }
+
+    #[test]
+    fn test_extra_column_strict_policy() {
+        let contract = create_test_contract().with_policy(EnforcementPolicy::strict());
+
+        let diff = ContractDiff::compare("test_model", &contract, &schema_with_extra_column(), None);
+
+        assert_eq!(diff.error_count(), 1);
+        assert_eq!(diff.diagnostics[0].code, DiagnosticCode::ContractExtraColumn);
+        assert_eq!(diff.diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_strict_policy_reports_column_count_summary() {
+        let contract = create_test_contract().with_policy(EnforcementPolicy::strict());
+
+        let diff = ContractDiff::compare("test_model", &contract, &schema_with_extra_column(), None);
+        let summary = diff
+            .diagnostics
+            .iter()
+            .find(|d| d.code == DiagnosticCode::ContractArityMismatch)
+            .expect("strict policy should summarize column counts");
+        assert_eq!(summary.severity, Severity::Info);
+        assert_eq!(summary.message, "contract: 3 columns, inferred: 4");
+        assert_eq!(summary.expected.as_deref(), Some("3 columns"));
+        assert_eq!(summary.actual.as_deref(), Some("4 columns"));
+
+        // No summary when the columns match, or outside strict mode
+        let matching = ContractDiff::compare("test_model", &contract, &create_test_contract().schema, None);
+        assert!(matching.diagnostics.iter().all(|d| d.code != DiagnosticCode::ContractArityMismatch));
+        let default = ContractDiff::compare("test_model", &create_test_contract(), &schema_with_extra_column(), None);
+        assert!(default.diagnostics.iter().all(|d| d.code != DiagnosticCode::ContractArityMismatch));
+    }
+
+    #[test]
+    fn test_extra_column_additive_policy() {
+        let contract = create_test_contract().with_policy(EnforcementPolicy::additive());
+
+        let diff = ContractDiff::compare("test_model", &contract, &schema_with_extra_column(), None);
+
+        assert!(!diff.has_errors());
+        assert!(!diff.has_warnings());
+        assert_eq!(diff.diagnostics.len(), 1);
+        assert_eq!(diff.diagnostics[0].code, DiagnosticCode::ContractExtraColumn);
+        assert_eq!(diff.diagnostics[0].severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_identifier_case_policy() {
+        let contract = Contract::new(Schema::from_columns(vec![
+            Column::new("user_id", LogicalType::Int),
+        ]));
+        let inferred = Schema::from_columns(vec![
+            Column::new("USER_ID", LogicalType::Int),
+        ]);
+
+        // Snowflake: unquoted identifiers fold, so the column matches
+        let snowflake = CompareOptions::new().with_identifier_case(IdentifierCase::Insensitive);
+        let diff = ContractDiff::compare_with_options("test_model", &contract, &inferred, None, &snowflake);
+        assert!(diff.diagnostics.is_empty());
+
+        // Postgres: case-sensitive, so it's a missing + extra column
+        let postgres = CompareOptions::new().with_identifier_case(IdentifierCase::Sensitive);
+        let diff = ContractDiff::compare_with_options("test_model", &contract, &inferred, None, &postgres);
+        assert_eq!(diff.error_count(), 1);
+        assert_eq!(diff.diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn test_column_order_matches() {
+        let contract = create_test_contract();
+        let options = CompareOptions::new().with_enforce_column_order(true);
+
+        let diff = ContractDiff::compare_with_options("test_model", &contract, &contract.schema, None, &options);
+
+        assert!(diff.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_column_order_swapped() {
+        let contract = create_test_contract();
+        let inferred = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("amount", LogicalType::Decimal { precision: Some(10), scale: Some(2) }),
+            Column::new("name", LogicalType::String),
+        ]);
+
+        // Order is ignored by default
+        let diff = ContractDiff::compare("test_model", &contract, &inferred, None);
+        assert!(diff.diagnostics.is_empty());
+
+        let options = CompareOptions::new().with_enforce_column_order(true);
+        let diff = ContractDiff::compare_with_options("test_model", &contract, &inferred, None, &options);
+
+        assert_eq!(diff.diagnostics.len(), 1);
+        assert_eq!(diff.diagnostics[0].code, DiagnosticCode::ContractColumnOrderMismatch);
+        assert_eq!(diff.diagnostics[0].severity, Severity::Warn);
+        assert!(diff.diagnostics[0].message.contains("position 2"));
+        assert!(diff.diagnostics[0].message.contains("expected 'name', got 'amount'"));
+    }
+
+    #[test]
+    fn test_fully_inferred_model_passes_coverage_gate() {
+        let contract = create_test_contract();
+        let options = CompareOptions::new().with_min_inference_coverage(0.8);
+
+        let diff = ContractDiff::compare_with_options("test_model", &contract, &contract.schema, None, &options);
+
+        assert_eq!(diff.inference_coverage, 1.0);
+        assert!(diff.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_half_unknown_model_fails_coverage_gate() {
+        let contract = Contract::new(Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::String),
+            Column::new("payload", LogicalType::String),
+            Column::new("score", LogicalType::Float),
+        ]));
+        let inferred = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::String),
+            Column::new("payload", LogicalType::Unknown),
+            Column::new("score", LogicalType::Unknown),
+        ]);
+
+        // Unknown types are compatible, so nothing is reported without the gate
+        let diff = ContractDiff::compare("test_model", &contract, &inferred, None);
+        assert_eq!(diff.inference_coverage, 0.5);
+        assert!(diff.diagnostics.is_empty());
+
+        let options = CompareOptions::new().with_min_inference_coverage(0.8);
+        let diff = ContractDiff::compare_with_options("test_model", &contract, &inferred, Some("models/m.sql".to_string()), &options);
+
+        assert_eq!(diff.diagnostics.len(), 1);
+        assert_eq!(diff.diagnostics[0].code, DiagnosticCode::SqlInferenceLowCoverage);
+        assert_eq!(diff.diagnostics[0].severity, Severity::Warn);
+        assert_eq!(diff.diagnostics[0].message, "Only 50% of contract columns have a concrete inferred type (minimum 80%)");
+
+        // [severity.overrides] escalates the gate to an error
+        let config = schemarefly_core::Config::from_toml(r#"
+            [contract]
+            min_inference_coverage = 0.8
+
+            [severity.overrides]
+            SQL_INFERENCE_LOW_COVERAGE = "error"
+        "#).unwrap();
+        let options = CompareOptions::from_config(&config);
+        let diff = ContractDiff::compare_with_options("test_model", &contract, &inferred, None, &options);
+        assert_eq!(diff.diagnostics[0].severity, Severity::Error);
+        assert!(diff.has_errors());
+    }
+
     #[test]
     fn test_type_compatibility() {
         // Int and Float are compatible
         assert!(types_compatible(&LogicalType::Int, &LogicalType::Float));
         assert!(types_compatible(&LogicalType::Float, &LogicalType::Int));
 
-        // Decimals are compatible
+        // Decimals are compatible when the inferred value fits the declared precision/scale
         let dec1 = LogicalType::Decimal { precision: Some(10), scale: Some(2) };
         let dec2 = LogicalType::Decimal { precision: Some(20), scale: Some(4) };
-        assert!(types_compatible(&dec1, &dec2));
+        let dec3 = LogicalType::Decimal { precision: Some(8), scale: Some(2) };
+        assert!(types_compatible(&dec2, &dec1));
+        assert!(types_compatible(&dec1, &dec3));
+        assert!(!types_compatible(&dec1, &dec2));
 
         // Unknown is compatible with everything
         assert!(types_compatible(&LogicalType::Unknown, &LogicalType::Int));
@@ -264,4 +735,59 @@ mod tests {
         // String and Int are not compatible
         assert!(!types_compatible(&LogicalType::String, &LogicalType::Int));
     }
+
+    #[test]
+    fn test_decimal_unspecified_precision_is_info() {
+        let contract = create_test_contract();
+        let inferred = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::String),
+            Column::new("amount", LogicalType::Decimal { precision: None, scale: None }),
+        ]);
+
+        let diff = ContractDiff::compare("test_model", &contract, &inferred, None);
+
+        assert!(!diff.has_errors());
+        assert!(!diff.has_warnings());
+        assert_eq!(diff.diagnostics.len(), 1);
+        assert_eq!(diff.diagnostics[0].code, DiagnosticCode::ContractTypeMismatch);
+        assert_eq!(diff.diagnostics[0].severity, Severity::Info);
+        assert!(diff.diagnostics[0].message.contains("inferred decimal precision unknown"));
+        assert!(diff.model_diff.type_changed.is_empty());
+    }
+
+    #[test]
+    fn test_decimal_specified_incompatible_is_error() {
+        let contract = create_test_contract();
+        let inferred = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::String),
+            Column::new("amount", LogicalType::Decimal { precision: Some(18), scale: Some(4) }),
+        ]);
+
+        let diff = ContractDiff::compare("test_model", &contract, &inferred, None);
+
+        assert_eq!(diff.error_count(), 1);
+        assert_eq!(diff.diagnostics[0].code, DiagnosticCode::ContractTypeMismatch);
+        assert!(diff.diagnostics[0].message.contains("expected DECIMAL(10, 2), got DECIMAL(18, 4)"));
+    }
+
+    #[test]
+    fn test_type_mismatch_has_sql_and_yaml_locations() {
+        let contract = create_test_contract()
+            .with_column_location("id", Location::with_position("models/schema.yml", 8, 9));
+        let inferred = Schema::from_columns(vec![
+            Column::new("id", LogicalType::String),
+            Column::new("name", LogicalType::String),
+            Column::new("amount", LogicalType::Decimal { precision: Some(10), scale: Some(2) }),
+        ]);
+
+        let diff = ContractDiff::compare("test_model", &contract, &inferred, Some("models/users.sql".to_string()));
+
+        assert_eq!(diff.diagnostics.len(), 1);
+        let diag = &diff.diagnostics[0];
+        assert_eq!(diag.code, DiagnosticCode::ContractTypeMismatch);
+        assert_eq!(diag.location.as_ref().unwrap().file, "models/users.sql");
+        assert_eq!(diag.related, vec![Location::with_position("models/schema.yml", 8, 9)]);
+    }
 }
@@ -0,0 +1,105 @@
+//! Contract-required policy
+//!
+//! Teams can require enforced contracts on models under selected paths
+//! (`[contract] require_contract_paths = ["models/marts/*"]`). Matching
+//! models without one fail the check with `CONTRACT_MISSING`.
+
+use schemarefly_core::config::ContractConfig;
+use schemarefly_core::{Diagnostic, DiagnosticCode, Location, Severity};
+use schemarefly_dbt::{ManifestNode, Manifest};
+
+/// Enforces `require_contract_paths`
+pub struct ContractPolicy;
+
+impl ContractPolicy {
+    /// Diagnostic for `node` if its path requires a contract it does not enforce
+    pub fn check_node(node: &ManifestNode, config: &ContractConfig) -> Option<Diagnostic> {
+        if !config.requires_contract(&node.original_file_path) || Self::has_enforced_contract(node) {
+            return None;
+        }
+
+        Some(
+            Diagnostic::new(
+                DiagnosticCode::ContractMissing,
+                Severity::Error,
+                format!(
+                    "Model '{}' matches require_contract_paths but has no enforced contract",
+                    node.name
+                ),
+            )
+            .with_location(Location::new(&node.original_file_path)),
+        )
+    }
+
+    /// Diagnostics for every model in the manifest that is missing a required contract
+    pub fn check(manifest: &Manifest, config: &ContractConfig) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<Diagnostic> = manifest
+            .models()
+            .values()
+            .filter_map(|node| Self::check_node(node, config))
+            .collect();
+        diagnostics.sort_by(|a, b| a.message.cmp(&b.message));
+        diagnostics
+    }
+
+    fn has_enforced_contract(node: &ManifestNode) -> bool {
+        node.config.contract.as_ref().is_some_and(|contract| contract.enforced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> Manifest {
+        Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.p.stg_users": {
+                    "unique_id": "model.p.stg_users", "name": "stg_users", "resource_type": "model",
+                    "package_name": "p", "path": "staging/stg_users.sql",
+                    "original_file_path": "models/staging/stg_users.sql"
+                },
+                "model.p.fct_orders": {
+                    "unique_id": "model.p.fct_orders", "name": "fct_orders", "resource_type": "model",
+                    "package_name": "p", "path": "marts/fct_orders.sql",
+                    "original_file_path": "models/marts/fct_orders.sql"
+                },
+                "model.p.dim_users": {
+                    "unique_id": "model.p.dim_users", "name": "dim_users", "resource_type": "model",
+                    "package_name": "p", "path": "marts/dim_users.sql",
+                    "original_file_path": "models/marts/dim_users.sql",
+                    "config": {"contract": {"enforced": true}}
+                }
+            },
+            "sources": {}
+        }"#).unwrap()
+    }
+
+    fn policy(paths: &[&str]) -> ContractConfig {
+        ContractConfig {
+            require_contract_paths: paths.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn marts_model_without_contract_fails() {
+        let diagnostics = ContractPolicy::check(&manifest(), &policy(&["models/marts/*"]));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::ContractMissing);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(diagnostics[0].message.contains("fct_orders"));
+        assert_eq!(diagnostics[0].location.as_ref().unwrap().file, "models/marts/fct_orders.sql");
+    }
+
+    #[test]
+    fn staging_model_without_contract_passes() {
+        let manifest = manifest();
+        let staging = manifest.get_node("model.p.stg_users").unwrap();
+
+        assert!(ContractPolicy::check_node(staging, &policy(&["models/marts/*"])).is_none());
+        assert!(ContractPolicy::check(&manifest, &ContractConfig::default()).is_empty());
+    }
+}
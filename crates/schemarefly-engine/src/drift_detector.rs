@@ -3,8 +3,8 @@
 //! This module implements the core drift detection logic that compares
 //! schemas defined in dbt manifests/contracts against actual warehouse schemas.
 
-use schemarefly_core::{Schema, LogicalType, Diagnostic, DiagnosticCode, Severity, Location, Nullability};
-use std::collections::HashSet;
+use crate::options::CompareOptions;
+use schemarefly_core::{Schema, Column, LogicalType, Diagnostic, DiagnosticCode, Severity, Location, Nullability};
 
 /// Result of comparing expected vs actual warehouse schema
 #[derive(Debug, Clone)]
@@ -27,142 +27,256 @@ impl DriftDetection {
     ///
     /// This detects four types of drift:
     /// - Dropped columns: columns in expected but not in actual
-    /// - Type changes: columns with different types
+    /// - Type changes: columns with different types (with
+    ///   `CompareOptions::timezone_aware`, also a timestamp gaining or losing
+    ///   its time zone; with `CompareOptions::integral_decimal_as_int`,
+    ///   `DECIMAL(p,0)` ↔ integer is not a change)
     /// - Nullability changes: columns with different nullability constraints
-    /// - New columns: columns in actual but not in expected (info level)
+    /// - Default changes: columns whose known default expression differs (info level)
+    /// - Accepted values changes: enum labels reported by the warehouse differ
+    ///   from the declared accepted values (warning level; skipped when the
+    ///   warehouse does not report enum values)
+    /// - New columns: columns in actual but not in expected (info level, or
+    ///   warning when added as NOT NULL without a default)
+    ///
+    /// Additions and changes of columns matching `CompareOptions::ignore_columns`
+    /// (loader metadata such as `_fivetran_synced`) are not reported. With
+    /// `CompareOptions::ignore_constraints`, nullability and defaults are not
+    /// compared at all.
     pub fn detect(
         table_id: impl Into<String>,
         expected: &Schema,
         actual: &Schema,
         file_path: Option<String>,
+    ) -> Self {
+        Self::detect_with_options(table_id, expected, actual, file_path, &CompareOptions::default())
+    }
+
+    /// Detect drift using explicit comparison options
+    pub fn detect_with_options(
+        table_id: impl Into<String>,
+        expected: &Schema,
+        actual: &Schema,
+        file_path: Option<String>,
+        options: &CompareOptions,
     ) -> Self {
         let table_id = table_id.into();
         let mut diagnostics = Vec::new();
+        let case = options.identifier_case;
+
+        let diff = expected.diff_with(actual, case, |a, b| {
+            types_match(a, b) || (options.integral_decimal_as_int && is_integral_decimal_and_int(a, b))
+        });
+
+        // Type drift
+        for change in diff.type_changed.iter().filter(|c| !options.is_column_ignored(&c.after.name)) {
+            let (expected_col, actual_col) = (&change.before, &change.after);
+            let message = format!(
+                "Column '{}' type changed: was {}, now {}",
+                expected_col.name,
+                expected_col.logical_type,
+                actual_col.logical_type
+            );
+
+            diagnostics.push(Diagnostic {
+                warehouse_type: actual_col.warehouse_type.clone(),
+                ..drift_diagnostic(
+                    DiagnosticCode::DriftTypeChange,
+                    Severity::Error,
+                    message,
+                    file_path.as_deref(),
+                    Some(expected_col.logical_type.to_string()),
+                    Some(actual_col.logical_type.to_string()),
+                )
+                .with_column(&expected_col.name)
+            });
+        }
 
-        // Track which columns we've seen from the expected schema
-        let mut seen_expected_cols = HashSet::new();
-
-        // Check each column in expected schema
-        for expected_col in &expected.columns {
-            seen_expected_cols.insert(&expected_col.name);
-
-            match actual.find_column(&expected_col.name) {
-                Some(actual_col) => {
-                    // Column exists - check for type drift
-                    if !types_match(&expected_col.logical_type, &actual_col.logical_type) {
-                        let message = format!(
-                            "Column '{}' type changed: was {}, now {}",
-                            expected_col.name,
-                            expected_col.logical_type,
-                            actual_col.logical_type
-                        );
-
-                        diagnostics.push(Diagnostic {
-                            code: DiagnosticCode::DriftTypeChange,
-                            severity: Severity::Error,
-                            message,
-                            location: file_path.as_ref().map(|path| Location {
-                                file: path.clone(),
-                                line: None,
-                                column: None,
-                                end_line: None,
-                                end_column: None,
-                            }),
-                            expected: Some(expected_col.logical_type.to_string()),
-                            actual: Some(actual_col.logical_type.to_string()),
-                            impact: vec![],
-                        });
-                    }
-
-                    // Check for nullability drift (only if both are known)
-                    if let Some((expected_null, actual_null)) = nullability_changed(&expected_col.nullable, &actual_col.nullable) {
-                        let severity = match (&expected_col.nullable, &actual_col.nullable) {
-                            // NOT NULL -> NULL is a warning (looser constraint)
-                            (Nullability::No, Nullability::Yes) => Severity::Warn,
-                            // NULL -> NOT NULL is an error (stricter constraint, may break inserts)
-                            (Nullability::Yes, Nullability::No) => Severity::Error,
-                            // Other changes are warnings
-                            _ => Severity::Warn,
-                        };
-
-                        let message = format!(
-                            "Column '{}' nullability changed: was {}, now {}",
-                            expected_col.name,
-                            expected_null,
-                            actual_null
-                        );
-
-                        diagnostics.push(Diagnostic {
-                            code: DiagnosticCode::DriftNullabilityChange,
-                            severity,
-                            message,
-                            location: file_path.as_ref().map(|path| Location {
-                                file: path.clone(),
-                                line: None,
-                                column: None,
-                                end_line: None,
-                                end_column: None,
-                            }),
-                            expected: Some(expected_null.to_string()),
-                            actual: Some(actual_null.to_string()),
-                            impact: vec![],
-                        });
-                    }
-                }
-                None => {
-                    // Column dropped from warehouse
+        // Time zone drift of timestamps (only if both are known)
+        if options.timezone_aware {
+            for (expected_col, actual_col) in expected
+                .matched_columns(actual, case)
+                .filter(|(_, actual_col)| !options.is_column_ignored(&actual_col.name))
+            {
+                if let Some((expected_type, actual_type)) = time_zone_changed(expected_col, actual_col) {
                     let message = format!(
-                        "Column '{}' was dropped from warehouse table (expected type: {})",
-                        expected_col.name,
-                        expected_col.logical_type
+                        "Column '{}' type changed: was {}, now {}",
+                        expected_col.name, expected_type, actual_type
                     );
 
                     diagnostics.push(Diagnostic {
-                        code: DiagnosticCode::DriftColumnDropped,
-                        severity: Severity::Error,
-                        message,
-                        location: file_path.as_ref().map(|path| Location {
-                            file: path.clone(),
-                            line: None,
-                            column: None,
-                            end_line: None,
-                            end_column: None,
-                        }),
-                        expected: Some(expected_col.name.clone()),
-                        actual: None,
-                        impact: vec![],
+                        warehouse_type: actual_col.warehouse_type.clone(),
+                        ..drift_diagnostic(
+                            DiagnosticCode::DriftTypeChange,
+                            Severity::Error,
+                            message,
+                            file_path.as_deref(),
+                            Some(expected_type.to_string()),
+                            Some(actual_type.to_string()),
+                        )
+                        .with_column(&expected_col.name)
                     });
                 }
             }
         }
 
-        // Check for new columns in actual schema
-        for actual_col in &actual.columns {
-            if !seen_expected_cols.contains(&actual_col.name) {
+        // Nullability drift (only reported when both sides are known)
+        for change in diff
+            .nullability_changed
+            .iter()
+            .filter(|c| !options.ignore_constraints && !options.is_column_ignored(&c.after.name))
+        {
+            let (expected_col, actual_col) = (&change.before, &change.after);
+            let severity = match (&expected_col.nullable, &actual_col.nullable) {
+                // NULL -> NOT NULL is an error (stricter constraint, may break inserts)
+                (Nullability::Yes, Nullability::No) => Severity::Error,
+                // NOT NULL -> NULL is a warning (looser constraint)
+                _ => Severity::Warn,
+            };
+            let (expected_null, actual_null) = (nullability_label(&expected_col.nullable), nullability_label(&actual_col.nullable));
+
+            let message = format!(
+                "Column '{}' nullability changed: was {}, now {}",
+                expected_col.name,
+                expected_null,
+                actual_null
+            );
+
+            diagnostics.push(drift_diagnostic(
+                DiagnosticCode::DriftNullabilityChange,
+                severity,
+                message,
+                file_path.as_deref(),
+                Some(expected_null.to_string()),
+                Some(actual_null.to_string()),
+            ).with_column(&expected_col.name));
+        }
+
+        // Default drift (only if both are known)
+        for (expected_col, actual_col) in expected
+            .matched_columns(actual, case)
+            .filter(|(_, actual_col)| !options.ignore_constraints && !options.is_column_ignored(&actual_col.name))
+        {
+            if let Some((expected_default, actual_default)) = default_changed(expected_col, actual_col) {
+                let message = format!(
+                    "Column '{}' default changed: was {}, now {}",
+                    expected_col.name,
+                    expected_default,
+                    actual_default
+                );
+
+                diagnostics.push(drift_diagnostic(
+                    DiagnosticCode::DriftDefaultChanged,
+                    Severity::Info,
+                    message,
+                    file_path.as_deref(),
+                    Some(expected_default),
+                    Some(actual_default),
+                ).with_column(&expected_col.name));
+            }
+        }
+
+        // Accepted values drift (only if both sides list them)
+        for (expected_col, actual_col) in expected
+            .matched_columns(actual, case)
+            .filter(|(_, actual_col)| !options.is_column_ignored(&actual_col.name))
+        {
+            if let Some((added, removed)) = accepted_values_changed(expected_col, actual_col) {
+                let mut changes = Vec::new();
+                if !added.is_empty() {
+                    changes.push(format!("warehouse adds {}", quoted_list(&added)));
+                }
+                if !removed.is_empty() {
+                    changes.push(format!("warehouse no longer allows {}", quoted_list(&removed)));
+                }
                 let message = format!(
-                    "New column '{}' added to warehouse table (type: {})",
-                    actual_col.name,
-                    actual_col.logical_type
+                    "Column '{}' accepted values changed: {}",
+                    expected_col.name,
+                    changes.join("; ")
                 );
 
-                diagnostics.push(Diagnostic {
-                    code: DiagnosticCode::DriftColumnAdded,
-                    severity: Severity::Info,
+                diagnostics.push(drift_diagnostic(
+                    DiagnosticCode::DriftAcceptedValuesChanged,
+                    Severity::Warn,
                     message,
-                    location: file_path.as_ref().map(|path| Location {
-                        file: path.clone(),
-                        line: None,
-                        column: None,
-                        end_line: None,
-                        end_column: None,
-                    }),
-                    expected: None,
-                    actual: Some(actual_col.name.clone()),
-                    impact: vec![],
-                });
+                    file_path.as_deref(),
+                    expected_col.accepted_values.as_deref().map(quoted_list),
+                    actual_col.accepted_values.as_deref().map(quoted_list),
+                ).with_column(&expected_col.name));
             }
         }
 
+        // Columns dropped from the warehouse
+        for expected_col in &diff.removed {
+            let message = format!(
+                "Column '{}' was dropped from warehouse table (expected type: {})",
+                expected_col.name,
+                expected_col.logical_type
+            );
+
+            diagnostics.push(drift_diagnostic(
+                DiagnosticCode::DriftColumnDropped,
+                Severity::Error,
+                message,
+                file_path.as_deref(),
+                Some(expected_col.name.clone()),
+                None,
+            ).with_column(&expected_col.name));
+        }
+
+        // New columns in the warehouse
+        for actual_col in diff.added.iter().filter(|c| !options.is_column_ignored(&c.name)) {
+            // A NOT NULL column without a default breaks inserts that don't set it;
+            // a generated column is computed by the warehouse and never set by inserts
+            let is_generated = actual_col.is_generated == Some(true);
+            let breaks_inserts = !options.ignore_constraints
+                && !is_generated
+                && actual_col.nullable == Nullability::No
+                && actual_col.has_default == Some(false);
+
+            let (severity, message) = if breaks_inserts {
+                (
+                    Severity::Warn,
+                    format!(
+                        "New NOT NULL column '{}' without a default added to warehouse table (type: {}); inserts that omit it will fail",
+                        actual_col.name,
+                        actual_col.logical_type
+                    ),
+                )
+            } else if is_generated {
+                (
+                    Severity::Info,
+                    format!(
+                        "New generated column '{}' added to warehouse table (type: {})",
+                        actual_col.name,
+                        actual_col.logical_type
+                    ),
+                )
+            } else {
+                (
+                    Severity::Info,
+                    format!(
+                        "New column '{}' added to warehouse table (type: {})",
+                        actual_col.name,
+                        actual_col.logical_type
+                    ),
+                )
+            };
+
+            diagnostics.push(Diagnostic {
+                warehouse_type: actual_col.warehouse_type.clone(),
+                ..drift_diagnostic(
+                    DiagnosticCode::DriftColumnAdded,
+                    severity,
+                    message,
+                    file_path.as_deref(),
+                    None,
+                    Some(actual_col.name.clone()),
+                )
+                .with_column(&actual_col.name)
+            });
+        }
+
         Self {
             table_id,
             expected: expected.clone(),
@@ -200,6 +314,56 @@ impl DriftDetection {
     pub fn info_count(&self) -> usize {
         self.diagnostics.iter().filter(|d| d.severity == Severity::Info).count()
     }
+
+    /// One info diagnostic counting this table's findings by kind
+    ///
+    /// For example `users: 1 dropped, 2 added, 1 type change`. Kinds with no
+    /// findings are left out; a table without drift reads `users: no drift`.
+    pub fn summary(&self) -> Diagnostic {
+        // (code, label for one finding, label for several)
+        const KINDS: [(DiagnosticCode, &str, &str); 7] = [
+            (DiagnosticCode::DriftColumnDropped, "dropped", "dropped"),
+            (DiagnosticCode::DriftColumnAdded, "added", "added"),
+            (DiagnosticCode::DriftTypeChange, "type change", "type changes"),
+            (DiagnosticCode::DriftNullabilityChange, "nullability change", "nullability changes"),
+            (DiagnosticCode::DriftDefaultChanged, "default change", "default changes"),
+            (DiagnosticCode::DriftViewDefinitionChanged, "view definition change", "view definition changes"),
+            (DiagnosticCode::DriftAcceptedValuesChanged, "accepted values change", "accepted values changes"),
+        ];
+
+        let parts: Vec<String> = KINDS
+            .iter()
+            .filter_map(|(code, one, many)| {
+                let n = self.diagnostics.iter().filter(|d| d.code == *code).count();
+                match n {
+                    0 => None,
+                    1 => Some(format!("1 {}", one)),
+                    _ => Some(format!("{} {}", n, many)),
+                }
+            })
+            .collect();
+
+        let message = if parts.is_empty() {
+            format!("{}: no drift", self.table_id)
+        } else {
+            format!("{}: {}", self.table_id, parts.join(", "))
+        };
+
+        Diagnostic {
+            location: self.diagnostics.first().and_then(|d| d.location.clone()),
+            model_id: self.diagnostics.first().and_then(|d| d.model_id.clone()),
+            ..Diagnostic::new(DiagnosticCode::DriftSummary, Severity::Info, message)
+        }
+    }
+
+    /// The summary standing in for this table's findings
+    ///
+    /// Takes the severity of the worst finding it replaces, so summarizing
+    /// never turns failing drift into a passing run.
+    pub fn replacement_summary(&self) -> Diagnostic {
+        let severity = self.diagnostics.iter().map(|d| d.severity).max().unwrap_or(Severity::Info);
+        Diagnostic { severity, ..self.summary() }
+    }
 }
 
 /// Check if two types match exactly
@@ -225,32 +389,134 @@ fn types_match(expected: &LogicalType, actual: &LogicalType) -> bool {
         // Unknown matches anything (since we don't have enough info)
         (LogicalType::Unknown, _) | (_, LogicalType::Unknown) => true,
 
+        // Arrays: compare element types
+        (LogicalType::Array { element_type: e1 }, LogicalType::Array { element_type: e2 }) => {
+            types_match(e1, e2)
+        }
+
+        // Structs: undeclared fields match any struct; otherwise fields must match by name and type
+        (LogicalType::Struct { fields: f1 }, LogicalType::Struct { fields: f2 }) => {
+            f1.is_empty()
+                || f2.is_empty()
+                || (f1.len() == f2.len()
+                    && f1.iter().all(|a| {
+                        f2.iter()
+                            .find(|b| b.name.eq_ignore_ascii_case(&a.name))
+                            .is_some_and(|b| types_match(&a.logical_type, &b.logical_type))
+                    }))
+        }
+
         // Everything else is a mismatch
         _ => false,
     }
 }
 
-/// Check if nullability has changed between expected and actual
+/// Whether one side is an integer and the other a decimal with scale 0
+fn is_integral_decimal_and_int(expected: &LogicalType, actual: &LogicalType) -> bool {
+    matches!(
+        (expected, actual),
+        (LogicalType::Decimal { scale: Some(0), .. }, LogicalType::Int)
+            | (LogicalType::Int, LogicalType::Decimal { scale: Some(0), .. })
+    )
+}
+
+/// Timestamp spellings when the time zone awareness of a timestamp column changed
+fn time_zone_changed(expected: &Column, actual: &Column) -> Option<(&'static str, &'static str)> {
+    let label = |time_zone: bool| if time_zone { "TIMESTAMP WITH TIME ZONE" } else { "TIMESTAMP" };
+
+    match (&expected.logical_type, &actual.logical_type, expected.time_zone, actual.time_zone) {
+        (LogicalType::Timestamp, LogicalType::Timestamp, Some(before), Some(after)) if before != after => {
+            Some((label(before), label(after)))
+        }
+        _ => None,
+    }
+}
+
+/// SQL spelling of a known nullability
+fn nullability_label(nullable: &Nullability) -> &'static str {
+    match nullable {
+        Nullability::No => "NOT NULL",
+        Nullability::Yes | Nullability::Unknown => "NULL",
+    }
+}
+
+/// Drift diagnostic located at the model file
+fn drift_diagnostic(
+    code: DiagnosticCode,
+    severity: Severity,
+    message: String,
+    file_path: Option<&str>,
+    expected: Option<String>,
+    actual: Option<String>,
+) -> Diagnostic {
+    Diagnostic {
+        code,
+        severity,
+        message,
+        location: file_path.map(Location::new),
+        expected,
+        actual,
+        impact: vec![],
+        related: vec![],
+        model_id: None,
+        column: None,
+        warehouse_type: None,
+    }
+}
+
+/// The default expression of a column, if known
 ///
-/// Returns Some((expected_str, actual_str)) if there's a meaningful change,
-/// None if nullability matches or if either is Unknown (can't determine)
-fn nullability_changed(expected: &Nullability, actual: &Nullability) -> Option<(&'static str, &'static str)> {
-    match (expected, actual) {
-        // Same nullability - no change
-        (Nullability::Yes, Nullability::Yes) => None,
-        (Nullability::No, Nullability::No) => None,
-        (Nullability::Unknown, _) | (_, Nullability::Unknown) => None, // Can't determine
+/// Returns `Some(None)` when the source reported that there is no default and
+/// `None` when it did not report defaults at all.
+fn known_default(column: &Column) -> Option<Option<&str>> {
+    match (&column.default, column.has_default) {
+        (Some(default), _) => Some(Some(default.trim())),
+        (None, Some(false)) => Some(None),
+        _ => None,
+    }
+}
 
-        // Actual changes
-        (Nullability::No, Nullability::Yes) => Some(("NOT NULL", "NULL")),
-        (Nullability::Yes, Nullability::No) => Some(("NULL", "NOT NULL")),
+/// Check if a column default changed between expected and actual
+///
+/// Returns Some((expected_str, actual_str)) if both defaults are known and
+/// differ, None otherwise.
+fn default_changed(expected: &Column, actual: &Column) -> Option<(String, String)> {
+    let expected = known_default(expected)?;
+    let actual = known_default(actual)?;
+    if expected == actual {
+        return None;
     }
+
+    let describe = |default: Option<&str>| default.unwrap_or("no default").to_string();
+    Some((describe(expected), describe(actual)))
+}
+
+/// Compare declared accepted values with the warehouse's enum values
+///
+/// Returns Some((added, removed)) — values only the warehouse allows and
+/// values it no longer allows — if both sides are known and differ as
+/// sets, None otherwise. Order is not compared.
+fn accepted_values_changed(expected: &Column, actual: &Column) -> Option<(Vec<String>, Vec<String>)> {
+    let expected = expected.accepted_values.as_ref()?;
+    let actual = actual.accepted_values.as_ref()?;
+
+    let added: Vec<String> = actual.iter().filter(|v| !expected.contains(v)).cloned().collect();
+    let removed: Vec<String> = expected.iter().filter(|v| !actual.contains(v)).cloned().collect();
+    if added.is_empty() && removed.is_empty() {
+        return None;
+    }
+
+    Some((added, removed))
+}
+
+/// `'a', 'b'` for display
+fn quoted_list(values: &[String]) -> String {
+    values.iter().map(|v| format!("'{}'", v)).collect::<Vec<_>>().join(", ")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use schemarefly_core::Column;
 
     fn create_test_schema() -> Schema {
         Schema::from_columns(vec![
@@ -260,6 +526,48 @@ mod tests {
         ])
     }
 
+    #[test]
+    fn test_summary_counts_match_diagnostics() {
+        let expected = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::String),
+            Column::new("amount", LogicalType::Float),
+            Column::new("legacy_flag", LogicalType::Bool),
+        ]);
+        let actual = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::String),
+            Column::new("amount", LogicalType::String),
+            Column::new("region", LogicalType::String),
+            Column::new("channel", LogicalType::String),
+        ]);
+
+        let drift = DriftDetection::detect("users", &expected, &actual, Some("models/users.sql".to_string()));
+        let count = |code| drift.diagnostics.iter().filter(|d| d.code == code).count();
+        assert_eq!(count(DiagnosticCode::DriftColumnDropped), 1);
+        assert_eq!(count(DiagnosticCode::DriftColumnAdded), 2);
+        assert_eq!(count(DiagnosticCode::DriftTypeChange), 1);
+
+        let summary = drift.summary();
+        assert_eq!(summary.code, DiagnosticCode::DriftSummary);
+        assert_eq!(summary.severity, Severity::Info);
+        assert_eq!(summary.message, "users: 1 dropped, 2 added, 1 type change");
+        assert_eq!(summary.location.unwrap().file, "models/users.sql");
+
+        let replacement = drift.replacement_summary();
+        assert_eq!(replacement.severity, Severity::Error);
+        assert_eq!(replacement.message, "users: 1 dropped, 2 added, 1 type change");
+    }
+
+    #[test]
+    fn test_summary_without_drift() {
+        let schema = create_test_schema();
+        let drift = DriftDetection::detect("orders", &schema, &schema, None);
+
+        assert_eq!(drift.summary().message, "orders: no drift");
+        assert!(drift.summary().location.is_none());
+    }
+
     #[test]
     fn test_no_drift() {
         let expected = create_test_schema();
@@ -414,4 +722,275 @@ mod tests {
         assert!(!drift.has_warnings());
         assert!(!drift.has_info());
     }
+
+    #[test]
+    fn test_identifier_case_snowflake_vs_postgres() {
+        use schemarefly_core::IdentifierCase;
+
+        let expected = Schema::from_columns(vec![Column::new("user_id", LogicalType::Int)]);
+        let actual = Schema::from_columns(vec![Column::new("USER_ID", LogicalType::Int)]);
+
+        let snowflake = CompareOptions::new().with_identifier_case(IdentifierCase::Insensitive);
+        let drift = DriftDetection::detect_with_options("test.table", &expected, &actual, None, &snowflake);
+        assert!(drift.diagnostics.is_empty());
+
+        // Case-sensitive comparison reports a spurious drop + add
+        let postgres = CompareOptions::new().with_identifier_case(IdentifierCase::Sensitive);
+        let drift = DriftDetection::detect_with_options("test.table", &expected, &actual, None, &postgres);
+        let codes: Vec<_> = drift.diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(codes, vec![DiagnosticCode::DriftColumnDropped, DiagnosticCode::DriftColumnAdded]);
+    }
+
+    #[test]
+    fn test_added_nullable_column_is_info() {
+        let expected = create_test_schema();
+        let mut actual = create_test_schema();
+        actual.columns.push(
+            Column::new("notes", LogicalType::String)
+                .with_nullability(Nullability::Yes)
+                .with_has_default(false),
+        );
+
+        let drift = DriftDetection::detect("test.table", &expected, &actual, None);
+
+        assert_eq!(drift.diagnostics.len(), 1);
+        assert_eq!(drift.diagnostics[0].code, DiagnosticCode::DriftColumnAdded);
+        assert_eq!(drift.diagnostics[0].severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_ignored_metadata_column_is_not_reported() {
+        let expected = create_test_schema();
+        let mut actual = create_test_schema();
+        actual.columns.push(Column::new("_FIVETRAN_DELETED", LogicalType::Bool));
+        actual.columns.push(Column::new("region", LogicalType::String));
+
+        let options = CompareOptions::new().with_ignore_columns(["_fivetran_*", "_loaded_at"]);
+        let drift = DriftDetection::detect_with_options("test.table", &expected, &actual, None, &options);
+
+        assert_eq!(drift.diagnostics.len(), 1);
+        assert_eq!(drift.diagnostics[0].code, DiagnosticCode::DriftColumnAdded);
+        assert!(drift.diagnostics[0].message.contains("'region'"));
+
+        // Without the patterns both additions are reported
+        let drift = DriftDetection::detect("test.table", &expected, &actual, None);
+        assert_eq!(drift.diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn test_timestamp_time_zone_change_is_drift_only_when_timezone_aware() {
+        let column = |name: &str, data_type: &str| Column::new(name, LogicalType::Timestamp).with_time_zone_of(data_type);
+        let expected = Schema::from_columns(vec![column("created_at", "timestamp"), column("updated_at", "timestamp")]);
+        let actual = Schema::from_columns(vec![
+            column("created_at", "timestamp with time zone"),
+            column("updated_at", "TIMESTAMP_NTZ"),
+        ]);
+
+        // Time zone agnostic (default): both are TIMESTAMP
+        let drift = DriftDetection::detect("test.table", &expected, &actual, None);
+        assert!(drift.diagnostics.is_empty());
+
+        let options = CompareOptions::new().with_timezone_aware(true);
+        let drift = DriftDetection::detect_with_options("test.table", &expected, &actual, None, &options);
+        assert_eq!(drift.diagnostics.len(), 1);
+        let diag = &drift.diagnostics[0];
+        assert_eq!(diag.code, DiagnosticCode::DriftTypeChange);
+        assert_eq!(diag.severity, Severity::Error);
+        assert_eq!(diag.message, "Column 'created_at' type changed: was TIMESTAMP, now TIMESTAMP WITH TIME ZONE");
+
+        // Unknown time zone awareness on one side is not drift
+        let unknown = Schema::from_columns(vec![Column::new("created_at", LogicalType::Timestamp)]);
+        let drift = DriftDetection::detect_with_options("test.table", &unknown, &actual, None, &options);
+        assert!(drift.diagnostics.iter().all(|d| d.code != DiagnosticCode::DriftTypeChange));
+    }
+
+    #[test]
+    fn test_accepted_values_change_is_warning() {
+        let expected = Schema::from_columns(vec![
+            Column::new("status", LogicalType::String).with_accepted_values(["placed", "shipped", "returned"]),
+            Column::new("channel", LogicalType::String).with_accepted_values(["web", "store"]),
+        ]);
+        let actual = Schema::from_columns(vec![
+            Column::new("status", LogicalType::String).with_accepted_values(["placed", "shipped", "refunded"]),
+            // Same set in another order
+            Column::new("channel", LogicalType::String).with_accepted_values(["store", "web"]),
+        ]);
+
+        let drift = DriftDetection::detect("test.table", &expected, &actual, None);
+        assert_eq!(drift.diagnostics.len(), 1);
+        let diag = &drift.diagnostics[0];
+        assert_eq!(diag.code, DiagnosticCode::DriftAcceptedValuesChanged);
+        assert_eq!(diag.severity, Severity::Warn);
+        assert_eq!(
+            diag.message,
+            "Column 'status' accepted values changed: warehouse adds 'refunded'; warehouse no longer allows 'returned'"
+        );
+        assert_eq!(diag.expected.as_deref(), Some("'placed', 'shipped', 'returned'"));
+        assert_eq!(diag.actual.as_deref(), Some("'placed', 'shipped', 'refunded'"));
+        assert_eq!(drift.summary().message, "test.table: 1 accepted values change");
+    }
+
+    #[test]
+    fn test_accepted_values_skipped_without_warehouse_enum() {
+        // The warehouse reports no enum values (not an enum type, or no introspection)
+        let expected = Schema::from_columns(vec![
+            Column::new("status", LogicalType::String).with_accepted_values(["placed", "shipped"]),
+        ]);
+        let actual = Schema::from_columns(vec![Column::new("status", LogicalType::String)]);
+
+        let drift = DriftDetection::detect("test.table", &expected, &actual, None);
+        assert!(drift.diagnostics.is_empty());
+
+        // Nor is anything reported for columns without declared values
+        let drift = DriftDetection::detect("test.table", &actual, &expected, None);
+        assert!(drift.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_integral_decimal_matches_int_only_when_enabled() {
+        let expected = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Decimal { precision: Some(10), scale: Some(0) }),
+            Column::new("amount", LogicalType::Decimal { precision: Some(10), scale: Some(2) }),
+        ]);
+        let actual = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("amount", LogicalType::Int),
+        ]);
+
+        // Off by default: both columns changed type
+        let drift = DriftDetection::detect("test.table", &expected, &actual, None);
+        assert_eq!(drift.diagnostics.len(), 2);
+        assert!(drift.diagnostics.iter().all(|d| d.code == DiagnosticCode::DriftTypeChange));
+
+        // Enabled: only the column with a fractional scale changed
+        let options = CompareOptions::new().with_integral_decimal_as_int(true);
+        let drift = DriftDetection::detect_with_options("test.table", &expected, &actual, None, &options);
+        assert_eq!(drift.diagnostics.len(), 1);
+        assert_eq!(drift.diagnostics[0].message, "Column 'amount' type changed: was DECIMAL(10, 2), now INT");
+
+        let drift = DriftDetection::detect_with_options("test.table", &actual, &expected, None, &options);
+        assert_eq!(drift.diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_added_not_null_column_without_default_is_warning() {
+        let expected = create_test_schema();
+        let mut actual = create_test_schema();
+        actual.columns.push(
+            Column::new("tenant_id", LogicalType::Int)
+                .with_nullability(Nullability::No)
+                .with_has_default(false),
+        );
+        actual.columns.push(
+            Column::new("created_at", LogicalType::Timestamp)
+                .with_nullability(Nullability::No)
+                .with_has_default(true),
+        );
+
+        let drift = DriftDetection::detect("test.table", &expected, &actual, None);
+
+        assert_eq!(drift.warning_count(), 1);
+        assert_eq!(drift.info_count(), 1);
+        let warning = drift.diagnostics.iter().find(|d| d.severity == Severity::Warn).unwrap();
+        assert_eq!(warning.code, DiagnosticCode::DriftColumnAdded);
+        assert!(warning.message.contains("NOT NULL column 'tenant_id' without a default"));
+    }
+
+    #[test]
+    fn test_added_generated_column_is_info() {
+        let expected = create_test_schema();
+        let mut actual = create_test_schema();
+        actual.columns.push(
+            Column::new("full_name", LogicalType::String)
+                .with_nullability(Nullability::No)
+                .with_has_default(false)
+                .with_generated(true),
+        );
+
+        let drift = DriftDetection::detect("test.table", &expected, &actual, None);
+
+        assert_eq!(drift.diagnostics.len(), 1);
+        let diag = &drift.diagnostics[0];
+        assert_eq!(diag.code, DiagnosticCode::DriftColumnAdded);
+        assert_eq!(diag.severity, Severity::Info);
+        assert_eq!(diag.message, "New generated column 'full_name' added to warehouse table (type: STRING)");
+    }
+
+    #[test]
+    fn test_mixed_drift_reports_every_category() {
+        let expected = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int).with_nullability(Nullability::Yes),
+            Column::new("name", LogicalType::String),
+            Column::new("legacy", LogicalType::String),
+        ]);
+        let actual = Schema::from_columns(vec![
+            Column::new("ID", LogicalType::Int).with_nullability(Nullability::No),
+            Column::new("name", LogicalType::Int),
+            Column::new("created_at", LogicalType::Timestamp),
+        ]);
+
+        let options = CompareOptions::new().with_identifier_case(schemarefly_core::IdentifierCase::Insensitive);
+        let drift = DriftDetection::detect_with_options("test.table", &expected, &actual, None, &options);
+
+        let mut codes: Vec<&str> = drift.diagnostics.iter().map(|d| d.code.as_str()).collect();
+        codes.sort();
+        assert_eq!(codes, vec![
+            "DRIFT_COLUMN_ADDED",
+            "DRIFT_COLUMN_DROPPED",
+            "DRIFT_NULLABILITY_CHANGE",
+            "DRIFT_TYPE_CHANGE",
+        ]);
+        assert_eq!(drift.error_count(), 3);
+        assert_eq!(drift.info_count(), 1);
+    }
+
+    #[test]
+    fn test_default_change_is_info() {
+        let expected = Schema::from_columns(vec![
+            Column::new("status", LogicalType::String).with_default("'active'"),
+            Column::new("created_at", LogicalType::Timestamp).with_default("now()"),
+            Column::new("notes", LogicalType::String).with_has_default(false),
+            Column::new("id", LogicalType::Int),
+        ]);
+        let actual = Schema::from_columns(vec![
+            Column::new("status", LogicalType::String).with_default("'pending'"),
+            Column::new("created_at", LogicalType::Timestamp).with_default(" now() "),
+            Column::new("notes", LogicalType::String).with_default("''"),
+            // Unknown expected default never reports drift
+            Column::new("id", LogicalType::Int).with_default("nextval('id_seq')"),
+        ]);
+
+        let drift = DriftDetection::detect("test.table", &expected, &actual, None);
+
+        assert_eq!(drift.diagnostics.len(), 2);
+        assert!(drift.diagnostics.iter().all(|d| d.code == DiagnosticCode::DriftDefaultChanged));
+        assert!(drift.diagnostics.iter().all(|d| d.severity == Severity::Info));
+        assert_eq!(drift.diagnostics[0].expected.as_deref(), Some("'active'"));
+        assert_eq!(drift.diagnostics[0].actual.as_deref(), Some("'pending'"));
+        assert_eq!(drift.diagnostics[1].expected.as_deref(), Some("no default"));
+        assert_eq!(drift.diagnostics[1].actual.as_deref(), Some("''"));
+    }
+
+    #[test]
+    fn test_structured_types_compare_by_shape() {
+        let tags = |element: LogicalType| Column::new("tags", LogicalType::Array { element_type: Box::new(element) });
+        let payload = |fields: Vec<Column>| Column::new("payload", LogicalType::Struct { fields });
+
+        // Undeclared struct fields and unknown array elements match any shape
+        let expected = Schema::from_columns(vec![tags(LogicalType::Unknown), payload(vec![])]);
+        let actual = Schema::from_columns(vec![
+            tags(LogicalType::Int),
+            payload(vec![Column::new("id", LogicalType::Int)]),
+        ]);
+        assert!(DriftDetection::detect("t", &expected, &actual, None).diagnostics.is_empty());
+
+        // Declared shapes must agree
+        let expected = Schema::from_columns(vec![
+            tags(LogicalType::String),
+            payload(vec![Column::new("id", LogicalType::Int)]),
+        ]);
+        let drift = DriftDetection::detect("t", &expected, &actual, None);
+        let codes: Vec<_> = drift.diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(codes, vec![DiagnosticCode::DriftTypeChange]);
+    }
 }
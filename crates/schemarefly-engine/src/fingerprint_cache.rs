@@ -0,0 +1,175 @@
+//! Persisted schema fingerprints for skipping unchanged drift comparisons
+//!
+//! After a drift run, every table whose warehouse schema matched its contract
+//! is recorded with a fingerprint of the (contract, warehouse) schema pair
+//! and the comparison options. On the next run, a table whose pair
+//! fingerprint is unchanged is known to still have no drift, so the
+//! comparison can be skipped. Tables that had
+//! drift are never recorded and are always compared again.
+
+use crate::CompareOptions;
+use schemarefly_core::Schema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Fingerprints of tables that had no drift on the last run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FingerprintCache {
+    /// Table identifier -> fingerprint of the (expected, actual) schema pair
+    entries: BTreeMap<String, u64>,
+}
+
+impl FingerprintCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the cache from a file
+    ///
+    /// A missing or unreadable cache file yields an empty cache, so the worst
+    /// case is that every table is compared again.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the cache to a file, creating parent directories as needed
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+
+    /// Fingerprint of an (expected, actual) schema pair compared under `options`
+    pub fn pair_fingerprint(expected: &Schema, actual: &Schema, options: &CompareOptions) -> u64 {
+        expected.fingerprint().rotate_left(1) ^ actual.fingerprint() ^ options.drift_fingerprint().rotate_left(2)
+    }
+
+    /// Whether the table was clean last run and neither schema nor options have changed since
+    pub fn is_unchanged(&self, table_id: &str, expected: &Schema, actual: &Schema, options: &CompareOptions) -> bool {
+        self.entries.get(table_id) == Some(&Self::pair_fingerprint(expected, actual, options))
+    }
+
+    /// Record the outcome of comparing a table
+    ///
+    /// Clean tables are remembered; tables with drift are forgotten so they
+    /// are compared (and reported) again next run.
+    pub fn record(&mut self, table_id: &str, expected: &Schema, actual: &Schema, options: &CompareOptions, has_drift: bool) {
+        if has_drift {
+            self.entries.remove(table_id);
+        } else {
+            self.entries.insert(table_id.to_string(), Self::pair_fingerprint(expected, actual, options));
+        }
+    }
+
+    /// Number of tables in the cache
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemarefly_core::{Column, LogicalType};
+
+    fn schema(amount_type: LogicalType) -> Schema {
+        Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("amount", amount_type),
+        ])
+    }
+
+    #[test]
+    fn unchanged_clean_table_is_skipped() {
+        let expected = schema(LogicalType::Int);
+        let options = CompareOptions::new();
+        let mut cache = FingerprintCache::new();
+        cache.record("db.sch.orders", &expected, &expected, &options, false);
+
+        assert!(cache.is_unchanged("db.sch.orders", &expected, &expected, &options));
+        assert!(!cache.is_unchanged("db.sch.orders", &expected, &schema(LogicalType::Float), &options));
+        assert!(!cache.is_unchanged("db.sch.other", &expected, &expected, &options));
+    }
+
+    #[test]
+    fn tables_with_drift_are_not_cached() {
+        let expected = schema(LogicalType::Int);
+        let actual = schema(LogicalType::Float);
+        let options = CompareOptions::new();
+        let mut cache = FingerprintCache::new();
+
+        cache.record("db.sch.orders", &expected, &expected, &options, false);
+        cache.record("db.sch.orders", &expected, &actual, &options, true);
+
+        assert!(cache.is_empty());
+        assert!(!cache.is_unchanged("db.sch.orders", &expected, &actual, &options));
+    }
+
+    #[test]
+    fn changed_options_or_column_details_are_compared_again() {
+        let expected = schema(LogicalType::Int);
+        let options = CompareOptions::new();
+        let mut cache = FingerprintCache::new();
+        cache.record("db.sch.orders", &expected, &expected, &options, false);
+
+        let ignoring = CompareOptions::new().with_ignore_columns(["_loaded_at"]);
+        assert!(!cache.is_unchanged("db.sch.orders", &expected, &expected, &ignoring));
+        let integral = CompareOptions::new().with_integral_decimal_as_int(true);
+        assert!(!cache.is_unchanged("db.sch.orders", &expected, &expected, &integral));
+
+        let mut defaulted = expected.clone();
+        defaulted.columns[1].default = Some("0".to_string());
+        defaulted.columns[1].has_default = Some(true);
+        assert!(!cache.is_unchanged("db.sch.orders", &expected, &defaulted, &options));
+
+        let mut generated = expected.clone();
+        generated.columns[1].is_generated = Some(true);
+        assert!(!cache.is_unchanged("db.sch.orders", &expected, &generated, &options));
+    }
+
+    #[test]
+    fn time_zone_change_is_compared_again() {
+        let options = CompareOptions::new().with_timezone_aware(true);
+        let mut expected = schema(LogicalType::Timestamp);
+        expected.columns[1].time_zone = Some(true);
+        let mut cache = FingerprintCache::new();
+        cache.record("db.sch.orders", &expected, &expected, &options, false);
+        assert!(cache.is_unchanged("db.sch.orders", &expected, &expected, &options));
+
+        let mut naive = expected.clone();
+        naive.columns[1].time_zone = Some(false);
+        assert!(!cache.is_unchanged("db.sch.orders", &expected, &naive, &options));
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("target").join("fingerprints.json");
+        let expected = schema(LogicalType::Int);
+
+        let options = CompareOptions::new();
+        let mut cache = FingerprintCache::new();
+        cache.record("db.sch.orders", &expected, &expected, &options, false);
+        cache.save(&path).unwrap();
+
+        let loaded = FingerprintCache::load(&path);
+        assert_eq!(loaded, cache);
+        assert!(FingerprintCache::load(&dir.path().join("missing.json")).is_empty());
+    }
+}
@@ -2,15 +2,38 @@
 //!
 //! This crate implements the main business logic for SchemaRefly:
 //! - Contract diff engine
+//! - Contract-required policy
 //! - Schema validation
 //! - Drift detection
+//! - View definition drift
+//! - Unit test fixture validation
+//! - Check opt-outs from dbt `meta`
+//! - Fingerprint cache for skipping unchanged drift comparisons
 //! - State comparison for Slim CI
+//! - Contract comparison between model versions
+//! - Git-based selection of changed models
 //! - Report generation
 
 pub mod contract_diff;
+pub mod contract_policy;
 pub mod drift_detector;
+pub mod view_definition;
+pub mod unit_test_check;
+pub mod meta_policy;
+pub mod fingerprint_cache;
 pub mod state_comparison;
+pub mod options;
+pub mod changed_models;
+pub mod version_diff;
 
 pub use contract_diff::ContractDiff;
+pub use contract_policy::ContractPolicy;
+pub use options::CompareOptions;
+pub use changed_models::ChangedModels;
+pub use version_diff::{VersionDiff, VersionChange};
 pub use drift_detector::DriftDetection;
+pub use view_definition::{detect_view_definition_drift, normalize_view_sql};
+pub use unit_test_check::UnitTestCheck;
+pub use meta_policy::MetaPolicy;
+pub use fingerprint_cache::FingerprintCache;
 pub use state_comparison::{StateComparison, StateComparisonResult, ModifiedModel, ModificationReason};
@@ -0,0 +1,155 @@
+//! Governance flags from dbt `meta`
+//!
+//! Models and columns can opt out of checks next to their definition:
+//! `meta: {schemarefly: {ignore_drift: true}}` or `ignore_contract: true`.
+//! A flagged model is skipped entirely; a flagged column is removed from
+//! both schemas before they are compared, so it produces no findings.
+
+use schemarefly_core::{IdentifierCase, Schema};
+use schemarefly_dbt::{ManifestNode, SchemaReflyMeta};
+
+/// `meta`-based opt-outs of one model
+pub struct MetaPolicy<'a> {
+    node: &'a ManifestNode,
+}
+
+impl<'a> MetaPolicy<'a> {
+    /// Policy declared on `node` and its columns
+    pub fn for_node(node: &'a ManifestNode) -> Self {
+        Self { node }
+    }
+
+    /// Whether the whole model is excluded from drift detection
+    pub fn skips_drift(&self) -> bool {
+        self.node.schemarefly_meta().ignore_drift
+    }
+
+    /// Whether the whole model is excluded from contract checks
+    pub fn skips_contract(&self) -> bool {
+        self.node.schemarefly_meta().ignore_contract
+    }
+
+    /// `schema` without the columns flagged `ignore_drift`
+    pub fn drift_schema(&self, schema: &Schema, case: IdentifierCase) -> Schema {
+        self.without_flagged(schema, case, |meta| meta.ignore_drift)
+    }
+
+    /// `schema` without the columns flagged `ignore_contract`
+    pub fn contract_schema(&self, schema: &Schema, case: IdentifierCase) -> Schema {
+        self.without_flagged(schema, case, |meta| meta.ignore_contract)
+    }
+
+    fn without_flagged(
+        &self,
+        schema: &Schema,
+        case: IdentifierCase,
+        flagged: impl Fn(&SchemaReflyMeta) -> bool,
+    ) -> Schema {
+        let ignored: Vec<&str> = self
+            .node
+            .columns
+            .values()
+            .filter(|col| flagged(&col.schemarefly_meta()))
+            .map(|col| col.name.as_str())
+            .collect();
+        if ignored.is_empty() {
+            return schema.clone();
+        }
+
+        let mut schema = schema.clone();
+        schema.columns.retain(|col| !ignored.iter().any(|name| case.matches(name, &col.name)));
+        schema
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DriftDetection;
+    use schemarefly_core::{Column, DiagnosticCode, LogicalType};
+    use schemarefly_dbt::Manifest;
+
+    fn manifest() -> Manifest {
+        Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.shop.users": {
+                    "unique_id": "model.shop.users", "name": "users", "resource_type": "model",
+                    "package_name": "shop", "path": "users.sql", "original_file_path": "models/users.sql",
+                    "config": {"contract": {"enforced": true}},
+                    "columns": {
+                        "id": {"name": "id", "data_type": "int"},
+                        "email": {"name": "email", "data_type": "varchar"},
+                        "synced_at": {
+                            "name": "synced_at", "data_type": "timestamp",
+                            "meta": {"schemarefly": {"ignore_drift": true}}
+                        }
+                    }
+                },
+                "model.shop.legacy": {
+                    "unique_id": "model.shop.legacy", "name": "legacy", "resource_type": "model",
+                    "package_name": "shop", "path": "legacy.sql", "original_file_path": "models/legacy.sql",
+                    "meta": {"owner": "data", "schemarefly": {"ignore_contract": true}}
+                },
+                "model.shop.orders": {
+                    "unique_id": "model.shop.orders", "name": "orders", "resource_type": "model",
+                    "package_name": "shop", "path": "orders.sql", "original_file_path": "models/orders.sql",
+                    "meta": {"schemarefly": {"ignore_contract": true}},
+                    "config": {"meta": {"schemarefly": {"ignore_drift": true, "ignore_contract": false}}}
+                }
+            },
+            "sources": {}
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn column_meta_suppresses_only_its_drift_findings() {
+        let manifest = manifest();
+        let node = manifest.get_node("model.shop.users").unwrap();
+        let policy = MetaPolicy::for_node(node);
+        assert!(!policy.skips_drift());
+
+        let expected = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("email", LogicalType::String),
+            Column::new("synced_at", LogicalType::Timestamp),
+        ]);
+        // Both `email` and `synced_at` changed type in the warehouse
+        let actual = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("email", LogicalType::Int),
+            Column::new("synced_at", LogicalType::String),
+        ]);
+
+        let case = IdentifierCase::Sensitive;
+        let drift = DriftDetection::detect(
+            "db.analytics.users",
+            &policy.drift_schema(&expected, case),
+            &policy.drift_schema(&actual, case),
+            None,
+        );
+
+        assert_eq!(drift.diagnostics.len(), 1);
+        assert_eq!(drift.diagnostics[0].code, DiagnosticCode::DriftTypeChange);
+        assert!(drift.diagnostics[0].message.contains("'email'"));
+
+        // The column still takes part in contract checks
+        assert_eq!(policy.contract_schema(&expected, case).columns.len(), 3);
+    }
+
+    #[test]
+    fn model_meta_skips_the_whole_check() {
+        let manifest = manifest();
+        let legacy = MetaPolicy::for_node(manifest.get_node("model.shop.legacy").unwrap());
+        assert!(legacy.skips_contract());
+        assert!(!legacy.skips_drift());
+    }
+
+    #[test]
+    fn config_meta_is_merged_over_meta() {
+        let manifest = manifest();
+        let orders = MetaPolicy::for_node(manifest.get_node("model.shop.orders").unwrap());
+        assert!(orders.skips_drift());
+        assert!(!orders.skips_contract());
+    }
+}
@@ -0,0 +1,139 @@
+//! Options shared by the contract diff and drift detection engines
+
+use schemarefly_core::config::glob_match;
+use schemarefly_core::{Config, DiagnosticCode, IdentifierCase, Severity};
+
+/// Options controlling how schemas are compared
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompareOptions {
+    /// How column names are matched between the two schemas
+    pub identifier_case: IdentifierCase,
+
+    /// Also report when shared columns appear in a different order than the contract
+    pub enforce_column_order: bool,
+
+    /// Report models where fewer contract columns than this fraction infer to a concrete type
+    pub min_inference_coverage: Option<f64>,
+
+    /// Severity of the low-coverage diagnostic (a warning when unset)
+    pub low_coverage_severity: Option<Severity>,
+
+    /// Glob patterns of warehouse-managed columns left out of drift findings
+    pub ignore_columns: Vec<String>,
+
+    /// Treat a timestamp gaining or losing its time zone as a type change
+    pub timezone_aware: bool,
+
+    /// Treat decimals with scale 0 and integers as the same type in drift findings
+    pub integral_decimal_as_int: bool,
+
+    /// Leave nullability and default changes out of drift findings
+    /// (for warehouses whose adapter does not report constraints)
+    pub ignore_constraints: bool,
+}
+
+impl CompareOptions {
+    /// Create default options (exact, case-sensitive matching)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Derive options from project configuration
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            identifier_case: config.dialect.identifier_case(),
+            enforce_column_order: config.contract.enforce_column_order,
+            min_inference_coverage: config.contract.min_inference_coverage,
+            low_coverage_severity: Some(
+                config.severity.get_severity(DiagnosticCode::SqlInferenceLowCoverage, Severity::Warn),
+            ),
+            ignore_columns: config.drift.ignore_columns.clone(),
+            timezone_aware: config.drift.timezone_aware,
+            integral_decimal_as_int: config.drift.treat_integral_decimal_as_int,
+            ignore_constraints: false,
+        }
+    }
+
+    /// Set the identifier case policy
+    pub fn with_identifier_case(mut self, identifier_case: IdentifierCase) -> Self {
+        self.identifier_case = identifier_case;
+        self
+    }
+
+    /// Require column order to match the contract
+    pub fn with_enforce_column_order(mut self, enforce_column_order: bool) -> Self {
+        self.enforce_column_order = enforce_column_order;
+        self
+    }
+
+    /// Leave columns matching these glob patterns out of drift findings
+    pub fn with_ignore_columns(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.ignore_columns = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Report `timestamp` ↔ `timestamptz` changes as type drift
+    pub fn with_timezone_aware(mut self, timezone_aware: bool) -> Self {
+        self.timezone_aware = timezone_aware;
+        self
+    }
+
+    /// Match `DECIMAL(p,0)` with integer columns
+    pub fn with_integral_decimal_as_int(mut self, integral_decimal_as_int: bool) -> Self {
+        self.integral_decimal_as_int = integral_decimal_as_int;
+        self
+    }
+
+    /// Skip nullability and default comparisons
+    pub fn with_ignore_constraints(mut self, ignore_constraints: bool) -> Self {
+        self.ignore_constraints = ignore_constraints;
+        self
+    }
+
+    /// Whether a column matches one of the `ignore_columns` patterns (case-insensitively)
+    pub fn is_column_ignored(&self, column: &str) -> bool {
+        let column = column.to_lowercase();
+        self.ignore_columns
+            .iter()
+            .any(|pattern| glob_match(&pattern.to_lowercase(), &column))
+    }
+
+    /// Set the minimum inferred coverage a model must reach
+    pub fn with_min_inference_coverage(mut self, min_inference_coverage: f64) -> Self {
+        self.min_inference_coverage = Some(min_inference_coverage);
+        self
+    }
+
+    /// Report models below the minimum coverage with this severity
+    pub fn with_low_coverage_severity(mut self, severity: Severity) -> Self {
+        self.low_coverage_severity = Some(severity);
+        self
+    }
+
+    /// Stable fingerprint of the options that affect drift findings
+    ///
+    /// Part of the persisted drift fingerprint, so a cached clean result is
+    /// not reused after the comparison rules change.
+    pub fn drift_fingerprint(&self) -> u64 {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{:?}", self.identifier_case).as_bytes());
+        hasher.update([
+            0,
+            self.enforce_column_order as u8,
+            self.timezone_aware as u8,
+            self.integral_decimal_as_int as u8,
+            self.ignore_constraints as u8,
+        ]);
+        for pattern in &self.ignore_columns {
+            hasher.update([0]);
+            hasher.update(pattern.as_bytes());
+        }
+
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        u64::from_be_bytes(bytes)
+    }
+}
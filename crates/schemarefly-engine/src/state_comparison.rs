@@ -283,7 +283,7 @@ impl StateComparison {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use schemarefly_dbt::{ManifestMetadata, NodeConfig, DependsOn, ColumnDefinition, ContractConfig};
+    use schemarefly_dbt::{ManifestMetadata, NodeConfig, DependsOn};
     use std::collections::HashMap;
 
     fn create_test_manifest(models: Vec<(&str, &str, Vec<&str>)>) -> Manifest {
@@ -299,16 +299,23 @@ mod tests {
                     package_name: "test".to_string(),
                     path: format!("models/{}.sql", name),
                     original_file_path: format!("models/{}.sql", name),
+                    patch_path: None,
                     database: Some("db".to_string()),
                     schema: Some("schema".to_string()),
                     alias: None,
                     config: NodeConfig::default(),
                     description: String::new(),
                     columns: HashMap::new(),
+                    meta: HashMap::new(),
                     depends_on: DependsOn {
                         nodes: deps.into_iter().map(String::from).collect(),
                     },
                     fqn: vec![name.to_string()],
+                    compiled_code: None,
+                    version: None,
+                    test_metadata: None,
+                    column_name: None,
+                    attached_node: None,
                 },
             );
         }
@@ -324,6 +331,7 @@ mod tests {
             sources: HashMap::new(),
             parent_map: HashMap::new(),
             child_map: HashMap::new(),
+            unit_tests: HashMap::new(),
         }
     }
 
@@ -0,0 +1,119 @@
+//! Unit test fixture validation
+//!
+//! dbt 1.8 unit tests compare a model's output against an `expect` fixture.
+//! A fixture column the model does not produce makes the test fail at run
+//! time, so it is reported up front as `UNIT_TEST_SCHEMA_MISMATCH`. Fixtures
+//! may list a subset of the model's columns, so missing columns are fine.
+
+use schemarefly_core::{Diagnostic, DiagnosticCode, IdentifierCase, Location, Schema, Severity};
+use schemarefly_dbt::{Manifest, ManifestNode, ManifestUnitTest};
+
+/// Checks unit test `expect` fixtures against a model schema
+pub struct UnitTestCheck;
+
+impl UnitTestCheck {
+    /// Diagnostic for `test` if its expected rows use columns not in `schema`
+    ///
+    /// Column names are compared case-insensitively. Fixtures whose columns
+    /// are not in the manifest (`sql` format, fixture files) are skipped.
+    pub fn check_test(test: &ManifestUnitTest, model_name: &str, schema: &Schema) -> Option<Diagnostic> {
+        let expected = test.expect.column_names()?;
+
+        let unknown: Vec<&str> = expected
+            .iter()
+            .filter(|name| schema.find_column_with(name, IdentifierCase::Insensitive).is_none())
+            .map(String::as_str)
+            .collect();
+        if unknown.is_empty() {
+            return None;
+        }
+
+        let mut diag = Diagnostic::new(
+            DiagnosticCode::UnitTestSchemaMismatch,
+            Severity::Error,
+            format!(
+                "Unit test '{}' expects column(s) not in model '{}': {}",
+                test.name,
+                model_name,
+                unknown.join(", ")
+            ),
+        )
+        .with_comparison(schema.column_names().join(", "), expected.join(", "));
+
+        if !test.original_file_path.is_empty() {
+            diag = diag.with_location(Location::new(&test.original_file_path));
+        }
+
+        Some(diag)
+    }
+
+    /// Diagnostics for every unit test of `model`, checked against `schema`
+    pub fn check_model(manifest: &Manifest, model: &ManifestNode, schema: &Schema) -> Vec<Diagnostic> {
+        manifest
+            .unit_tests_for(model)
+            .into_iter()
+            .filter_map(|test| Self::check_test(test, &model.name, schema))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemarefly_dbt::ContractExtractor;
+
+    fn manifest() -> Manifest {
+        Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.shop.orders": {
+                    "unique_id": "model.shop.orders", "name": "orders", "resource_type": "model",
+                    "package_name": "shop", "path": "orders.sql", "original_file_path": "models/orders.sql",
+                    "config": {"contract": {"enforced": true}},
+                    "columns": {
+                        "order_id": {"name": "order_id", "data_type": "int"},
+                        "amount": {"name": "amount", "data_type": "numeric(10,2)"}
+                    }
+                }
+            },
+            "sources": {},
+            "unit_tests": {
+                "unit_test.shop.orders.test_amounts": {
+                    "unique_id": "unit_test.shop.orders.test_amounts", "name": "test_amounts", "model": "orders",
+                    "original_file_path": "models/orders.yml",
+                    "given": [{"input": "ref('stg_orders')", "rows": [{"id": 1, "cents": 150}], "format": "dict"}],
+                    "expect": {"rows": [{"order_id": 1, "amount": 1.5}, {"order_id": 2, "total": 2.0}], "format": "dict"},
+                    "depends_on": {"nodes": ["model.shop.orders"]}
+                },
+                "unit_test.shop.orders.test_ids": {
+                    "unique_id": "unit_test.shop.orders.test_ids", "name": "test_ids", "model": "orders",
+                    "expect": {"rows": "ORDER_ID\n1\n2", "format": "csv"},
+                    "depends_on": {"nodes": ["model.shop.orders"]}
+                },
+                "unit_test.shop.orders.test_sql": {
+                    "unique_id": "unit_test.shop.orders.test_sql", "name": "test_sql", "model": "orders",
+                    "expect": {"rows": "select 1 as nope", "format": "sql"},
+                    "depends_on": {"nodes": ["model.shop.orders"]}
+                }
+            }
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn expect_columns_outside_the_contract_are_reported() {
+        let manifest = manifest();
+        let model = manifest.get_node("model.shop.orders").unwrap();
+        let contract = ContractExtractor::extract_from_node(model).unwrap();
+
+        assert_eq!(manifest.unit_tests_for(model).len(), 3);
+
+        let diagnostics = UnitTestCheck::check_model(&manifest, model, &contract.schema);
+        assert_eq!(diagnostics.len(), 1);
+        let diag = &diagnostics[0];
+        assert_eq!(diag.code, DiagnosticCode::UnitTestSchemaMismatch);
+        assert_eq!(diag.severity, Severity::Error);
+        assert_eq!(diag.message, "Unit test 'test_amounts' expects column(s) not in model 'orders': total");
+        assert_eq!(diag.actual.as_deref(), Some("amount, order_id, total"));
+        assert_eq!(diag.location.as_ref().unwrap().file, "models/orders.yml");
+    }
+}
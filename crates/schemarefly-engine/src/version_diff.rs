@@ -0,0 +1,272 @@
+//! Contract comparison between versions of a dbt model
+//!
+//! dbt model versions (`users` v1 and v2) let a team evolve a model while
+//! consumers migrate. A new version should be a compatible evolution of the
+//! old one: removing a column, narrowing a type or dropping a NOT NULL
+//! guarantee breaks consumers, while added columns and widened types do not.
+
+use schemarefly_core::{IdentifierCase, LogicalType, Nullability, Schema};
+use std::fmt;
+
+/// One column-level difference between two versions' contracts
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionChange {
+    /// Column present in the old version only (breaking)
+    ColumnRemoved { column: String },
+
+    /// Column present in the new version only (additive)
+    ColumnAdded { column: String },
+
+    /// Type changed to one that cannot hold every old value (breaking)
+    TypeNarrowed { column: String, from: LogicalType, to: LogicalType },
+
+    /// Type changed to one that holds every old value (additive)
+    TypeWidened { column: String, from: LogicalType, to: LogicalType },
+
+    /// NOT NULL column became nullable (breaking)
+    NullabilityLoosened { column: String },
+
+    /// Nullable column became NOT NULL (additive)
+    NullabilityTightened { column: String },
+}
+
+impl VersionChange {
+    /// Whether consumers of the old version may break
+    pub fn is_breaking(&self) -> bool {
+        matches!(
+            self,
+            Self::ColumnRemoved { .. } | Self::TypeNarrowed { .. } | Self::NullabilityLoosened { .. }
+        )
+    }
+}
+
+impl fmt::Display for VersionChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ColumnRemoved { column } => write!(f, "Column '{}' was removed", column),
+            Self::ColumnAdded { column } => write!(f, "Column '{}' was added", column),
+            Self::TypeNarrowed { column, from, to } => {
+                write!(f, "Column '{}' type narrowed: {} -> {}", column, from, to)
+            }
+            Self::TypeWidened { column, from, to } => {
+                write!(f, "Column '{}' type widened: {} -> {}", column, from, to)
+            }
+            Self::NullabilityLoosened { column } => write!(f, "Column '{}' is no longer NOT NULL", column),
+            Self::NullabilityTightened { column } => write!(f, "Column '{}' is now NOT NULL", column),
+        }
+    }
+}
+
+/// Result of comparing the contracts of two versions of a model
+#[derive(Debug, Clone)]
+pub struct VersionDiff {
+    /// Model name
+    pub model: String,
+
+    /// Old version
+    pub from_version: String,
+
+    /// New version
+    pub to_version: String,
+
+    /// Column-level changes, breaking and additive
+    pub changes: Vec<VersionChange>,
+}
+
+impl VersionDiff {
+    /// Compare the contract of `from_version` with that of `to_version`
+    pub fn compare(
+        model: impl Into<String>,
+        from_version: impl Into<String>,
+        to_version: impl Into<String>,
+        from: &Schema,
+        to: &Schema,
+        case: IdentifierCase,
+    ) -> Self {
+        let diff = from.diff_with(to, case, |a, b| a == b);
+        let mut changes = Vec::new();
+
+        for column in &diff.removed {
+            changes.push(VersionChange::ColumnRemoved { column: column.name.clone() });
+        }
+
+        for change in &diff.type_changed {
+            let column = change.before.name.clone();
+            let from = change.before.logical_type.clone();
+            let to = change.after.logical_type.clone();
+            changes.push(if is_widening(&from, &to) {
+                VersionChange::TypeWidened { column, from, to }
+            } else {
+                VersionChange::TypeNarrowed { column, from, to }
+            });
+        }
+
+        for change in &diff.nullability_changed {
+            let column = change.before.name.clone();
+            changes.push(match change.after.nullable {
+                Nullability::No => VersionChange::NullabilityTightened { column },
+                _ => VersionChange::NullabilityLoosened { column },
+            });
+        }
+
+        for column in &diff.added {
+            changes.push(VersionChange::ColumnAdded { column: column.name.clone() });
+        }
+
+        Self {
+            model: model.into(),
+            from_version: from_version.into(),
+            to_version: to_version.into(),
+            changes,
+        }
+    }
+
+    /// Changes that may break consumers of the old version
+    pub fn breaking(&self) -> impl Iterator<Item = &VersionChange> {
+        self.changes.iter().filter(|c| c.is_breaking())
+    }
+
+    /// Changes that keep the old version's consumers working
+    pub fn additive(&self) -> impl Iterator<Item = &VersionChange> {
+        self.changes.iter().filter(|c| !c.is_breaking())
+    }
+
+    /// Whether any change is breaking
+    pub fn has_breaking(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+}
+
+/// Decimal digits of the largest integer (a 64-bit `BIGINT`)
+const INT_DIGITS: u16 = 19;
+
+/// Whether every value of type `from` can be represented as `to`
+fn is_widening(from: &LogicalType, to: &LogicalType) -> bool {
+    match (from, to) {
+        // A float's 53-bit mantissa cannot hold every integer exactly
+        (LogicalType::Int, LogicalType::Decimal { precision: None, .. }) => true,
+        (LogicalType::Int, LogicalType::Decimal { precision: Some(p), scale }) => {
+            p.saturating_sub(scale.unwrap_or(0)) >= INT_DIGITS
+        }
+        (LogicalType::Decimal { .. }, LogicalType::Float) => true,
+        (LogicalType::Date, LogicalType::Timestamp) => true,
+
+        // Both the integer and fractional digits must fit
+        (
+            LogicalType::Decimal { precision: Some(p1), scale: s1 },
+            LogicalType::Decimal { precision: Some(p2), scale: s2 },
+        ) => {
+            let (s1, s2) = (s1.unwrap_or(0), s2.unwrap_or(0));
+            s1 <= s2 && p1.saturating_sub(s1) <= p2.saturating_sub(s2)
+        }
+
+        // Dropping a declared precision removes no values
+        (LogicalType::Decimal { .. }, LogicalType::Decimal { precision: None, .. }) => true,
+
+        (LogicalType::Array { element_type: e1 }, LogicalType::Array { element_type: e2 }) => is_widening(e1, e2),
+
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemarefly_dbt::{ContractExtractor, Manifest};
+
+    /// A manifest with `users` v1 and v2, where v2 drops `email`, adds
+    /// `region` and widens `score` from INT to NUMERIC(21,2)
+    fn versioned_manifest() -> Manifest {
+        let node = |version: u32, columns: &str| {
+            format!(
+                r#""model.shop.users.v{version}": {{
+                    "unique_id": "model.shop.users.v{version}",
+                    "name": "users",
+                    "resource_type": "model",
+                    "package_name": "shop",
+                    "path": "users_v{version}.sql",
+                    "original_file_path": "models/users_v{version}.sql",
+                    "version": {version},
+                    "config": {{"contract": {{"enforced": true}}}},
+                    "columns": {{{columns}}}
+                }}"#
+            )
+        };
+        let column = |name: &str, data_type: &str| {
+            format!(r#""{name}": {{"name": "{name}", "data_type": "{data_type}"}}"#)
+        };
+
+        let v1 = node(1, &[column("id", "int"), column("email", "varchar"), column("score", "int")].join(","));
+        let v2 = node(2, &[column("id", "int"), column("score", "numeric(21,2)"), column("region", "varchar")].join(","));
+
+        Manifest::from_str(&format!(
+            r#"{{
+                "metadata": {{"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"}},
+                "nodes": {{{v1}, {v2}}},
+                "sources": {{}}
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn removed_column_between_versions_is_breaking() {
+        let manifest = versioned_manifest();
+        let contract = |version| {
+            let node = manifest.find_model_version("users", version).unwrap();
+            ContractExtractor::extract_from_node(node).unwrap().schema
+        };
+
+        let diff = VersionDiff::compare("users", "v1", "v2", &contract("v1"), &contract("2"), IdentifierCase::Insensitive);
+
+        assert!(diff.has_breaking());
+        assert_eq!(
+            diff.breaking().collect::<Vec<_>>(),
+            vec![&VersionChange::ColumnRemoved { column: "email".to_string() }]
+        );
+
+        let additive: Vec<String> = diff.additive().map(|c| c.to_string()).collect();
+        assert_eq!(
+            additive,
+            vec![
+                "Column 'score' type widened: INT -> DECIMAL(21, 2)".to_string(),
+                "Column 'region' was added".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn narrowed_type_is_breaking() {
+        let from = Schema::from_columns(vec![schemarefly_core::Column::new(
+            "amount",
+            LogicalType::Decimal { precision: Some(12), scale: Some(2) },
+        )]);
+        let to = Schema::from_columns(vec![schemarefly_core::Column::new("amount", LogicalType::Int)]);
+
+        let diff = VersionDiff::compare("orders", "1", "2", &from, &to, IdentifierCase::Insensitive);
+        assert!(matches!(diff.changes.as_slice(), [VersionChange::TypeNarrowed { .. }]));
+        assert!(diff.has_breaking());
+    }
+
+    #[test]
+    fn int_widens_only_to_decimals_with_enough_integer_digits() {
+        let int = LogicalType::Int;
+        let decimal = |precision, scale| LogicalType::Decimal { precision: Some(precision), scale: Some(scale) };
+
+        assert!(is_widening(&int, &decimal(19, 0)));
+        assert!(is_widening(&int, &decimal(21, 2)));
+        assert!(is_widening(&int, &LogicalType::Decimal { precision: None, scale: None }));
+        assert!(!is_widening(&int, &decimal(12, 2)));
+        assert!(!is_widening(&int, &decimal(19, 1)));
+    }
+
+    #[test]
+    fn int_to_float_is_breaking() {
+        let from = Schema::from_columns(vec![schemarefly_core::Column::new("id", LogicalType::Int)]);
+        let to = Schema::from_columns(vec![schemarefly_core::Column::new("id", LogicalType::Float)]);
+
+        let diff = VersionDiff::compare("orders", "1", "2", &from, &to, IdentifierCase::Insensitive);
+        assert!(matches!(diff.changes.as_slice(), [VersionChange::TypeNarrowed { .. }]));
+        assert!(diff.has_breaking());
+    }
+}
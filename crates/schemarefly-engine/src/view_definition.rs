@@ -0,0 +1,336 @@
+//! View definition drift
+//!
+//! For views the warehouse stores the SQL the view was created with, which
+//! can drift from the model in the repo when someone edits the view by hand.
+//! Both sides are parsed and printed back in one canonical form so that
+//! formatting, comments, keyword case, identifier quoting and the rewrites
+//! PostgreSQL applies in `pg_get_viewdef` do not count as drift.
+
+use schemarefly_core::{Diagnostic, DiagnosticCode, Location, Severity};
+use sqlparser::ast::{
+    BinaryOperator, CastKind, DataType, Expr, FunctionArg, FunctionArgExpr, FunctionArguments, GroupByExpr, Ident, JoinConstraint,
+    JoinOperator, ObjectName, Query, Select, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins,
+};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+/// Normalize view SQL for comparison
+///
+/// A single query is parsed and canonicalized the way `pg_get_viewdef`
+/// prints it back: redundant parentheses, column qualifiers, database and
+/// schema prefixes, `::text`-style casts and literal casts are removed,
+/// `= ANY (ARRAY[...])` reads as `IN (...)`, and aliases that repeat the
+/// column name are dropped. The result, or the raw
+/// SQL when it does not parse, is then printed token by token: comments and
+/// whitespace are dropped (keeping one space between words), identifiers and
+/// keywords are lowercased and unquoted, and string literals are kept verbatim.
+pub fn normalize_view_sql(sql: &str) -> String {
+    let canonical = match Parser::parse_sql(&PostgreSqlDialect {}, sql) {
+        Ok(mut statements) if statements.len() == 1 => match &mut statements[0] {
+            Statement::Query(query) => {
+                canonicalize_query(query);
+                query.to_string()
+            }
+            _ => sql.to_string(),
+        },
+        _ => sql.to_string(),
+    };
+
+    print_tokens(&canonical)
+}
+
+/// Compare the warehouse's view SQL with the model's compiled SQL
+///
+/// Returns a warning when the normalized definitions differ.
+pub fn detect_view_definition_drift(
+    table_id: &str,
+    warehouse_sql: &str,
+    model_sql: &str,
+    file_path: Option<&str>,
+) -> Option<Diagnostic> {
+    let expected = normalize_view_sql(model_sql);
+    let actual = normalize_view_sql(warehouse_sql);
+    if expected == actual {
+        return None;
+    }
+
+    Some(Diagnostic {
+        code: DiagnosticCode::DriftViewDefinitionChanged,
+        severity: Severity::Warn,
+        message: format!(
+            "View '{}' definition in the warehouse differs from the model's compiled SQL",
+            table_id
+        ),
+        location: file_path.map(Location::new),
+        expected: Some(expected),
+        actual: Some(actual),
+        impact: vec![],
+        related: vec![],
+        model_id: None,
+        column: None,
+        warehouse_type: None,
+    })
+}
+
+/// Print SQL token by token, with a space only between two words
+fn print_tokens(sql: &str) -> String {
+    let Ok(tokens) = Tokenizer::new(&PostgreSqlDialect {}, sql).tokenize() else {
+        return sql.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    };
+
+    let mut out = String::with_capacity(sql.len());
+    let mut prev_is_word = false;
+    for token in tokens {
+        let text = match token {
+            Token::Whitespace(_) | Token::SemiColon => continue,
+            Token::Word(word) => word.value.to_lowercase(),
+            Token::SingleQuotedString(value) => format!("'{}'", value.replace('\'', "''")),
+            other => other.to_string(),
+        };
+        let is_word = text.starts_with(|c: char| c.is_alphanumeric() || matches!(c, '_' | '\'' | '$'));
+        if prev_is_word && is_word {
+            out.push(' ');
+        }
+        out.push_str(&text);
+        prev_is_word = is_word;
+    }
+    out
+}
+
+fn canonicalize_query(query: &mut Query) {
+    if let Some(with) = &mut query.with {
+        for cte in &mut with.cte_tables {
+            canonicalize_query(&mut cte.query);
+        }
+    }
+    canonicalize_set_expr(&mut query.body);
+    if let Some(order_by) = &mut query.order_by {
+        for item in &mut order_by.exprs {
+            canonicalize_expr(&mut item.expr);
+        }
+    }
+}
+
+fn canonicalize_set_expr(body: &mut SetExpr) {
+    match body {
+        SetExpr::Select(select) => canonicalize_select(select),
+        SetExpr::Query(query) => canonicalize_query(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            canonicalize_set_expr(left);
+            canonicalize_set_expr(right);
+        }
+        _ => {}
+    }
+}
+
+fn canonicalize_select(select: &mut Select) {
+    for item in &mut select.projection {
+        match item {
+            SelectItem::UnnamedExpr(expr) => canonicalize_expr(expr),
+            SelectItem::ExprWithAlias { expr, alias } => {
+                canonicalize_expr(expr);
+                // `users.id AS id` is how the view stores a plain `id`
+                if derived_name(expr).is_some_and(|name| name.eq_ignore_ascii_case(&alias.value)) {
+                    *item = SelectItem::UnnamedExpr(expr.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    for table in &mut select.from {
+        canonicalize_table_with_joins(table);
+    }
+    for expr in select.selection.iter_mut().chain(select.having.iter_mut()) {
+        canonicalize_expr(expr);
+    }
+    if let GroupByExpr::Expressions(exprs, _) = &mut select.group_by {
+        exprs.iter_mut().for_each(canonicalize_expr);
+    }
+}
+
+fn canonicalize_table_with_joins(table: &mut TableWithJoins) {
+    // `(a JOIN b ON ...) JOIN c` is the same left-deep join as `a JOIN b ON ... JOIN c`
+    while let TableFactor::NestedJoin { table_with_joins, alias: None } = &table.relation {
+        let mut inner = (**table_with_joins).clone();
+        inner.joins.append(&mut table.joins);
+        *table = inner;
+    }
+
+    canonicalize_table_factor(&mut table.relation);
+    for join in &mut table.joins {
+        canonicalize_table_factor(&mut join.relation);
+        if let JoinOperator::Inner(JoinConstraint::On(expr))
+        | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+        | JoinOperator::RightOuter(JoinConstraint::On(expr))
+        | JoinOperator::FullOuter(JoinConstraint::On(expr)) = &mut join.join_operator
+        {
+            canonicalize_expr(expr);
+        }
+    }
+}
+
+fn canonicalize_table_factor(factor: &mut TableFactor) {
+    match factor {
+        // The view stores `schema.table` or just `table` depending on the search path
+        TableFactor::Table { name, .. } => {
+            if let Some(table) = name.0.pop() {
+                *name = ObjectName(vec![table]);
+            }
+        }
+        TableFactor::Derived { subquery, .. } => canonicalize_query(subquery),
+        TableFactor::NestedJoin { table_with_joins, .. } => canonicalize_table_with_joins(table_with_joins),
+        _ => {}
+    }
+}
+
+fn canonicalize_expr(expr: &mut Expr) {
+    match expr {
+        Expr::BinaryOp { left, right, .. } => {
+            canonicalize_expr(left);
+            canonicalize_expr(right);
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::IsTrue(expr)
+        | Expr::IsFalse(expr) => canonicalize_expr(expr),
+        Expr::AnyOp { left, right, .. } => {
+            canonicalize_expr(left);
+            canonicalize_expr(right);
+        }
+        Expr::Array(array) => array.elem.iter_mut().for_each(canonicalize_expr),
+        Expr::InList { expr, list, .. } => {
+            canonicalize_expr(expr);
+            list.iter_mut().for_each(canonicalize_expr);
+        }
+        Expr::Between { expr, low, high, .. } => {
+            canonicalize_expr(expr);
+            canonicalize_expr(low);
+            canonicalize_expr(high);
+        }
+        Expr::Like { expr, pattern, .. } | Expr::ILike { expr, pattern, .. } => {
+            canonicalize_expr(expr);
+            canonicalize_expr(pattern);
+        }
+        Expr::Case { operand, conditions, results, else_result } => {
+            operand.iter_mut().chain(else_result.iter_mut()).for_each(|e| canonicalize_expr(e));
+            conditions.iter_mut().chain(results.iter_mut()).for_each(canonicalize_expr);
+        }
+        Expr::Function(function) => {
+            if let FunctionArguments::List(list) = &mut function.args {
+                for arg in &mut list.args {
+                    if let FunctionArg::Unnamed(FunctionArgExpr::Expr(e)) | FunctionArg::Named { arg: FunctionArgExpr::Expr(e), .. } = arg {
+                        canonicalize_expr(e);
+                    }
+                }
+            }
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            canonicalize_expr(expr);
+            canonicalize_query(subquery);
+        }
+        Expr::Subquery(query) | Expr::Exists { subquery: query, .. } => canonicalize_query(query),
+        _ => {}
+    }
+
+    match expr {
+        Expr::Nested(inner) => *expr = (**inner).clone(),
+        Expr::CompoundIdentifier(parts) => {
+            if let Some(column) = parts.pop() {
+                *expr = Expr::Identifier(column);
+            }
+        }
+        // `x IN ('a', 'b')` is stored as `x = ANY ((ARRAY['a', 'b'])::text[])`
+        Expr::AnyOp { left, compare_op: BinaryOperator::Eq, right, is_some: false } => {
+            let array = match &**right {
+                Expr::Cast { expr, .. } => expr,
+                other => other,
+            };
+            if let Expr::Array(array) = array {
+                *expr = Expr::InList { expr: left.clone(), list: array.elem.clone(), negated: false };
+            }
+        }
+        // `'active'::text`, `(status)::text`: casts the view adds for type resolution
+        Expr::Cast { kind: CastKind::DoubleColon, expr: inner, data_type, format: None }
+            if matches!(**inner, Expr::Value(_)) || is_text_type(data_type) =>
+        {
+            *expr = (**inner).clone();
+        }
+        _ => {}
+    }
+}
+
+/// Whether the type is one of PostgreSQL's character types
+fn is_text_type(data_type: &DataType) -> bool {
+    matches!(data_type, DataType::Text | DataType::Varchar(_) | DataType::CharacterVarying(_))
+}
+
+/// Output column name of an unaliased expression, as PostgreSQL derives it
+fn derived_name(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Identifier(Ident { value, .. }) => Some(value),
+        Expr::Function(function) => function.name.0.last().map(|ident| ident.value.as_str()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalization_ignores_formatting_comments_case_and_quoting() {
+        let model = "-- active users only\nselect id,\n       \"Email\"\nfrom analytics.users\nwhere status = 'Active'  /* soft deletes */\n";
+        let warehouse = " SELECT id, email\n   FROM analytics.users\n  WHERE status = 'Active';";
+
+        assert_eq!(normalize_view_sql(model), "select id,email from users where status='Active'");
+        assert_eq!(normalize_view_sql(warehouse), normalize_view_sql(model));
+        assert_eq!(normalize_view_sql("select 'it''s  here' as x;"), "select 'it''s  here' as x");
+        assert_eq!(normalize_view_sql(r#"select "a""b" from t"#), normalize_view_sql(r#"SELECT "A""B" FROM t"#));
+    }
+
+    #[test]
+    fn matches_pg_get_viewdef_output() {
+        // Compiled model SQL and what `pg_get_viewdef` returns for the view built from it
+        let model = "select u.id, u.email, count(o.id) as order_count\n\
+                     from \"warehouse\".\"analytics\".\"users\" u\n\
+                     left join \"warehouse\".\"analytics\".\"orders\" o on o.user_id = u.id and o.status = 'paid'\n\
+                     where u.country in ('NL', 'BE') and u.deleted_at is null\n\
+                     group by u.id, u.email";
+        let warehouse = " SELECT u.id,\n    u.email,\n    count(o.id) AS order_count\n   \
+                         FROM (analytics.users u\n     \
+                         LEFT JOIN analytics.orders o ON (((o.user_id = u.id) AND ((o.status)::text = 'paid'::text))))\n  \
+                         WHERE (((u.country)::text = ANY ((ARRAY['NL'::character varying, 'BE'::character varying])::text[])) AND (u.deleted_at IS NULL))\n  \
+                         GROUP BY u.id, u.email;";
+        assert_eq!(normalize_view_sql(warehouse), normalize_view_sql(model));
+
+        let simple = " SELECT users.id,\n    users.email\n   FROM analytics.users\n  WHERE ((users.status)::text = 'active'::text);";
+        assert_eq!(
+            normalize_view_sql(simple),
+            normalize_view_sql("select id, email from analytics.users where status = 'active'")
+        );
+    }
+
+    #[test]
+    fn reports_changed_definition_only() {
+        let model = "select id, email from analytics.users";
+
+        assert!(detect_view_definition_drift("db.analytics.active_users", "SELECT id,\n  email FROM analytics.users;", model, None).is_none());
+
+        let diag = detect_view_definition_drift(
+            "db.analytics.active_users",
+            " SELECT users.id,\n    users.email,\n    users.phone\n   FROM analytics.users;",
+            model,
+            Some("models/active_users.sql"),
+        )
+        .unwrap();
+        assert_eq!(diag.code, DiagnosticCode::DriftViewDefinitionChanged);
+        assert_eq!(diag.severity, Severity::Warn);
+        assert_eq!(diag.expected.as_deref(), Some("select id,email from users"));
+        assert_eq!(diag.actual.as_deref(), Some("select id,email,phone from users"));
+        assert_eq!(diag.location.unwrap().file, "models/active_users.sql");
+    }
+}
@@ -3,9 +3,14 @@
 //! These benchmarks measure the performance of SchemaRefly's incremental
 //! computation system with large DAGs and complex dependencies.
 
+// `queries::infer_schema` returns a Diagnostic on failure
+#![allow(clippy::result_large_err)]
+
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use schemarefly_core::Config;
+use schemarefly_core::{Column, Config, LogicalType, Schema};
+use schemarefly_dbt::Manifest;
 use schemarefly_incremental::{queries, SchemaReflyDatabase};
+use schemarefly_sql::{InferenceContext, SchemaInference, SqlParser};
 use std::path::PathBuf;
 
 /// Generate a large manifest JSON with N models
@@ -81,6 +86,83 @@ fn generate_large_manifest(num_models: usize) -> String {
     )
 }
 
+/// Generate a manifest with N models plus the sections SchemaRefly never reads
+///
+/// Sections follow dbt's layout: `macros`, `docs` and `disabled` sit between
+/// `sources` and `parent_map`; `semantic_models` and `unit_tests` come last.
+fn generate_manifest_with_unused_sections(num_models: usize) -> String {
+    let code = "select id, amount from {{ ref('upstream') }} where amount > 0\n".repeat(20);
+    let entries = |prefix: &str, value: &serde_json::Value| {
+        (0..num_models)
+            .map(|i| format!(r#""{}.project.item_{}": {}"#, prefix, i, value))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+
+    let macro_json = serde_json::json!({"macro_sql": code, "arguments": [], "depends_on": {"macros": []}});
+    let doc_json = serde_json::json!({"block_contents": code});
+    let disabled_json = serde_json::json!([{"raw_code": code, "resource_type": "model"}]);
+    let semantic_json = serde_json::json!({"model": "ref('model_0')", "measures": [{"name": "total", "agg": "sum"}]});
+    let unit_test_json = serde_json::json!({"given": [{"input": "ref('model_0')", "rows": [{"id": 1}]}], "expect": {"rows": [{"id": 1}]}});
+
+    let manifest = generate_large_manifest(num_models);
+    let middle = format!(
+        r#""macros": {{{}}}, "docs": {{{}}}, "disabled": {{{}}}, "parent_map":"#,
+        entries("macro", &macro_json),
+        entries("doc", &doc_json),
+        entries("model", &disabled_json),
+    );
+    let tail = format!(
+        r#", "semantic_models": {{{}}}, "unit_tests": {{{}}}}}"#,
+        entries("semantic_model", &semantic_json),
+        entries("unit_test", &unit_test_json),
+    );
+
+    let manifest = manifest.replacen(r#""parent_map":"#, &middle, 1);
+    let end = manifest.rfind('}').unwrap();
+    format!("{}{}", &manifest[..end], tail)
+}
+
+/// Generate a manifest with N contracted models of `num_columns` columns each
+fn generate_contracted_manifest(num_models: usize, num_columns: usize) -> String {
+    let columns: serde_json::Map<String, serde_json::Value> = (0..num_columns)
+        .map(|c| {
+            let name = format!("col_{}", c);
+            (name.clone(), serde_json::json!({"name": name, "data_type": "int"}))
+        })
+        .collect();
+
+    let nodes: serde_json::Map<String, serde_json::Value> = (0..num_models)
+        .map(|i| {
+            let model_id = format!("model.project.model_{}", i);
+            let node = serde_json::json!({
+                "unique_id": model_id,
+                "name": format!("model_{}", i),
+                "resource_type": "model",
+                "package_name": "project",
+                "path": format!("models/model_{}.sql", i),
+                "original_file_path": format!("models/model_{}.sql", i),
+                "database": "analytics",
+                "schema": "dbt_prod",
+                "config": {"materialized": "table", "contract": {"enforced": true}},
+                "columns": columns,
+            });
+            (model_id, node)
+        })
+        .collect();
+
+    serde_json::json!({
+        "metadata": {
+            "dbt_schema_version": "https://schemas.getdbt.com/dbt/manifest/v10.json",
+            "dbt_version": "1.5.0",
+            "generated_at": "2024-01-01T00:00:00Z"
+        },
+        "nodes": nodes,
+        "sources": {}
+    })
+    .to_string()
+}
+
 /// Generate complex SQL for a model with N columns and joins
 fn generate_complex_sql(model_num: usize, num_columns: usize, num_joins: usize) -> String {
     let mut select_cols = Vec::new();
@@ -131,6 +213,23 @@ fn bench_manifest_parsing(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark: Full vs partial parse of a 5k-model manifest with unused sections
+fn bench_manifest_partial_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("manifest_partial_parsing");
+    group.sample_size(20);
+
+    let manifest_json = generate_manifest_with_unused_sections(5000);
+
+    group.bench_function("full", |b| {
+        b.iter(|| black_box(Manifest::from_str(&manifest_json).unwrap()))
+    });
+    group.bench_function("partial", |b| {
+        b.iter(|| black_box(Manifest::from_str_partial(&manifest_json).unwrap()))
+    });
+
+    group.finish();
+}
+
 /// Benchmark: SQL parsing with caching (measure cache hits)
 fn bench_sql_parsing_with_cache(c: &mut Criterion) {
     let mut group = c.benchmark_group("sql_parsing_cache");
@@ -194,6 +293,95 @@ fn bench_schema_inference(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark: Building the inference context for a large contracted project
+///
+/// Every model is registered under three names sharing one schema, so this
+/// tracks the allocations `bench_schema_inference` pays before inferring.
+/// Compare runs with `--save-baseline` / `--baseline`.
+///
+/// Interning the table names (an `Arc<str>` interner) was measured against
+/// plain `String` keys and dropped, since each name is inserted once and never
+/// shared. Medians, interned -> `String`:
+///
+/// | benchmark                | interned | `String`            |
+/// |--------------------------|----------|---------------------|
+/// | `inference_context/100`  | 626.5 µs | 453.0 µs            |
+/// | `inference_context/500`  | 2.898 ms | 2.574 ms            |
+/// | `inference_context/1000` | 5.883 ms | 4.989 ms            |
+/// | `schema_inference/10`    | 178.1 µs | 163.2 – 179.3 µs    |
+/// | `schema_inference/50`    | 360.1 µs | 405.3 – 472.7 µs    |
+/// | `schema_inference/100`   | 561.2 µs | 521.5 – 612.6 µs    |
+///
+/// `schema_inference` is dominated by parsing, so repeat runs land on both
+/// sides of either baseline.
+fn bench_inference_context(c: &mut Criterion) {
+    let mut group = c.benchmark_group("inference_context");
+
+    for num_models in [100, 500, 1000].iter() {
+        let manifest = Manifest::from_str(&generate_contracted_manifest(*num_models, 20)).unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_models),
+            num_models,
+            |b, _| {
+                b.iter(|| black_box(InferenceContext::from_manifest(&manifest)));
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Benchmark: Visiting every model once through `models()` vs `models_iter()`
+///
+/// `models()` copies each unique_id into a fresh map before the first node is
+/// visited; `models_iter()` borrows from the manifest and allocates nothing.
+fn bench_model_iteration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("model_iteration");
+
+    for num_models in [1000, 5000].iter() {
+        let manifest = Manifest::from_str(&generate_large_manifest(*num_models)).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("models", num_models), num_models, |b, _| {
+            b.iter(|| black_box(manifest.models().into_iter().filter(|(_, node)| !node.name.is_empty()).count()));
+        });
+
+        group.bench_with_input(BenchmarkId::new("models_iter", num_models), num_models, |b, _| {
+            b.iter(|| black_box(manifest.models_iter().filter(|(_, node)| !node.name.is_empty()).count()));
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmark: Inference of a SELECT projecting every column of a wide table
+fn bench_wide_select_inference(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wide_select_inference");
+
+    for num_columns in [100, 500, 1000].iter() {
+        let columns: Vec<Column> = (0..*num_columns)
+            .map(|i| Column::new(format!("col_{}", i), LogicalType::Int))
+            .collect();
+        let mut context = InferenceContext::new();
+        context.add_table("wide", Schema::from_columns(columns));
+
+        let projection: Vec<String> = (0..*num_columns).map(|i| format!("col_{}", i)).collect();
+        let sql = format!("SELECT {} FROM wide", projection.join(", "));
+        let parsed = SqlParser::new().parse(&sql, None).unwrap();
+        let statement = parsed.first_statement().unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_columns),
+            num_columns,
+            |b, _| {
+                b.iter(|| black_box(SchemaInference::new(&context).infer_statement(statement).unwrap()));
+            },
+        );
+    }
+
+    group.finish();
+}
+
 /// Benchmark: Incremental recomputation (modify one file in a DAG)
 fn bench_incremental_recomputation(c: &mut Criterion) {
     let mut group = c.benchmark_group("incremental_recomputation");
@@ -331,8 +519,12 @@ fn bench_cache_efficiency(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_manifest_parsing,
+    bench_manifest_partial_parsing,
     bench_sql_parsing_with_cache,
     bench_schema_inference,
+    bench_inference_context,
+    bench_model_iteration,
+    bench_wide_select_inference,
     bench_incremental_recomputation,
     bench_downstream_models,
     bench_contract_checking_end_to_end,
@@ -4,6 +4,7 @@
 //! time-to-live (TTL) expiration. This prevents redundant warehouse queries
 //! and speeds up drift detection.
 
+use crate::stats::{CacheStats, StatsCounter};
 use schemarefly_core::Schema;
 use schemarefly_catalog::TableIdentifier;
 use std::collections::HashMap;
@@ -56,6 +57,9 @@ pub struct WarehouseCache {
 
     /// Default TTL for cache entries
     default_ttl: Duration,
+
+    /// Hit/miss counters for `get`
+    counter: StatsCounter,
 }
 
 impl WarehouseCache {
@@ -68,6 +72,7 @@ impl WarehouseCache {
         Self {
             cache: Arc::new(RwLock::new(HashMap::new())),
             default_ttl: ttl,
+            counter: StatsCounter::default(),
         }
     }
 
@@ -111,12 +116,14 @@ impl WarehouseCache {
         if let Ok(cache) = self.cache.read() {
             if let Some(entry) = cache.get(&key) {
                 if entry.is_valid() {
+                    self.counter.hit();
                     return Some(Arc::clone(&entry.schema));
                 }
             }
         }
 
         // Entry doesn't exist or is expired - evict it
+        self.counter.miss();
         self.evict(table);
         None
     }
@@ -180,6 +187,15 @@ impl WarehouseCache {
     }
 }
 
+impl WarehouseCache {
+    /// Hit/miss counts for `get` lookups so far
+    ///
+    /// Expired entries count as misses.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.counter.snapshot()
+    }
+}
+
 impl Default for WarehouseCache {
     /// Create a cache with default TTL of 5 minutes
     fn default() -> Self {
@@ -306,4 +322,18 @@ mod tests {
         assert_eq!(valid, 0);
         assert_eq!(expired, 2);
     }
+
+    #[test]
+    fn test_cache_hit_miss_counters() {
+        let cache = WarehouseCache::new(Duration::from_secs(60));
+        let table = create_test_table();
+
+        assert!(cache.get(&table).is_none());
+        cache.insert(table.clone(), create_test_schema());
+        assert!(cache.get(&table).is_some());
+        assert!(cache.get(&table).is_some());
+
+        assert_eq!(cache.cache_stats(), CacheStats { hits: 2, misses: 1 });
+        assert_eq!(cache.cache_stats().lookups(), 3);
+    }
 }
@@ -4,6 +4,8 @@
 //! in SchemaRefly. The database tracks inputs (files, manifest, catalog) and
 //! provides tracked functions for derived computations.
 
+use crate::stats::{CacheStats, StatsCounter};
+
 /// Database trait for SchemaRefly incremental computation
 ///
 /// All tracked functions take `&dyn Db` as their first parameter.
@@ -33,9 +35,38 @@ pub trait Db: salsa::Database {}
 /// let schema = queries::infer_schema(&db, file, config, manifest);
 /// ```
 #[salsa::db]
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct SchemaReflyDatabase {
     storage: salsa::Storage<Self>,
+
+    /// Query hit/miss counters, fed by Salsa events
+    stats: StatsCounter,
+}
+
+impl SchemaReflyDatabase {
+    /// Hit/miss counts for tracked queries so far
+    ///
+    /// A miss is a query that executed; a hit is a memoized result that was
+    /// re-validated after an input changed and reused without executing.
+    /// Repeat lookups within a single revision are free and not counted.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.stats.snapshot()
+    }
+}
+
+impl Default for SchemaReflyDatabase {
+    fn default() -> Self {
+        let stats = StatsCounter::default();
+        let counter = stats.clone();
+
+        let storage = salsa::Storage::new(Some(Box::new(move |event: salsa::Event| match event.kind {
+            salsa::EventKind::WillExecute { .. } => counter.miss(),
+            salsa::EventKind::DidValidateMemoizedValue { .. } => counter.hit(),
+            _ => {}
+        })));
+
+        Self { storage, stats }
+    }
 }
 
 #[salsa::db]
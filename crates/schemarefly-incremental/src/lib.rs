@@ -7,8 +7,10 @@
 //! ## Architecture
 //!
 //! - **Inputs**: SqlFile, ManifestInput, CatalogInput, ConfigInput
+//! - **Workspaces**: One ConfigInput per `schemarefly.toml`, chosen per file
 //! - **Tracked Functions**: Parsing, inference, contract checking
 //! - **Caching**: Warehouse metadata with TTL
+//! - **Statistics**: Hit/miss counters for queries and the warehouse cache
 //!
 //! When a file changes, Salsa tracks dependencies and only recomputes affected
 //! queries, making SchemaRefly fast even with large dbt projects.
@@ -32,6 +34,10 @@
 pub mod db;
 pub mod queries;
 pub mod cache;
+pub mod stats;
+pub mod workspace;
 
 pub use db::{Db, SchemaReflyDatabase};
 pub use cache::WarehouseCache;
+pub use stats::CacheStats;
+pub use workspace::ConfigInputs;
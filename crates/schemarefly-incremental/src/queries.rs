@@ -3,9 +3,14 @@
 //! This module defines all Salsa inputs (base data that can change) and
 //! tracked functions (derived computations) for incremental recomputation.
 
-use schemarefly_core::{Schema, Diagnostic, Config};
+// `infer_schema` fails with a full Diagnostic; boxing it would only add
+// noise to every caller for no real benefit here.
+#![allow(clippy::result_large_err)]
+
+use schemarefly_core::{Schema, Column, Diagnostic, DiagnosticCode, Severity, Location, Config};
 use schemarefly_dbt::Manifest;
 use schemarefly_sql::ParsedSql;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// Input: SQL file with its path and contents
@@ -31,6 +36,14 @@ pub struct ManifestInput {
     /// Raw JSON content of manifest.json
     #[returns(ref)]
     pub json: String,
+
+    /// Model schemas read instead of the manifest's contracts, keyed by unique_id
+    ///
+    /// Lets dependents see an upstream model's unsaved edits before dbt
+    /// recompiles. Empty unless set through `ManifestInput::builder`.
+    #[default]
+    #[returns(ref)]
+    pub model_schemas: BTreeMap<String, Schema>,
 }
 
 /// Input: dbt catalog JSON (optional)
@@ -57,12 +70,13 @@ pub struct ConfigInput {
 /// Tracked function: Parse manifest JSON into Manifest struct
 ///
 /// This is memoized and only recomputed when the manifest JSON changes.
-/// Returns `None` if parsing fails.
+/// Only the sections SchemaRefly uses are parsed (see
+/// `Manifest::from_str_partial`). Returns `None` if parsing fails.
 #[salsa::tracked]
 pub fn manifest(db: &dyn salsa::Database, input: ManifestInput) -> Option<Manifest> {
     let json = input.json(db);
 
-    match serde_json::from_str(json) {
+    match Manifest::from_str_partial(json) {
         Ok(manifest) => Some(manifest),
         Err(e) => {
             eprintln!("Failed to parse manifest: {}", e);
@@ -97,40 +111,106 @@ pub fn parse_sql(
         .map_err(|e| format!("Parse error: {}", e))
 }
 
+/// Schema inferred for a SQL file, with the recoverable problems met on the way
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferredSchema {
+    /// Inferred columns; only the explicitly named ones when not `complete`
+    pub schema: Schema,
+
+    /// Whether every output column was inferred (false after an unexpandable SELECT *)
+    pub complete: bool,
+
+    /// One diagnostic per recoverable problem, without a location
+    pub warnings: Vec<Diagnostic>,
+}
+
 /// Tracked function: Infer schema for a SQL file
 ///
 /// This is memoized and only recomputed when:
 /// - The parsed SQL changes (which depends on file contents + config)
 /// - The manifest changes (affects ref() resolution and type information)
+///
+/// Failures are returned as a diagnostic so callers can tell them apart by
+/// code. A recoverable problem (see `infer_schema_partial`) is a failure here,
+/// except an ORDER BY on an unknown column, which never changes the schema.
 #[salsa::tracked]
 pub fn infer_schema(
     db: &dyn salsa::Database,
     file: SqlFile,
     config: ConfigInput,
     manifest_input: ManifestInput,
-) -> Result<Schema, String> {
+) -> Result<Schema, Diagnostic> {
+    let inferred = infer_schema_partial(db, file, config, manifest_input)?;
+
+    let mut failures = inferred
+        .warnings
+        .into_iter()
+        .filter(|w| w.code != DiagnosticCode::SqlOrderByUnknownColumn);
+
+    match failures.next() {
+        Some(warning) => Err(warning),
+        None => Ok(inferred.schema),
+    }
+}
+
+/// Tracked function: Infer schema for a SQL file, carrying on past recoverable problems
+///
+/// An unexpandable SELECT * or an unaliased aggregate is reported as a
+/// warning next to whatever schema could still be inferred; only problems
+/// that leave no usable schema fail.
+#[salsa::tracked]
+pub fn infer_schema_partial(
+    db: &dyn salsa::Database,
+    file: SqlFile,
+    config: ConfigInput,
+    manifest_input: ManifestInput,
+) -> Result<InferredSchema, Diagnostic> {
     use schemarefly_sql::{SchemaInference, InferenceContext};
 
     // Get parsed SQL (cached)
-    let parsed = parse_sql(db, file, config)
-        .map_err(|e| format!("Cannot infer schema - {}", e))?;
+    let parsed = parse_sql(db, file, config).map_err(|e| Diagnostic::new(
+        DiagnosticCode::SqlParseError,
+        Severity::Error,
+        format!("Cannot infer schema - {}", e),
+    ))?;
 
     // Get manifest (cached)
-    let manifest_val = manifest(db, manifest_input)
-        .ok_or_else(|| "Failed to parse manifest".to_string())?;
+    let manifest_val = manifest(db, manifest_input).ok_or_else(|| Diagnostic::new(
+        DiagnosticCode::SqlInferenceError,
+        Severity::Error,
+        "Failed to parse manifest",
+    ))?;
 
     // Create inference context from manifest
-    let context = InferenceContext::from_manifest(&manifest_val);
+    let mut context = InferenceContext::from_manifest(&manifest_val);
+    for (node_id, schema) in manifest_input.model_schemas(db) {
+        if let Some(node) = manifest_val.get_node(node_id) {
+            context.add_model(node_id, node, schema.clone());
+        }
+    }
 
     // Infer schema
-    let inference = SchemaInference::new(&context);
+    let inference_config = &config.config(db).inference;
+    let inference = SchemaInference::new(&context)
+        .with_max_depth(inference_config.max_depth)
+        .with_nullability_from_where(inference_config.nullability_from_where);
+
+    if let Some(stmt) = parsed.model_statement() {
+        let partial = inference
+            .infer_statement_partial(stmt)
+            .map_err(|e| inference.create_diagnostic(&e))?;
 
-    if let Some(stmt) = parsed.first_statement() {
-        inference
-            .infer_statement(stmt)
-            .map_err(|e| format!("Inference error: {}", e))
+        Ok(InferredSchema {
+            complete: partial.is_complete(),
+            warnings: partial.warnings.iter().map(|w| inference.create_diagnostic(w)).collect(),
+            schema: partial.schema,
+        })
     } else {
-        Err("No SQL statement found".to_string())
+        Err(Diagnostic::new(
+            DiagnosticCode::SqlInferenceError,
+            Severity::Error,
+            "No SQL statement found",
+        ))
     }
 }
 
@@ -148,19 +228,56 @@ pub fn check_contract(
     config: ConfigInput,
     manifest_input: ManifestInput,
 ) -> Vec<Diagnostic> {
-    use schemarefly_engine::ContractDiff;
-    use schemarefly_dbt::ContractExtractor;
+    match compare_contract(db, file, config, manifest_input) {
+        ContractCheck::Compared(diff) => diff.diagnostics,
+        ContractCheck::Unverifiable(diagnostics) => diagnostics,
+        ContractCheck::Skipped => Vec::new(),
+    }
+}
 
-    // Get inferred schema (cached)
-    let inferred = match infer_schema(db, file, config, manifest_input) {
-        Ok(schema) => schema,
-        Err(_) => return Vec::new(), // Can't check contract if inference failed
-    };
+/// Tracked function: Structured contract diff for a model
+///
+/// Returns `None` when the file has no contract or its schema could not be
+/// inferred. Shares the cached inference with `check_contract`.
+#[salsa::tracked]
+pub fn model_contract_diff(
+    db: &dyn salsa::Database,
+    file: SqlFile,
+    config: ConfigInput,
+    manifest_input: ManifestInput,
+) -> Option<schemarefly_core::ModelContractDiff> {
+    match compare_contract(db, file, config, manifest_input) {
+        ContractCheck::Compared(diff) => Some(diff.model_diff),
+        ContractCheck::Unverifiable(_) | ContractCheck::Skipped => None,
+    }
+}
+
+/// Outcome of comparing a file's contract to its inferred schema
+enum ContractCheck {
+    /// Contract compared against the inferred schema
+    Compared(schemarefly_engine::ContractDiff),
+
+    /// SELECT * hid every contract column
+    Unverifiable(Vec<Diagnostic>),
+
+    /// No contract, no manifest, or inference failed
+    Skipped,
+}
+
+fn compare_contract(
+    db: &dyn salsa::Database,
+    file: SqlFile,
+    config: ConfigInput,
+    manifest_input: ManifestInput,
+) -> ContractCheck {
+    use schemarefly_engine::{CompareOptions, ContractDiff, MetaPolicy};
+    use schemarefly_dbt::ContractExtractor;
+    use schemarefly_sql::SchemaInference;
 
     // Get manifest (cached)
     let manifest_val = match manifest(db, manifest_input) {
         Some(m) => m,
-        None => return Vec::new(), // Can't check contract if manifest is invalid
+        None => return ContractCheck::Skipped, // Can't check contract if manifest is invalid
     };
 
     // Get file path
@@ -170,18 +287,155 @@ pub fn check_contract(
     // Find model in manifest by path
     for (node_id, node) in manifest_val.models() {
         if node.original_file_path == path_str {
-            // Check if model has a contract
-            if let Some(contract) = ContractExtractor::extract_from_node(node) {
+            // `meta: {schemarefly: {ignore_contract: true}}` opts the model out
+            let meta_policy = MetaPolicy::for_node(node);
+            if meta_policy.skips_contract() {
+                return ContractCheck::Skipped;
+            }
+
+            // Check if model has a contract. Column lines come from the YAML on disk;
+            // editing that YAML also changes the manifest, which re-runs this query.
+            let config_val = config.config(db);
+            if let Some(contract) = ContractExtractor::extract_with_locations(node, &config_val.project_root) {
+                // Get inferred schema (cached)
+                let InferredSchema { schema: inferred, complete, warnings } =
+                    match infer_schema_partial(db, file, config, manifest_input) {
+                        Ok(inferred) => inferred,
+                        Err(_) => return ContractCheck::Skipped, // Can't check contract if inference failed
+                    };
+
+                // Apply the configured enforcement policy for this model
+                let mut contract = contract.with_policy(config_val.enforcement_policy_for(&node.name));
+                let mut options = CompareOptions::from_config(config_val);
+
+                // Columns with `ignore_contract` meta are left out on both sides
+                contract.schema = meta_policy.contract_schema(&contract.schema, options.identifier_case);
+                let inferred = meta_policy.contract_schema(&inferred, options.identifier_case);
+
+                // Contract columns hidden by SELECT * can't be checked; compare the rest
+                let mut unverifiable = Vec::new();
+                if !complete {
+                    let (known, hidden): (Vec<Column>, Vec<Column>) = contract
+                        .schema
+                        .columns
+                        .iter()
+                        .cloned()
+                        .partition(|col| inferred.find_column_with(&col.name, options.identifier_case).is_some());
+
+                    unverifiable = unverifiable_contract_columns(&contract, &hidden, &path_str);
+                    if known.is_empty() {
+                        return ContractCheck::Unverifiable(unverifiable);
+                    }
+
+                    contract.schema = Schema::from_columns(known);
+                    // Positions are unknown while SELECT * columns are missing
+                    options.enforce_column_order = false;
+                }
+
                 // Compare contract to inferred schema
-                let diff = ContractDiff::compare(node_id, &contract, &inferred, Some(path_str));
+                let mut diff = ContractDiff::compare_with_options(node_id, &contract, &inferred, Some(path_str.clone()), &options);
 
-                return diff.diagnostics;
+                // Recoverable inference problems are reported next to the comparison
+                diff.diagnostics.extend(
+                    warnings
+                        .into_iter()
+                        .map(|d| d.with_location(Location::new(&path_str)))
+                        .chain(unverifiable),
+                );
+
+                // Statements besides the analyzed one are ignored; say so
+                if let Ok(parsed) = parse_sql(db, file, config) {
+                    diff.diagnostics.extend(
+                        parsed
+                            .multiple_statements_diagnostic()
+                            .map(|d| d.with_location(Location::new(&path_str))),
+                    );
+                }
+
+                // Only the first of same-named output columns is compared; flag the rest
+                diff.diagnostics.extend(
+                    SchemaInference::duplicate_column_diagnostics(&inferred, options.identifier_case)
+                        .into_iter()
+                        .map(|d| d.with_location(Location::new(&path_str))),
+                );
+
+                return ContractCheck::Compared(diff);
             }
         }
     }
 
     // No contract found for this model
-    Vec::new()
+    ContractCheck::Skipped
+}
+
+/// Tracked function: Describe the schema inferred for a model without a contract
+///
+/// Returns an info diagnostic listing the inferred columns, or `None` when
+/// the file's model has a contract, is not in the manifest, or its schema
+/// could not be fully inferred.
+#[salsa::tracked]
+pub fn inferred_schema_info(
+    db: &dyn salsa::Database,
+    file: SqlFile,
+    config: ConfigInput,
+    manifest_input: ManifestInput,
+) -> Option<Diagnostic> {
+    use schemarefly_dbt::ContractExtractor;
+
+    let manifest_val = manifest(db, manifest_input)?;
+    let path_str = file.path(db).to_string_lossy().to_string();
+
+    let models = manifest_val.models();
+    let (node_id, node) = models.iter().find(|(_, node)| node.original_file_path == path_str)?;
+    if ContractExtractor::extract_from_node(node).is_some() {
+        return None;
+    }
+
+    let schema = infer_schema(db, file, config, manifest_input).ok()?;
+    let columns: Vec<String> = schema
+        .columns
+        .iter()
+        .map(|col| format!("{} {}", col.name, col.logical_type))
+        .collect();
+
+    Some(
+        Diagnostic::new(
+            DiagnosticCode::SqlInferredSchema,
+            Severity::Info,
+            format!(
+                "Model '{}' has no contract; inferred {} columns: {}",
+                node.name,
+                columns.len(),
+                columns.join(", ")
+            ),
+        )
+        .with_location(Location::new(&path_str))
+        .with_model_id(node_id.clone()),
+    )
+}
+
+/// One warning per contract column that SELECT * prevented from being checked
+fn unverifiable_contract_columns(contract: &schemarefly_core::Contract, columns: &[Column], path: &str) -> Vec<Diagnostic> {
+    columns
+        .iter()
+        .map(|col| {
+            let mut diag = Diagnostic::new(
+                DiagnosticCode::SqlSelectStarUnexpandable,
+                Severity::Warn,
+                format!(
+                    "Contract column '{}' could not be verified because of unexpandable SELECT *",
+                    col.name
+                ),
+            )
+            .with_location(Location::new(path));
+
+            if let Some(yaml) = contract.column_locations.get(&col.name) {
+                diag = diag.with_related(yaml.clone());
+            }
+
+            diag
+        })
+        .collect()
 }
 
 /// Tracked function: Get downstream dependencies for a model
@@ -0,0 +1,91 @@
+//! Per-project configuration for multi-config workspaces
+//!
+//! A monorepo can hold several dbt projects, each with its own
+//! `schemarefly.toml`. `ConfigInputs` gives every file the `ConfigInput` of
+//! the nearest config file above it, creating one Salsa input per config
+//! file. Reloading one config only sets that input, so queries for files
+//! under other projects stay cached.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use salsa::Setter;
+use schemarefly_core::config::ConfigError;
+use schemarefly_core::Config;
+
+use crate::db::SchemaReflyDatabase;
+use crate::queries::ConfigInput;
+
+/// One `ConfigInput` per discovered `schemarefly.toml`
+pub struct ConfigInputs {
+    /// Config for files with no `schemarefly.toml` above them
+    fallback: Config,
+
+    /// Input holding `fallback`, created on first use
+    fallback_input: Option<ConfigInput>,
+
+    /// Config file path → its input
+    inputs: HashMap<PathBuf, ConfigInput>,
+}
+
+impl ConfigInputs {
+    /// Create an empty registry; `fallback` applies outside every project
+    pub fn new(fallback: Config) -> Self {
+        Self {
+            fallback,
+            fallback_input: None,
+            inputs: HashMap::new(),
+        }
+    }
+
+    /// Config input for the file at `file_path`
+    ///
+    /// The nearest `schemarefly.toml` is discovered from the file's directory
+    /// upwards (see [`Config::find_config_file`]) and loaded the first time
+    /// it is seen. Files in the same project share one input.
+    pub fn for_file(&mut self, db: &SchemaReflyDatabase, file_path: &Path) -> Result<ConfigInput, ConfigError> {
+        let dir = file_path.parent().unwrap_or(Path::new("."));
+
+        let Some(config_path) = Config::find_config_file(dir) else {
+            return Ok(self.fallback(db));
+        };
+
+        if let Some(input) = self.inputs.get(&config_path) {
+            return Ok(*input);
+        }
+
+        let input = ConfigInput::new(db, Config::from_file(&config_path)?);
+        self.inputs.insert(config_path, input);
+        Ok(input)
+    }
+
+    /// Input holding the fallback config
+    pub fn fallback(&mut self, db: &SchemaReflyDatabase) -> ConfigInput {
+        let fallback = &self.fallback;
+        *self.fallback_input.get_or_insert_with(|| ConfigInput::new(db, fallback.clone()))
+    }
+
+    /// Re-read the config file at `config_path` after it changed
+    ///
+    /// Only queries that used this config are invalidated. Returns `false`
+    /// if the file has not been loaded yet (nothing depends on it) or its
+    /// contents parse to the same config.
+    pub fn reload(&mut self, db: &mut SchemaReflyDatabase, config_path: &Path) -> Result<bool, ConfigError> {
+        let Some(input) = self.inputs.get(config_path).copied() else {
+            return Ok(false);
+        };
+
+        let config = Config::from_file(config_path)?;
+        if input.config(db) == &config {
+            return Ok(false);
+        }
+
+        input.set_config(db).to(config);
+        Ok(true)
+    }
+
+    /// Config files loaded so far
+    pub fn config_paths(&self) -> impl Iterator<Item = &Path> {
+        self.inputs.keys().map(PathBuf::as_path)
+    }
+}
@@ -1,7 +1,7 @@
 //! Integration tests for Salsa incremental computation
 
-use schemarefly_incremental::{SchemaReflyDatabase, queries};
-use schemarefly_core::Config;
+use schemarefly_incremental::{ConfigInputs, SchemaReflyDatabase, queries};
+use schemarefly_core::{Config, DiagnosticCode, Severity};
 use std::path::PathBuf;
 
 #[test]
@@ -166,6 +166,38 @@ fn test_infer_schema_with_valid_sql() {
     assert_eq!(schema.columns.len(), 2, "Should infer 2 columns");
 }
 
+#[test]
+fn test_infer_schema_ignores_order_by_unknown_column() {
+    let db = SchemaReflyDatabase::default();
+
+    let manifest_json = r#"{
+        "metadata": {
+            "dbt_schema_version": "https://schemas.getdbt.com/dbt/manifest/v10.json",
+            "dbt_version": "1.5.0",
+            "generated_at": "2024-01-01T00:00:00Z"
+        },
+        "nodes": {},
+        "sources": {},
+        "parent_map": {},
+        "child_map": {}
+    }"#.to_string();
+
+    let path = PathBuf::from("models/test.sql");
+    let sql = "SELECT 1 AS id ORDER BY missing_col".to_string();
+
+    let sql_file = queries::SqlFile::new(&db, path, sql);
+    let manifest_input = queries::ManifestInput::new(&db, manifest_json);
+    let config_input = queries::ConfigInput::new(&db, Config::default());
+
+    let schema = queries::infer_schema(&db, sql_file, config_input, manifest_input)
+        .expect("ORDER BY never changes the inferred schema");
+    assert_eq!(schema.column_names(), vec!["id"]);
+
+    let inferred = queries::infer_schema_partial(&db, sql_file, config_input, manifest_input).unwrap();
+    assert_eq!(inferred.warnings.len(), 1);
+    assert_eq!(inferred.warnings[0].code, DiagnosticCode::SqlOrderByUnknownColumn);
+}
+
 #[test]
 fn test_infer_schema_with_invalid_sql() {
     let db = SchemaReflyDatabase::default();
@@ -224,6 +256,275 @@ fn test_check_contract_no_contract() {
     assert!(diagnostics.is_empty(), "Should have no diagnostics when no contract exists");
 }
 
+#[test]
+fn test_check_contract_select_star_reports_unverifiable_columns() {
+    let db = SchemaReflyDatabase::default();
+
+    let manifest_json = r#"{
+        "metadata": {
+            "dbt_schema_version": "https://schemas.getdbt.com/dbt/manifest/v10.json",
+            "dbt_version": "1.5.0",
+            "generated_at": "2024-01-01T00:00:00Z"
+        },
+        "nodes": {
+            "model.test.users": {
+                "unique_id": "model.test.users",
+                "name": "users",
+                "resource_type": "model",
+                "package_name": "test",
+                "path": "users.sql",
+                "original_file_path": "models/users.sql",
+                "config": {"contract": {"enforced": true}},
+                "columns": {
+                    "id": {"name": "id", "data_type": "integer"},
+                    "email": {"name": "email", "data_type": "varchar"}
+                }
+            }
+        },
+        "sources": {},
+        "parent_map": {},
+        "child_map": {}
+    }"#.to_string();
+
+    let path = PathBuf::from("models/users.sql");
+    let sql = "SELECT * FROM users".to_string();
+    let config = Config::default();
+
+    let sql_file = queries::SqlFile::new(&db, path, sql);
+    let manifest_input = queries::ManifestInput::new(&db, manifest_json);
+    let config_input = queries::ConfigInput::new(&db, config);
+
+    let diagnostics = queries::check_contract(&db, sql_file, config_input, manifest_input);
+
+    assert_eq!(diagnostics.len(), 2, "One warning per contract column");
+    for diag in &diagnostics {
+        assert_eq!(diag.code, DiagnosticCode::SqlSelectStarUnexpandable);
+        assert_eq!(diag.severity, Severity::Warn);
+        assert!(diag.message.contains("could not be verified because of unexpandable SELECT *"));
+    }
+    assert!(diagnostics.iter().any(|d| d.message.contains("'id'")));
+    assert!(diagnostics.iter().any(|d| d.message.contains("'email'")));
+}
+
+#[test]
+fn test_check_contract_compares_known_columns_past_select_star() {
+    let db = SchemaReflyDatabase::default();
+
+    let manifest_json = r#"{
+        "metadata": {
+            "dbt_schema_version": "https://schemas.getdbt.com/dbt/manifest/v10.json",
+            "dbt_version": "1.5.0",
+            "generated_at": "2024-01-01T00:00:00Z"
+        },
+        "nodes": {
+            "model.test.stg_users": {
+                "unique_id": "model.test.stg_users",
+                "name": "stg_users",
+                "resource_type": "model",
+                "package_name": "test",
+                "path": "stg_users.sql",
+                "original_file_path": "models/stg_users.sql",
+                "config": {"contract": {"enforced": true}},
+                "columns": {
+                    "email": {"name": "email", "data_type": "varchar"}
+                }
+            },
+            "model.test.users": {
+                "unique_id": "model.test.users",
+                "name": "users",
+                "resource_type": "model",
+                "package_name": "test",
+                "path": "users.sql",
+                "original_file_path": "models/users.sql",
+                "config": {"contract": {"enforced": true}},
+                "columns": {
+                    "id": {"name": "id", "data_type": "integer"},
+                    "status": {"name": "status", "data_type": "integer"},
+                    "email": {"name": "email", "data_type": "varchar"}
+                }
+            }
+        },
+        "sources": {},
+        "parent_map": {},
+        "child_map": {}
+    }"#.to_string();
+
+    let path = PathBuf::from("models/users.sql");
+    let sql = "SELECT *, 1 AS id, 'active' AS status FROM stg_users".to_string();
+
+    let sql_file = queries::SqlFile::new(&db, path, sql);
+    let manifest_input = queries::ManifestInput::new(&db, manifest_json);
+    let config_input = queries::ConfigInput::new(&db, Config::default());
+
+    let inferred = queries::infer_schema_partial(&db, sql_file, config_input, manifest_input).unwrap();
+    assert!(!inferred.complete);
+    assert_eq!(inferred.schema.column_names(), vec!["id", "status"]);
+
+    let diagnostics = queries::check_contract(&db, sql_file, config_input, manifest_input);
+    let codes: Vec<DiagnosticCode> = diagnostics.iter().map(|d| d.code).collect();
+
+    // The known columns are compared: `id` matches, `status` does not
+    assert_eq!(codes.iter().filter(|c| **c == DiagnosticCode::ContractTypeMismatch).count(), 1);
+    assert!(diagnostics.iter().any(|d| d.code == DiagnosticCode::ContractTypeMismatch && d.message.contains("status")));
+    assert!(!codes.contains(&DiagnosticCode::ContractMissingColumn));
+
+    // The SELECT * warning is reported alongside, and `email` is left unverified
+    let select_star: Vec<_> = diagnostics.iter().filter(|d| d.code == DiagnosticCode::SqlSelectStarUnexpandable).collect();
+    assert_eq!(select_star.len(), 2);
+    assert!(select_star.iter().all(|d| d.severity == Severity::Warn));
+    assert!(select_star.iter().any(|d| d.message.contains("'email' could not be verified")));
+
+    assert!(queries::model_contract_diff(&db, sql_file, config_input, manifest_input).is_some());
+}
+
+#[test]
+fn test_inferred_schema_info_for_model_without_contract() {
+    let db = SchemaReflyDatabase::default();
+
+    let manifest_json = r#"{
+        "metadata": {
+            "dbt_schema_version": "https://schemas.getdbt.com/dbt/manifest/v10.json",
+            "dbt_version": "1.5.0",
+            "generated_at": "2024-01-01T00:00:00Z"
+        },
+        "nodes": {
+            "model.test.users": {
+                "unique_id": "model.test.users",
+                "name": "users",
+                "resource_type": "model",
+                "package_name": "test",
+                "path": "users.sql",
+                "original_file_path": "models/users.sql",
+                "config": {"contract": {"enforced": true}},
+                "columns": {"id": {"name": "id", "data_type": "integer"}}
+            },
+            "model.test.events": {
+                "unique_id": "model.test.events",
+                "name": "events",
+                "resource_type": "model",
+                "package_name": "test",
+                "path": "events.sql",
+                "original_file_path": "models/events.sql"
+            }
+        },
+        "sources": {},
+        "parent_map": {},
+        "child_map": {}
+    }"#.to_string();
+
+    let manifest_input = queries::ManifestInput::new(&db, manifest_json);
+    let config_input = queries::ConfigInput::new(&db, Config::default());
+
+    let events = queries::SqlFile::new(
+        &db,
+        PathBuf::from("models/events.sql"),
+        "SELECT 1 AS event_id, 'click' AS kind".to_string(),
+    );
+    let info = queries::inferred_schema_info(&db, events, config_input, manifest_input)
+        .expect("contract-less model with successful inference is described");
+    assert_eq!(info.code, DiagnosticCode::SqlInferredSchema);
+    assert_eq!(info.severity, Severity::Info);
+    assert_eq!(info.message, "Model 'events' has no contract; inferred 2 columns: event_id INT, kind STRING");
+    assert_eq!(info.model_id.as_deref(), Some("model.test.events"));
+
+    // Models with a contract are checked instead
+    let users = queries::SqlFile::new(&db, PathBuf::from("models/users.sql"), "SELECT 1 AS id".to_string());
+    assert!(queries::inferred_schema_info(&db, users, config_input, manifest_input).is_none());
+}
+
+#[test]
+fn test_model_contract_diff_type_mismatch() {
+    let db = SchemaReflyDatabase::default();
+
+    let manifest_json = r#"{
+        "metadata": {
+            "dbt_schema_version": "https://schemas.getdbt.com/dbt/manifest/v10.json",
+            "dbt_version": "1.5.0",
+            "generated_at": "2024-01-01T00:00:00Z"
+        },
+        "nodes": {
+            "model.test.users": {
+                "unique_id": "model.test.users",
+                "name": "users",
+                "resource_type": "model",
+                "package_name": "test",
+                "path": "users.sql",
+                "original_file_path": "models/users.sql",
+                "config": {"contract": {"enforced": true}},
+                "columns": {
+                    "id": {"name": "id", "data_type": "integer"}
+                }
+            }
+        },
+        "sources": {},
+        "parent_map": {},
+        "child_map": {}
+    }"#.to_string();
+
+    let sql_file = queries::SqlFile::new(&db, PathBuf::from("models/users.sql"), "SELECT 'abc' AS id".to_string());
+    let manifest_input = queries::ManifestInput::new(&db, manifest_json);
+    let config_input = queries::ConfigInput::new(&db, Config::default());
+
+    let diagnostics = queries::check_contract(&db, sql_file, config_input, manifest_input);
+    assert!(diagnostics.iter().any(|d| d.code == DiagnosticCode::ContractTypeMismatch));
+
+    let diff = queries::model_contract_diff(&db, sql_file, config_input, manifest_input)
+        .expect("model with a contract has a diff");
+    assert_eq!(diff.model_id, "model.test.users");
+    assert_eq!(diff.type_changed.len(), 1);
+    assert_eq!(diff.type_changed[0].name, "id");
+    assert_eq!(diff.type_changed[0].from, "INT");
+    assert_eq!(diff.type_changed[0].to, "STRING");
+
+    let report = schemarefly_core::Report::from_diagnostics(diagnostics).with_model_diffs(vec![diff]);
+    assert_eq!(report.model_diffs.as_ref().map(Vec::len), Some(1));
+}
+
+#[test]
+fn test_check_contract_warns_on_multiple_statements() {
+    let db = SchemaReflyDatabase::default();
+
+    let manifest_json = r#"{
+        "metadata": {"dbt_schema_version": "v10", "dbt_version": "1.5.0", "generated_at": "2024-01-01T00:00:00Z"},
+        "nodes": {
+            "model.test.users": {
+                "unique_id": "model.test.users", "name": "users", "resource_type": "model",
+                "package_name": "test", "path": "users.sql", "original_file_path": "models/users.sql",
+                "config": {"contract": {"enforced": true}},
+                "columns": {"id": {"name": "id", "data_type": "integer"}}
+            }
+        },
+        "sources": {}
+    }"#.to_string();
+    let manifest_input = queries::ManifestInput::new(&db, manifest_json);
+    let config_input = queries::ConfigInput::new(&db, Config::default());
+
+    // A second statement after a trailing `;` is ignored, with a warning
+    let sql_file = queries::SqlFile::new(
+        &db,
+        PathBuf::from("models/users.sql"),
+        "SELECT 1 AS id; SELECT 'abc' AS id".to_string(),
+    );
+    let diagnostics = queries::check_contract(&db, sql_file, config_input, manifest_input);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, DiagnosticCode::SqlMultipleStatements);
+    assert_eq!(diagnostics[0].severity, Severity::Warn);
+    assert_eq!(diagnostics[0].message, "Model SQL contains 2 statements; only the first is analyzed");
+    assert_eq!(diagnostics[0].location.as_ref().unwrap().file, "models/users.sql");
+
+    // A hook before the SELECT: the SELECT is inferred and compared
+    let hooked = queries::SqlFile::new(
+        &db,
+        PathBuf::from("models/users.sql"),
+        "SET search_path TO analytics; SELECT 'abc' AS id".to_string(),
+    );
+    let diagnostics = queries::check_contract(&db, hooked, config_input, manifest_input);
+    let codes: Vec<DiagnosticCode> = diagnostics.iter().map(|d| d.code).collect();
+    assert!(codes.contains(&DiagnosticCode::ContractTypeMismatch));
+    let warning = diagnostics.iter().find(|d| d.code == DiagnosticCode::SqlMultipleStatements).unwrap();
+    assert_eq!(warning.message, "Model SQL contains 2 statements; only the final SELECT is analyzed");
+}
+
 #[test]
 fn test_downstream_models_empty_manifest() {
     let db = SchemaReflyDatabase::default();
@@ -319,3 +620,91 @@ fn test_incremental_recomputation_only_on_change() {
     assert_ne!(parsed1_v1.unwrap().sql, parsed1_v2.unwrap().sql);
     assert_eq!(parsed2.unwrap(), parsed2_v2.unwrap());
 }
+
+#[test]
+fn test_cache_stats_warm_run_hits_more_than_cold_run() {
+    use salsa::Setter;
+
+    let mut db = SchemaReflyDatabase::default();
+
+    let manifest_json = r#"{
+        "metadata": {
+            "dbt_schema_version": "https://schemas.getdbt.com/dbt/manifest/v10.json",
+            "dbt_version": "1.5.0",
+            "generated_at": "2024-01-01T00:00:00Z"
+        },
+        "nodes": {},
+        "sources": {},
+        "parent_map": {},
+        "child_map": {}
+    }"#.to_string();
+
+    let users = queries::SqlFile::new(&db, PathBuf::from("models/users.sql"), "SELECT 1 AS user_id".to_string());
+    let orders = queries::SqlFile::new(&db, PathBuf::from("models/orders.sql"), "SELECT 1 AS order_id".to_string());
+    let manifest_input = queries::ManifestInput::new(&db, manifest_json);
+    let config_input = queries::ConfigInput::new(&db, Config::default());
+
+    // Cold run: every query executes
+    assert!(queries::infer_schema(&db, users, config_input, manifest_input).is_ok());
+    let cold = db.cache_stats();
+    assert_eq!(cold.hits, 0);
+    assert!(cold.misses > 0);
+
+    // Edit an unrelated file, then run the identical queries again
+    orders.set_contents(&mut db).to("SELECT 2 AS order_id".to_string());
+    assert!(queries::infer_schema(&db, users, config_input, manifest_input).is_ok());
+    let warm = db.cache_stats();
+
+    let warm_hits = warm.hits - cold.hits;
+    let warm_misses = warm.misses - cold.misses;
+    assert!(warm_hits > cold.hits);
+    assert_eq!(warm_misses, 0);
+}
+
+#[test]
+fn test_files_under_different_configs_use_their_own_settings() {
+    let mut db = SchemaReflyDatabase::default();
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::create_dir(workspace.path().join(".git")).unwrap();
+
+    // Project `shallow` limits query nesting; `deep` keeps the default
+    let project = |name: &str, config: &str| {
+        let models = workspace.path().join(name).join("models");
+        std::fs::create_dir_all(&models).unwrap();
+        std::fs::write(workspace.path().join(name).join("schemarefly.toml"), config).unwrap();
+        models.join("nested.sql")
+    };
+    let shallow_path = project("shallow", "[inference]\nmax_depth = 1\n");
+    let deep_path = project("deep", "");
+
+    let manifest_input = queries::ManifestInput::new(&db, r#"{
+        "metadata": {"dbt_schema_version": "v10", "dbt_version": "1.5.0", "generated_at": "2024-01-01T00:00:00Z"},
+        "nodes": {},
+        "sources": {}
+    }"#.to_string());
+
+    let sql = "SELECT id FROM (SELECT id FROM (SELECT 1 AS id) a) b".to_string();
+    let shallow = queries::SqlFile::new(&db, shallow_path.clone(), sql.clone());
+    let deep = queries::SqlFile::new(&db, deep_path.clone(), sql);
+
+    let mut configs = ConfigInputs::new(Config::default());
+    let shallow_config = configs.for_file(&db, &shallow_path).unwrap();
+    let deep_config = configs.for_file(&db, &deep_path).unwrap();
+    assert!(shallow_config != deep_config);
+    assert_eq!(configs.config_paths().count(), 2);
+
+    let err = queries::infer_schema(&db, shallow, shallow_config, manifest_input).unwrap_err();
+    assert_eq!(err.code, DiagnosticCode::SqlInferenceError);
+    assert!(queries::infer_schema(&db, deep, deep_config, manifest_input).is_ok());
+
+    // Raising the limit in one project only re-runs that project's queries
+    let shallow_toml = workspace.path().join("shallow").join("schemarefly.toml");
+    std::fs::write(&shallow_toml, "[inference]\nmax_depth = 8\n").unwrap();
+    assert!(configs.reload(&mut db, &shallow_toml).unwrap());
+    assert!(!configs.reload(&mut db, &shallow_toml).unwrap());
+
+    let before = db.cache_stats();
+    assert!(queries::infer_schema(&db, deep, deep_config, manifest_input).is_ok());
+    assert_eq!(db.cache_stats().misses, before.misses);
+    assert!(queries::infer_schema(&db, shallow, shallow_config, manifest_input).is_ok());
+}
@@ -49,6 +49,12 @@ impl DbtContext {
         self
     }
 
+    /// Add project variables, replacing any with the same name
+    pub fn with_vars(mut self, vars: impl IntoIterator<Item = (String, serde_json::Value)>) -> Self {
+        self.vars.extend(vars);
+        self
+    }
+
     /// Add a model config value
     pub fn add_config(&mut self, key: impl Into<String>, value: serde_json::Value) -> &mut Self {
         self.model_config.insert(key.into(), value);
@@ -5,13 +5,15 @@
 //! for dbt SQL files.
 
 use schemarefly_core::{Config, Diagnostic as SchemaDiagnostic, Severity};
-use schemarefly_incremental::{queries, SchemaReflyDatabase};
-use std::collections::HashMap;
+use schemarefly_incremental::{queries, ConfigInputs, SchemaReflyDatabase};
+use schemarefly_sql::{DbtFunctionExtractor, DbtReference};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::{
-    Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
     DidOpenTextDocumentParams, DidSaveTextDocumentParams, GotoDefinitionParams,
     GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability,
     InitializeParams, InitializeResult, InitializedParams, Location, MarkedString, MessageType,
@@ -20,10 +22,16 @@ use tower_lsp::lsp_types::{
 };
 use tower_lsp::{Client, LanguageServer};
 
+use crate::token::token_at;
+
+/// Maximum number of model files checked by the workspace-wide pass on startup
+pub const WORKSPACE_DIAGNOSTICS_LIMIT: usize = 500;
+
 /// LSP backend for SchemaRefly
 ///
 /// Tracks all open documents in the workspace and provides LSP features.
 /// Creates a fresh Salsa database for each request (Salsa handles caching internally).
+#[derive(Clone)]
 pub struct Backend {
     /// LSP client for communicating with the editor
     client: Client,
@@ -49,13 +57,9 @@ impl Backend {
         }
     }
 
-    /// Load dbt manifest from workspace
+    /// Load dbt manifest from the workspace's dbt target directory
     async fn load_manifest(&self) -> Option<String> {
-        let root_uri = self.root_uri.read().await;
-        let root_path = root_uri.as_ref()?.to_file_path().ok()?;
-
-        // Try to find manifest.json in target/ directory
-        let manifest_path = root_path.join("target").join("manifest.json");
+        let manifest_path = self.config.read().await.manifest_path();
 
         match tokio::fs::read_to_string(&manifest_path).await {
             Ok(content) => {
@@ -87,7 +91,8 @@ impl Backend {
             let config_path = root_path.join("schemarefly.toml");
 
             if let Ok(content) = tokio::fs::read_to_string(&config_path).await {
-                if let Ok(config) = toml::from_str::<Config>(&content) {
+                if let Ok(mut config) = toml::from_str::<Config>(&content) {
+                    config.project_root = root_path.clone();
                     self.client
                         .log_message(
                             MessageType::INFO,
@@ -99,8 +104,14 @@ impl Backend {
             }
         }
 
-        // Return default config if not found
-        Config::default()
+        // Return default config (rooted at the workspace) if not found
+        match root_uri.as_ref().and_then(|u| u.to_file_path().ok()) {
+            Some(root_path) => Config {
+                project_root: root_path,
+                ..Config::default()
+            },
+            None => Config::default(),
+        }
     }
 
     /// Compute diagnostics for a document
@@ -123,33 +134,266 @@ impl Backend {
         let manifest_json = self.manifest_json.read().await;
         let config = self.config.read().await;
 
-        if manifest_json.is_none() {
+        let Some(manifest_json) = manifest_json.as_ref() else {
             // No manifest loaded - can't run diagnostics
             return Vec::new();
-        }
+        };
+
+        // Related locations (e.g. contract YAML) are relative to the project root
+        let root_path = self.root_uri.read().await
+            .as_ref()
+            .and_then(|u| u.to_file_path().ok());
 
         // Create fresh Salsa database for this request
         // Salsa handles caching internally based on input values
         let db = SchemaReflyDatabase::default();
+        let manifest_input = queries::ManifestInput::new(&db, manifest_json.clone());
+        let config_input = Self::config_for(&db, &mut ConfigInputs::new(config.clone()), &file_path);
 
-        // Create Salsa inputs
-        let sql_file = queries::SqlFile::new(&db, file_path.clone(), content);
-        let manifest_input =
-            queries::ManifestInput::new(&db, manifest_json.as_ref().unwrap().clone());
-        let config_input = queries::ConfigInput::new(&db, config.clone());
+        Self::diagnostics_for(&db, &file_path, content, config_input, manifest_input, root_path.as_deref())
+    }
+
+    /// Config input for `file_path` from the nearest `schemarefly.toml` above it
+    ///
+    /// Files outside every project, or under a config that fails to load,
+    /// use the workspace config.
+    fn config_for(db: &SchemaReflyDatabase, configs: &mut ConfigInputs, file_path: &Path) -> queries::ConfigInput {
+        configs.for_file(db, file_path).unwrap_or_else(|_| configs.fallback(db))
+    }
+
+    /// Replace `ref()` / `source()` / `var()` calls in model text so it parses
+    fn preprocessed(
+        db: &SchemaReflyDatabase,
+        content: &str,
+        config_input: queries::ConfigInput,
+        manifest_input: queries::ManifestInput,
+    ) -> String {
+        let manifest = queries::manifest(db, manifest_input);
+        let (preprocessed, _) = DbtFunctionExtractor::preprocess_with_vars(content, manifest.as_ref(), &config_input.config(db).vars);
+        preprocessed
+    }
+
+    /// Run the contract check for one file and convert the results to LSP diagnostics
+    ///
+    /// `content` is the raw model text; dbt calls are replaced before parsing.
+    /// The manifest names models by project-relative path, so absolute paths
+    /// under `root_path` are made relative before the lookup.
+    fn diagnostics_for(
+        db: &SchemaReflyDatabase,
+        file_path: &Path,
+        content: String,
+        config_input: queries::ConfigInput,
+        manifest_input: queries::ManifestInput,
+        root_path: Option<&Path>,
+    ) -> Vec<Diagnostic> {
+        let model_path = root_path
+            .and_then(|root| file_path.strip_prefix(root).ok())
+            .unwrap_or(file_path);
+
+        let sql = Self::preprocessed(db, &content, config_input, manifest_input);
+        let sql_file = queries::SqlFile::new(db, model_path.to_path_buf(), sql);
 
         // Run contract checking (returns SchemaRefly diagnostics)
-        let schema_diagnostics = queries::check_contract(&db, sql_file, config_input, manifest_input);
+        let mut diagnostics = queries::check_contract(db, sql_file, config_input, manifest_input);
+        if let Some(max) = config_input.config(db).max_diagnostics_per_model {
+            let model = model_path.file_stem().unwrap_or_default().to_string_lossy();
+            diagnostics = SchemaDiagnostic::cap(diagnostics, max, &model);
+        }
 
-        // Convert to LSP diagnostics
-        schema_diagnostics
+        diagnostics
             .into_iter()
-            .map(|d| self.convert_diagnostic(d))
+            .map(|d| Self::convert_diagnostic(d, root_path))
+            .collect()
+    }
+
+    /// Publish diagnostics for `uri` and for every open document downstream of it
+    ///
+    /// Downstream models come from the manifest's dependency graph and are
+    /// checked against the schema inferred from the edited text rather than
+    /// the upstream contract, so their diagnostics follow unsaved edits. All
+    /// files share one Salsa database; closed documents are never rechecked.
+    async fn publish_with_downstream(&self, uri: &Url) {
+        let manifest_json = self.manifest_json.read().await.clone();
+        let root_path = self.root_uri.read().await.as_ref().and_then(|u| u.to_file_path().ok());
+        let (Some(manifest_json), Some(root_path)) = (manifest_json, root_path) else {
+            let diagnostics = self.compute_diagnostics(uri).await;
+            self.client.publish_diagnostics(uri.clone(), diagnostics, None).await;
+            return;
+        };
+        let config = self.config.read().await.clone();
+        let documents = self.documents.read().await.clone();
+
+        let db = SchemaReflyDatabase::default();
+        let manifest_input = queries::ManifestInput::new(&db, manifest_json.clone());
+        let mut configs = ConfigInputs::new(config);
+
+        let upstream_id = Self::model_id_at(&db, manifest_input, &root_path, uri);
+        let downstream: Vec<Url> = upstream_id
+            .as_deref()
+            .map(|id| Self::downstream_documents(&db, manifest_input, &root_path, id))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|downstream| downstream != uri && documents.contains_key(downstream))
+            .collect();
+
+        // Dependents read the edited model's inferred schema instead of its contract
+        let mut model_schemas = BTreeMap::new();
+        if let (Some(id), Some(content), Ok(path)) = (&upstream_id, documents.get(uri), uri.to_file_path()) {
+            let model_path = path.strip_prefix(&root_path).unwrap_or(&path).to_path_buf();
+            let config_input = Self::config_for(&db, &mut configs, &path);
+            let sql = Self::preprocessed(&db, content, config_input, manifest_input);
+            let file = queries::SqlFile::new(&db, model_path, sql);
+            if let Ok(inferred) = queries::infer_schema_partial(&db, file, config_input, manifest_input) {
+                if inferred.complete {
+                    model_schemas.insert(id.clone(), inferred.schema);
+                }
+            }
+        }
+        let downstream_manifest = queries::ManifestInput::builder(manifest_json)
+            .model_schemas(model_schemas)
+            .new(&db);
+
+        let published: Vec<(Url, Vec<Diagnostic>)> = std::iter::once((uri.clone(), manifest_input))
+            .chain(downstream.into_iter().map(|target| (target, downstream_manifest)))
+            .map(|(target, manifest_input)| {
+                let diagnostics = match (documents.get(&target), target.to_file_path()) {
+                    (Some(content), Ok(path)) => Self::diagnostics_for(
+                        &db,
+                        &path,
+                        content.clone(),
+                        Self::config_for(&db, &mut configs, &path),
+                        manifest_input,
+                        Some(&root_path),
+                    ),
+                    _ => Vec::new(),
+                };
+                (target, diagnostics)
+            })
+            .collect();
+
+        for (target, diagnostics) in published {
+            self.client.publish_diagnostics(target, diagnostics, None).await;
+        }
+    }
+
+    /// Unique id of the model whose file is at `uri`
+    fn model_id_at(
+        db: &SchemaReflyDatabase,
+        manifest_input: queries::ManifestInput,
+        root_path: &Path,
+        uri: &Url,
+    ) -> Option<String> {
+        let model_path = uri.to_file_path().ok()?.strip_prefix(root_path).ok()?.to_path_buf();
+        queries::manifest(db, manifest_input)?
+            .models()
+            .into_iter()
+            .find(|(_, node)| Path::new(&node.original_file_path) == model_path)
+            .map(|(id, _)| id)
+    }
+
+    /// File URIs of models downstream of `node_id`, sorted
+    fn downstream_documents(
+        db: &SchemaReflyDatabase,
+        manifest_input: queries::ManifestInput,
+        root_path: &Path,
+        node_id: &str,
+    ) -> Vec<Url> {
+        let Some(manifest) = queries::manifest(db, manifest_input) else {
+            return Vec::new();
+        };
+
+        let mut downstream: Vec<Url> = queries::downstream_models(db, manifest_input, node_id.to_string())
+            .iter()
+            .filter_map(|id| manifest.get_node(id))
+            .filter_map(|node| Url::from_file_path(root_path.join(&node.original_file_path)).ok())
+            .collect();
+        downstream.sort();
+        downstream.dedup();
+        downstream
+    }
+
+    /// Publish diagnostics for every model file in the manifest
+    ///
+    /// Runs on a blocking thread so startup is not held up, shares one Salsa
+    /// database across files so the manifest is parsed once, and stops after
+    /// `WORKSPACE_DIAGNOSTICS_LIMIT` files. Open documents use their editor
+    /// contents instead of what is on disk.
+    async fn publish_workspace_diagnostics(&self) {
+        let Some(manifest_json) = self.manifest_json.read().await.clone() else {
+            return;
+        };
+        let Some(root_path) = self.root_uri.read().await.as_ref().and_then(|u| u.to_file_path().ok()) else {
+            return;
+        };
+        let config = self.config.read().await.clone();
+        let open_documents = self.documents.read().await.clone();
+
+        let task = tokio::task::spawn_blocking(move || {
+            Self::workspace_diagnostics(&manifest_json, config, &root_path, &open_documents)
+        });
+
+        let published = match task.await {
+            Ok(published) => published,
+            Err(e) => {
+                self.client
+                    .log_message(MessageType::ERROR, format!("Workspace diagnostics failed: {}", e))
+                    .await;
+                return;
+            }
+        };
+
+        let count = published.len();
+        for (uri, diagnostics) in published {
+            self.client.publish_diagnostics(uri, diagnostics, None).await;
+        }
+
+        self.client
+            .log_message(MessageType::INFO, format!("Published workspace diagnostics for {} models", count))
+            .await;
+    }
+
+    /// Compute diagnostics for each model file, sorted by path
+    fn workspace_diagnostics(
+        manifest_json: &str,
+        config: Config,
+        root_path: &Path,
+        open_documents: &HashMap<Url, String>,
+    ) -> Vec<(Url, Vec<Diagnostic>)> {
+        let db = SchemaReflyDatabase::default();
+        let manifest_input = queries::ManifestInput::new(&db, manifest_json.to_string());
+        let mut configs = ConfigInputs::new(config);
+
+        let Some(manifest) = queries::manifest(&db, manifest_input) else {
+            return Vec::new();
+        };
+
+        let mut model_paths: Vec<PathBuf> = manifest
+            .models()
+            .values()
+            .map(|node| root_path.join(&node.original_file_path))
+            .collect();
+        model_paths.sort();
+        model_paths.dedup();
+
+        model_paths
+            .into_iter()
+            .take(WORKSPACE_DIAGNOSTICS_LIMIT)
+            .filter_map(|path| {
+                let uri = Url::from_file_path(&path).ok()?;
+                let content = match open_documents.get(&uri) {
+                    Some(text) => text.clone(),
+                    None => std::fs::read_to_string(&path).ok()?,
+                };
+                let config_input = Self::config_for(&db, &mut configs, &path);
+                let diagnostics =
+                    Self::diagnostics_for(&db, &path, content, config_input, manifest_input, Some(root_path));
+                Some((uri, diagnostics))
+            })
             .collect()
     }
 
     /// Convert SchemaRefly diagnostic to LSP diagnostic
-    fn convert_diagnostic(&self, diag: SchemaDiagnostic) -> Diagnostic {
+    fn convert_diagnostic(diag: SchemaDiagnostic, root_path: Option<&std::path::Path>) -> Diagnostic {
         let severity = match diag.severity {
             Severity::Error => DiagnosticSeverity::ERROR,
             Severity::Warn => DiagnosticSeverity::WARNING,
@@ -175,12 +419,50 @@ impl Backend {
             code: Some(NumberOrString::String(diag.code.as_str().to_string())),
             source: Some("schemarefly".to_string()),
             message: diag.message,
+            related_information: Self::related_information(&diag.related, root_path),
             ..Default::default()
         }
     }
 
+    /// Convert related locations to LSP related information
+    fn related_information(
+        related: &[schemarefly_core::Location],
+        root_path: Option<&std::path::Path>,
+    ) -> Option<Vec<DiagnosticRelatedInformation>> {
+        let info: Vec<DiagnosticRelatedInformation> = related
+            .iter()
+            .filter_map(|loc| {
+                let path = std::path::Path::new(&loc.file);
+                let path = match root_path {
+                    Some(root) if path.is_relative() => root.join(path),
+                    _ => path.to_path_buf(),
+                };
+                let uri = Url::from_file_path(path).ok()?;
+
+                // LSP positions are 0-indexed
+                let line = loc.line.unwrap_or(1).saturating_sub(1) as u32;
+                let character = loc.column.unwrap_or(1).saturating_sub(1) as u32;
+                let position = Position { line, character };
+
+                Some(DiagnosticRelatedInformation {
+                    location: Location {
+                        uri,
+                        range: Range { start: position, end: position },
+                    },
+                    message: "Declared in contract here".to_string(),
+                })
+            })
+            .collect();
+
+        if info.is_empty() { None } else { Some(info) }
+    }
+
     /// Get hover information for a position in a document
-    async fn get_hover(&self, uri: &Url, _position: Position) -> Option<Hover> {
+    ///
+    /// Over a `ref('x')` call this shows model `x`'s schema; over a column
+    /// of the current model it shows that column's type; anywhere else it
+    /// shows the current model's inferred schema.
+    async fn get_hover(&self, uri: &Url, position: Position) -> Option<Hover> {
         // Get document content
         let documents = self.documents.read().await;
         let content = documents.get(uri)?.clone();
@@ -197,19 +479,84 @@ impl Backend {
             return None;
         }
 
+        let root_path = self.root_uri.read().await
+            .as_ref()
+            .and_then(|u| u.to_file_path().ok());
+
         // Create fresh Salsa database
         let db = SchemaReflyDatabase::default();
 
         // Create Salsa inputs
-        let sql_file = queries::SqlFile::new(&db, file_path, content);
         let manifest_input = queries::ManifestInput::new(&db, manifest_json.as_ref().unwrap().clone());
-        let config_input = queries::ConfigInput::new(&db, config.clone());
+        let config_input = Self::config_for(&db, &mut ConfigInputs::new(config.clone()), &file_path);
+
+        let token = token_at(&content, position);
+
+        if let Some(DbtReference::Ref { model_name, .. }) = token.as_ref().and_then(|t| t.reference.clone()) {
+            let manifest = queries::manifest(&db, manifest_input)?;
+            let markdown = Self::upstream_schema_markdown(
+                &db,
+                &manifest,
+                &model_name,
+                config_input,
+                manifest_input,
+                root_path.as_deref(),
+            )?;
+            return Some(Self::markdown_hover(markdown));
+        }
+
+        // Replace ref()/source() calls so the SQL parses
+        let preprocessed = Self::preprocessed(&db, &content, config_input, manifest_input);
+        let sql_file = queries::SqlFile::new(&db, file_path, preprocessed);
 
         // Infer schema
         let schema = queries::infer_schema(&db, sql_file, config_input, manifest_input).ok()?;
 
-        // Format schema as markdown
-        let mut markdown = String::from("## Inferred Schema\n\n");
+        if let Some(column) = token.as_ref().and_then(|t| schema.find_column(&t.text)) {
+            return Some(Self::markdown_hover(format!("`{}`: {}", column.name, column.logical_type)));
+        }
+
+        Some(Self::markdown_hover(Self::schema_markdown("Inferred Schema", &schema)))
+    }
+
+    /// Schema of an upstream model as hover markdown
+    ///
+    /// Uses the model's contract when it has one; otherwise infers the schema
+    /// from the model's SQL file under `root_path`.
+    fn upstream_schema_markdown(
+        db: &SchemaReflyDatabase,
+        manifest: &schemarefly_dbt::Manifest,
+        model_name: &str,
+        config_input: queries::ConfigInput,
+        manifest_input: queries::ManifestInput,
+        root_path: Option<&Path>,
+    ) -> Option<String> {
+        let node = Self::model_named(manifest, model_name)?;
+
+        if let Some(contract) = schemarefly_dbt::ContractExtractor::extract_from_node(node) {
+            return Some(Self::schema_markdown(&format!("`{}` (contract)", node.name), &contract.schema));
+        }
+
+        let sql = std::fs::read_to_string(root_path?.join(&node.original_file_path)).ok()?;
+        let (preprocessed, _) = DbtFunctionExtractor::preprocess_with_vars(&sql, Some(manifest), &config_input.config(db).vars);
+        let sql_file = queries::SqlFile::new(db, PathBuf::from(&node.original_file_path), preprocessed);
+        let schema = queries::infer_schema(db, sql_file, config_input, manifest_input).ok()?;
+
+        Some(Self::schema_markdown(&format!("`{}` (inferred)", node.name), &schema))
+    }
+
+    /// Model with the given name, preferring the lowest unique_id when names repeat
+    fn model_named<'m>(manifest: &'m schemarefly_dbt::Manifest, name: &str) -> Option<&'m schemarefly_dbt::ManifestNode> {
+        manifest
+            .nodes
+            .values()
+            .filter(|n| n.resource_type == "model" && n.name == name)
+            .min_by(|a, b| a.unique_id.cmp(&b.unique_id))
+    }
+
+    /// Format a schema as a markdown table under a heading
+    fn schema_markdown(title: &str, schema: &schemarefly_core::Schema) -> String {
+        let mut markdown = format!("## {}\n\n", title);
         markdown.push_str("| Column | Type |\n");
         markdown.push_str("|--------|------|\n");
 
@@ -217,21 +564,26 @@ impl Backend {
             markdown.push_str(&format!("| `{}` | {} |\n", col.name, col.logical_type));
         }
 
-        Some(Hover {
+        markdown
+    }
+
+    /// Wrap markdown in a hover response
+    fn markdown_hover(markdown: String) -> Hover {
+        Hover {
             contents: HoverContents::Scalar(MarkedString::String(markdown)),
             range: None,
-        })
+        }
     }
 
     /// Get go-to-definition location for a position in a document
     async fn get_definition(
         &self,
         uri: &Url,
-        _position: Position,
+        position: Position,
     ) -> Option<GotoDefinitionResponse> {
         // Get document content
         let documents = self.documents.read().await;
-        let _content = documents.get(uri)?.clone();
+        let content = documents.get(uri)?.clone();
         drop(documents);
 
         // Get manifest
@@ -245,16 +597,25 @@ impl Backend {
 
         // Parse manifest
         let manifest_input = queries::ManifestInput::new(&db, manifest_json.as_ref().unwrap().clone());
-        let _manifest = queries::manifest(&db, manifest_input)?;
-
-        // TODO: Parse the SQL at the cursor position to identify:
-        // 1. If it's a ref('model_name') -> find the model file
-        // 2. If it's a contract column reference -> find the YAML definition
+        let manifest = queries::manifest(&db, manifest_input)?;
 
-        // For now, return a placeholder that goes to the schema.yml
         let root_uri = self.root_uri.read().await;
         let root_path = root_uri.as_ref()?.to_file_path().ok()?;
 
+        // A ref('model_name') under the cursor jumps to that model's SQL file
+        if let Some(DbtReference::Ref { model_name, .. }) = token_at(&content, position).and_then(|t| t.reference) {
+            let node = Self::model_named(&manifest, &model_name)?;
+            let model_uri = Url::from_file_path(root_path.join(&node.original_file_path)).ok()?;
+            return Some(GotoDefinitionResponse::Scalar(Location {
+                uri: model_uri,
+                range: Range::default(),
+            }));
+        }
+
+        // TODO: If it's a contract column reference -> find the YAML definition
+
+        // Otherwise return a placeholder that goes to the schema.yml
+
         // Try to find schema.yml in models/ directory
         let schema_path = root_path.join("models").join("schema.yml");
         if schema_path.exists() {
@@ -285,13 +646,13 @@ impl LanguageServer for Backend {
         // Store root URI
         *self.root_uri.write().await = params.root_uri.clone();
 
-        // Load manifest and config
+        // Load config first; it decides where the manifest lives
+        *self.config.write().await = self.load_config().await;
+
         if let Some(manifest) = self.load_manifest().await {
             *self.manifest_json.write().await = Some(manifest);
         }
 
-        *self.config.write().await = self.load_config().await;
-
         // Report initialization to client
         self.client
             .log_message(MessageType::INFO, "SchemaRefly LSP initialized")
@@ -317,6 +678,10 @@ impl LanguageServer for Backend {
         self.client
             .log_message(MessageType::INFO, "SchemaRefly LSP server initialized")
             .await;
+
+        // Populate the Problems panel project-wide without delaying other requests
+        let backend = self.clone();
+        tokio::spawn(async move { backend.publish_workspace_diagnostics().await });
     }
 
     async fn shutdown(&self) -> Result<()> {
@@ -347,11 +712,8 @@ impl LanguageServer for Backend {
                 .await
                 .insert(uri.clone(), change.text.clone());
 
-            // Compute and publish diagnostics on change (if fast enough)
-            let diagnostics = self.compute_diagnostics(&uri).await;
-            self.client
-                .publish_diagnostics(uri, diagnostics, None)
-                .await;
+            // Republish this file and any open downstream models (if fast enough)
+            self.publish_with_downstream(&uri).await;
         }
     }
 
@@ -363,11 +725,11 @@ impl LanguageServer for Backend {
             self.documents.write().await.insert(uri.clone(), text);
         }
 
-        // Reload manifest and config on save
+        // Reload config and manifest on save
+        *self.config.write().await = self.load_config().await;
         if let Some(manifest) = self.load_manifest().await {
             *self.manifest_json.write().await = Some(manifest);
         }
-        *self.config.write().await = self.load_config().await;
 
         // Compute and publish diagnostics
         let diagnostics = self.compute_diagnostics(&uri).await;
@@ -401,3 +763,283 @@ impl LanguageServer for Backend {
         Ok(self.get_definition(&uri, position).await)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use tower::{Service, ServiceExt};
+    use tower_lsp::jsonrpc::Request;
+    use tokio::sync::mpsc::UnboundedReceiver;
+    use tower_lsp::LspService;
+
+    fn write_project(root: &Path) {
+        let model = |name: &str, parent: Option<&str>| {
+            let depends_on: Vec<String> = parent.iter().map(|p| format!("model.shop.{p}")).collect();
+            format!(
+                r#""model.shop.{name}": {{
+                    "unique_id": "model.shop.{name}",
+                    "name": "{name}",
+                    "resource_type": "model",
+                    "package_name": "shop",
+                    "path": "{name}.sql",
+                    "original_file_path": "models/{name}.sql",
+                    "database": "analytics",
+                    "schema": "core",
+                    "config": {{"contract": {{"enforced": true}}}},
+                    "depends_on": {{"nodes": {depends_on:?}}},
+                    "columns": {{
+                        "id": {{"name": "id", "data_type": "integer"}},
+                        "name": {{"name": "name", "data_type": "varchar"}}
+                    }}
+                }}"#
+            )
+        };
+        let manifest = format!(
+            r#"{{
+                "metadata": {{"dbt_schema_version": "v10", "dbt_version": "1.7.0", "generated_at": "2024-01-01T00:00:00Z"}},
+                "nodes": {{{}, {}, {}}},
+                "sources": {{
+                    "source.shop.raw.users": {{
+                        "unique_id": "source.shop.raw.users", "source_name": "raw", "name": "users",
+                        "database": "raw", "schema": "app", "resource_type": "source", "package_name": "shop",
+                        "path": "models/sources.yml", "original_file_path": "models/sources.yml",
+                        "columns": {{"name": {{"name": "name", "data_type": "varchar"}}}}
+                    }}
+                }}
+            }}"#,
+            model("users", None),
+            model("orders", Some("users")),
+            model("revenue", Some("orders"))
+        );
+
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::create_dir_all(root.join("models")).unwrap();
+        std::fs::write(root.join("target/manifest.json"), manifest).unwrap();
+        // Real models select through dbt calls, which only parse once preprocessed
+        std::fs::write(root.join("models/users.sql"), "SELECT 'abc' AS id, name FROM {{ source('raw', 'users') }}").unwrap();
+        std::fs::write(root.join("models/orders.sql"), "SELECT 'abc' AS id, name FROM {{ ref('users') }}").unwrap();
+        std::fs::write(root.join("models/revenue.sql"), "SELECT id, name FROM {{ ref('orders') }}").unwrap();
+    }
+
+    /// Collect publishDiagnostics notifications until the server goes quiet
+    async fn published_until_quiet(messages: &mut UnboundedReceiver<Request>) -> HashMap<String, Vec<Diagnostic>> {
+        let mut published = HashMap::new();
+        while let Ok(Some(message)) =
+            tokio::time::timeout(std::time::Duration::from_millis(300), messages.recv()).await
+        {
+            if message.method() == "textDocument/publishDiagnostics" {
+                let params = message.params().cloned().unwrap();
+                let uri = params["uri"].as_str().unwrap().to_string();
+                published.insert(uri, serde_json::from_value(params["diagnostics"].clone()).unwrap());
+            }
+        }
+        published
+    }
+
+    /// Wait for the background workspace pass started by `initialized`
+    async fn wait_for_workspace_pass(messages: &mut UnboundedReceiver<Request>) {
+        let wait = async {
+            loop {
+                let message = messages.recv().await.expect("client socket closed");
+                let is_done = message.method() == "window/logMessage"
+                    && message.params().and_then(|p| p["message"].as_str())
+                        .is_some_and(|m| m.starts_with("Published workspace diagnostics"));
+                if is_done {
+                    break;
+                }
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(10), wait)
+            .await
+            .expect("workspace diagnostics pass did not finish");
+    }
+
+    /// Start a server rooted at `root` and run the initialize handshake
+    ///
+    /// Server-to-client messages are drained into the returned channel so
+    /// they never block the server.
+    async fn start_server(root: &Path) -> (LspService<Backend>, UnboundedReceiver<Request>) {
+        let (mut service, mut socket) = LspService::new(Backend::new);
+        let root_uri = Url::from_file_path(root).unwrap();
+
+        let (messages_tx, messages) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(message) = socket.next().await {
+                if messages_tx.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let initialize = Request::build("initialize")
+            .params(serde_json::json!({"capabilities": {}, "rootUri": root_uri}))
+            .id(1)
+            .finish();
+        service.ready().await.unwrap().call(initialize).await.unwrap();
+
+        let initialized = Request::build("initialized").params(serde_json::json!({})).finish();
+        service.ready().await.unwrap().call(initialized).await.unwrap();
+
+        (service, messages)
+    }
+
+    #[tokio::test]
+    async fn initialized_publishes_diagnostics_for_every_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        write_project(&root);
+
+        let (_service, mut messages) = start_server(&root).await;
+
+        // Collect publishes until every model has been reported
+        let mut published: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+        let collect = async {
+            while published.len() < 3 {
+                let message = messages.recv().await.expect("client socket closed");
+                if message.method() == "textDocument/publishDiagnostics" {
+                    let params = message.params().cloned().unwrap();
+                    let uri = params["uri"].as_str().unwrap().to_string();
+                    let diagnostics = serde_json::from_value(params["diagnostics"].clone()).unwrap();
+                    published.insert(uri, diagnostics);
+                }
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(10), collect)
+            .await
+            .expect("workspace diagnostics were not published");
+
+        for model in ["orders", "users"] {
+            let uri = Url::from_file_path(root.join("models").join(format!("{model}.sql"))).unwrap();
+            let diagnostics = &published[uri.as_str()];
+            assert!(
+                diagnostics
+                    .iter()
+                    .any(|d| d.code == Some(NumberOrString::String("CONTRACT_TYPE_MISMATCH".to_string()))),
+                "expected a type mismatch for {model}, got {diagnostics:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn hover_over_ref_shows_upstream_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        write_project(&root);
+
+        let sql = "SELECT id, name FROM {{ ref('users') }}";
+        let path = root.join("models/report.sql");
+        std::fs::write(&path, sql).unwrap();
+        let uri = Url::from_file_path(&path).unwrap();
+
+        let (mut service, _messages) = start_server(&root).await;
+
+        let open = Request::build("textDocument/didOpen")
+            .params(serde_json::json!({
+                "textDocument": {"uri": uri, "languageId": "sql", "version": 1, "text": sql}
+            }))
+            .finish();
+        service.ready().await.unwrap().call(open).await.unwrap();
+
+        let mut hover_at = |id: i64, character: usize| {
+            let request = Request::build("textDocument/hover")
+                .params(serde_json::json!({
+                    "textDocument": {"uri": uri},
+                    "position": {"line": 0, "character": character}
+                }))
+                .id(id)
+                .finish();
+            let call = service.call(request);
+            async move {
+                let response = call.await.unwrap().unwrap();
+                let (_, result) = response.into_parts();
+                let hover: Hover = serde_json::from_value(result.unwrap()).unwrap();
+                match hover.contents {
+                    HoverContents::Scalar(MarkedString::String(markdown)) => markdown,
+                    other => panic!("unexpected hover contents: {other:?}"),
+                }
+            }
+        };
+
+        // Cursor on `users` inside ref('users')
+        let users = hover_at(2, sql.find("users").unwrap() + 2).await;
+        assert!(users.starts_with("## `users` (contract)"), "{users}");
+        assert!(users.contains("| `id` | INT |"), "{users}");
+        assert!(users.contains("| `name` | STRING |"), "{users}");
+
+        // Cursor on the projected `name` column
+        let name = hover_at(3, sql.find("name").unwrap() + 1).await;
+        assert_eq!(name, "`name`: STRING");
+    }
+
+    #[tokio::test]
+    async fn editing_upstream_republishes_open_downstream_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        write_project(&root);
+
+        let (mut service, mut messages) = start_server(&root).await;
+        wait_for_workspace_pass(&mut messages).await;
+
+        let uri = |model: &str| Url::from_file_path(root.join(format!("models/{model}.sql"))).unwrap();
+        let open = |model: &str, text: &str| {
+            Request::build("textDocument/didOpen")
+                .params(serde_json::json!({
+                    "textDocument": {"uri": uri(model), "languageId": "sql", "version": 1, "text": text}
+                }))
+                .finish()
+        };
+        service.ready().await.unwrap().call(open("users", "SELECT 1 AS id, name FROM {{ source('raw', 'users') }}")).await.unwrap();
+        service.ready().await.unwrap().call(open("orders", "SELECT id, name FROM {{ ref('users') }}")).await.unwrap();
+        let before = published_until_quiet(&mut messages).await;
+        assert_eq!(before.get(uri("orders").as_str()), Some(&Vec::new()));
+
+        // `id` becomes a string upstream, while the users contract still says integer
+        let change = Request::build("textDocument/didChange")
+            .params(serde_json::json!({
+                "textDocument": {"uri": uri("users"), "version": 2},
+                "contentChanges": [{"text": "SELECT 'abc' AS id, name FROM {{ source('raw', 'users') }}"}]
+            }))
+            .finish();
+        service.ready().await.unwrap().call(change).await.unwrap();
+
+        let published = published_until_quiet(&mut messages).await;
+
+        // The open child now sees the edited schema; the closed grandchild is not rechecked
+        let is_type_mismatch = |d: &Diagnostic| d.code == Some(NumberOrString::String("CONTRACT_TYPE_MISMATCH".to_string()));
+        assert!(published[uri("users").as_str()].iter().any(is_type_mismatch));
+        let orders = &published[uri("orders").as_str()];
+        assert!(orders.iter().any(|d| is_type_mismatch(d) && d.message.contains("id")), "{orders:?}");
+        assert!(!published.contains_key(uri("revenue").as_str()));
+    }
+
+    #[test]
+    fn each_file_uses_the_nearest_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        write_project(&root);
+        let nested = "SELECT id FROM (SELECT id FROM (SELECT 'abc' AS id) a) b";
+        std::fs::write(root.join("models/users.sql"), nested).unwrap();
+        let manifest_json = std::fs::read_to_string(root.join("target/manifest.json")).unwrap();
+        let users = Url::from_file_path(root.join("models/users.sql")).unwrap();
+        let codes = |published: &[(Url, Vec<Diagnostic>)]| -> Vec<String> {
+            let (_, diagnostics) = published.iter().find(|(uri, _)| *uri == users).unwrap();
+            diagnostics
+                .iter()
+                .filter_map(|d| match &d.code {
+                    Some(NumberOrString::String(code)) => Some(code.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let default = Backend::workspace_diagnostics(&manifest_json, Config::default(), &root, &HashMap::new());
+        assert!(codes(&default).contains(&"CONTRACT_TYPE_MISMATCH".to_string()));
+
+        // A config next to the models limits nesting, so inference gives up and nothing is compared
+        std::fs::write(root.join("models/schemarefly.toml"), "[inference]\nmax_depth = 1\n").unwrap();
+        let limited = Backend::workspace_diagnostics(&manifest_json, Config::default(), &root, &HashMap::new());
+        assert!(codes(&limited).is_empty(), "{:?}", codes(&limited));
+    }
+}
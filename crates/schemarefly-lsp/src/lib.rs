@@ -21,5 +21,7 @@
 //! in dbt projects.
 
 mod backend;
+pub mod token;
 
 pub use backend::Backend;
+pub use token::{token_at, Token};
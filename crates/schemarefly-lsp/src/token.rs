@@ -0,0 +1,199 @@
+//! Token lookup at a cursor position
+//!
+//! Hover, go-to-definition and future completion/code actions all need to
+//! know what is under the cursor. `token_at` returns the identifier touching
+//! the position and, when the cursor sits inside a `ref(...)` or
+//! `source(...)` call, the parsed dbt reference.
+
+use schemarefly_sql::{DbtFunctionExtractor, DbtReference};
+use tower_lsp::lsp_types::{Position, Range};
+
+/// dbt functions whose arguments name another node
+const REFERENCE_FUNCTIONS: &[&str] = &["ref(", "source("];
+
+/// The token under a cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// Identifier text (letters, digits, `_`)
+    pub text: String,
+
+    /// Span of the identifier (UTF-16 columns)
+    pub range: Range,
+
+    /// The `ref(...)`/`source(...)` call containing the cursor, if any
+    pub reference: Option<DbtReference>,
+}
+
+/// Identifier under `position` in `content`, plus any enclosing dbt reference
+///
+/// Returns `None` when the cursor is not touching an identifier (e.g. in
+/// whitespace) or the position is past the end of the document.
+pub fn token_at(content: &str, position: Position) -> Option<Token> {
+    let offset = offset_at(content, position)?;
+    let (start, end) = identifier_span(content, offset)?;
+
+    Some(Token {
+        text: content[start..end].to_string(),
+        range: Range {
+            start: position_at(content, start),
+            end: position_at(content, end),
+        },
+        reference: reference_at(content, offset),
+    })
+}
+
+/// Byte offset of an LSP position (UTF-16 columns) in `content`
+pub fn offset_at(content: &str, position: Position) -> Option<usize> {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        line_start += content[line_start..].find('\n')? + 1;
+    }
+
+    let line = content[line_start..].split('\n').next().unwrap_or_default();
+    let mut units = 0;
+    for (index, ch) in line.char_indices() {
+        if units >= position.character as usize {
+            return Some(line_start + index);
+        }
+        units += ch.len_utf16();
+    }
+
+    Some(line_start + line.len())
+}
+
+/// LSP position (UTF-16 columns) of a byte offset in `content`
+pub fn position_at(content: &str, offset: usize) -> Position {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: before[line_start..].encode_utf16().count() as u32,
+    }
+}
+
+/// Byte span of the identifier touching `offset`, if any
+fn identifier_span(content: &str, offset: usize) -> Option<(usize, usize)> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+    let start = content[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_ident(*c))
+        .last()
+        .map_or(offset, |(i, _)| i);
+    let end = content[offset..]
+        .char_indices()
+        .find(|(_, c)| !is_ident(*c))
+        .map_or(content.len(), |(i, _)| offset + i);
+
+    (start < end).then_some((start, end))
+}
+
+/// The `ref(...)`/`source(...)` call spanning `offset`, if any
+fn reference_at(content: &str, offset: usize) -> Option<DbtReference> {
+    for function in REFERENCE_FUNCTIONS {
+        let mut search = 0;
+        while let Some(found) = content[search..].find(function) {
+            let start = search + found;
+            let Some(close) = content[start..].find(')') else {
+                break;
+            };
+            let end = start + close;
+
+            // Skip longer names that merely end in `ref`/`source`
+            let standalone = !content[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_');
+
+            if standalone && (start..=end).contains(&offset) {
+                let block = format!("{{{{ {} }}}}", &content[start..=end]);
+                return DbtFunctionExtractor::extract(&block).into_iter().next();
+            }
+
+            search = end;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQL: &str = "SELECT id, name\nFROM {{ ref('orders') }} JOIN {{ source(\"raw\", \"users\") }}";
+
+    fn position_of(needle: &str) -> Position {
+        position_at(SQL, SQL.find(needle).unwrap())
+    }
+
+    #[test]
+    fn cursor_inside_identifier() {
+        let mut position = position_of("name");
+        position.character += 2;
+
+        let token = token_at(SQL, position).unwrap();
+
+        assert_eq!(token.text, "name");
+        assert_eq!(token.range.start, position_of("name"));
+        assert_eq!(token.range.end.character, token.range.start.character + 4);
+        assert_eq!(token.reference, None);
+    }
+
+    #[test]
+    fn cursor_inside_ref_string() {
+        let token = token_at(SQL, position_of("orders")).unwrap();
+
+        assert_eq!(token.text, "orders");
+        assert_eq!(
+            token.reference,
+            Some(DbtReference::Ref { model_name: "orders".to_string(), unique_id: None })
+        );
+
+        // The function name itself is inside the call too
+        assert!(matches!(token_at(SQL, position_of("ref")).unwrap().reference, Some(DbtReference::Ref { .. })));
+    }
+
+    #[test]
+    fn cursor_inside_source_call() {
+        let token = token_at(SQL, position_of("users")).unwrap();
+
+        assert_eq!(
+            token.reference,
+            Some(DbtReference::Source {
+                source_name: "raw".to_string(),
+                table_name: "users".to_string(),
+                unique_id: None,
+            })
+        );
+    }
+
+    #[test]
+    fn cursor_in_whitespace() {
+        let sql = "SELECT   id";
+        assert_eq!(token_at(sql, Position { line: 0, character: 7 }), None);
+        assert_eq!(token_at(sql, Position { line: 3, character: 0 }), None);
+    }
+
+    #[test]
+    fn offsets_round_trip_utf16_positions() {
+        let sql = "SELECT 'é' AS accent,\n  id";
+        let id = sql.find("id").unwrap();
+
+        assert_eq!(position_at(sql, id), Position { line: 1, character: 2 });
+        assert_eq!(offset_at(sql, Position { line: 1, character: 2 }), Some(id));
+
+        let accent = sql.find("accent").unwrap();
+        assert_eq!(position_at(sql, accent).character, 14);
+        assert_eq!(token_at(sql, position_at(sql, accent)).unwrap().text, "accent");
+    }
+
+    #[test]
+    fn lookalike_function_names_are_not_references() {
+        let sql = "SELECT xref('orders') AS a";
+        let token = token_at(sql, position_at(sql, sql.find("orders").unwrap())).unwrap();
+        assert_eq!(token.reference, None);
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! Handles dbt Jinja templates like {{ ref('model') }} and {{ source('source', 'table') }}
 
+use schemarefly_core::{Diagnostic, DiagnosticCode, Location, Severity};
 use schemarefly_dbt::Manifest;
+use schemarefly_jinja::{DbtContext, JinjaPreprocessor};
 use std::collections::HashMap;
 
 /// A reference to a dbt model or source
@@ -160,6 +162,92 @@ impl DbtFunctionExtractor {
         }
     }
 
+    /// unique_id of the manifest node a reference names
+    ///
+    /// A `ref()` may target a model, seed or snapshot, a model winning over a
+    /// seed or snapshot of the same name; a `source()` targets a source.
+    /// Unlike `resolve`, which only fills in model ids for preprocessing, this
+    /// answers whether the reference names anything at all.
+    pub fn resolved_target(ref_: &DbtReference, manifest: &Manifest) -> Option<String> {
+        match ref_ {
+            DbtReference::Ref { model_name, .. } => manifest
+                .nodes
+                .iter()
+                .filter(|(_, node)| node.name == *model_name)
+                .filter_map(|(node_id, node)| match node.resource_type.as_str() {
+                    "model" => Some((0, node_id)),
+                    "seed" | "snapshot" => Some((1, node_id)),
+                    _ => None,
+                })
+                .min()
+                .map(|(_, node_id)| node_id.clone()),
+            DbtReference::Source { source_name, table_name, .. } => manifest
+                .sources
+                .iter()
+                .find(|(_, source)| source.source_name == *source_name && source.name == *table_name)
+                .map(|(source_id, _)| source_id.clone()),
+        }
+    }
+
+    /// Diagnostics for references that name nothing in the manifest
+    ///
+    /// Runs before preprocessing, which would otherwise rewrite a typo into a
+    /// table name that only fails later as an unknown table. A `ref()` may
+    /// target a model, seed or snapshot. Each diagnostic names the closest
+    /// existing target when one is near enough to be a likely typo.
+    pub fn unresolved_references(sql: &str, manifest: &Manifest, file_path: Option<&str>) -> Vec<Diagnostic> {
+        let mut seen = std::collections::HashSet::new();
+        let mut diagnostics = Vec::new();
+
+        for ref_ in Self::extract(sql) {
+            let pattern = Self::reference_pattern(&ref_);
+            if !seen.insert(pattern.clone()) || Self::resolved_target(&ref_, manifest).is_some() {
+                continue;
+            }
+
+            let message = match &ref_ {
+                DbtReference::Ref { model_name, .. } => {
+                    let targets: Vec<String> = manifest
+                        .nodes
+                        .values()
+                        .filter(|node| matches!(node.resource_type.as_str(), "model" | "seed" | "snapshot"))
+                        .map(|node| node.name.clone())
+                        .collect();
+                    let mut message = format!("{} does not match any model, seed or snapshot in the manifest", pattern);
+                    if let Some(closest) = closest_name(model_name, targets) {
+                        message.push_str(&format!("; did you mean ref('{}')?", closest));
+                    }
+                    message
+                }
+                DbtReference::Source { source_name, table_name, .. } => {
+                    let name = format!("{}.{}", source_name, table_name);
+                    let targets: Vec<String> = manifest
+                        .sources
+                        .values()
+                        .map(|source| format!("{}.{}", source.source_name, source.name))
+                        .collect();
+                    let mut message = format!("{} does not match any source in the manifest", pattern);
+                    if let Some(closest) = closest_name(&name, targets) {
+                        let (source, table) = closest.split_once('.').unwrap_or_default();
+                        message.push_str(&format!("; did you mean source('{}', '{}')?", source, table));
+                    }
+                    message
+                }
+            };
+
+            let mut diag = Diagnostic::new(DiagnosticCode::SqlUnresolvedRef, Severity::Error, message);
+            if let Some(file) = file_path {
+                diag = diag.with_location(match line_and_column(sql, &pattern) {
+                    Some((line, column)) => Location::with_position(file, line, column),
+                    None => Location::new(file),
+                });
+            }
+            diagnostics.push(diag);
+        }
+
+        diagnostics
+    }
+
     /// Preprocess SQL to replace dbt functions with table names
     ///
     /// This allows the SQL to be parsed by standard SQL parsers.
@@ -176,40 +264,7 @@ impl DbtFunctionExtractor {
 
         // Replace each reference with a table name
         for (i, ref_) in references.iter().enumerate() {
-            let placeholder = match ref_ {
-                DbtReference::Ref { model_name, unique_id } => {
-                    // Use the model name or unique_id
-                    if let Some(id) = unique_id {
-                        // Extract table name from unique_id
-                        if let Some(node) = manifest.and_then(|m| m.get_node(id)) {
-                            format!("{}.{}.{}",
-                                node.database.as_ref().unwrap_or(&"db".to_string()),
-                                node.schema.as_ref().unwrap_or(&"schema".to_string()),
-                                node.alias.as_ref().unwrap_or(&node.name)
-                            )
-                        } else {
-                            model_name.clone()
-                        }
-                    } else {
-                        model_name.clone()
-                    }
-                }
-                DbtReference::Source { source_name, table_name, unique_id } => {
-                    if let Some(id) = unique_id {
-                        if let Some(source) = manifest.and_then(|m| m.get_source(id)) {
-                            format!("{}.{}.{}",
-                                source.database.as_ref().unwrap_or(&"db".to_string()),
-                                &source.schema,
-                                source.identifier.as_ref().unwrap_or(&source.name)
-                            )
-                        } else {
-                            format!("{}.{}", source_name, table_name)
-                        }
-                    } else {
-                        format!("{}.{}", source_name, table_name)
-                    }
-                }
-            };
+            let placeholder = Self::table_name(ref_, manifest);
 
             // Find and replace the {{ }} block
             let pattern = format!("{{{{ {} }}}}", Self::reference_pattern(ref_));
@@ -229,6 +284,70 @@ impl DbtFunctionExtractor {
         (result, replacements)
     }
 
+    /// Preprocess SQL, then render the Jinja left over with dbt `vars`
+    ///
+    /// References are rewritten first, so `{% if var('x') %}` branches keep
+    /// their resolved table names; `var()` sees `vars` on top of the default
+    /// project variables. If rendering fails the preprocessed SQL is returned
+    /// unrendered, the same as `preprocess`.
+    pub fn preprocess_with_vars(
+        sql: &str,
+        manifest: Option<&Manifest>,
+        vars: &HashMap<String, serde_json::Value>,
+    ) -> (String, HashMap<String, DbtReference>) {
+        let (preprocessed, replacements) = Self::preprocess(sql, manifest);
+        if !JinjaPreprocessor::has_jinja(&preprocessed) {
+            return (preprocessed, replacements);
+        }
+
+        let context = DbtContext::default().with_vars(vars.clone());
+        match JinjaPreprocessor::new(context).preprocess(&preprocessed, None) {
+            Ok(rendered) => (rendered.rendered_sql, replacements),
+            Err(_) => (preprocessed, replacements),
+        }
+    }
+
+    /// Table name a reference is rewritten to during preprocessing
+    ///
+    /// Resolved references use the manifest's `database.schema.alias`; unresolved
+    /// ones fall back to the bare model name or `source.table`.
+    pub fn table_name(ref_: &DbtReference, manifest: Option<&Manifest>) -> String {
+        match ref_ {
+            DbtReference::Ref { model_name, unique_id } => {
+                // Use the model name or unique_id
+                if let Some(id) = unique_id {
+                    // Extract table name from unique_id
+                    if let Some(node) = manifest.and_then(|m| m.get_node(id)) {
+                        format!("{}.{}.{}",
+                            node.database.as_ref().unwrap_or(&"db".to_string()),
+                            node.schema.as_ref().unwrap_or(&"schema".to_string()),
+                            node.alias.as_ref().unwrap_or(&node.name)
+                        )
+                    } else {
+                        model_name.clone()
+                    }
+                } else {
+                    model_name.clone()
+                }
+            }
+            DbtReference::Source { source_name, table_name, unique_id } => {
+                if let Some(id) = unique_id {
+                    if let Some(source) = manifest.and_then(|m| m.get_source(id)) {
+                        format!("{}.{}.{}",
+                            source.database.as_ref().unwrap_or(&"db".to_string()),
+                            &source.schema,
+                            source.identifier.as_ref().unwrap_or(&source.name)
+                        )
+                    } else {
+                        format!("{}.{}", source_name, table_name)
+                    }
+                } else {
+                    format!("{}.{}", source_name, table_name)
+                }
+            }
+        }
+    }
+
     /// Get the pattern to match for a reference
     fn reference_pattern(ref_: &DbtReference) -> String {
         match ref_ {
@@ -242,6 +361,46 @@ impl DbtFunctionExtractor {
     }
 }
 
+/// Candidate closest to `name` by edit distance, if close enough to be a typo
+fn closest_name(name: &str, candidates: impl IntoIterator<Item = String>) -> Option<String> {
+    let max_distance = (name.chars().count() / 3).max(2);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, &candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings, by character
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// 1-based line and column of the first `{{ pattern }}` block in `sql`
+fn line_and_column(sql: &str, pattern: &str) -> Option<(usize, usize)> {
+    let offset = [format!("{{{{ {} }}}}", pattern), format!("{{{{{}}}}}", pattern)]
+        .iter()
+        .find_map(|block| sql.find(block.as_str()))?;
+
+    let before = &sql[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rfind('\n').map_or(offset, |newline| offset - newline - 1) + 1;
+    Some((line, column))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -315,4 +474,77 @@ mod tests {
             assert!(preprocessed.contains("."));
         }
     }
+
+    #[test]
+    fn preprocess_with_vars_renders_var_branches() {
+        let sql = "SELECT id{% if var('include_email') %}, email{% endif %} FROM {{ ref('users') }}";
+
+        let with = HashMap::from([("include_email".to_string(), serde_json::json!(true))]);
+        let (rendered, _) = DbtFunctionExtractor::preprocess_with_vars(sql, None, &with);
+        assert_eq!(rendered, "SELECT id, email FROM users");
+
+        let without = HashMap::from([("include_email".to_string(), serde_json::json!(false))]);
+        let (rendered, _) = DbtFunctionExtractor::preprocess_with_vars(sql, None, &without);
+        assert_eq!(rendered, "SELECT id FROM users");
+
+        // An undefined var cannot render; the SQL is left as preprocessed
+        let (rendered, _) = DbtFunctionExtractor::preprocess_with_vars(sql, None, &HashMap::new());
+        assert!(rendered.contains("{% if var('include_email') %}"));
+    }
+
+    fn manifest() -> Manifest {
+        Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.shop.customers": {
+                    "unique_id": "model.shop.customers", "name": "customers", "resource_type": "model",
+                    "package_name": "shop", "path": "customers.sql", "original_file_path": "models/customers.sql"
+                },
+                "seed.shop.country_codes": {
+                    "unique_id": "seed.shop.country_codes", "name": "country_codes", "resource_type": "seed",
+                    "package_name": "shop", "path": "country_codes.csv", "original_file_path": "seeds/country_codes.csv"
+                }
+            },
+            "sources": {
+                "source.shop.raw.orders": {
+                    "unique_id": "source.shop.raw.orders", "name": "orders", "source_name": "raw",
+                    "schema": "raw", "resource_type": "source", "package_name": "shop"
+                }
+            }
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn unresolved_ref_is_reported_with_suggestion() {
+        let sql = "SELECT c.id, cc.name\nFROM {{ ref('custmers') }} c\nJOIN {{ ref('country_codes') }} cc ON cc.code = c.country";
+
+        let diags = DbtFunctionExtractor::unresolved_references(sql, &manifest(), Some("models/report.sql"));
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code, DiagnosticCode::SqlUnresolvedRef);
+        assert_eq!(diags[0].severity, Severity::Error);
+        assert_eq!(
+            diags[0].message,
+            "ref('custmers') does not match any model, seed or snapshot in the manifest; did you mean ref('customers')?"
+        );
+        let location = diags[0].location.as_ref().unwrap();
+        assert_eq!((location.line, location.column), (Some(2), Some(6)));
+    }
+
+    #[test]
+    fn unresolved_source_is_reported_with_suggestion() {
+        let sql = "SELECT * FROM {{ source('raw', 'ordrs') }} JOIN {{ source('crm', 'accounts') }} USING (id)";
+
+        let diags = DbtFunctionExtractor::unresolved_references(sql, &manifest(), None);
+        let messages: Vec<&str> = diags.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "source('raw', 'ordrs') does not match any source in the manifest; did you mean source('raw', 'orders')?",
+                "source('crm', 'accounts') does not match any source in the manifest",
+            ]
+        );
+        assert!(diags.iter().all(|d| d.code == DiagnosticCode::SqlUnresolvedRef && d.location.is_none()));
+
+        assert!(DbtFunctionExtractor::unresolved_references("SELECT * FROM {{ source('raw', 'orders') }}", &manifest(), None).is_empty());
+    }
 }
@@ -0,0 +1,182 @@
+//! Offline "explain plan" for schema inference
+//!
+//! Shows how the dbt references in a model were resolved before inference runs:
+//! which `ref()`/`source()` calls were found, whether each one matched a manifest
+//! node, the table name it was rewritten to, and the schema (if any) that the
+//! `InferenceContext` holds for it. This makes `UnknownTable` failures easy to
+//! trace back to the reference that didn't resolve.
+
+use crate::dbt_functions::{DbtFunctionExtractor, DbtReference};
+use crate::inference::InferenceContext;
+use schemarefly_core::Schema;
+use schemarefly_dbt::Manifest;
+use std::collections::HashMap;
+
+/// Explanation for a single dbt reference
+#[derive(Debug, Clone)]
+pub struct ReferenceExplanation {
+    /// The reference as extracted from the SQL (with resolved unique_id, if any)
+    pub reference: DbtReference,
+
+    /// Table name the reference is rewritten to in the preprocessed SQL
+    pub table_name: String,
+
+    /// Schema loaded into the inference context for this reference
+    pub schema: Option<Schema>,
+}
+
+impl ReferenceExplanation {
+    /// Whether the reference resolved to a manifest node
+    pub fn is_resolved(&self) -> bool {
+        match &self.reference {
+            DbtReference::Ref { unique_id, .. } => unique_id.is_some(),
+            DbtReference::Source { unique_id, .. } => unique_id.is_some(),
+        }
+    }
+
+    /// Human-readable form of the reference, e.g. `ref('users')`
+    pub fn display_name(&self) -> String {
+        match &self.reference {
+            DbtReference::Ref { model_name, .. } => format!("ref('{}')", model_name),
+            DbtReference::Source { source_name, table_name, .. } => {
+                format!("source('{}', '{}')", source_name, table_name)
+            }
+        }
+    }
+
+    /// Resolved unique_id, if any
+    pub fn unique_id(&self) -> Option<&str> {
+        match &self.reference {
+            DbtReference::Ref { unique_id, .. } => unique_id.as_deref(),
+            DbtReference::Source { unique_id, .. } => unique_id.as_deref(),
+        }
+    }
+}
+
+/// Explain plan for inferring a model's schema
+#[derive(Debug, Clone)]
+pub struct InferenceExplanation {
+    /// All references found in the model, in source order
+    pub references: Vec<ReferenceExplanation>,
+
+    /// SQL after dbt functions were replaced with table names
+    pub preprocessed_sql: String,
+}
+
+impl InferenceExplanation {
+    /// Build an explanation for the given model SQL
+    pub fn explain(sql: &str, manifest: &Manifest, context: &InferenceContext) -> Self {
+        Self::explain_with_vars(sql, manifest, context, &HashMap::new())
+    }
+
+    /// Build an explanation, rendering `var()` and `{% if %}` with project vars
+    pub fn explain_with_vars(
+        sql: &str,
+        manifest: &Manifest,
+        context: &InferenceContext,
+        vars: &HashMap<String, serde_json::Value>,
+    ) -> Self {
+        let mut references = DbtFunctionExtractor::extract(sql);
+        DbtFunctionExtractor::resolve(&mut references, manifest);
+
+        let references = references
+            .into_iter()
+            .map(|reference| {
+                let table_name = DbtFunctionExtractor::table_name(&reference, Some(manifest));
+                let mut explanation = ReferenceExplanation {
+                    reference,
+                    table_name,
+                    schema: None,
+                };
+
+                // Prefer the name the SQL will actually use, then fall back to unique_id
+                explanation.schema = context
+                    .get_table_schema(&explanation.table_name)
+                    .or_else(|| explanation.unique_id().and_then(|id| context.get_table_schema(id)))
+                    .cloned();
+
+                explanation
+            })
+            .collect();
+
+        let (preprocessed_sql, _) = DbtFunctionExtractor::preprocess_with_vars(sql, Some(manifest), vars);
+
+        Self {
+            references,
+            preprocessed_sql,
+        }
+    }
+
+    /// References that did not resolve to a manifest node
+    pub fn unresolved(&self) -> Vec<&ReferenceExplanation> {
+        self.references.iter().filter(|r| !r.is_resolved()).collect()
+    }
+
+    /// Whether every reference resolved to a manifest node
+    pub fn all_resolved(&self) -> bool {
+        self.references.iter().all(|r| r.is_resolved())
+    }
+
+    /// Render the explanation as plain text
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        if self.references.is_empty() {
+            out.push_str("No ref() or source() calls found\n");
+            return out;
+        }
+
+        for (i, r) in self.references.iter().enumerate() {
+            out.push_str(&format!("{}. {}\n", i + 1, r.display_name()));
+
+            match r.unique_id() {
+                Some(id) => out.push_str(&format!("   resolved:   {}\n", id)),
+                None => out.push_str("   resolved:   UNRESOLVED (no matching node in manifest)\n"),
+            }
+
+            out.push_str(&format!("   table name: {}\n", r.table_name));
+
+            match &r.schema {
+                Some(schema) => {
+                    out.push_str(&format!("   schema:     {} columns\n", schema.columns.len()));
+                    for col in &schema.columns {
+                        out.push_str(&format!("     - {}: {}\n", col.name, col.logical_type));
+                    }
+                }
+                None => out.push_str("   schema:     none loaded\n"),
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemarefly_core::{Column, LogicalType};
+
+    #[test]
+    fn unresolved_ref_without_manifest_entries() {
+        let manifest = Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {},
+            "sources": {}
+        }"#).unwrap();
+        let mut context = InferenceContext::new();
+        context.add_table("users", Schema::from_columns(vec![Column::new("id", LogicalType::Int)]));
+
+        let explanation = InferenceExplanation::explain(
+            "SELECT id FROM {{ ref('users') }}",
+            &manifest,
+            &context,
+        );
+
+        assert_eq!(explanation.references.len(), 1);
+        assert!(!explanation.all_resolved());
+        assert_eq!(explanation.references[0].table_name, "users");
+        // The schema is still found by the unqualified name
+        assert!(explanation.references[0].schema.is_some());
+        assert!(explanation.render().contains("UNRESOLVED"));
+    }
+}
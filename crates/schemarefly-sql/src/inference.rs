@@ -5,22 +5,87 @@
 
 use sqlparser::ast::{
     Statement, Query, SetExpr, Select, SelectItem, Expr, DataType,
-    TableFactor, JoinOperator, FunctionArg, ObjectName, Value,
+    TableFactor, JoinOperator, FunctionArg, FunctionArgExpr, ObjectName, Value, Insert, BinaryOperator,
+    TableAlias, OrderByExpr,
 };
-use schemarefly_core::{Schema, Column, LogicalType, Diagnostic, DiagnosticCode, Severity};
+use schemarefly_core::{Schema, Column, LogicalType, Nullability, Diagnostic, DiagnosticCode, Severity, IdentifierCase};
 use schemarefly_dbt::Manifest;
-use std::collections::HashMap;
+use crate::resolver::ColumnIndex;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Names given to unnamed projections that cannot be named from the SQL itself
+///
+/// When several unnamed projections share one of these, or an operator name
+/// derived from identical operands (`a_gt_b`), each gets a 1-based suffix
+/// (`expr_1`, `expr_2`) so they stay distinct.
+const PLACEHOLDER_NAMES: &[&str] = &["expr", "case_expr", "literal", "null"];
+
+/// Default maximum nesting depth of queries and expressions during inference
+pub const DEFAULT_MAX_DEPTH: usize = 256;
 
 /// Schema inference engine
 pub struct SchemaInference<'a> {
     /// Inference context with available schemas
     context: &'a InferenceContext,
+
+    /// Maximum nesting depth before inference gives up
+    max_depth: usize,
+
+    /// Tighten nullability of columns filtered non-null by the WHERE clause
+    nullability_from_where: bool,
+
+    /// Current nesting depth of queries and expressions
+    depth: Cell<usize>,
+
+    /// Whether `infer_statement_partial` is running
+    partial: Cell<bool>,
+
+    /// Problems seen during the last inference that did not stop it
+    warnings: RefCell<Vec<InferenceError>>,
+}
+
+/// Schema inferred despite recoverable problems
+///
+/// Returned by `SchemaInference::infer_statement_partial`. When a `SELECT *`
+/// could not be expanded its columns are missing from `schema`, which then
+/// holds only the columns that were named explicitly.
+#[derive(Debug)]
+pub struct PartialInference {
+    /// Inferred columns
+    pub schema: Schema,
+
+    /// Recoverable problems, in the order they were found
+    pub warnings: Vec<InferenceError>,
+}
+
+impl PartialInference {
+    /// Whether `schema` holds every output column of the query
+    pub fn is_complete(&self) -> bool {
+        !self.warnings.iter().any(|w| matches!(w, InferenceError::SelectStarWithoutCatalog))
+    }
+}
+
+/// Decrements the inference depth when a nested query/expression is done
+struct DepthGuard<'g> {
+    depth: &'g Cell<usize>,
+}
+
+impl Drop for DepthGuard<'_> {
+    fn drop(&mut self) {
+        self.depth.set(self.depth.get() - 1);
+    }
 }
 
 /// Context for schema inference containing available table schemas
+///
+/// A model is reachable under several names (name, unique_id and
+/// `database.schema.name`); all of them share one `Arc<Schema>`, so a large
+/// manifest holds each schema once.
 pub struct InferenceContext {
     /// Map of table names to their schemas
-    table_schemas: HashMap<String, Schema>,
+    table_schemas: HashMap<String, Arc<Schema>>,
 
     /// Whether to use catalog for SELECT * expansion
     use_catalog: bool,
@@ -37,27 +102,29 @@ impl InferenceContext {
 
     /// Add a table schema to the context
     pub fn add_table(&mut self, name: impl Into<String>, schema: Schema) {
-        self.table_schemas.insert(name.into(), schema);
+        self.add_shared_table(&name.into(), Arc::new(schema));
+    }
+
+    /// Add a table schema that is also registered under other names
+    pub fn add_shared_table(&mut self, name: &str, schema: Arc<Schema>) {
+        self.table_schemas.insert(name.to_string(), schema);
     }
 
     /// Load schemas from manifest
+    ///
+    /// Models with a contract use the contract schema. Ephemeral models
+    /// without one have no table to describe, so their schema is inferred
+    /// from their `compiled_code` once the tables they read are known.
     pub fn from_manifest(manifest: &Manifest) -> Self {
         let mut context = Self::new();
+        let mut ephemeral = Vec::new();
 
         // Add contract schemas from manifest
         for (node_id, node) in manifest.models() {
             if let Some(contract) = schemarefly_dbt::ContractExtractor::extract_from_node(node) {
-                // Use the model name as the table name
-                context.add_table(node.name.clone(), contract.schema.clone());
-
-                // Also add with the full unique_id
-                context.add_table(node_id.clone(), contract.schema.clone());
-
-                // Add with fully qualified name
-                if let (Some(database), Some(schema)) = (&node.database, &node.schema) {
-                    let fqn = format!("{}.{}.{}", database, schema, node.name);
-                    context.add_table(fqn, contract.schema);
-                }
+                context.add_model(&node_id, node, contract.schema);
+            } else if node.is_ephemeral() && node.compiled_code.is_some() {
+                ephemeral.push((node_id, node));
             }
         }
 
@@ -76,29 +143,63 @@ impl InferenceContext {
                     .collect();
 
                 if !columns.is_empty() {
-                    let schema = Schema::from_columns(columns);
+                    let schema = Arc::new(Schema::from_columns(columns));
 
                     // Add with source name (e.g., "raw.users")
-                    context.add_table(format!("{}.{}", source.source_name, source.name), schema.clone());
+                    context.add_shared_table(&format!("{}.{}", source.source_name, source.name), Arc::clone(&schema));
 
                     // Add with fully qualified name (e.g., "raw_db.raw.users")
                     if let Some(database) = &source.database {
                         let fqn = format!("{}.{}.{}", database, source.schema, source.name);
-                        context.add_table(fqn, schema.clone());
+                        context.add_shared_table(&fqn, Arc::clone(&schema));
                     }
 
                     // Add with unique_id
-                    context.add_table(source_id.clone(), schema);
+                    context.add_shared_table(source_id, schema);
+                }
+            }
+        }
+
+        // Ephemeral models can read each other, so keep going while any resolves
+        while !ephemeral.is_empty() {
+            let before = ephemeral.len();
+            ephemeral.retain(|(node_id, node)| {
+                let sql = node.compiled_code.as_deref().unwrap_or_default();
+                match context.infer_sql(sql) {
+                    Some(schema) => {
+                        context.add_model(node_id, node, schema);
+                        false
+                    }
+                    None => true,
                 }
+            });
+            if ephemeral.len() == before {
+                break;
             }
         }
 
         context
     }
 
+    /// Register a model's schema under its name, unique_id and `database.schema.name`
+    pub fn add_model(&mut self, node_id: &str, node: &schemarefly_dbt::ManifestNode, schema: Schema) {
+        let schema = Arc::new(schema);
+        if let (Some(database), Some(db_schema)) = (&node.database, &node.schema) {
+            self.add_shared_table(&format!("{}.{}.{}", database, db_schema, node.name), Arc::clone(&schema));
+        }
+        self.add_shared_table(node_id, Arc::clone(&schema));
+        self.add_shared_table(&node.name, schema);
+    }
+
+    /// Schema of the model statement of `sql`, if it parses and infers cleanly
+    fn infer_sql(&self, sql: &str) -> Option<Schema> {
+        let parsed = crate::parser::SqlParser::new().parse(sql, None).ok()?;
+        SchemaInference::new(self).infer_statement(parsed.model_statement()?).ok()
+    }
+
     /// Get schema for a table
     pub fn get_table_schema(&self, name: &str) -> Option<&Schema> {
-        self.table_schemas.get(name)
+        self.table_schemas.get(name).map(Arc::as_ref)
     }
 
     /// Enable catalog usage for SELECT * expansion
@@ -117,47 +218,245 @@ impl Default for InferenceContext {
 impl<'a> SchemaInference<'a> {
     /// Create a new schema inference engine
     pub fn new(context: &'a InferenceContext) -> Self {
-        Self { context }
+        Self {
+            context,
+            max_depth: DEFAULT_MAX_DEPTH,
+            nullability_from_where: false,
+            depth: Cell::new(0),
+            partial: Cell::new(false),
+            warnings: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Set the maximum nesting depth of queries and expressions
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Mark projected columns as non-nullable when WHERE filters out their NULLs
+    ///
+    /// Only top-level `AND`ed predicates of the form `col = <literal>`,
+    /// `<literal> = col` and `col IS NOT NULL` are considered.
+    pub fn with_nullability_from_where(mut self, enabled: bool) -> Self {
+        self.nullability_from_where = enabled;
+        self
+    }
+
+    /// Enter one level of nesting, failing once the depth limit is exceeded
+    fn enter(&self) -> Result<DepthGuard<'_>, InferenceError> {
+        let depth = self.depth.get() + 1;
+        if depth > self.max_depth {
+            return Err(InferenceError::RecursionLimitExceeded(self.max_depth));
+        }
+        self.depth.set(depth);
+        Ok(DepthGuard { depth: &self.depth })
     }
 
     /// Infer schema from a parsed SQL statement
+    ///
+    /// Problems that never change the output (an ORDER BY on an unknown
+    /// column) do not fail inference; they are available from `take_warnings`.
     pub fn infer_statement(&self, statement: &Statement) -> Result<Schema, InferenceError> {
+        self.warnings.borrow_mut().clear();
+        self.infer_any_statement(statement)
+    }
+
+    /// Warnings recorded by the last `infer_statement`
+    pub fn take_warnings(&self) -> Vec<InferenceError> {
+        std::mem::take(&mut *self.warnings.borrow_mut())
+    }
+
+    fn infer_any_statement(&self, statement: &Statement) -> Result<Schema, InferenceError> {
         match statement {
             Statement::Query(query) => self.infer_query(query),
+            Statement::Insert(insert) => self.infer_insert(insert),
+            Statement::CreateTable(create) => {
+                // CREATE TABLE x [(cols)] AS SELECT ... - infer from the embedded query
+                let query = create.query.as_ref().ok_or_else(|| {
+                    InferenceError::UnsupportedStatement("CREATE TABLE without AS SELECT".to_string())
+                })?;
+                let names: Vec<String> = create.columns.iter().map(|c| c.name.value.clone()).collect();
+                self.infer_ddl_query(&create.name, &names, query)
+            }
+            Statement::CreateView { name, columns, query, .. } => {
+                let names: Vec<String> = columns.iter().map(|c| c.name.value.clone()).collect();
+                self.infer_ddl_query(name, &names, query)
+            }
             _ => Err(InferenceError::UnsupportedStatement(
-                "Only SELECT queries are supported".to_string()
+                "Only SELECT, INSERT ... SELECT, and CREATE TABLE/VIEW AS statements are supported".to_string()
             )),
         }
     }
 
+    /// Infer schema from a statement, carrying on past recoverable problems
+    ///
+    /// Problems that leave the rest of the schema intact (see
+    /// `InferenceError::is_recoverable`) are collected as warnings instead of
+    /// stopping inference; any other problem is still returned as an error.
+    pub fn infer_statement_partial(&self, statement: &Statement) -> Result<PartialInference, InferenceError> {
+        self.warnings.borrow_mut().clear();
+        self.partial.set(true);
+        let result = self.infer_any_statement(statement);
+        self.partial.set(false);
+        let warnings = self.take_warnings();
+
+        result.map(|schema| PartialInference { schema, warnings })
+    }
+
+    /// Record a recoverable problem, or fail with it outside partial inference
+    fn recover(&self, error: InferenceError) -> Result<(), InferenceError> {
+        if !self.partial.get() {
+            return Err(error);
+        }
+        self.warn(error);
+        Ok(())
+    }
+
+    /// Record a problem that never fails inference
+    fn warn(&self, error: InferenceError) {
+        self.warnings.borrow_mut().push(error);
+    }
+
+    /// Infer schema from the query wrapped by a CREATE TABLE AS / CREATE VIEW AS
+    ///
+    /// The DDL wrapper is ignored except for an explicit column list, which
+    /// overrides the inferred column names positionally.
+    fn infer_ddl_query(
+        &self,
+        name: &ObjectName,
+        column_names: &[String],
+        query: &Query,
+    ) -> Result<Schema, InferenceError> {
+        let inferred = self.infer_query(query)?;
+
+        if column_names.is_empty() {
+            return Ok(inferred);
+        }
+
+        if column_names.len() != inferred.columns.len() {
+            return Err(InferenceError::ColumnListArityMismatch {
+                name: name.to_string(),
+                expected: column_names.len(),
+                actual: inferred.columns.len(),
+            });
+        }
+
+        let columns = column_names
+            .iter()
+            .zip(inferred.columns)
+            .map(|(name, col)| Column { name: name.clone(), ..col })
+            .collect();
+
+        Ok(Schema::from_columns(columns))
+    }
+
+    /// Infer schema from an `INSERT INTO target [(cols)] SELECT ...` statement
+    ///
+    /// The SELECT's columns are aligned positionally to the target column list
+    /// (or, if no list is given, to the target table's schema when it is known).
+    /// The resulting schema uses the target column names with the SELECT's types.
+    fn infer_insert(&self, insert: &Insert) -> Result<Schema, InferenceError> {
+        let source = insert.source.as_ref().ok_or_else(|| {
+            InferenceError::UnsupportedStatement("INSERT without a SELECT source".to_string())
+        })?;
+
+        let inferred = self.infer_query(source)?;
+        let target_name = insert.table_name.to_string();
+        let target_schema = self.context.get_table_schema(&target_name);
+
+        // Target columns: explicit list first, then the known target table
+        let target_columns: Vec<String> = if !insert.columns.is_empty() {
+            insert.columns.iter().map(|c| c.value.clone()).collect()
+        } else if let Some(schema) = target_schema {
+            schema.columns.iter().map(|c| c.name.clone()).collect()
+        } else {
+            return Ok(inferred);
+        };
+
+        if target_columns.len() != inferred.columns.len() {
+            return Err(InferenceError::InsertArityMismatch {
+                table: target_name,
+                expected: target_columns.len(),
+                actual: inferred.columns.len(),
+            });
+        }
+
+        let mut columns = Vec::with_capacity(target_columns.len());
+        for (target_col, inferred_col) in target_columns.into_iter().zip(inferred.columns) {
+            if let Some(expected) = target_schema
+                .and_then(|s| s.find_column(&target_col))
+                .map(|c| &c.logical_type)
+            {
+                if !Self::insert_types_compatible(expected, &inferred_col.logical_type) {
+                    return Err(InferenceError::InsertTypeMismatch {
+                        column: target_col,
+                        expected: expected.to_string(),
+                        actual: inferred_col.logical_type.to_string(),
+                    });
+                }
+            }
+
+            columns.push(Column {
+                name: target_col,
+                ..inferred_col
+            });
+        }
+
+        Ok(Schema::from_columns(columns))
+    }
+
+    /// Whether a value of type `actual` can be inserted into a column of type `expected`
+    ///
+    /// Lenient: Unknown matches anything and parameterized types (e.g. DECIMAL)
+    /// only need to agree on the base type.
+    fn insert_types_compatible(expected: &LogicalType, actual: &LogicalType) -> bool {
+        matches!(expected, LogicalType::Unknown)
+            || matches!(actual, LogicalType::Unknown)
+            || std::mem::discriminant(expected) == std::mem::discriminant(actual)
+    }
+
     /// Infer schema from a query
     fn infer_query(&self, query: &Query) -> Result<Schema, InferenceError> {
         // For now, we only handle simple SELECT queries
         // CTEs will be handled by resolving them first
+        let _guard = self.enter()?;
+
+        if query.with.as_ref().is_some_and(|with| with.recursive) {
+            return Err(InferenceError::UnsupportedConstruct(UnsupportedKind::RecursiveCte));
+        }
 
-        self.infer_set_expr(&query.body)
+        // ORDER BY never changes the output columns, but a plain SELECT can
+        // check what it references against the projection and its sources
+        match query.body.as_ref() {
+            SetExpr::Select(select) => {
+                let order_by = query.order_by.as_ref().map_or(&[][..], |order_by| order_by.exprs.as_slice());
+                self.infer_select(select, order_by)
+            }
+            body => self.infer_set_expr(body),
+        }
     }
 
     /// Infer schema from a set expression
     fn infer_set_expr(&self, set_expr: &SetExpr) -> Result<Schema, InferenceError> {
         match set_expr {
-            SetExpr::Select(select) => self.infer_select(select),
+            SetExpr::Select(select) => self.infer_select(select, &[]),
             SetExpr::Query(query) => self.infer_query(query),
             SetExpr::SetOperation { left, .. } => {
                 // For UNION, INTERSECT, etc., use the left schema
                 // (both should be compatible)
                 self.infer_set_expr(left)
             }
-            _ => Err(InferenceError::UnsupportedConstruct(
-                "Unsupported set expression".to_string()
-            )),
+            SetExpr::Values(_) => Err(InferenceError::UnsupportedConstruct(UnsupportedKind::Values)),
+            _ => Err(InferenceError::UnsupportedConstruct(UnsupportedKind::SetExpression)),
         }
     }
 
-    /// Infer schema from a SELECT statement
-    fn infer_select(&self, select: &Select) -> Result<Schema, InferenceError> {
+    /// Infer schema from a SELECT statement, validating the query's ORDER BY
+    fn infer_select(&self, select: &Select, order_by: &[OrderByExpr]) -> Result<Schema, InferenceError> {
         // First, build a map of available columns from FROM clause
         let source_schema = self.infer_from_clause(&select.from)?;
+        let source = ColumnIndex::new(&source_schema);
 
         // Check if this is a GROUP BY query
         // GroupByExpr is an enum, extract expressions from it
@@ -169,66 +468,61 @@ impl<'a> SchemaInference<'a> {
         };
 
         let has_group_by = !group_by_exprs.is_empty();
-        let group_by_cols: Vec<String> = group_by_exprs.iter()
-            .filter_map(|expr| {
-                // Extract column name from GROUP BY expression
-                match expr {
-                    Expr::Identifier(ident) => Some(ident.value.clone()),
-                    Expr::CompoundIdentifier(idents) => {
-                        idents.last().map(|i| i.value.clone())
-                    }
-                    _ => None,
-                }
-            })
-            .collect();
+
+        // Columns the WHERE clause guarantees are not NULL
+        let non_null = if self.nullability_from_where {
+            Self::non_null_filtered_columns(select.selection.as_ref())
+        } else {
+            Vec::new()
+        };
 
         // Then infer the output schema from SELECT list
         let mut columns = Vec::new();
+        let mut generated = Vec::new();
+
+        for (index, item) in select.projection.iter().enumerate() {
+            // GROUP BY ordinals are 1-based positions in the projection list
+            let position = index + 1;
 
-        for item in &select.projection {
             match item {
                 SelectItem::UnnamedExpr(expr) => {
-                    let (col_type, col_name) = self.infer_expr(expr, &source_schema)?;
+                    let (col_type, col_name) = self.infer_expr(expr, &source)?;
 
                     // If this is a GROUP BY query, check if expr is valid
-                    if has_group_by {
+                    // (window functions are computed after grouping)
+                    if has_group_by && !Self::is_window_expr(expr) {
                         let is_aggregate = Self::is_aggregate_expr(expr);
-                        let is_group_key = group_by_cols.contains(&col_name);
+                        let is_group_key = Self::is_group_key(&group_by_exprs, position, expr, None);
 
                         if is_aggregate {
                             // Warn about missing alias on aggregate
-                            return Err(InferenceError::AggregateWithoutAlias(col_name));
+                            self.recover(InferenceError::AggregateWithoutAlias(col_name.clone()))?;
                         } else if !is_group_key {
                             // Column not in GROUP BY and not an aggregate
                             return Err(InferenceError::InvalidGroupByColumn(col_name));
                         }
                     }
 
-                    columns.push(Column::new(col_name, col_type));
+                    // Generated names may repeat; a repeated column reference is left as is
+                    if PLACEHOLDER_NAMES.contains(&col_name.as_str()) || Self::is_operator_expr(expr) {
+                        generated.push(columns.len());
+                    }
+                    columns.push(Self::with_where_nullability(Column::new(col_name, col_type), expr, &non_null));
                 }
                 SelectItem::ExprWithAlias { expr, alias } => {
-                    let (col_type, _) = self.infer_expr(expr, &source_schema)?;
+                    let (col_type, _) = self.infer_expr(expr, &source)?;
 
                     // If this is a GROUP BY query, validate
-                    if has_group_by {
+                    if has_group_by && !Self::is_window_expr(expr) {
                         let is_aggregate = Self::is_aggregate_expr(expr);
-                        let col_name = alias.value.clone();
-
-                        // Check if the underlying expression is in GROUP BY
-                        let is_group_key = match expr {
-                            Expr::Identifier(ident) => group_by_cols.contains(&ident.value),
-                            Expr::CompoundIdentifier(idents) => {
-                                idents.last().map(|i| group_by_cols.contains(&i.value)).unwrap_or(false)
-                            }
-                            _ => false,
-                        };
+                        let is_group_key = Self::is_group_key(&group_by_exprs, position, expr, Some(&alias.value));
 
                         if !is_aggregate && !is_group_key {
-                            return Err(InferenceError::InvalidGroupByColumn(col_name));
+                            return Err(InferenceError::InvalidGroupByColumn(alias.value.clone()));
                         }
                     }
 
-                    columns.push(Column::new(alias.value.clone(), col_type));
+                    columns.push(Self::with_where_nullability(Column::new(alias.value.clone(), col_type), expr, &non_null));
                 }
                 SelectItem::Wildcard(_) => {
                     // SELECT * - expand all columns from source
@@ -236,7 +530,7 @@ impl<'a> SchemaInference<'a> {
                         columns.extend(source_schema.columns.clone());
                     } else {
                         // Warn that we can't guarantee schema
-                        return Err(InferenceError::SelectStarWithoutCatalog);
+                        self.recover(InferenceError::SelectStarWithoutCatalog)?;
                     }
                 }
                 SelectItem::QualifiedWildcard(name, _) => {
@@ -251,7 +545,242 @@ impl<'a> SchemaInference<'a> {
             }
         }
 
-        Ok(Schema::from_columns(columns))
+        Self::number_generated_names(&mut columns, &generated);
+
+        let schema = Schema::from_columns(columns);
+        self.check_order_by(order_by, &schema, &source);
+
+        Ok(schema)
+    }
+
+    /// Check that ORDER BY only references output aliases or source columns
+    ///
+    /// Ordinals and other expressions without column references are not
+    /// checked. Each unresolved column is a warning, never a failure.
+    fn check_order_by(&self, order_by: &[OrderByExpr], output: &Schema, source: &ColumnIndex) {
+        let mut refs = Vec::new();
+        for item in order_by {
+            Self::collect_column_refs(&item.expr, &mut refs);
+        }
+
+        for expr in refs {
+            let resolved = match expr {
+                Expr::Identifier(ident) => {
+                    output.find_column(&ident.value).is_some() || source.column(&ident.value).is_some()
+                }
+                // Qualified names (`u.id`) always refer to a source column
+                Expr::CompoundIdentifier(idents) => {
+                    idents.last().is_some_and(|ident| source.column(&ident.value).is_some())
+                }
+                _ => true,
+            };
+
+            if !resolved {
+                self.warn(InferenceError::OrderByUnknownColumn(expr.to_string()));
+            }
+        }
+    }
+
+    /// Column references in an expression, outside subqueries
+    fn collect_column_refs<'e>(expr: &'e Expr, refs: &mut Vec<&'e Expr>) {
+        match expr {
+            Expr::Identifier(_) | Expr::CompoundIdentifier(_) => refs.push(expr),
+            Expr::Nested(inner)
+            | Expr::UnaryOp { expr: inner, .. }
+            | Expr::Cast { expr: inner, .. }
+            | Expr::IsNull(inner)
+            | Expr::IsNotNull(inner) => Self::collect_column_refs(inner, refs),
+            Expr::BinaryOp { left, right, .. } => {
+                Self::collect_column_refs(left, refs);
+                Self::collect_column_refs(right, refs);
+            }
+            Expr::Function(func) => {
+                if let sqlparser::ast::FunctionArguments::List(list) = &func.args {
+                    for arg in &list.args {
+                        if let FunctionArg::Unnamed(FunctionArgExpr::Expr(arg))
+                        | FunctionArg::Named { arg: FunctionArgExpr::Expr(arg), .. } = arg
+                        {
+                            Self::collect_column_refs(arg, refs);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Column references that a WHERE clause filters to non-NULL values
+    ///
+    /// Walks the top-level `AND` chain only; predicates under `OR` or `NOT`
+    /// guarantee nothing on their own.
+    fn non_null_filtered_columns(selection: Option<&Expr>) -> Vec<&Expr> {
+        let mut columns = Vec::new();
+        let mut pending: Vec<&Expr> = selection.into_iter().collect();
+
+        while let Some(expr) = pending.pop() {
+            match expr {
+                Expr::Nested(inner) => pending.push(inner),
+                Expr::BinaryOp { left, op: BinaryOperator::And, right } => {
+                    pending.push(left);
+                    pending.push(right);
+                }
+                Expr::IsNotNull(inner) if Self::is_column_ref(inner) => columns.push(&**inner),
+                Expr::BinaryOp { left, op: BinaryOperator::Eq, right } => {
+                    if Self::is_column_ref(left) && Self::is_non_null_literal(right) {
+                        columns.push(&**left);
+                    } else if Self::is_non_null_literal(left) && Self::is_column_ref(right) {
+                        columns.push(&**right);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        columns
+    }
+
+    /// Mark `column` non-nullable if its projection is a WHERE-filtered column reference
+    fn with_where_nullability(column: Column, expr: &Expr, non_null: &[&Expr]) -> Column {
+        let filtered = Self::is_column_ref(expr)
+            && non_null.iter().any(|filtered| Self::same_column_ref(filtered, expr));
+
+        if filtered {
+            column.with_nullability(Nullability::No)
+        } else {
+            column
+        }
+    }
+
+    fn is_column_ref(expr: &Expr) -> bool {
+        matches!(expr, Expr::Identifier(_) | Expr::CompoundIdentifier(_))
+    }
+
+    fn is_non_null_literal(expr: &Expr) -> bool {
+        match expr {
+            Expr::Nested(inner) => Self::is_non_null_literal(inner),
+            Expr::Value(value) => !matches!(value, Value::Null | Value::Placeholder(_)),
+            _ => false,
+        }
+    }
+
+    /// Whether two column references name the same column
+    ///
+    /// Names compare case-insensitively; qualifiers are only compared when
+    /// both references have one (`o.status` matches `status` but not `c.status`).
+    fn same_column_ref(a: &Expr, b: &Expr) -> bool {
+        let parts = |e: &Expr| -> Vec<String> {
+            match e {
+                Expr::Identifier(ident) => vec![ident.value.to_lowercase()],
+                Expr::CompoundIdentifier(idents) => idents.iter().map(|i| i.value.to_lowercase()).collect(),
+                _ => Vec::new(),
+            }
+        };
+        let (a, b) = (parts(a), parts(b));
+
+        match (a.split_last(), b.split_last()) {
+            (Some((a_name, a_qualifier)), Some((b_name, b_qualifier))) => {
+                a_name == b_name
+                    && (a_qualifier.is_empty() || b_qualifier.is_empty() || a_qualifier == b_qualifier)
+            }
+            _ => false,
+        }
+    }
+
+    /// Suffix generated names shared by several unnamed projections
+    ///
+    /// `generated` holds the indices of columns named with a placeholder or
+    /// from their operator; aliased columns and column references are never
+    /// renamed.
+    fn number_generated_names(columns: &mut [Column], generated: &[usize]) {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for &index in generated {
+            *counts.entry(columns[index].name.clone()).or_default() += 1;
+        }
+
+        let mut next: HashMap<String, usize> = HashMap::new();
+        for &index in generated {
+            let name = columns[index].name.clone();
+            if counts.get(&name).copied().unwrap_or(0) > 1 {
+                let n = next.entry(name.clone()).or_default();
+                *n += 1;
+                columns[index].name = format!("{}_{}", name, n);
+            }
+        }
+    }
+
+    /// Whether an expression is a (possibly parenthesized) binary operator
+    fn is_operator_expr(expr: &Expr) -> bool {
+        match expr {
+            Expr::Nested(inner) => Self::is_operator_expr(inner),
+            Expr::BinaryOp { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Derive a column name for an unnamed operator expression from its operands
+    ///
+    /// `a > b` becomes `a_gt_b` and `a + 1` becomes `a_plus_1`. Returns `None`
+    /// when an operand (or the operator) has no readable name.
+    fn derived_name(expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Identifier(ident) => Some(ident.value.clone()),
+            Expr::CompoundIdentifier(idents) => idents.last().map(|i| i.value.clone()),
+            Expr::Value(Value::Number(n, _)) => Some(n.replace('.', "_")),
+            Expr::Nested(inner) => Self::derived_name(inner),
+            Expr::BinaryOp { left, op, right } => {
+                let op = match op {
+                    BinaryOperator::Gt => "gt",
+                    BinaryOperator::Lt => "lt",
+                    BinaryOperator::GtEq => "gte",
+                    BinaryOperator::LtEq => "lte",
+                    BinaryOperator::Eq => "eq",
+                    BinaryOperator::NotEq => "neq",
+                    BinaryOperator::And => "and",
+                    BinaryOperator::Or => "or",
+                    BinaryOperator::Plus => "plus",
+                    BinaryOperator::Minus => "minus",
+                    BinaryOperator::Multiply => "mul",
+                    BinaryOperator::Divide => "div",
+                    BinaryOperator::Modulo => "mod",
+                    BinaryOperator::StringConcat => "concat",
+                    _ => return None,
+                };
+                Some(format!("{}_{}_{}", Self::derived_name(left)?, op, Self::derived_name(right)?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a projected expression is covered by the GROUP BY clause
+    ///
+    /// A GROUP BY item matches when it is the ordinal of the projection
+    /// (`GROUP BY 1`), the projection's alias, the same column name, or the
+    /// same expression (compared structurally, ignoring keyword case).
+    fn is_group_key(group_by: &[&Expr], position: usize, expr: &Expr, alias: Option<&str>) -> bool {
+        let column_name = |e: &Expr| match e {
+            Expr::Identifier(ident) => Some(ident.value.clone()),
+            Expr::CompoundIdentifier(idents) => idents.last().map(|i| i.value.clone()),
+            _ => None,
+        };
+        let projected_name = column_name(expr);
+
+        group_by.iter().any(|key| {
+            if let Expr::Value(Value::Number(n, _)) = key {
+                return n.parse::<usize>().ok() == Some(position);
+            }
+
+            if let (Some(alias), Expr::Identifier(ident)) = (alias, key) {
+                if ident.value == alias {
+                    return true;
+                }
+            }
+
+            if projected_name.is_some() && column_name(key) == projected_name {
+                return true;
+            }
+
+            *key == expr || key.to_string().eq_ignore_ascii_case(&expr.to_string())
+        })
     }
 
     /// Check if an expression is an aggregate function
@@ -268,6 +797,35 @@ impl<'a> SchemaInference<'a> {
         }
     }
 
+    /// Whether a window frame bound is offset by something other than a constant
+    ///
+    /// `ROWS BETWEEN 3 PRECEDING` and `RANGE INTERVAL '7 days' PRECEDING` are
+    /// fine; an offset computed from columns can't be checked.
+    fn has_variable_frame_offset(over: &sqlparser::ast::WindowType) -> bool {
+        use sqlparser::ast::{WindowFrameBound, WindowType};
+
+        let WindowType::WindowSpec(spec) = over else {
+            return false;
+        };
+        let Some(frame) = &spec.window_frame else {
+            return false;
+        };
+
+        std::iter::once(&frame.start_bound)
+            .chain(frame.end_bound.as_ref())
+            .any(|bound| match bound {
+                WindowFrameBound::Preceding(Some(offset)) | WindowFrameBound::Following(Some(offset)) => {
+                    !matches!(offset.as_ref(), Expr::Value(_) | Expr::Interval(_))
+                }
+                _ => false,
+            })
+    }
+
+    /// Check if an expression is a window function call (`... OVER (...)`)
+    fn is_window_expr(expr: &Expr) -> bool {
+        matches!(expr, Expr::Function(func) if func.over.is_some())
+    }
+
     /// Infer available columns from FROM clause
     fn infer_from_clause(&self, from: &[sqlparser::ast::TableWithJoins]) -> Result<Schema, InferenceError> {
         if from.is_empty() {
@@ -292,6 +850,10 @@ impl<'a> SchemaInference<'a> {
     /// Infer schema from a table factor
     fn infer_table_factor(&self, table_factor: &TableFactor) -> Result<Schema, InferenceError> {
         match table_factor {
+            // `FROM generate_series(1, 10)` parses as a table with arguments
+            TableFactor::Table { name, args: Some(args), alias, .. } => {
+                self.infer_table_function(name, &args.args, alias.as_ref())
+            }
             TableFactor::Table { name, .. } => {
                 let table_name = name.to_string();
 
@@ -301,13 +863,108 @@ impl<'a> SchemaInference<'a> {
                     Err(InferenceError::UnknownTable(table_name))
                 }
             }
-            TableFactor::Derived { subquery, .. } => {
-                // Recursively infer subquery schema
-                self.infer_query(subquery)
+            TableFactor::Derived { lateral, subquery, .. } => {
+                // Recursively infer subquery schema; a LATERAL subquery may
+                // project columns of earlier FROM items, which it cannot see
+                match self.infer_query(subquery) {
+                    Err(InferenceError::UnknownColumn(_)) if *lateral => {
+                        Err(InferenceError::UnsupportedConstruct(UnsupportedKind::LateralJoin))
+                    }
+                    result => result,
+                }
             }
-            _ => Err(InferenceError::UnsupportedConstruct(
-                "Unsupported table factor".to_string()
-            )),
+            TableFactor::Function { name, args, alias, .. } => {
+                self.infer_table_function(name, args, alias.as_ref())
+            }
+            TableFactor::TableFunction { expr: Expr::Function(func), alias } => {
+                let args = match &func.args {
+                    sqlparser::ast::FunctionArguments::List(arg_list) => arg_list.args.clone(),
+                    _ => vec![],
+                };
+                self.infer_table_function(&func.name, &args, alias.as_ref())
+            }
+            TableFactor::UNNEST { alias, array_exprs, with_offset, with_offset_alias, .. } => {
+                let mut columns: Vec<Column> = array_exprs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, expr)| {
+                        let element = self.array_element_type(expr);
+                        Column::new(table_function_column_name(alias.as_ref(), i, "unnest"), element)
+                    })
+                    .collect();
+
+                if *with_offset {
+                    let name = with_offset_alias.as_ref().map_or("offset".to_string(), |a| a.value.clone());
+                    columns.push(Column::new(name, LogicalType::Int));
+                }
+
+                Ok(Schema::from_columns(columns))
+            }
+            TableFactor::Pivot { .. } => Err(InferenceError::UnsupportedConstruct(UnsupportedKind::Pivot)),
+            TableFactor::Unpivot { .. } => Err(InferenceError::UnsupportedConstruct(UnsupportedKind::Unpivot)),
+            TableFactor::MatchRecognize { .. } => {
+                Err(InferenceError::UnsupportedConstruct(UnsupportedKind::MatchRecognize))
+            }
+            TableFactor::NestedJoin { .. } => Err(InferenceError::UnsupportedConstruct(UnsupportedKind::NestedJoin)),
+            TableFactor::JsonTable { .. } | TableFactor::OpenJsonTable { .. } => {
+                Err(InferenceError::UnsupportedConstruct(UnsupportedKind::JsonTable))
+            }
+            _ => Err(InferenceError::UnsupportedConstruct(UnsupportedKind::TableFactor)),
+        }
+    }
+
+    /// Best-effort schema for a set-returning function used as a relation
+    ///
+    /// `generate_series` yields one column typed after its start argument (INT
+    /// for numbers) and `unnest` yields one column per array argument. Other
+    /// functions have no known shape.
+    fn infer_table_function(
+        &self,
+        name: &ObjectName,
+        args: &[FunctionArg],
+        alias: Option<&TableAlias>,
+    ) -> Result<Schema, InferenceError> {
+        let func_name = name.0.last().map(|i| i.value.to_lowercase()).unwrap_or_default();
+        let arg_exprs: Vec<&Expr> = args
+            .iter()
+            .filter_map(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(expr))
+                | FunctionArg::Named { arg: FunctionArgExpr::Expr(expr), .. } => Some(expr),
+                _ => None,
+            })
+            .collect();
+
+        match func_name.as_str() {
+            "generate_series" => {
+                let start_type = arg_exprs
+                    .first()
+                    .and_then(|expr| self.infer_expr(expr, &ColumnIndex::new(&Schema::new())).ok())
+                    .map(|(logical_type, _)| logical_type);
+                let logical_type = match start_type {
+                    Some(LogicalType::Timestamp) | Some(LogicalType::Date) => LogicalType::Timestamp,
+                    Some(t @ LogicalType::Decimal { .. }) | Some(t @ LogicalType::Float) => t,
+                    _ => LogicalType::Int,
+                };
+                let column = table_function_column_name(alias, 0, &func_name);
+                Ok(Schema::from_columns(vec![Column::new(column, logical_type)]))
+            }
+            "unnest" => {
+                let columns = arg_exprs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, expr)| Column::new(table_function_column_name(alias, i, &func_name), self.array_element_type(expr)))
+                    .collect();
+                Ok(Schema::from_columns(columns))
+            }
+            _ => Err(InferenceError::UnknownTableFunction(name.to_string())),
+        }
+    }
+
+    /// Element type of an array-valued expression (Unknown if not an array)
+    fn array_element_type(&self, expr: &Expr) -> LogicalType {
+        match self.infer_expr(expr, &ColumnIndex::new(&Schema::new())) {
+            Ok((LogicalType::Array { element_type }, _)) => *element_type,
+            _ => LogicalType::Unknown,
         }
     }
 
@@ -319,11 +976,12 @@ impl<'a> SchemaInference<'a> {
         _join_op: &JoinOperator,
     ) -> Result<Schema, InferenceError> {
         let mut columns = left.columns;
+        let mut seen: HashSet<String> = columns.iter().map(|c| c.name.clone()).collect();
 
         // Add right columns, handling conflicts
         for right_col in right.columns {
             // Check for duplicate column names
-            if columns.iter().any(|c| c.name == right_col.name) {
+            if !seen.insert(right_col.name.clone()) {
                 // Column name collision - in a real implementation,
                 // we'd handle this based on the JOIN type and constraints
                 // For now, we'll keep the left column
@@ -337,13 +995,15 @@ impl<'a> SchemaInference<'a> {
     }
 
     /// Infer type and name from an expression
-    fn infer_expr(&self, expr: &Expr, source_schema: &Schema) -> Result<(LogicalType, String), InferenceError> {
+    fn infer_expr(&self, expr: &Expr, source: &ColumnIndex) -> Result<(LogicalType, String), InferenceError> {
+        let _guard = self.enter()?;
+
         match expr {
             Expr::Identifier(ident) => {
                 let col_name = ident.value.clone();
 
                 // Find column in source schema
-                if let Some(col) = source_schema.find_column(&col_name) {
+                if let Some(col) = source.column(&col_name) {
                     Ok((col.logical_type.clone(), col_name))
                 } else {
                     Err(InferenceError::UnknownColumn(col_name))
@@ -354,7 +1014,7 @@ impl<'a> SchemaInference<'a> {
                 // e.g., table.column
                 let col_name = idents.last().unwrap().value.clone();
 
-                if let Some(col) = source_schema.find_column(&col_name) {
+                if let Some(col) = source.column(&col_name) {
                     Ok((col.logical_type.clone(), col_name))
                 } else {
                     Err(InferenceError::UnknownColumn(col_name))
@@ -364,7 +1024,7 @@ impl<'a> SchemaInference<'a> {
             Expr::Cast { expr, data_type, .. } => {
                 // CAST(expr AS type)
                 let logical_type = self.sqlparser_type_to_logical(data_type)?;
-                let (_, name) = self.infer_expr(expr, source_schema)?;
+                let (_, name) = self.infer_expr(expr, source)?;
                 Ok((logical_type, name))
             }
 
@@ -381,19 +1041,25 @@ impl<'a> SchemaInference<'a> {
                     sqlparser::ast::FunctionArguments::Subquery(_) => vec![],
                     sqlparser::ast::FunctionArguments::List(arg_list) => arg_list.args.clone(),
                 };
-                self.infer_function(&func.name, &args_vec, source_schema)
+                if func.over.as_ref().is_some_and(Self::has_variable_frame_offset) {
+                    return Err(InferenceError::UnsupportedConstruct(UnsupportedKind::WindowFrame));
+                }
+                self.infer_function(&func.name, &args_vec, source)
             }
 
             Expr::BinaryOp { left, op, right } => {
                 // Binary operation
-                let (left_type, _) = self.infer_expr(left, source_schema)?;
-                let (right_type, _) = self.infer_expr(right, source_schema)?;
+                let (left_type, _) = self.infer_expr(left, source)?;
+                let (right_type, _) = self.infer_expr(right, source)?;
 
                 // Infer result type based on operation
                 let result_type = self.infer_binary_op_type(&left_type, &right_type, op)?;
-                Ok((result_type, "expr".to_string()))
+                let name = Self::derived_name(expr).unwrap_or_else(|| "expr".to_string());
+                Ok((result_type, name))
             }
 
+            Expr::Nested(inner) => self.infer_expr(inner, source),
+
             Expr::Case { .. } => {
                 // CASE expression - for now, return Unknown
                 Ok((LogicalType::Unknown, "case_expr".to_string()))
@@ -492,7 +1158,7 @@ impl<'a> SchemaInference<'a> {
         &self,
         name: &ObjectName,
         args: &[FunctionArg],
-        source_schema: &Schema,
+        source: &ColumnIndex,
     ) -> Result<(LogicalType, String), InferenceError> {
         let func_name = name.to_string().to_uppercase();
 
@@ -503,7 +1169,7 @@ impl<'a> SchemaInference<'a> {
                 // Return type depends on argument type
                 // For simplicity, we'll return the argument type
                 if let Some(FunctionArg::Unnamed(sqlparser::ast::FunctionArgExpr::Expr(expr))) = args.first() {
-                    let (arg_type, _) = self.infer_expr(expr, source_schema)?;
+                    let (arg_type, _) = self.infer_expr(expr, source)?;
                     arg_type
                 } else {
                     LogicalType::Unknown
@@ -511,10 +1177,14 @@ impl<'a> SchemaInference<'a> {
             }
             "CONCAT" | "UPPER" | "LOWER" | "TRIM" | "SUBSTRING" => LogicalType::String,
             "NOW" | "CURRENT_TIMESTAMP" | "CURRENT_DATE" => LogicalType::Timestamp,
-            "COALESCE" | "IFNULL" | "NULLIF" => {
+            // Window ranking functions
+            "ROW_NUMBER" | "RANK" | "DENSE_RANK" | "NTILE" => LogicalType::Int,
+            "PERCENT_RANK" | "CUME_DIST" => LogicalType::Float,
+            "COALESCE" | "IFNULL" | "NULLIF"
+            | "LAG" | "LEAD" | "FIRST_VALUE" | "LAST_VALUE" | "NTH_VALUE" => {
                 // Return type is the type of the first argument
                 if let Some(FunctionArg::Unnamed(sqlparser::ast::FunctionArgExpr::Expr(expr))) = args.first() {
-                    let (arg_type, _) = self.infer_expr(expr, source_schema)?;
+                    let (arg_type, _) = self.infer_expr(expr, source)?;
                     arg_type
                 } else {
                     LogicalType::Unknown
@@ -556,6 +1226,24 @@ impl<'a> SchemaInference<'a> {
         }
     }
 
+    /// Warn about output column names that appear more than once
+    ///
+    /// Columns are looked up by name when compared with a contract, so only
+    /// the first of the duplicates would be checked.
+    pub fn duplicate_column_diagnostics(schema: &Schema, case: IdentifierCase) -> Vec<Diagnostic> {
+        schema
+            .duplicate_column_names(case)
+            .into_iter()
+            .map(|name| {
+                Diagnostic::new(
+                    DiagnosticCode::SqlDuplicateColumnName,
+                    Severity::Warn,
+                    format!("Column '{}' appears more than once in the query output; alias one of them", name),
+                )
+            })
+            .collect()
+    }
+
     /// Generate diagnostic for inference warnings
     pub fn create_diagnostic(&self, error: &InferenceError) -> Diagnostic {
         match error {
@@ -569,6 +1257,11 @@ impl<'a> SchemaInference<'a> {
                 Severity::Error,
                 format!("Unknown table: {}", name)
             ),
+            InferenceError::UnknownTableFunction(name) => Diagnostic::new(
+                DiagnosticCode::SqlUnsupportedSyntax,
+                Severity::Info,
+                format!("Output shape of table function '{}' is unknown; schema not inferred", name)
+            ),
             InferenceError::UnknownColumn(name) => Diagnostic::new(
                 DiagnosticCode::SqlInferenceError,
                 Severity::Error,
@@ -584,6 +1277,36 @@ impl<'a> SchemaInference<'a> {
                 Severity::Error,
                 format!("Column '{}' must appear in GROUP BY or be part of an aggregate function", col)
             ),
+            InferenceError::InsertArityMismatch { table, expected, actual } => Diagnostic::new(
+                DiagnosticCode::SqlInferenceError,
+                Severity::Error,
+                format!("INSERT into '{}' lists {} target columns but the SELECT produces {}", table, expected, actual)
+            ).with_comparison(format!("{} columns", expected), format!("{} columns", actual)),
+            InferenceError::InsertTypeMismatch { column, expected, actual } => Diagnostic::new(
+                DiagnosticCode::SqlInferenceError,
+                Severity::Error,
+                format!("INSERT column '{}' has type {} but the SELECT produces {}", column, expected, actual)
+            ).with_comparison(expected.clone(), actual.clone()),
+            InferenceError::ColumnListArityMismatch { name, expected, actual } => Diagnostic::new(
+                DiagnosticCode::SqlInferenceError,
+                Severity::Error,
+                format!("'{}' declares {} columns but its query produces {}", name, expected, actual)
+            ).with_comparison(format!("{} columns", expected), format!("{} columns", actual)),
+            InferenceError::UnsupportedConstruct(kind) => Diagnostic::new(
+                DiagnosticCode::SqlUnsupportedSyntax,
+                Severity::Error,
+                format!("Unsupported SQL construct: {}; schema not inferred", kind)
+            ).with_comparison("supported SQL", kind.as_str()),
+            InferenceError::OrderByUnknownColumn(name) => Diagnostic::new(
+                DiagnosticCode::SqlOrderByUnknownColumn,
+                Severity::Warn,
+                format!("ORDER BY references '{}', which is neither an output column nor a source column", name)
+            ),
+            InferenceError::RecursionLimitExceeded(limit) => Diagnostic::new(
+                DiagnosticCode::SqlInferenceError,
+                Severity::Error,
+                format!("Query nesting exceeds the inference depth limit of {}; simplify the query or raise [inference] max_depth", limit)
+            ),
             _ => Diagnostic::new(
                 DiagnosticCode::SqlInferenceError,
                 Severity::Error,
@@ -600,7 +1323,7 @@ pub enum InferenceError {
     UnsupportedStatement(String),
 
     #[error("Unsupported SQL construct: {0}")]
-    UnsupportedConstruct(String),
+    UnsupportedConstruct(UnsupportedKind),
 
     #[error("Unknown table: {0}")]
     UnknownTable(String),
@@ -608,6 +1331,9 @@ pub enum InferenceError {
     #[error("Unknown column: {0}")]
     UnknownColumn(String),
 
+    #[error("Unknown table function shape: {0}")]
+    UnknownTableFunction(String),
+
     #[error("SELECT * without catalog")]
     SelectStarWithoutCatalog,
 
@@ -619,6 +1345,148 @@ pub enum InferenceError {
 
     #[error("Column '{0}' not in GROUP BY and not an aggregate")]
     InvalidGroupByColumn(String),
+
+    #[error("INSERT into '{table}' expects {expected} columns, SELECT produces {actual}")]
+    InsertArityMismatch {
+        table: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("INSERT column '{column}' expects {expected}, SELECT produces {actual}")]
+    InsertTypeMismatch {
+        column: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("'{name}' declares {expected} columns, query produces {actual}")]
+    ColumnListArityMismatch {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("Query nesting exceeds the maximum inference depth of {0}")]
+    RecursionLimitExceeded(usize),
+
+    #[error("ORDER BY references unknown column: {0}")]
+    OrderByUnknownColumn(String),
+}
+
+impl InferenceError {
+    /// Whether inference can carry on past this problem
+    ///
+    /// An unexpandable `SELECT *` only hides its own columns, an
+    /// unaliased aggregate still has a (generated) name and ORDER BY never
+    /// changes the output; everything else leaves no usable schema.
+    pub fn is_recoverable(&self) -> bool {
+        matches!(
+            self,
+            InferenceError::SelectStarWithoutCatalog
+                | InferenceError::AggregateWithoutAlias(_)
+                | InferenceError::OrderByUnknownColumn(_)
+        )
+    }
+
+    /// The construct that stopped inference, for unsupported-SQL errors
+    pub fn unsupported_kind(&self) -> Option<UnsupportedKind> {
+        match self {
+            InferenceError::UnsupportedConstruct(kind) => Some(*kind),
+            _ => None,
+        }
+    }
+}
+
+/// SQL construct that schema inference does not support
+///
+/// Carried by `InferenceError::UnsupportedConstruct` so failures can be
+/// tallied per construct; `as_str` is the stable machine-readable name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum UnsupportedKind {
+    /// LATERAL subquery projecting columns of earlier FROM items
+    LateralJoin,
+
+    /// PIVOT table operator
+    Pivot,
+
+    /// UNPIVOT table operator
+    Unpivot,
+
+    /// MATCH_RECOGNIZE table operator
+    MatchRecognize,
+
+    /// Parenthesized join used as a relation, e.g. `FROM (a JOIN b ON ...)`
+    NestedJoin,
+
+    /// JSON_TABLE / OPENJSON relation
+    JsonTable,
+
+    /// WITH RECURSIVE query
+    RecursiveCte,
+
+    /// VALUES list used as a query
+    Values,
+
+    /// Window frame offset that is not a constant
+    WindowFrame,
+
+    /// Other query body (e.g. `TABLE t`, DML in a subquery)
+    SetExpression,
+
+    /// Other FROM-clause relation
+    TableFactor,
+}
+
+impl UnsupportedKind {
+    /// Stable machine-readable name, e.g. `LATERAL_JOIN`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LateralJoin => "LATERAL_JOIN",
+            Self::Pivot => "PIVOT",
+            Self::Unpivot => "UNPIVOT",
+            Self::MatchRecognize => "MATCH_RECOGNIZE",
+            Self::NestedJoin => "NESTED_JOIN",
+            Self::JsonTable => "JSON_TABLE",
+            Self::RecursiveCte => "RECURSIVE_CTE",
+            Self::Values => "VALUES",
+            Self::WindowFrame => "WINDOW_FRAME",
+            Self::SetExpression => "SET_EXPRESSION",
+            Self::TableFactor => "TABLE_FACTOR",
+        }
+    }
+}
+
+impl std::fmt::Display for UnsupportedKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            Self::LateralJoin => "LATERAL subquery referencing outer columns",
+            Self::Pivot => "PIVOT",
+            Self::Unpivot => "UNPIVOT",
+            Self::MatchRecognize => "MATCH_RECOGNIZE",
+            Self::NestedJoin => "parenthesized join",
+            Self::JsonTable => "JSON table function",
+            Self::RecursiveCte => "WITH RECURSIVE",
+            Self::Values => "VALUES list",
+            Self::WindowFrame => "window frame with a non-constant offset",
+            Self::SetExpression => "query body",
+            Self::TableFactor => "FROM-clause relation",
+        };
+        f.write_str(description)
+    }
+}
+
+/// Output column name for a table function: the alias column list, then the
+/// table alias (single-column functions), then the function name
+fn table_function_column_name(alias: Option<&TableAlias>, index: usize, func_name: &str) -> String {
+    match alias {
+        Some(alias) => match alias.columns.get(index) {
+            Some(column) => column.name.value.clone(),
+            None if index == 0 => alias.name.value.clone(),
+            None => func_name.to_string(),
+        },
+        None => func_name.to_string(),
+    }
 }
 
 #[cfg(test)]
@@ -674,6 +1542,37 @@ mod tests {
         assert_eq!(schema.columns[2].name, "user_email");
     }
 
+    #[test]
+    fn unnamed_comparisons_get_distinct_names() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+
+        let parser = SqlParser::new();
+        let sql = "SELECT id > age, (age >= 18), id = 1 OR age < 2, LOWER(name) = email, id + 1 = LENGTH(name) FROM users";
+        let parsed = parser.parse(sql, None).unwrap();
+
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        let names: Vec<&str> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+
+        assert_eq!(names, vec!["id_gt_age", "age_gte_18", "id_eq_1_or_age_lt_2", "expr_1", "expr_2"]);
+        assert!(schema.columns.iter().all(|c| matches!(c.logical_type, LogicalType::Bool)));
+    }
+
+    #[test]
+    fn identical_unnamed_expressions_get_distinct_names() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+
+        let parser = SqlParser::new();
+        let sql = "SELECT id > age, (id > age), id, age + 1, age + 1 AS next_age FROM users";
+        let parsed = parser.parse(sql, None).unwrap();
+
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        let names: Vec<&str> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+
+        assert_eq!(names, vec!["id_gt_age_1", "id_gt_age_2", "id", "age_plus_1", "next_age"]);
+    }
+
     #[test]
     fn infer_with_cast() {
         let context = create_test_context();
@@ -703,6 +1602,80 @@ mod tests {
         assert!(matches!(result.unwrap_err(), InferenceError::SelectStarWithoutCatalog));
     }
 
+    #[test]
+    fn partial_inference_keeps_named_columns_past_select_star() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+
+        let parsed = SqlParser::new().parse("SELECT *, UPPER(name) AS display_name FROM users", None).unwrap();
+        let statement = parsed.first_statement().unwrap();
+
+        // Strict inference still stops at the unexpandable SELECT *
+        assert!(matches!(inference.infer_statement(statement), Err(InferenceError::SelectStarWithoutCatalog)));
+
+        let partial = inference.infer_statement_partial(statement).unwrap();
+        assert_eq!(partial.schema.column_names(), vec!["display_name"]);
+        assert!(matches!(partial.warnings.as_slice(), [InferenceError::SelectStarWithoutCatalog]));
+        assert!(!partial.is_complete());
+
+        // Fatal problems are still errors
+        let parsed = SqlParser::new().parse("SELECT *, id FROM missing_table", None).unwrap();
+        assert!(matches!(
+            inference.infer_statement_partial(parsed.first_statement().unwrap()),
+            Err(InferenceError::UnknownTable(_))
+        ));
+    }
+
+    #[test]
+    fn order_by_alias_or_source_column_is_accepted() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+        let parser = SqlParser::new();
+
+        for sql in [
+            "SELECT name AS user_name FROM users ORDER BY user_name",
+            "SELECT name FROM users u ORDER BY u.age DESC, LOWER(email), 1",
+        ] {
+            let parsed = parser.parse(sql, None).unwrap();
+            let partial = inference.infer_statement_partial(parsed.first_statement().unwrap()).unwrap();
+            assert!(partial.warnings.is_empty(), "{}: {:?}", sql, partial.warnings);
+        }
+    }
+
+    #[test]
+    fn order_by_unknown_column_warns() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+
+        let parsed = SqlParser::new().parse("SELECT id, name FROM users ORDER BY created_at", None).unwrap();
+        let partial = inference.infer_statement_partial(parsed.first_statement().unwrap()).unwrap();
+
+        // The output columns are unaffected
+        assert_eq!(partial.schema.column_names(), vec!["id", "name"]);
+        assert!(partial.is_complete());
+
+        let diags: Vec<Diagnostic> = partial.warnings.iter().map(|w| inference.create_diagnostic(w)).collect();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code, DiagnosticCode::SqlOrderByUnknownColumn);
+        assert_eq!(diags[0].severity, Severity::Warn);
+        assert!(diags[0].message.contains("'created_at'"));
+    }
+
+    #[test]
+    fn order_by_unknown_column_does_not_fail_strict_inference() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+
+        let parsed = SqlParser::new().parse("SELECT id, name FROM users ORDER BY created_at", None).unwrap();
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+
+        assert_eq!(schema.column_names(), vec!["id", "name"]);
+        assert!(matches!(
+            inference.take_warnings().as_slice(),
+            [InferenceError::OrderByUnknownColumn(name)] if name == "created_at"
+        ));
+    }
+
     #[test]
     fn infer_select_star_with_catalog() {
         let mut context = create_test_context();
@@ -753,6 +1726,78 @@ mod tests {
         assert!(matches!(schema.columns[1].logical_type, LogicalType::Int));
     }
 
+    #[test]
+    fn infer_qualify_with_row_number() {
+        let mut context = create_test_context();
+        context.add_table("orders", Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("customer_id", LogicalType::Int),
+            Column::new("amount", LogicalType::Float),
+        ]));
+        let inference = SchemaInference::new(&context);
+        let parser = SqlParser::snowflake();
+
+        let sql = "SELECT customer_id, amount, \
+                   ROW_NUMBER() OVER (PARTITION BY customer_id ORDER BY amount DESC) AS rn \
+                   FROM orders QUALIFY rn = 1";
+        let parsed = parser.parse(sql, None).unwrap();
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+
+        assert_eq!(schema.column_names(), vec!["customer_id", "amount", "rn"]);
+        assert_eq!(schema.columns[2].logical_type, LogicalType::Int);
+
+        // Window columns are valid alongside GROUP BY keys and aggregates
+        let sql = "SELECT customer_id, SUM(amount) AS total, \
+                   RANK() OVER (ORDER BY SUM(amount) DESC) AS spend_rank \
+                   FROM orders GROUP BY customer_id QUALIFY spend_rank <= 10";
+        let parsed = parser.parse(sql, None).unwrap();
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+
+        assert_eq!(schema.column_names(), vec!["customer_id", "total", "spend_rank"]);
+        assert_eq!(schema.columns[2].logical_type, LogicalType::Int);
+    }
+
+    #[test]
+    fn infer_where_equality_tightens_nullability() {
+        let context = create_test_context();
+        let parser = SqlParser::new();
+        let sql = "SELECT u.id, name AS user_name, email, age \
+                   FROM users u \
+                   WHERE u.name = 'active' AND (email IS NOT NULL OR age > 18) AND 21 = age";
+        let parsed = parser.parse(sql, None).unwrap();
+        let stmt = parsed.first_statement().unwrap();
+
+        // Off by default
+        let schema = SchemaInference::new(&context).infer_statement(stmt).unwrap();
+        assert!(schema.columns.iter().all(|c| c.nullable == Nullability::Unknown));
+
+        let schema = SchemaInference::new(&context)
+            .with_nullability_from_where(true)
+            .infer_statement(stmt)
+            .unwrap();
+        let nullability: Vec<Nullability> = schema.columns.iter().map(|c| c.nullable).collect();
+
+        // name = literal and literal = age tighten; IS NOT NULL under OR does not
+        assert_eq!(
+            nullability,
+            vec![Nullability::Unknown, Nullability::No, Nullability::Unknown, Nullability::No]
+        );
+    }
+
+    #[test]
+    fn infer_where_is_not_null_tightens_nullability() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context).with_nullability_from_where(true);
+        let parser = SqlParser::new();
+
+        let sql = "SELECT id, email FROM users WHERE email IS NOT NULL AND id = NULL";
+        let parsed = parser.parse(sql, None).unwrap();
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+
+        assert_eq!(schema.columns[0].nullable, Nullability::Unknown);
+        assert_eq!(schema.columns[1].nullable, Nullability::No);
+    }
+
     #[test]
     fn infer_group_by_without_alias_errors() {
         let context = create_test_context();
@@ -803,4 +1848,346 @@ mod tests {
         assert_eq!(schema.columns[2].name, "cnt");
         assert_eq!(schema.columns[3].name, "avg_id");
     }
+
+    #[test]
+    fn infer_insert_select_matching_columns() {
+        let mut context = create_test_context();
+        context.add_table("user_copy", Schema::from_columns(vec![
+            Column::new("user_id", LogicalType::Int),
+            Column::new("user_name", LogicalType::String),
+        ]));
+        let inference = SchemaInference::new(&context);
+
+        let parser = SqlParser::new();
+        let sql = "INSERT INTO user_copy (user_id, user_name) SELECT id, name FROM users";
+        let parsed = parser.parse(sql, None).unwrap();
+
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+
+        // Target column names with the SELECT's types
+        assert_eq!(schema.columns.len(), 2);
+        assert_eq!(schema.columns[0].name, "user_id");
+        assert_eq!(schema.columns[1].name, "user_name");
+        assert!(matches!(schema.columns[0].logical_type, LogicalType::Int));
+        assert!(matches!(schema.columns[1].logical_type, LogicalType::String));
+    }
+
+    #[test]
+    fn infer_generate_series_as_relation() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+        let parser = SqlParser::postgres();
+
+        let parsed = parser.parse("SELECT n FROM generate_series(1, 10) AS n", None).unwrap();
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert_eq!(schema.column_names(), vec!["n"]);
+        assert_eq!(schema.columns[0].logical_type, LogicalType::Int);
+
+        // A column alias list names the output column
+        let parsed = parser.parse("SELECT day_offset FROM generate_series(1, 10) AS t(day_offset)", None).unwrap();
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert_eq!(schema.column_names(), vec!["day_offset"]);
+        assert_eq!(schema.columns[0].logical_type, LogicalType::Int);
+    }
+
+    #[test]
+    fn unknown_table_function_is_info() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+        let parser = SqlParser::postgres();
+
+        let parsed = parser.parse("SELECT key FROM json_each('{}') AS j", None).unwrap();
+        let err = inference.infer_statement(parsed.first_statement().unwrap()).unwrap_err();
+        assert!(matches!(err, InferenceError::UnknownTableFunction(ref name) if name == "json_each"));
+
+        let diag = inference.create_diagnostic(&err);
+        assert_eq!(diag.code, DiagnosticCode::SqlUnsupportedSyntax);
+        assert_eq!(diag.severity, Severity::Info);
+    }
+
+    #[test]
+    fn unsupported_constructs_report_their_kind() {
+        let mut context = create_test_context();
+        context.add_table("sales", Schema::from_columns(vec![
+            Column::new("quarter", LogicalType::String),
+            Column::new("amount", LogicalType::Int),
+        ]));
+        let inference = SchemaInference::new(&context);
+
+        let cases = [
+            ("SELECT u.id, d.doubled FROM users u CROSS JOIN LATERAL (SELECT u.id * 2 AS doubled) d", UnsupportedKind::LateralJoin),
+            ("SELECT * FROM sales PIVOT (SUM(amount) FOR quarter IN ('Q1', 'Q2')) AS p", UnsupportedKind::Pivot),
+            ("WITH RECURSIVE t(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 5) SELECT n FROM t", UnsupportedKind::RecursiveCte),
+            ("SELECT x FROM (VALUES (1), (2)) AS v(x)", UnsupportedKind::Values),
+            (
+                "SELECT SUM(amount) OVER (ORDER BY quarter ROWS BETWEEN amount PRECEDING AND CURRENT ROW) AS s FROM sales",
+                UnsupportedKind::WindowFrame,
+            ),
+        ];
+
+        for (sql, kind) in cases {
+            let parsed = SqlParser::new().parse(sql, None).unwrap();
+            let err = inference.infer_statement(parsed.first_statement().unwrap()).unwrap_err();
+            assert_eq!(err.unsupported_kind(), Some(kind), "{}", sql);
+
+            let diag = inference.create_diagnostic(&err);
+            assert_eq!(diag.code, DiagnosticCode::SqlUnsupportedSyntax);
+            assert!(diag.message.contains(&kind.to_string()), "{}", diag.message);
+            assert_eq!(diag.actual.as_deref(), Some(kind.as_str()));
+        }
+
+        let constant = "SELECT SUM(amount) OVER (ORDER BY quarter ROWS BETWEEN 3 PRECEDING AND CURRENT ROW) AS s FROM sales";
+        let parsed = SqlParser::new().parse(constant, None).unwrap();
+        assert!(inference.infer_statement(parsed.first_statement().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn lateral_subquery_without_outer_references_is_inferred() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+
+        let parsed = SqlParser::new()
+            .parse("SELECT u.id, d.n FROM users u CROSS JOIN LATERAL (SELECT 1 AS n) d", None)
+            .unwrap();
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert_eq!(schema.column_names(), vec!["id", "n"]);
+        assert_eq!(UnsupportedKind::LateralJoin.as_str(), "LATERAL_JOIN");
+    }
+
+    #[test]
+    fn join_without_aliases_reports_duplicate_id() {
+        let mut context = create_test_context();
+        context.add_table("orders", Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("user_id", LogicalType::Int),
+        ]));
+        let inference = SchemaInference::new(&context);
+
+        let sql = "SELECT u.id, o.id, u.name FROM users u JOIN orders o ON o.user_id = u.id";
+        let parsed = SqlParser::new().parse(sql, None).unwrap();
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert_eq!(schema.column_names(), vec!["id", "id", "name"]);
+
+        let diags = SchemaInference::duplicate_column_diagnostics(&schema, IdentifierCase::Sensitive);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code, DiagnosticCode::SqlDuplicateColumnName);
+        assert_eq!(diags[0].severity, Severity::Warn);
+        assert!(diags[0].message.starts_with("Column 'id' appears more than once"));
+
+        // Aliasing one side resolves the collision
+        let sql = "SELECT u.id, o.id AS order_id FROM users u JOIN orders o ON o.user_id = u.id";
+        let parsed = SqlParser::new().parse(sql, None).unwrap();
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert!(SchemaInference::duplicate_column_diagnostics(&schema, IdentifierCase::Sensitive).is_empty());
+    }
+
+    #[test]
+    fn infer_insert_select_column_count_mismatch() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+
+        let parser = SqlParser::new();
+        let sql = "INSERT INTO user_copy (user_id, user_name, user_email) SELECT id, name FROM users";
+        let parsed = parser.parse(sql, None).unwrap();
+
+        let err = inference.infer_statement(parsed.first_statement().unwrap()).unwrap_err();
+        assert!(matches!(
+            err,
+            InferenceError::InsertArityMismatch { expected: 3, actual: 2, .. }
+        ));
+
+        let diag = inference.create_diagnostic(&err);
+        assert_eq!(diag.code, DiagnosticCode::SqlInferenceError);
+        assert_eq!(diag.severity, Severity::Error);
+    }
+
+    #[test]
+    fn infer_insert_select_type_mismatch() {
+        let mut context = create_test_context();
+        context.add_table("user_copy", Schema::from_columns(vec![
+            Column::new("user_id", LogicalType::Int),
+            Column::new("user_name", LogicalType::Int),
+        ]));
+        let inference = SchemaInference::new(&context);
+
+        let parser = SqlParser::new();
+        let sql = "INSERT INTO user_copy SELECT id, name FROM users";
+        let parsed = parser.parse(sql, None).unwrap();
+
+        let err = inference.infer_statement(parsed.first_statement().unwrap()).unwrap_err();
+        assert!(matches!(err, InferenceError::InsertTypeMismatch { ref column, .. } if column == "user_name"));
+    }
+
+    #[test]
+    fn infer_create_table_as_matches_bare_select() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+        let parser = SqlParser::new();
+
+        let bare = parser.parse("SELECT id, name FROM users", None).unwrap();
+        let ctas = parser.parse("CREATE TABLE user_names AS SELECT id, name FROM users", None).unwrap();
+
+        let expected = inference.infer_statement(bare.first_statement().unwrap()).unwrap();
+        let schema = inference.infer_statement(ctas.first_statement().unwrap()).unwrap();
+
+        assert_eq!(schema, expected);
+    }
+
+    #[test]
+    fn infer_create_view_as() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+
+        let parser = SqlParser::new();
+        let parsed = parser.parse("CREATE OR REPLACE VIEW v_users AS SELECT id, email FROM users", None).unwrap();
+
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert_eq!(schema.columns.len(), 2);
+        assert_eq!(schema.columns[1].name, "email");
+    }
+
+    #[test]
+    fn infer_create_view_column_list_overrides_names() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+
+        let parser = SqlParser::new();
+        let parsed = parser.parse("CREATE VIEW v_users (user_id, user_name) AS SELECT id, name FROM users", None).unwrap();
+
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert_eq!(schema.columns[0].name, "user_id");
+        assert_eq!(schema.columns[1].name, "user_name");
+        assert!(matches!(schema.columns[1].logical_type, LogicalType::String));
+
+        let mismatched = parser.parse("CREATE VIEW v_users (user_id) AS SELECT id, name FROM users", None).unwrap();
+        let err = inference.infer_statement(mismatched.first_statement().unwrap()).unwrap_err();
+        assert!(matches!(err, InferenceError::ColumnListArityMismatch { expected: 1, actual: 2, .. }));
+    }
+
+    #[test]
+    fn infer_nested_query_past_depth_limit_errors() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context).with_max_depth(4);
+
+        let parser = SqlParser::new();
+        let sql = "SELECT id FROM (SELECT id FROM (SELECT id FROM (SELECT id FROM (SELECT id FROM users) a) b) c) d";
+        let parsed = parser.parse(sql, None).unwrap();
+
+        let err = inference.infer_statement(parsed.first_statement().unwrap()).unwrap_err();
+        assert!(matches!(err, InferenceError::RecursionLimitExceeded(4)));
+        assert_eq!(inference.create_diagnostic(&err).severity, Severity::Error);
+
+        // The same query fits comfortably within the default limit
+        let inference = SchemaInference::new(&context);
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert_eq!(schema.columns.len(), 1);
+    }
+
+    #[test]
+    fn infer_group_by_ordinal() {
+        let context = create_test_context();
+        let inference = SchemaInference::new(&context);
+
+        let parser = SqlParser::new();
+        let sql = "SELECT name, COUNT(*) AS user_count FROM users GROUP BY 1";
+        let parsed = parser.parse(sql, None).unwrap();
+
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert_eq!(schema.column_names(), vec!["name", "user_count"]);
+
+        // An ordinal that points at a different projection doesn't cover `name`
+        let sql = "SELECT name, age, COUNT(*) AS user_count FROM users GROUP BY 2";
+        let parsed = parser.parse(sql, None).unwrap();
+        let result = inference.infer_statement(parsed.first_statement().unwrap());
+        assert!(matches!(result.unwrap_err(), InferenceError::InvalidGroupByColumn(ref c) if c == "name"));
+    }
+
+    #[test]
+    fn infer_group_by_expression() {
+        let mut context = InferenceContext::new();
+        context.add_table("events", Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("ts", LogicalType::Timestamp),
+        ]));
+        let inference = SchemaInference::new(&context);
+
+        let parser = SqlParser::new();
+        let sql = "SELECT DATE_TRUNC('day', ts) AS event_day, COUNT(*) AS event_count \
+                   FROM events GROUP BY date_trunc('day', ts)";
+        let parsed = parser.parse(sql, None).unwrap();
+
+        let schema = inference.infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert_eq!(schema.column_names(), vec!["event_day", "event_count"]);
+
+        // Grouping by the alias is accepted too
+        let sql = "SELECT DATE_TRUNC('day', ts) AS event_day, COUNT(*) AS event_count FROM events GROUP BY event_day";
+        let parsed = parser.parse(sql, None).unwrap();
+        assert!(inference.infer_statement(parsed.first_statement().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn ephemeral_models_resolve_for_downstream_inference() {
+        let ephemeral = |name: &str, sql: &str| format!(r#""model.shop.{name}": {{
+            "unique_id": "model.shop.{name}", "name": "{name}", "resource_type": "model",
+            "package_name": "shop", "path": "{name}.sql", "original_file_path": "models/{name}.sql",
+            "database": "analytics", "schema": "core",
+            "config": {{"materialized": "ephemeral"}},
+            "compiled_code": "{sql}"
+        }}"#);
+        let manifest = Manifest::from_str(&format!(
+            r#"{{
+                "metadata": {{"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"}},
+                "nodes": {{ {}, {} }},
+                "sources": {{
+                    "source.shop.raw.users": {{
+                        "unique_id": "source.shop.raw.users", "name": "users", "source_name": "raw",
+                        "database": "lake", "schema": "raw", "resource_type": "source", "package_name": "shop",
+                        "columns": {{
+                            "id": {{"name": "id", "data_type": "int"}},
+                            "email": {{"name": "email", "data_type": "varchar"}}
+                        }}
+                    }}
+                }}
+            }}"#,
+            // int_users reads stg_users, so it resolves only after stg_users has
+            ephemeral("int_users", "select id, upper(email) as email_upper from analytics.core.stg_users"),
+            ephemeral("stg_users", "select id, email from lake.raw.users"),
+        ))
+        .unwrap();
+        assert!(manifest.get_node("model.shop.int_users").unwrap().is_ephemeral());
+
+        let context = InferenceContext::from_manifest(&manifest);
+        let (sql, _) = crate::DbtFunctionExtractor::preprocess(
+            "select id, email_upper from {{ ref('int_users') }}",
+            Some(&manifest),
+        );
+        let parsed = SqlParser::new().parse(&sql, None).unwrap();
+
+        let schema = SchemaInference::new(&context).infer_statement(parsed.first_statement().unwrap()).unwrap();
+        assert_eq!(schema.column_names(), vec!["id", "email_upper"]);
+        assert_eq!(schema.columns[0].logical_type, LogicalType::Int);
+    }
+
+    #[test]
+    fn table_aliases_share_one_schema() {
+        let manifest = Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.shop.users": {
+                    "unique_id": "model.shop.users", "name": "users", "resource_type": "model",
+                    "package_name": "shop", "path": "users.sql", "original_file_path": "models/users.sql",
+                    "database": "analytics", "schema": "core",
+                    "config": {"contract": {"enforced": true}},
+                    "columns": {"id": {"name": "id", "data_type": "int"}}
+                }
+            },
+            "sources": {}
+        }"#).unwrap();
+
+        let context = InferenceContext::from_manifest(&manifest);
+        let by_name = context.get_table_schema("users").unwrap();
+        assert!(std::ptr::eq(by_name, context.get_table_schema("model.shop.users").unwrap()));
+        assert!(std::ptr::eq(by_name, context.get_table_schema("analytics.core.users").unwrap()));
+        assert_eq!(by_name.column_names(), vec!["id"]);
+    }
 }
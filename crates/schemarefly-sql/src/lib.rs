@@ -5,6 +5,8 @@
 //! - Resolving CTEs, aliases, and references
 //! - Resolving dbt-specific functions (ref, source)
 //! - Schema inference from SQL queries
+//! - Explaining how dbt references were resolved for inference
+//! - Bundling a model with its upstream schemas to replay inference
 //! - Extracting location information for diagnostics
 
 // Diagnostic-carrying error enums are intentionally large; boxing them would
@@ -15,8 +17,12 @@ pub mod parser;
 pub mod resolver;
 pub mod dbt_functions;
 pub mod inference;
+pub mod explain;
+pub mod repro;
 
 pub use parser::{SqlParser, ParsedSql, ParseError};
-pub use resolver::{NameResolver, ResolvedName};
+pub use resolver::{ColumnIndex, NameResolver, ResolvedName};
 pub use dbt_functions::{DbtFunctionExtractor, DbtReference};
-pub use inference::{SchemaInference, InferenceContext, InferenceError};
+pub use inference::{SchemaInference, InferenceContext, InferenceError, PartialInference, UnsupportedKind};
+pub use explain::{InferenceExplanation, ReferenceExplanation};
+pub use repro::ReproBundle;
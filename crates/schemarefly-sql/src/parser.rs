@@ -52,6 +52,11 @@ impl SqlParser {
         }
     }
 
+    /// The sqlparser dialect used for tokenizing and parsing
+    pub fn dialect(&self) -> &dyn Dialect {
+        &*self.dialect
+    }
+
     /// Parse SQL string into AST
     ///
     /// Returns ParsedSql on success, or ParseError with diagnostic on failure.
@@ -166,6 +171,41 @@ impl ParsedSql {
         self.statements.first()
     }
 
+    /// The statement a model's schema is inferred from
+    ///
+    /// A dbt model holds one SELECT. In a file with several statements this
+    /// is the first one, unless only the last is a query (`SET ...; SELECT ...`
+    /// after concatenated hooks), in which case it is the last.
+    pub fn model_statement(&self) -> Option<&Statement> {
+        match (self.statements.first(), self.statements.last()) {
+            (Some(Statement::Query(_)), _) => self.first_statement(),
+            (_, Some(last @ Statement::Query(_))) => Some(last),
+            _ => self.first_statement(),
+        }
+    }
+
+    /// Warning for SQL holding more than one statement, naming the one `model_statement` analyzes
+    pub fn multiple_statements_diagnostic(&self) -> Option<Diagnostic> {
+        if self.statements.len() < 2 {
+            return None;
+        }
+
+        let analyzed = if self.first_statement() == self.model_statement() {
+            "the first"
+        } else {
+            "the final SELECT"
+        };
+        Some(Diagnostic::new(
+            DiagnosticCode::SqlMultipleStatements,
+            Severity::Warn,
+            format!(
+                "Model SQL contains {} statements; only {} is analyzed",
+                self.statements.len(),
+                analyzed
+            ),
+        ))
+    }
+
     /// Check if this is a SELECT statement
     pub fn is_select(&self) -> bool {
         matches!(
@@ -273,6 +313,30 @@ mod tests {
         assert!(parsed.is_select());
     }
 
+    #[test]
+    fn multiple_statements_pick_the_model_query() {
+        let parser = SqlParser::new();
+
+        let parsed = parser.parse("SELECT 1 AS a; SELECT 2 AS b", None).unwrap();
+        assert_eq!(parsed.model_statement(), parsed.first_statement());
+        let diag = parsed.multiple_statements_diagnostic().unwrap();
+        assert_eq!(diag.code, DiagnosticCode::SqlMultipleStatements);
+        assert_eq!(diag.severity, Severity::Warn);
+        assert_eq!(diag.message, "Model SQL contains 2 statements; only the first is analyzed");
+
+        // A hook concatenated before the model's SELECT
+        let parsed = parser.parse("SET search_path TO analytics; SELECT id FROM users", None).unwrap();
+        assert_eq!(parsed.model_statement(), parsed.statements.last());
+        assert_eq!(
+            parsed.multiple_statements_diagnostic().unwrap().message,
+            "Model SQL contains 2 statements; only the final SELECT is analyzed"
+        );
+
+        let parsed = parser.parse("SELECT id FROM users;", None).unwrap();
+        assert_eq!(parsed.statement_count(), 1);
+        assert!(parsed.multiple_statements_diagnostic().is_none());
+    }
+
     #[test]
     fn parse_invalid_sql() {
         let parser = SqlParser::new();
@@ -0,0 +1,319 @@
+//! Self-contained reproduction bundles for inference bugs
+//!
+//! A `ReproBundle` holds a model's preprocessed SQL together with the schemas
+//! of the tables it references directly, so an inference problem can be
+//! replayed (and turned into a test) without the rest of the dbt project.
+//! Bundles can be redacted: identifiers are renamed consistently and literals,
+//! comments and column metadata dropped, so the redacted bundle still replays
+//! the same way.
+
+use crate::explain::InferenceExplanation;
+use crate::inference::{InferenceContext, PartialInference, SchemaInference};
+use crate::parser::SqlParser;
+use schemarefly_core::{Column, DialectConfig, Schema};
+use schemarefly_dbt::Manifest;
+use serde::{Deserialize, Serialize};
+use sqlparser::keywords::Keyword;
+use sqlparser::ast::DollarQuotedString;
+use sqlparser::tokenizer::{Token, Tokenizer, Whitespace, Word};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Bundle format version, bumped on incompatible changes
+pub const REPRO_BUNDLE_VERSION: u32 = 1;
+
+/// Everything needed to replay schema inference for one model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReproBundle {
+    /// Bundle format version
+    pub version: u32,
+
+    /// Model unique_id (`model` once redacted)
+    pub model: String,
+
+    /// SQL dialect the model is parsed with
+    pub dialect: DialectConfig,
+
+    /// Model SQL after dbt functions were replaced with table names
+    pub sql: String,
+
+    /// Schemas of the referenced tables, keyed by the name used in `sql`
+    pub tables: BTreeMap<String, Schema>,
+
+    /// Whether identifiers and literals were renamed
+    #[serde(default)]
+    pub redacted: bool,
+}
+
+impl ReproBundle {
+    /// Collect the bundle for a model from its raw SQL
+    ///
+    /// Only the tables referenced through `ref()`/`source()` are included;
+    /// references without a known schema are left out, so the replay fails
+    /// the same way inference did.
+    pub fn build(
+        model: impl Into<String>,
+        sql: &str,
+        dialect: DialectConfig,
+        manifest: &Manifest,
+        context: &InferenceContext,
+        vars: &HashMap<String, serde_json::Value>,
+    ) -> Self {
+        let explanation = InferenceExplanation::explain_with_vars(sql, manifest, context, vars);
+
+        let tables = explanation
+            .references
+            .into_iter()
+            .filter_map(|r| r.schema.map(|schema| (r.table_name, schema)))
+            .collect();
+
+        Self {
+            version: REPRO_BUNDLE_VERSION,
+            model: model.into(),
+            dialect,
+            sql: explanation.preprocessed_sql,
+            tables,
+            redacted: false,
+        }
+    }
+
+    /// Inference context holding only the bundled tables
+    pub fn context(&self) -> InferenceContext {
+        let mut context = InferenceContext::new();
+        for (name, schema) in &self.tables {
+            context.add_table(name.clone(), schema.clone());
+        }
+        context
+    }
+
+    /// Parse and infer the bundled SQL against the bundled tables
+    pub fn replay(&self) -> Result<PartialInference, String> {
+        let parsed = SqlParser::from_dialect(&self.dialect)
+            .parse(&self.sql, None)
+            .map_err(|e| e.to_string())?;
+        let stmt = parsed.model_statement().ok_or_else(|| "No SQL statement found".to_string())?;
+
+        let context = self.context();
+        SchemaInference::new(&context)
+            .infer_statement_partial(stmt)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Copy with identifiers renamed and literals and comments removed
+    ///
+    /// Table and column names, and every other identifier that is not a
+    /// keyword or a function name, become `id_N`, the same name mapping to
+    /// the same replacement everywhere. String literals of every quoting
+    /// style become `redacted` and comments are dropped. Bundled columns keep
+    /// only their name, type and flags: provenance, default expressions and
+    /// accepted values are removed. Types, numbers and the shape of the
+    /// query are kept.
+    pub fn redacted(&self) -> Result<Self, String> {
+        let mut names = NameMap::default();
+
+        // Bundled names are renamed even where they read as keywords (`raw`)
+        let known: HashSet<&str> = self
+            .tables
+            .iter()
+            .flat_map(|(name, schema)| name.split('.').chain(schema.columns.iter().map(|c| c.name.as_str())))
+            .collect();
+
+        let parser = SqlParser::from_dialect(&self.dialect);
+        let tokens = Tokenizer::new(parser.dialect(), &self.sql)
+            .tokenize()
+            .map_err(|e| e.to_string())?;
+
+        let mut sql = String::with_capacity(self.sql.len());
+        for (i, token) in tokens.iter().enumerate() {
+            let is_call = tokens[i + 1..]
+                .iter()
+                .find(|t| !matches!(t, Token::Whitespace(_)))
+                .is_some_and(|t| *t == Token::LParen);
+
+            let token = match token {
+                Token::Word(word)
+                    if word.quote_style.is_some()
+                        || known.contains(word.value.as_str())
+                        || (word.keyword == Keyword::NoKeyword && !is_call) =>
+                {
+                    Token::Word(Word { value: names.rename(&word.value), ..word.clone() })
+                }
+                Token::Whitespace(Whitespace::SingleLineComment { .. }) => Token::Whitespace(Whitespace::Newline),
+                Token::Whitespace(Whitespace::MultiLineComment(_)) => Token::Whitespace(Whitespace::Space),
+                other => redact_literal(other),
+            };
+            sql.push_str(&token.to_string());
+        }
+
+        let tables = self
+            .tables
+            .iter()
+            .map(|(name, schema)| {
+                let name = name.split('.').map(|part| names.rename(part)).collect::<Vec<_>>().join(".");
+                let columns = schema
+                    .columns
+                    .iter()
+                    .map(|col| Column {
+                        nullable: col.nullable,
+                        has_default: col.has_default,
+                        enforced: col.enforced,
+                        time_zone: col.time_zone,
+                        is_generated: col.is_generated,
+                        ..Column::new(names.rename(&col.name), col.logical_type.clone())
+                    })
+                    .collect();
+                (name, Schema::from_columns(columns))
+            })
+            .collect();
+
+        Ok(Self {
+            version: self.version,
+            model: "model".to_string(),
+            dialect: self.dialect.clone(),
+            sql,
+            tables,
+            redacted: true,
+        })
+    }
+}
+
+/// A literal token with its contents replaced, keeping its quoting style
+fn redact_literal(token: &Token) -> Token {
+    let redacted = || "redacted".to_string();
+    match token {
+        Token::SingleQuotedString(_) => Token::SingleQuotedString(redacted()),
+        Token::DoubleQuotedString(_) => Token::DoubleQuotedString(redacted()),
+        Token::TripleSingleQuotedString(_) => Token::TripleSingleQuotedString(redacted()),
+        Token::TripleDoubleQuotedString(_) => Token::TripleDoubleQuotedString(redacted()),
+        Token::DollarQuotedString(_) => Token::DollarQuotedString(DollarQuotedString { value: redacted(), tag: None }),
+        Token::SingleQuotedByteStringLiteral(_) => Token::SingleQuotedByteStringLiteral(redacted()),
+        Token::DoubleQuotedByteStringLiteral(_) => Token::DoubleQuotedByteStringLiteral(redacted()),
+        Token::TripleSingleQuotedByteStringLiteral(_) => Token::TripleSingleQuotedByteStringLiteral(redacted()),
+        Token::TripleDoubleQuotedByteStringLiteral(_) => Token::TripleDoubleQuotedByteStringLiteral(redacted()),
+        Token::SingleQuotedRawStringLiteral(_) => Token::SingleQuotedRawStringLiteral(redacted()),
+        Token::DoubleQuotedRawStringLiteral(_) => Token::DoubleQuotedRawStringLiteral(redacted()),
+        Token::TripleSingleQuotedRawStringLiteral(_) => Token::TripleSingleQuotedRawStringLiteral(redacted()),
+        Token::TripleDoubleQuotedRawStringLiteral(_) => Token::TripleDoubleQuotedRawStringLiteral(redacted()),
+        Token::NationalStringLiteral(_) => Token::NationalStringLiteral(redacted()),
+        Token::EscapedStringLiteral(_) => Token::EscapedStringLiteral(redacted()),
+        Token::UnicodeStringLiteral(_) => Token::UnicodeStringLiteral(redacted()),
+        Token::HexStringLiteral(_) => Token::HexStringLiteral("00".to_string()),
+        other => other.clone(),
+    }
+}
+
+/// Consistent identifier → placeholder mapping
+#[derive(Default)]
+struct NameMap {
+    names: HashMap<String, String>,
+}
+
+impl NameMap {
+    fn rename(&mut self, name: &str) -> String {
+        let next = self.names.len() + 1;
+        self.names.entry(name.to_string()).or_insert_with(|| format!("id_{}", next)).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemarefly_core::{ColumnRef, LogicalType};
+
+    fn manifest() -> Manifest {
+        Manifest::from_str(r#"{
+            "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+            "nodes": {
+                "model.shop.users": {
+                    "unique_id": "model.shop.users",
+                    "name": "users",
+                    "resource_type": "model",
+                    "package_name": "shop",
+                    "database": "analytics",
+                    "schema": "core",
+                    "path": "users.sql",
+                    "original_file_path": "models/users.sql",
+                    "config": {"contract": {"enforced": true}},
+                    "columns": {
+                        "id": {"name": "id", "data_type": "int"},
+                        "email": {"name": "email", "data_type": "varchar"}
+                    }
+                }
+            },
+            "sources": {
+                "source.shop.raw.orders": {
+                    "unique_id": "source.shop.raw.orders",
+                    "name": "orders",
+                    "source_name": "raw",
+                    "database": "lake",
+                    "schema": "raw",
+                    "resource_type": "source",
+                    "package_name": "shop",
+                    "columns": {
+                        "order_id": {"name": "order_id", "data_type": "int"},
+                        "user_id": {"name": "user_id", "data_type": "int"}
+                    }
+                }
+            }
+        }"#).unwrap()
+    }
+
+    const SQL: &str = "SELECT u.email, o.order_id, 'vip' AS tier \
+                       FROM {{ ref('users') }} u JOIN {{ source('raw', 'orders') }} o ON o.user_id = u.id";
+
+    #[test]
+    fn bundle_holds_sql_and_referenced_schemas() {
+        let manifest = manifest();
+        let context = InferenceContext::from_manifest(&manifest);
+        let bundle = ReproBundle::build("model.shop.user_orders", SQL, DialectConfig::Postgres, &manifest, &context, &HashMap::new());
+
+        assert!(bundle.sql.contains("FROM analytics.core.users u JOIN lake.raw.orders o"));
+        assert_eq!(bundle.tables.keys().collect::<Vec<_>>(), vec!["analytics.core.users", "lake.raw.orders"]);
+        let mut order_columns = bundle.tables["lake.raw.orders"].column_names();
+        order_columns.sort();
+        assert_eq!(order_columns, vec!["order_id", "user_id"]);
+        assert_eq!(bundle.tables["analytics.core.users"].find_column("id").unwrap().logical_type, LogicalType::Int);
+
+        // Round-trips through JSON and replays without the manifest
+        let json = serde_json::to_string(&bundle).unwrap();
+        let replayed: ReproBundle = serde_json::from_str(&json).unwrap();
+        assert_eq!(replayed, bundle);
+        assert_eq!(replayed.replay().unwrap().schema.column_names(), vec!["email", "order_id", "tier"]);
+    }
+
+    #[test]
+    fn redacted_bundle_replays_with_the_same_shape() {
+        let manifest = manifest();
+        let context = InferenceContext::from_manifest(&manifest);
+        let sql = "-- loyalty audit for acme\n\
+                   SELECT u.email, o.order_id, 'vip' AS tier, E'gold\\n' AS rank, $$platinum$$ AS band /* q3 cohort */ \
+                   FROM {{ ref('users') }} u JOIN {{ source('raw', 'orders') }} o ON o.user_id = u.id";
+        let mut bundle = ReproBundle::build("model.shop.user_orders", sql, DialectConfig::Postgres, &manifest, &context, &HashMap::new());
+
+        let users = bundle.tables.get_mut("analytics.core.users").unwrap();
+        let email = users.columns.iter_mut().find(|c| c.name == "email").unwrap();
+        email.default = Some("'nobody@example.com'".to_string());
+        email.has_default = Some(true);
+        email.accepted_values = Some(vec!["premium_tier".to_string()]);
+        email.provenance.push(ColumnRef { source: "crm_contacts".to_string(), column: "contact_email".to_string() });
+
+        let redacted = bundle.redacted().unwrap();
+        assert!(redacted.redacted);
+        assert_eq!(redacted.model, "model");
+
+        let json = serde_json::to_string(&redacted).unwrap();
+        for secret in [
+            "email", "orders", "order_id", "user_id", "users", "analytics", "core", "lake", "raw", "shop", "tier",
+            "vip", "gold", "platinum", "loyalty", "acme", "cohort", "nobody", "premium_tier", "crm_contacts",
+            "contact_email",
+        ] {
+            assert!(!json.contains(secret), "{} leaked: {}", secret, json);
+        }
+        let email = redacted.tables.values().flat_map(|s| &s.columns).find(|c| c.has_default == Some(true)).unwrap();
+        assert!(email.default.is_none() && email.accepted_values.is_none() && email.provenance.is_empty());
+
+        let original = bundle.replay().unwrap().schema;
+        let replayed = redacted.replay().unwrap().schema;
+        let types = |s: &Schema| s.columns.iter().map(|c| c.logical_type.clone()).collect::<Vec<_>>();
+        assert_eq!(types(&replayed), types(&original));
+    }
+}
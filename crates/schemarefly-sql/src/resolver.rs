@@ -2,6 +2,7 @@
 //!
 //! Resolves names in SQL queries to their definitions.
 
+use schemarefly_core::{Column, Schema};
 use sqlparser::ast::{Statement, Query, SetExpr, Select, TableFactor, Cte, SelectItem, Expr};
 use std::collections::HashMap;
 
@@ -30,6 +31,34 @@ pub enum ResolvedName {
     },
 }
 
+/// Name index over the columns a SELECT can reference
+///
+/// Built once per SELECT from its FROM-clause schema, so resolving each
+/// projected column is a hash lookup rather than a scan of the schema.
+/// When a name appears more than once the first column wins, matching
+/// `Schema::find_column`.
+pub struct ColumnIndex<'s> {
+    schema: &'s Schema,
+    positions: HashMap<&'s str, usize>,
+}
+
+impl<'s> ColumnIndex<'s> {
+    /// Index the columns of `schema`
+    pub fn new(schema: &'s Schema) -> Self {
+        let mut positions = HashMap::with_capacity(schema.columns.len());
+        for (position, column) in schema.columns.iter().enumerate() {
+            positions.entry(column.name.as_str()).or_insert(position);
+        }
+
+        Self { schema, positions }
+    }
+
+    /// Look up a column by name
+    pub fn column(&self, name: &str) -> Option<&'s Column> {
+        self.positions.get(name).map(|&position| &self.schema.columns[position])
+    }
+}
+
 /// Name resolver for SQL queries
 pub struct NameResolver {
     /// Map of CTE names to their definitions
@@ -359,4 +388,22 @@ mod tests {
         assert!(resolver.is_column_alias("user_name"));
         assert!(resolver.is_column_alias("user_email"));
     }
+
+    #[test]
+    fn column_index_matches_schema_scan() {
+        use schemarefly_core::LogicalType;
+
+        let schema = Schema::from_columns(vec![
+            Column::new("id", LogicalType::Int),
+            Column::new("name", LogicalType::String),
+            // Duplicate name from a join: the first column must win
+            Column::new("id", LogicalType::String),
+            Column::new("Name", LogicalType::Bool),
+        ]);
+        let index = ColumnIndex::new(&schema);
+
+        for name in ["id", "name", "Name", "NAME", "missing"] {
+            assert_eq!(index.column(name), schema.find_column(name), "lookup of '{}'", name);
+        }
+    }
 }
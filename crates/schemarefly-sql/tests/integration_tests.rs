@@ -307,3 +307,43 @@ fn infer_schema_complex_aggregation() {
     // Aggregates should have correct types
     assert!(matches!(schema.columns[3].logical_type, LogicalType::Int)); // COUNT
 }
+
+#[test]
+fn explain_flags_unresolved_ref() {
+    use schemarefly_sql::{InferenceContext, InferenceExplanation};
+
+    let manifest = Manifest::from_str(r#"{
+        "metadata": {"dbt_schema_version": "v12", "dbt_version": "1.8.0", "generated_at": "2024-01-01T00:00:00Z"},
+        "nodes": {
+            "model.mini_dbt_project.users": {
+                "unique_id": "model.mini_dbt_project.users", "name": "users", "resource_type": "model",
+                "package_name": "mini_dbt_project", "path": "users.sql", "original_file_path": "models/users.sql"
+            }
+        },
+        "sources": {}
+    }"#).unwrap();
+    let context = InferenceContext::from_manifest(&manifest);
+
+    let sql = r#"
+        SELECT u.id, m.value
+        FROM {{ ref('users') }} AS u
+        JOIN {{ ref('does_not_exist') }} AS m ON u.id = m.id
+    "#;
+
+    let explanation = InferenceExplanation::explain(sql, &manifest, &context);
+    assert_eq!(explanation.references.len(), 2);
+
+    // The existing model resolves to its manifest node
+    assert!(explanation.references[0].is_resolved());
+    assert_eq!(explanation.references[0].unique_id(), Some("model.mini_dbt_project.users"));
+
+    // The missing model is flagged as unresolved, with no schema loaded
+    let unresolved = explanation.unresolved();
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0].display_name(), "ref('does_not_exist')");
+    assert!(unresolved[0].schema.is_none());
+
+    let rendered = explanation.render();
+    assert!(rendered.contains("ref('does_not_exist')"));
+    assert!(rendered.contains("UNRESOLVED"));
+}